@@ -1,7 +1,8 @@
 use ark_serialize::SerializationError;
 use clap::{Parser, ValueEnum};
 use solidity_verifiers::{
-    Groth16VerifierKey, KZG10VerifierKey, NovaCycleFoldVerifierKey, ProtocolVerifierKey,
+    Groth16VerifierKey, HyperNovaCycleFoldVerifierKey, KZG10VerifierKey, NovaCycleFoldVerifierKey,
+    ProtoGalaxyCycleFoldVerifierKey, ProtocolVerifierKey,
 };
 use std::{env, fmt::Display, path::PathBuf};
 
@@ -16,6 +17,8 @@ pub(crate) enum Protocol {
     Groth16,
     Kzg,
     NovaCycleFold,
+    HyperNovaCycleFold,
+    ProtoGalaxyCycleFold,
 }
 
 impl Display for Protocol {
@@ -42,6 +45,14 @@ impl Protocol {
                 data,
             )?
             .render_as_template(pragma)),
+            Self::HyperNovaCycleFold => Ok(
+                HyperNovaCycleFoldVerifierKey::deserialize_protocol_verifier_key(data)?
+                    .render_as_template(pragma),
+            ),
+            Self::ProtoGalaxyCycleFold => Ok(
+                ProtoGalaxyCycleFoldVerifierKey::deserialize_protocol_verifier_key(data)?
+                    .render_as_template(pragma),
+            ),
         }
     }
 }
@@ -85,6 +96,12 @@ Solidity Verifier currently supports the generation of Solidity smart contracts
 
     Nova + CycleFold Decider:
         Implements the decider circuit verification for the Nova proof system in conjunction with the CycleFold protocol optimization.
+
+    HyperNova + CycleFold Decider:
+        Implements the decider circuit verification for the HyperNova proof system in conjunction with the CycleFold protocol optimization.
+
+    ProtoGalaxy + CycleFold Decider:
+        Implements the decider circuit verification for the ProtoGalaxy proof system in conjunction with the CycleFold protocol optimization.
 ";
 #[derive(Debug, Parser)]
 #[command(author = "0xPARC & PSE", version, about = ABOUT, long_about = Some(LONG_ABOUT))]
@@ -131,6 +131,14 @@ pub trait ArithSerializer {
     fn params_to_le_bytes(&self) -> Vec<u8>;
 }
 
+/// Serializes the parameters of a list of constraint systems, as used by
+/// schemes that fold several circuits (e.g. non-uniform IVC).
+impl<A: ArithSerializer> ArithSerializer for Vec<A> {
+    fn params_to_le_bytes(&self) -> Vec<u8> {
+        self.iter().flat_map(|a| a.params_to_le_bytes()).collect()
+    }
+}
+
 /// `ArithSampler` allows sampling random pairs of witness and instance that
 /// satisfy the constraint system `self`.
 ///
@@ -147,7 +155,9 @@ pub trait ArithSerializer {
 /// [HyperNova]: https://eprint.iacr.org/2023/573.pdf
 pub trait ArithSampler<C: Curve, W, U>: ArithRelation<W, U> {
     /// Samples a random witness and instance that satisfy the constraint system.
-    fn sample_witness_instance<CS: CommitmentScheme<C, true>>(
+    /// The `H` const generic specifies whether the commitment scheme is hiding, in which case
+    /// random blinding factors are also sampled for the commitments.
+    fn sample_witness_instance<CS: CommitmentScheme<C, H>, const H: bool>(
         &self,
         params: &CS::ProverParams,
         rng: impl RngCore,
@@ -0,0 +1,433 @@
+/// HyperKZG multilinear polynomial commitment, adapted from
+/// [Nova's HyperKZG](https://github.com/microsoft/Nova/blob/main/src/provider/hyperkzg.rs) (which
+/// builds on the Gemini transformation, https://eprint.iacr.org/2022/420) into the
+/// CommitmentScheme trait.
+///
+/// A vector `v` is seen as the evaluations over the boolean hypercube of the multilinear
+/// polynomial `ṽ`, with the same variable ordering as the one used in `utils::mle` (padding `v`
+/// with zeros up to `2^ℓ`), and it is committed as the univariate polynomial with coefficients
+/// `v`. Notice that this differs from `KZG`, which commits to the polynomial interpolating `v`
+/// over a domain, so while the parameters are the same, the commitments are not.
+///
+/// To open `ṽ` at a point `x` of `ℓ` variables, the prover commits to the polynomials
+/// `f_1, ..., f_{ℓ-1}` obtained by folding `f_0 = v` over each variable,
+/// `f_{i+1}[j] = (1 - x_i) f_i[2j] + x_i f_i[2j+1]`, and proves the evaluations of `f_0, ...,
+/// f_{ℓ-1}` at `r`, `-r` and `r^2` for a random `r`. Then the verifier checks that
+/// `f_{i+1}(r^2) = (1 - x_i) (f_i(r) + f_i(-r)) / 2 + x_i (f_i(r) - f_i(-r)) / (2r)`, where
+/// `f_ℓ(r^2)` is the claimed evaluation `ṽ(x)`. The evaluations are batched into a single
+/// polynomial, which is opened with three KZG proofs that are verified with a single pairing
+/// check.
+///
+/// The challenges `r`, `q` (batching) and `d` (pairing check batching) are obtained from the given
+/// transcript in `prove` & `verify`, and from a Poseidon transcript initialized with the opening
+/// point in `prove_with_challenge` & `verify_with_challenge`, in which case the point is expected
+/// to be already bound to the commitment.
+use ark_crypto_primitives::sponge::{poseidon::PoseidonSponge, CryptographicSponge};
+use ark_ec::{pairing::Pairing, VariableBaseMSM};
+use ark_ff::{Field, PrimeField};
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+use ark_poly_commit::kzg10::VerifierKey;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{log2, marker::PhantomData, rand::RngCore, One, Zero};
+
+use super::kzg::{ProverKey, KZG};
+use super::CommitmentScheme;
+use crate::transcript::{poseidon::poseidon_canonical_config, Transcript};
+use crate::utils::powers_of;
+use crate::{Curve, Error};
+
+#[derive(Debug, Clone, Default, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Proof<C: Curve> {
+    /// Evaluation of the multilinear polynomial at the opening point.
+    pub eval: C::ScalarField,
+    /// Commitments to the folded polynomials `f_1, ..., f_{ℓ-1}`.
+    pub coms: Vec<C>,
+    /// Evaluations of `f_0, ..., f_{ℓ-1}` at `r`, `-r` and `r^2` respectively.
+    pub evals: Vec<Vec<C::ScalarField>>,
+    /// KZG proofs of the batched polynomial at `r`, `-r` and `r^2` respectively.
+    pub w: Vec<C>,
+}
+
+/// HyperKZG implements the CommitmentScheme trait for the HyperKZG multilinear polynomial
+/// commitment scheme, where the challenges are the points at which the multilinear polynomial is
+/// evaluated.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct HyperKZG<'a, E: Pairing, const H: bool = false> {
+    _a: PhantomData<&'a ()>,
+    _e: PhantomData<E>,
+}
+
+impl<'a, E: Pairing<G1: Curve>, const H: bool> HyperKZG<'a, E, H> {
+    /// Commits to the univariate polynomial with coefficients `f`, ie. `Σ f_k β^k G`.
+    fn commit_coeffs(params: &ProverKey<'a, E::G1>, f: &[E::ScalarField]) -> Result<E::G1, Error> {
+        if f.len() > params.powers_of_g.len() {
+            return Err(Error::PedersenParamsLen(params.powers_of_g.len(), f.len()));
+        }
+        let coeffs = f.iter().map(|f_k| f_k.into_bigint()).collect::<Vec<_>>();
+        Ok(E::G1::msm_bigint(&params.powers_of_g[..f.len()], &coeffs))
+    }
+
+    /// Returns the KZG proof of the evaluation at `u` of the univariate polynomial with
+    /// coefficients `f`, ie. the commitment to the quotient `(f(X) - f(u)) / (X - u)`, which is
+    /// obtained through synthetic division.
+    fn open_coeffs(
+        params: &ProverKey<'a, E::G1>,
+        f: &[E::ScalarField],
+        u: E::ScalarField,
+    ) -> Result<E::G1, Error> {
+        let mut quotient = vec![E::ScalarField::zero(); f.len().saturating_sub(1)];
+        let mut acc = E::ScalarField::zero();
+        for k in (1..f.len()).rev() {
+            acc = f[k] + u * acc;
+            quotient[k - 1] = acc;
+        }
+        Self::commit_coeffs(params, &quotient)
+    }
+
+    /// Returns the polynomials `f_0, ..., f_{ℓ-1}` obtained by folding `v` over the variables of
+    /// `point`, together with the evaluation `ṽ(point)`. For `ℓ = 0` it returns `f_0` (the
+    /// constant `ṽ`).
+    #[allow(clippy::type_complexity)]
+    fn fold(
+        v: &[E::ScalarField],
+        point: &[E::ScalarField],
+    ) -> Result<(Vec<Vec<E::ScalarField>>, E::ScalarField), Error> {
+        let n = 1 << point.len();
+        if v.len() > n {
+            return Err(Error::NotExpectedLength(v.len(), n));
+        }
+        let mut f = v.to_vec();
+        f.resize(n, E::ScalarField::zero());
+
+        let mut polys = Vec::with_capacity(point.len());
+        for x_i in point {
+            let next = f
+                .chunks(2)
+                .map(|c| c[0] + *x_i * (c[1] - c[0]))
+                .collect::<Vec<_>>();
+            polys.push(f);
+            f = next;
+        }
+        let eval = f[0];
+        if polys.is_empty() {
+            polys.push(f);
+        }
+        Ok((polys, eval))
+    }
+
+    fn open(
+        params: &ProverKey<'a, E::G1>,
+        transcript: &mut impl Transcript<E::ScalarField>,
+        point: &[E::ScalarField],
+        v: &[E::ScalarField],
+    ) -> Result<Proof<E::G1>, Error> {
+        let zero = E::ScalarField::zero();
+        let (polys, eval) = Self::fold(v, point)?;
+        let coms = polys[1..]
+            .iter()
+            .map(|f| Self::commit_coeffs(params, f))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        transcript.absorb(&eval);
+        for com in coms.iter() {
+            transcript.absorb_nonnative(com);
+        }
+        let r = transcript.get_challenge();
+        let u = [r, -r, r.square()];
+
+        let evals = u
+            .iter()
+            .map(|u_j| {
+                polys
+                    .iter()
+                    .map(|f| DensePolynomial::from_coefficients_slice(f).evaluate(u_j))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        for evals_j in evals.iter() {
+            transcript.absorb(evals_j);
+        }
+        let q = transcript.get_challenge();
+
+        // B(X) = Σ q^i f_i(X)
+        let mut B = vec![zero; polys[0].len()];
+        for (f, q_i) in polys.iter().zip(powers_of(q, polys.len())) {
+            for (B_k, f_k) in B.iter_mut().zip(f) {
+                *B_k += q_i * f_k;
+            }
+        }
+        let w = u
+            .iter()
+            .map(|u_j| Self::open_coeffs(params, &B, *u_j))
+            .collect::<Result<Vec<_>, _>>()?;
+        for w_j in w.iter() {
+            transcript.absorb_nonnative(w_j);
+        }
+
+        Ok(Proof {
+            eval,
+            coms,
+            evals,
+            w,
+        })
+    }
+
+    fn check(
+        params: &VerifierKey<E>,
+        transcript: &mut impl Transcript<E::ScalarField>,
+        point: &[E::ScalarField],
+        cm: &E::G1,
+        proof: &Proof<E::G1>,
+    ) -> Result<(), Error> {
+        let n_polys = point.len().max(1);
+        if proof.coms.len() != n_polys - 1 {
+            return Err(Error::NotExpectedLength(proof.coms.len(), n_polys - 1));
+        }
+        if proof.evals.len() != 3 {
+            return Err(Error::NotExpectedLength(proof.evals.len(), 3));
+        }
+        if let Some(evals_j) = proof.evals.iter().find(|e| e.len() != n_polys) {
+            return Err(Error::NotExpectedLength(evals_j.len(), n_polys));
+        }
+        if proof.w.len() != 3 {
+            return Err(Error::NotExpectedLength(proof.w.len(), 3));
+        }
+
+        transcript.absorb(&proof.eval);
+        for com in proof.coms.iter() {
+            transcript.absorb_nonnative(com);
+        }
+        let r = transcript.get_challenge();
+        let u = [r, -r, r.square()];
+        for evals_j in proof.evals.iter() {
+            transcript.absorb(evals_j);
+        }
+        let q = transcript.get_challenge();
+        for w_j in proof.w.iter() {
+            transcript.absorb_nonnative(w_j);
+        }
+        let d = transcript.get_challenge();
+
+        // check the consistency of the evaluations of the folded polynomials
+        let (e_r, e_neg_r, e_r2) = (&proof.evals[0], &proof.evals[1], &proof.evals[2]);
+        if point.is_empty() && e_r[0] != proof.eval {
+            return Err(Error::CommitmentVerificationFail);
+        }
+        let two_inv = E::ScalarField::from(2_u64)
+            .inverse()
+            .ok_or(Error::CantBeZero("2".to_string()))?;
+        let two_r_inv = (r + r)
+            .inverse()
+            .ok_or(Error::CantBeZero("r".to_string()))?;
+        for (i, x_i) in point.iter().enumerate() {
+            let next = if i + 1 < point.len() {
+                e_r2[i + 1]
+            } else {
+                proof.eval
+            };
+            let even = (e_r[i] + e_neg_r[i]) * two_inv;
+            let odd = (e_r[i] - e_neg_r[i]) * two_r_inv;
+            if (E::ScalarField::one() - x_i) * even + *x_i * odd != next {
+                return Err(Error::CommitmentVerificationFail);
+            }
+        }
+
+        // check the KZG proofs of B(X) = Σ q^i f_i(X) at u_j, batched through the powers of `d`:
+        // e(Σ d^j (C_B - B(u_j) G + u_j W_j), H) == e(Σ d^j W_j, βH)
+        let q_powers = powers_of(q, n_polys);
+        let C_B = std::iter::once(cm)
+            .chain(proof.coms.iter())
+            .zip(q_powers.iter())
+            .map(|(c, q_i)| *c * q_i)
+            .sum::<E::G1>();
+        let mut lhs = E::G1::zero();
+        let mut rhs = E::G1::zero();
+        for (((u_j, evals_j), w_j), d_j) in u
+            .iter()
+            .zip(proof.evals.iter())
+            .zip(proof.w.iter())
+            .zip(powers_of(d, 3))
+        {
+            let B_u_j: E::ScalarField = evals_j
+                .iter()
+                .zip(q_powers.iter())
+                .map(|(e, q_i)| *e * q_i)
+                .sum();
+            lhs += (C_B - params.g * B_u_j + *w_j * u_j) * d_j;
+            rhs += *w_j * d_j;
+        }
+        if !E::multi_pairing([lhs, -rhs], [params.h, params.beta_h]).is_zero() {
+            return Err(Error::CommitmentVerificationFail);
+        }
+        Ok(())
+    }
+}
+
+impl<'a, E: Pairing<G1: Curve>, const H: bool> CommitmentScheme<E::G1, H> for HyperKZG<'a, E, H> {
+    type ProverParams = ProverKey<'a, E::G1>;
+    type VerifierParams = VerifierKey<E>;
+    type Proof = Proof<E::G1>;
+    type ProverChallenge = Vec<E::ScalarField>;
+    type Challenge = Vec<E::ScalarField>;
+
+    fn is_hiding() -> bool {
+        if H {
+            return true;
+        }
+        false
+    }
+
+    /// setup returns the tuple (ProverKey, VerifierKey), which are the same as the `KZG` ones. For
+    /// real world deployments the setup must be computed in the most trustless way possible,
+    /// usually through a MPC ceremony.
+    fn setup(
+        rng: impl RngCore,
+        len: usize,
+    ) -> Result<(Self::ProverParams, Self::VerifierParams), Error> {
+        if H {
+            return Err(Error::NotSupportedYet("hiding HyperKZG".to_string()));
+        }
+        KZG::<'a, E>::setup(rng, len)
+    }
+
+    fn commit(
+        params: &Self::ProverParams,
+        v: &[E::ScalarField],
+        blind: &E::ScalarField,
+    ) -> Result<E::G1, Error> {
+        if !blind.is_zero() {
+            return Err(Error::BlindingNotZero);
+        }
+        Self::commit_coeffs(params, v)
+    }
+
+    /// prove opens the multilinear extension of `v` at a point of `max(1, log2(v.len()))`
+    /// variables obtained from the transcript.
+    fn prove(
+        params: &Self::ProverParams,
+        transcript: &mut impl Transcript<E::ScalarField>,
+        cm: &E::G1,
+        v: &[E::ScalarField],
+        blind: &E::ScalarField,
+        _rng: Option<&mut dyn RngCore>,
+    ) -> Result<Self::Proof, Error> {
+        if !blind.is_zero() {
+            return Err(Error::BlindingNotZero);
+        }
+        transcript.absorb_nonnative(cm);
+        let point = transcript.get_challenges((log2(v.len()) as usize).max(1));
+        Self::open(params, transcript, &point, v)
+    }
+
+    fn prove_with_challenge(
+        params: &Self::ProverParams,
+        challenge: Self::ProverChallenge,
+        v: &[E::ScalarField],
+        blind: &E::ScalarField,
+        _rng: Option<&mut dyn RngCore>,
+    ) -> Result<Self::Proof, Error> {
+        if !blind.is_zero() {
+            return Err(Error::BlindingNotZero);
+        }
+        let mut transcript =
+            PoseidonSponge::<E::ScalarField>::new(&poseidon_canonical_config::<E::ScalarField>());
+        transcript.absorb(&challenge);
+        Self::open(params, &mut transcript, &challenge, v)
+    }
+
+    /// verify checks the opening at the point obtained from the transcript, whose number of
+    /// variables is taken from the proof.
+    fn verify(
+        params: &Self::VerifierParams,
+        transcript: &mut impl Transcript<E::ScalarField>,
+        cm: &E::G1,
+        proof: &Self::Proof,
+    ) -> Result<(), Error> {
+        transcript.absorb_nonnative(cm);
+        let point = transcript.get_challenges(proof.coms.len() + 1);
+        Self::check(params, transcript, &point, cm, proof)
+    }
+
+    fn verify_with_challenge(
+        params: &Self::VerifierParams,
+        challenge: Self::Challenge,
+        cm: &E::G1,
+        proof: &Self::Proof,
+    ) -> Result<(), Error> {
+        let mut transcript =
+            PoseidonSponge::<E::ScalarField>::new(&poseidon_canonical_config::<E::ScalarField>());
+        transcript.absorb(&challenge);
+        Self::check(params, &mut transcript, &challenge, cm, proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::{Bn254, Fr, G1Projective as G1};
+    use ark_std::{test_rng, UniformRand};
+
+    use super::*;
+    use crate::utils::mle::dense_vec_to_dense_mle;
+
+    #[test]
+    fn test_hyperkzg() -> Result<(), Error> {
+        let mut rng = &mut test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let (pk, vk) = HyperKZG::<Bn254>::setup(&mut rng, 16)?;
+
+        // also test a vector whose length is not a power of two
+        for len in [1, 2, 13, 16] {
+            let v: Vec<Fr> = std::iter::repeat_with(|| Fr::rand(rng)).take(len).collect();
+            let cm = HyperKZG::<Bn254>::commit(&pk, &v, &Fr::zero())?;
+            // the commitment is the one of the univariate polynomial with coefficients `v`
+            assert_eq!(
+                cm,
+                v.iter()
+                    .zip(pk.powers_of_g.iter())
+                    .map(|(v_k, g_k)| *g_k * v_k)
+                    .sum::<G1>()
+            );
+
+            // opening at a point obtained from the transcript
+            let mut transcript_p = PoseidonSponge::<Fr>::new(&poseidon_config);
+            let proof =
+                HyperKZG::<Bn254>::prove(&pk, &mut transcript_p, &cm, &v, &Fr::zero(), None)?;
+            let mut transcript_v = PoseidonSponge::<Fr>::new(&poseidon_config);
+            HyperKZG::<Bn254>::verify(&vk, &mut transcript_v, &cm, &proof)?;
+
+            // opening at an externally given point
+            let n_vars = (log2(len) as usize).max(1);
+            let point: Vec<Fr> = std::iter::repeat_with(|| Fr::rand(rng))
+                .take(n_vars)
+                .collect();
+            let proof =
+                HyperKZG::<Bn254>::prove_with_challenge(&pk, point.clone(), &v, &Fr::zero(), None)?;
+            assert_eq!(
+                proof.eval,
+                dense_vec_to_dense_mle(n_vars, &v).evaluate(&point)
+            );
+            HyperKZG::<Bn254>::verify_with_challenge(&vk, point.clone(), &cm, &proof)?;
+
+            // a wrong evaluation does not verify
+            let mut bad_proof = proof.clone();
+            bad_proof.eval += Fr::one();
+            assert!(
+                HyperKZG::<Bn254>::verify_with_challenge(&vk, point.clone(), &cm, &bad_proof)
+                    .is_err()
+            );
+            // nor does the proof at another point
+            let mut bad_point = point.clone();
+            bad_point[0] += Fr::one();
+            assert!(HyperKZG::<Bn254>::verify_with_challenge(&vk, bad_point, &cm, &proof).is_err());
+            // nor for another commitment
+            assert!(HyperKZG::<Bn254>::verify_with_challenge(
+                &vk,
+                point,
+                &(cm + G1::rand(rng)),
+                &proof
+            )
+            .is_err());
+        }
+        Ok(())
+    }
+}
@@ -7,8 +7,22 @@
 /// i. <s, b> computation is done in log time following a modification of the equation 3 in section
 /// 3.2 from the paper.
 /// ii. s computation is done in 2^{k+1}-2 instead of k*2^k.
+///
+/// The opening only takes the evaluation point `x` as initial challenge (which `prove` and
+/// `verify` derive from the given transcript, and which can be provided externally through
+/// `prove_with_challenge` and `verify_with_challenge`). The point `U` and the round challenges are
+/// derived through a Poseidon transcript over `C::BaseField` that absorbs `x`, the commitment `P`
+/// and the claimed evaluation `v` (so that `U` is bound to the statement, otherwise the prover
+/// could shift `P` by a multiple of `U` to open it to another value), followed by the proof's `L`
+/// and `R`, so that they can be natively recomputed in-circuit by
+/// `IPAGadget::verify_with_challenge`.
+use ark_crypto_primitives::sponge::{
+    constraints::CryptographicSpongeVar,
+    poseidon::{constraints::PoseidonSpongeVar, PoseidonSponge},
+    CryptographicSponge,
+};
 use ark_ec::AffineRepr;
-use ark_ff::{Field, PrimeField};
+use ark_ff::{BigInteger, Field, PrimeField};
 use ark_r1cs_std::{
     alloc::{AllocVar, AllocationMode},
     boolean::Boolean,
@@ -16,8 +30,9 @@ use ark_r1cs_std::{
     eq::EqGadget,
     fields::{emulated_fp::EmulatedFpVar, FieldVar},
     prelude::CurveVar,
+    R1CSVar,
 };
-use ark_relations::r1cs::{Namespace, SynthesisError};
+use ark_relations::r1cs::{ConstraintSystemRef, Namespace, SynthesisError};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::{cfg_iter, rand::RngCore, UniformRand, Zero};
 use core::{borrow::Borrow, marker::PhantomData};
@@ -25,7 +40,7 @@ use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIter
 
 use super::{pedersen::Params as PedersenParams, CommitmentScheme};
 use crate::folding::circuits::CF2;
-use crate::transcript::Transcript;
+use crate::transcript::{poseidon::poseidon_canonical_config, Transcript, TranscriptVar};
 use crate::utils::{
     powers_of,
     vec::{vec_add, vec_scalar_mul},
@@ -48,13 +63,69 @@ pub struct IPA<C: Curve, const H: bool = false> {
     _c: PhantomData<C>,
 }
 
+/// Number of bits of the challenges derived from the initial challenge (`U`'s scalar and the round
+/// challenges).
+pub const IPA_N_BITS_CHALLENGE: usize = 128;
+
+impl<C: Curve, const H: bool> IPA<C, H> {
+    /// Returns the transcript from which the challenges of the opening of `P` to `v` at `x` are
+    /// derived.
+    fn challenges_transcript(
+        x: &C::ScalarField,
+        P: &C,
+        v: &C::ScalarField,
+    ) -> PoseidonSponge<C::BaseField> {
+        let mut transcript =
+            PoseidonSponge::<C::BaseField>::new(&poseidon_canonical_config::<C::BaseField>());
+        transcript.absorb_nonnative(x);
+        transcript.absorb_point(P);
+        transcript.absorb_nonnative(v);
+        transcript
+    }
+
+    fn get_challenge(transcript: &mut PoseidonSponge<C::BaseField>) -> C::ScalarField {
+        let bits = transcript.get_challenge_nbits(IPA_N_BITS_CHALLENGE);
+        C::ScalarField::from(<C::ScalarField as PrimeField>::BigInt::from_bits_le(&bits))
+    }
+
+    /// Returns the challenges `(U, u)` of the opening of `P` to `v` at `x` with the given proof,
+    /// which are the ones that `IPAGadget::verify` expects.
+    pub fn challenges(
+        x: &C::ScalarField,
+        P: &C,
+        v: &C::ScalarField,
+        proof: &Proof<C>,
+    ) -> (C, Vec<C::ScalarField>) {
+        let k = proof.L.len().min(proof.R.len());
+        let mut transcript = Self::challenges_transcript(x, P, v);
+        let U = C::generator().mul(Self::get_challenge(&mut transcript));
+        let mut u: Vec<C::ScalarField> = vec![C::ScalarField::zero(); k];
+        for j in (0..k).rev() {
+            transcript.absorb_point(&proof.L[j]);
+            transcript.absorb_point(&proof.R[j]);
+            u[j] = Self::get_challenge(&mut transcript);
+        }
+        (U, u)
+    }
+
+    /// Same as `setup`, but deriving the generators from the given `label` (see
+    /// `PedersenParams::from_label`) instead of sampling them from an `rng`.
+    pub fn setup_from_label(
+        label: &[u8],
+        len: usize,
+    ) -> Result<(PedersenParams<C>, PedersenParams<C>), Error> {
+        let p = PedersenParams::<C>::from_label(label, len.next_power_of_two());
+        Ok((p.clone(), p))
+    }
+}
+
 /// Implements the CommitmentScheme trait for IPA
 impl<C: Curve, const H: bool> CommitmentScheme<C, H> for IPA<C, H> {
     type ProverParams = PedersenParams<C>;
     type VerifierParams = PedersenParams<C>;
     type Proof = (Proof<C>, C::ScalarField, C::ScalarField); // (proof, v=p(x), r=blinding factor)
-    type ProverChallenge = (C::ScalarField, C, Vec<C::ScalarField>);
-    type Challenge = (C::ScalarField, C, Vec<C::ScalarField>);
+    type ProverChallenge = C::ScalarField;
+    type Challenge = C::ScalarField;
 
     fn is_hiding() -> bool {
         if H {
@@ -104,6 +175,18 @@ impl<C: Curve, const H: bool> CommitmentScheme<C, H> for IPA<C, H> {
         a: &[C::ScalarField], // vector
         blind: &C::ScalarField,
         rng: Option<&mut dyn RngCore>,
+    ) -> Result<Self::Proof, Error> {
+        transcript.absorb_nonnative(P);
+        let x = transcript.get_challenge(); // challenge value at which we evaluate
+        Self::prove_with_challenge(params, x, a, blind, rng)
+    }
+
+    fn prove_with_challenge(
+        params: &Self::ProverParams,
+        challenge: Self::ProverChallenge, // challenge value at which we evaluate
+        a: &[C::ScalarField],             // vector
+        blind: &C::ScalarField,
+        rng: Option<&mut dyn RngCore>,
     ) -> Result<Self::Proof, Error> {
         if !a.len().is_power_of_two() {
             return Err(Error::NotPowerOfTwo("a".to_string(), a.len()));
@@ -133,15 +216,15 @@ impl<C: Curve, const H: bool> CommitmentScheme<C, H> for IPA<C, H> {
             r = vec![];
         }
 
-        transcript.absorb_nonnative(P);
-        let x = transcript.get_challenge(); // challenge value at which we evaluate
-        let s = transcript.get_challenge();
-        let U = C::generator().mul(s);
-
+        let x = challenge;
         let mut a = a.to_owned();
         let mut b = powers_of(x, d);
         let v = inner_prod(&a, &b)?;
 
+        let P = Self::commit(params, &a, blind)?;
+        let mut transcript = Self::challenges_transcript(&x, &P, &v);
+        let U = C::generator().mul(Self::get_challenge(&mut transcript));
+
         let mut G = params.generators.clone();
 
         let mut L: Vec<C> = vec![C::zero(); k];
@@ -164,9 +247,9 @@ impl<C: Curve, const H: bool> CommitmentScheme<C, H> for IPA<C, H> {
                 R[j] = C::msm_unchecked(&G[..m], &a[m..]) + U.mul(inner_prod(&a[m..], &b[..m])?);
             }
             // get challenge for the j-th round
-            transcript.absorb_nonnative(&L[j]);
-            transcript.absorb_nonnative(&R[j]);
-            u[j] = transcript.get_challenge();
+            transcript.absorb_point(&L[j]);
+            transcript.absorb_point(&R[j]);
+            u[j] = Self::get_challenge(&mut transcript);
 
             let uj = u[j];
             let uj_inv = u[j]
@@ -214,39 +297,15 @@ impl<C: Curve, const H: bool> CommitmentScheme<C, H> for IPA<C, H> {
         ))
     }
 
-    fn prove_with_challenge(
-        _params: &Self::ProverParams,
-        _challenge: Self::ProverChallenge,
-        _a: &[C::ScalarField], // vector
-        _blind: &C::ScalarField,
-        _rng: Option<&mut dyn RngCore>,
-    ) -> Result<Self::Proof, Error> {
-        // not supported because the prover logic computes challenges as it advances on the logic
-        Err(Error::NotSupported("IPA::prove_with_challenge".to_string()))
-    }
-
     fn verify(
         params: &Self::VerifierParams,
         transcript: &mut impl Transcript<C::ScalarField>,
         P: &C, // commitment
         proof: &Self::Proof,
     ) -> Result<(), Error> {
-        let (p, _r) = (proof.0.clone(), proof.1);
-        let k = p.L.len();
-
         transcript.absorb_nonnative(P);
         let x = transcript.get_challenge(); // challenge value at which we evaluate
-        let s = transcript.get_challenge();
-        let U = C::generator().mul(s);
-        let mut u: Vec<C::ScalarField> = vec![C::ScalarField::zero(); k];
-        for i in (0..k).rev() {
-            transcript.absorb_nonnative(&p.L[i]);
-            transcript.absorb_nonnative(&p.R[i]);
-            u[i] = transcript.get_challenge();
-        }
-        let challenge = (x, U, u);
-
-        Self::verify_with_challenge(params, challenge, P, proof)
+        Self::verify_with_challenge(params, x, P, proof)
     }
 
     fn verify_with_challenge(
@@ -256,12 +315,13 @@ impl<C: Curve, const H: bool> CommitmentScheme<C, H> for IPA<C, H> {
         proof: &Self::Proof,
     ) -> Result<(), Error> {
         let (p, v, r) = (proof.0.clone(), proof.1, proof.2);
-        let (x, U, u) = challenge;
+        let x = challenge;
 
         let k = p.L.len();
         if p.R.len() != k {
             return Err(Error::CommitmentVerificationFail);
         }
+        let (U, u) = Self::challenges(&x, P, &v, &p);
         if !H && (!r.is_zero()) {
             return Err(Error::BlindingNotZero);
         }
@@ -546,12 +606,87 @@ impl<C: Curve, const H: bool> IPAGadget<C, H> {
         // q_0 == q_1
         q_0.is_eq(&q_1)
     }
+
+    /// Same as `verify`, but deriving the challenges `U` and `u` in-circuit from the initial
+    /// challenge `x` (the evaluation point), the commitment `P`, the evaluation `v` and the proof's
+    /// `L` and `R`, matching the challenges derived natively by `IPA::prove_with_challenge` and
+    /// `IPA::verify_with_challenge`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_with_challenge<const K: usize>(
+        g: &[C::Var],                              // params.generators
+        h: &C::Var,                                // params.h
+        x: &EmulatedFpVar<C::ScalarField, CF2<C>>, // evaluation point, challenge
+        v: &EmulatedFpVar<C::ScalarField, CF2<C>>, // value at evaluation point
+        P: &C::Var,                                // commitment
+        p: &ProofVar<C>,
+        r: &EmulatedFpVar<C::ScalarField, CF2<C>>, // blinding factor
+    ) -> Result<Boolean<CF2<C>>, SynthesisError> {
+        if p.L.len() != K || p.R.len() != K {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let cs = x.cs().or(P.cs());
+
+        // absorb x, P and v as `IPA::challenges_transcript` does
+        let mut transcript =
+            PoseidonSpongeVar::<CF2<C>>::new(cs.clone(), &poseidon_canonical_config::<CF2<C>>());
+        Self::absorb_nonnative(&mut transcript, x)?;
+        transcript.absorb_point::<C, C::Var>(P)?;
+        Self::absorb_nonnative(&mut transcript, v)?;
+
+        let s_bits = transcript.get_challenge_nbits(IPA_N_BITS_CHALLENGE)?;
+        let U = C::Var::constant(C::generator()).scalar_mul_le(s_bits.iter())?;
+
+        let mut u = vec![EmulatedFpVar::<C::ScalarField, CF2<C>>::zero(); K];
+        for j in (0..K).rev() {
+            transcript.absorb_point::<C, C::Var>(&p.L[j])?;
+            transcript.absorb_point::<C, C::Var>(&p.R[j])?;
+            let u_j_bits = transcript.get_challenge_nbits(IPA_N_BITS_CHALLENGE)?;
+            u[j] = Self::challenge_from_bits(cs.clone(), &u_j_bits)?;
+        }
+        let u: [EmulatedFpVar<C::ScalarField, CF2<C>>; K] =
+            u.try_into().map_err(|_| SynthesisError::Unsatisfiable)?;
+
+        Self::verify::<K>(g, h, x, v, P, p, r, &u, &U)
+    }
+
+    /// Absorbs the emulated field element `x` in the same way as the native `absorb_nonnative`.
+    fn absorb_nonnative(
+        transcript: &mut PoseidonSpongeVar<CF2<C>>,
+        x: &EmulatedFpVar<C::ScalarField, CF2<C>>,
+    ) -> Result<(), SynthesisError> {
+        let bits_per_limb = CF2::<C>::MODULUS_BIT_SIZE as usize - 1;
+        let limbs = x.to_bits_le()?[..C::ScalarField::MODULUS_BIT_SIZE as usize]
+            .chunks(bits_per_limb)
+            .map(Boolean::le_bits_to_fp)
+            .collect::<Result<Vec<_>, _>>()?;
+        transcript.absorb(&limbs)
+    }
+
+    /// Returns the emulated field element whose little-endian bits are `bits`.
+    fn challenge_from_bits(
+        cs: ConstraintSystemRef<CF2<C>>,
+        bits: &[Boolean<CF2<C>>],
+    ) -> Result<EmulatedFpVar<C::ScalarField, CF2<C>>, SynthesisError> {
+        let c = EmulatedFpVar::<C::ScalarField, CF2<C>>::new_witness(cs, || {
+            let bits = bits
+                .iter()
+                .map(|b| b.value())
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(C::ScalarField::from(
+                <C::ScalarField as PrimeField>::BigInt::from_bits_le(&bits),
+            ))
+        })?;
+        let c_bits = c.to_bits_le()?;
+        let mut expected = bits.to_vec();
+        expected.resize(c_bits.len(), Boolean::FALSE);
+        c_bits.enforce_equal(&expected)?;
+        Ok(c)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use ark_crypto_primitives::sponge::{poseidon::PoseidonSponge, CryptographicSponge};
-    use ark_ec::PrimeGroup;
     use ark_pallas::{constraints::GVar, Fq, Fr, Projective};
     use ark_r1cs_std::eq::EqGadget;
     use ark_relations::r1cs::ConstraintSystem;
@@ -604,6 +739,109 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_ipa_setup_from_label() -> Result<(), Error> {
+        let _ = test_ipa_setup_from_label_opt::<false>()?;
+        let _ = test_ipa_setup_from_label_opt::<true>()?;
+        Ok(())
+    }
+    fn test_ipa_setup_from_label_opt<const hiding: bool>() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let d: usize = 16;
+        let label = b"sonobe-ipa-test";
+
+        // two independent setups give the same params, which can be checked against the label
+        let (params, _) = IPA::<Projective, hiding>::setup_from_label(label, d)?;
+        assert_eq!(
+            params,
+            IPA::<Projective, hiding>::setup_from_label(label, d)?.0
+        );
+        params.verify_label(label)?;
+        assert!(params.verify_label(b"another-label").is_err());
+
+        // the params are usable by the commitment scheme
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let mut transcript_p = PoseidonSponge::<Fr>::new(&poseidon_config);
+        let mut transcript_v = PoseidonSponge::<Fr>::new(&poseidon_config);
+        let a: Vec<Fr> = std::iter::repeat_with(|| Fr::rand(&mut rng))
+            .take(d)
+            .collect();
+        let r_blind: Fr = if hiding {
+            Fr::rand(&mut rng)
+        } else {
+            Fr::zero()
+        };
+        let cm = IPA::<Projective, hiding>::commit(&params, &a, &r_blind)?;
+        let proof = IPA::<Projective, hiding>::prove(
+            &params,
+            &mut transcript_p,
+            &cm,
+            &a,
+            &r_blind,
+            Some(&mut rng),
+        )?;
+        IPA::<Projective, hiding>::verify(&params, &mut transcript_v, &cm, &proof)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_ipa_with_challenge() -> Result<(), Error> {
+        let _ = test_ipa_with_challenge_opt::<false>()?;
+        let _ = test_ipa_with_challenge_opt::<true>()?;
+        Ok(())
+    }
+    fn test_ipa_with_challenge_opt<const hiding: bool>() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let d: usize = 16;
+        let (params, _) = IPA::<Projective, hiding>::setup(&mut rng, d)?;
+
+        let a: Vec<Fr> = std::iter::repeat_with(|| Fr::rand(&mut rng))
+            .take(d)
+            .collect();
+        let r_blind: Fr = if hiding {
+            Fr::rand(&mut rng)
+        } else {
+            Fr::zero()
+        };
+        let cm = IPA::<Projective, hiding>::commit(&params, &a, &r_blind)?;
+
+        // externally derived challenge, as done by the deciders
+        let challenge = Fr::rand(&mut rng);
+        let proof = IPA::<Projective, hiding>::prove_with_challenge(
+            &params,
+            challenge,
+            &a,
+            &r_blind,
+            Some(&mut rng),
+        )?;
+        IPA::<Projective, hiding>::verify_with_challenge(&params, challenge, &cm, &proof)?;
+        assert_eq!(proof.1, inner_prod(&a, &powers_of(challenge, d))?);
+
+        // the proof does not verify for another challenge
+        assert!(IPA::<Projective, hiding>::verify_with_challenge(
+            &params,
+            challenge + Fr::from(1),
+            &cm,
+            &proof
+        )
+        .is_err());
+
+        // the commitment shifted by a multiple of U can not be opened to another value with the
+        // same L and R, since U depends on the commitment and on the claimed value
+        let (U, _) = IPA::<Projective, hiding>::challenges(&challenge, &cm, &proof.1, &proof.0);
+        let wrong_v = proof.1 + Fr::from(1);
+        let shifted_cm = cm + U * (proof.1 - wrong_v);
+        let wrong_proof = (proof.0.clone(), wrong_v, proof.2);
+        assert!(IPA::<Projective, hiding>::verify_with_challenge(
+            &params,
+            challenge,
+            &shifted_cm,
+            &wrong_proof
+        )
+        .is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_ipa_gadget() -> Result<(), Error> {
         let _ = test_ipa_gadget_opt::<false>()?;
@@ -653,22 +891,15 @@ mod tests {
         let mut transcript_v = PoseidonSponge::<Fr>::new(&poseidon_config);
         transcript_v.absorb_nonnative(&cm);
         let challenge = transcript_v.get_challenge(); // challenge value at which we evaluate
-        let s = transcript_v.get_challenge();
-        let U = Projective::generator() * s;
-        let mut u: Vec<Fr> = vec![Fr::zero(); k];
-        for i in (0..k).rev() {
-            transcript_v.absorb_nonnative(&proof.0.L[i]);
-            transcript_v.absorb_nonnative(&proof.0.R[i]);
-            u[i] = transcript_v.get_challenge();
-        }
+        let (U, u) = IPA::<Projective, hiding>::challenges(&challenge, &cm, &proof.1, &proof.0);
 
         // prepare inputs
-        let gVar = Vec::<GVar>::new_constant(cs.clone(), params.generators)?;
+        let gVar = Vec::<GVar>::new_constant(cs.clone(), params.generators.clone())?;
         let hVar = GVar::new_constant(cs.clone(), params.h)?;
         let challengeVar = EmulatedFpVar::<Fr, Fq>::new_witness(cs.clone(), || Ok(challenge))?;
         let vVar = EmulatedFpVar::<Fr, Fq>::new_witness(cs.clone(), || Ok(proof.1))?;
         let cmVar = GVar::new_witness(cs.clone(), || Ok(cm))?;
-        let proofVar = ProofVar::<Projective>::new_witness(cs.clone(), || Ok(proof.0))?;
+        let proofVar = ProofVar::<Projective>::new_witness(cs.clone(), || Ok(proof.0.clone()))?;
         let r_blindVar = EmulatedFpVar::<Fr, Fq>::new_witness(cs.clone(), || Ok(r_blind))?;
         let uVar_vec = Vec::<EmulatedFpVar<Fr, Fq>>::new_witness(cs.clone(), || Ok(u))?;
         let uVar: [EmulatedFpVar<Fr, Fq>; k] = uVar_vec.try_into().map_err(|_| {
@@ -692,7 +923,42 @@ mod tests {
             &UVar,
         )?;
         v.enforce_equal(&Boolean::TRUE)?;
+
+        // the same, but deriving the challenges in-circuit from the initial challenge
+        let v = IPAGadget::<Projective, hiding>::verify_with_challenge::<k>(
+            &gVar,
+            &hVar,
+            &challengeVar,
+            &vVar,
+            &cmVar,
+            &proofVar,
+            &r_blindVar,
+        )?;
+        v.enforce_equal(&Boolean::TRUE)?;
         assert!(cs.is_satisfied()?);
+
+        // a wrong evaluation is rejected in-circuit, even when shifting the commitment by the
+        // corresponding multiple of U
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        let wrong_v = proof.1 + Fr::from(1);
+        let shifted_cm = cm + U * (proof.1 - wrong_v);
+        let gVar = Vec::<GVar>::new_constant(cs.clone(), params.generators)?;
+        let hVar = GVar::new_constant(cs.clone(), params.h)?;
+        let challengeVar = EmulatedFpVar::<Fr, Fq>::new_witness(cs.clone(), || Ok(challenge))?;
+        let vVar = EmulatedFpVar::<Fr, Fq>::new_witness(cs.clone(), || Ok(wrong_v))?;
+        let cmVar = GVar::new_witness(cs.clone(), || Ok(shifted_cm))?;
+        let proofVar = ProofVar::<Projective>::new_witness(cs.clone(), || Ok(proof.0))?;
+        let r_blindVar = EmulatedFpVar::<Fr, Fq>::new_witness(cs.clone(), || Ok(r_blind))?;
+        let v = IPAGadget::<Projective, hiding>::verify_with_challenge::<k>(
+            &gVar,
+            &hVar,
+            &challengeVar,
+            &vVar,
+            &cmVar,
+            &proofVar,
+            &r_blindVar,
+        )?;
+        assert!(!v.value()?);
         Ok(())
     }
 }
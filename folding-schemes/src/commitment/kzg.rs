@@ -5,8 +5,20 @@
 /// vectors indistinctly, and the arkworks KZG10 implementation contains all the methods under the
 /// same trait, which requires the Pairing trait, where the prover does not need access to the
 /// Pairing but only to G1.
-use ark_ec::{pairing::Pairing, CurveGroup, VariableBaseMSM};
-use ark_ff::PrimeField;
+///
+/// In hiding mode (`H=true`), the commitment is blinded with an extra generator `γG`, as
+/// `cm = <v, [β^i G]> + r⋅γG`, which keeps it additively homomorphic in both the vector and the
+/// blinding factor `r` (as needed when folding). Opening it with the constant blinding polynomial
+/// `r` would reveal `r`, so, as in arkworks' hiding KZG10, each opening samples a fresh random
+/// blinding polynomial `σ(X) = σ_0 + σ_1⋅X`, and its commitment `mask = σ(β)γG` (over the powers
+/// `[γG, γβG]`) is added to the opened commitment with a challenge `c` derived from the opening
+/// point, the evaluation and the mask. The proof then opens `cm + c⋅mask`, whose blinding
+/// polynomial is `r + c⋅σ(X)`: it carries the mask and the evaluation
+/// `random_v = r + c⋅σ(z)` of the blinding polynomial, which does not reveal `r`, while the
+/// quotient of the blinding polynomial is added to the witness `w`. No secret is kept in the
+/// `ProverKey`, so it can be shared with untrusted provers.
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_ff::{BigInteger, PrimeField};
 use ark_poly::{
     univariate::{DenseOrSparsePolynomial, DensePolynomial},
     DenseUVPolynomial, Polynomial,
@@ -17,56 +29,82 @@ use ark_poly_commit::kzg10::{
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Valid};
 use ark_std::rand::RngCore;
 use ark_std::{borrow::Cow, fmt::Debug};
-use ark_std::{One, Zero};
+use ark_std::{One, UniformRand, Zero};
 use core::marker::PhantomData;
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use sha3::{Digest, Keccak256};
 
-use super::CommitmentScheme;
+use super::{check_batch_lengths, CommitmentScheme};
 use crate::transcript::Transcript;
 use crate::utils::vec::poly_from_vec;
 use crate::{Curve, Error};
 
 /// ProverKey defines a similar struct as in ark_poly_commit::kzg10::Powers, but instead of
 /// depending on the Pairing trait it depends on the SonobeCurve trait.
+///
+/// The hiding data (`powers_of_gamma_g`) is only used and serialized when `H=true`, so the
+/// serialization of the non-hiding keys only contains `powers_of_g`.
 #[derive(Debug, Clone, Default, Eq, PartialEq)]
-pub struct ProverKey<'a, C: Curve> {
+pub struct ProverKey<'a, C: Curve, const H: bool = false> {
     /// Group elements of the form `β^i G`, for different values of `i`.
     pub powers_of_g: Cow<'a, [C::Affine]>,
+    /// Group elements `γG` and `γβG`, used in hiding mode to commit to the blinding polynomials.
+    pub powers_of_gamma_g: Vec<C::Affine>,
 }
 
-impl<'a, C: Curve> CanonicalSerialize for ProverKey<'a, C> {
+impl<'a, C: Curve, const H: bool> CanonicalSerialize for ProverKey<'a, C, H> {
     fn serialize_with_mode<W: std::io::prelude::Write>(
         &self,
         mut writer: W,
         compress: ark_serialize::Compress,
     ) -> Result<(), ark_serialize::SerializationError> {
-        self.powers_of_g.serialize_with_mode(&mut writer, compress)
+        self.powers_of_g
+            .serialize_with_mode(&mut writer, compress)?;
+        if H {
+            self.powers_of_gamma_g
+                .serialize_with_mode(&mut writer, compress)?;
+        }
+        Ok(())
     }
 
     fn serialized_size(&self, compress: ark_serialize::Compress) -> usize {
-        self.powers_of_g.serialized_size(compress)
+        let size = self.powers_of_g.serialized_size(compress);
+        if !H {
+            return size;
+        }
+        size + self.powers_of_gamma_g.serialized_size(compress)
     }
 }
 
-impl<'a, C: Curve> CanonicalDeserialize for ProverKey<'a, C> {
+impl<'a, C: Curve, const H: bool> CanonicalDeserialize for ProverKey<'a, C, H> {
     fn deserialize_with_mode<R: std::io::prelude::Read>(
-        reader: R,
+        mut reader: R,
         compress: ark_serialize::Compress,
         validate: ark_serialize::Validate,
     ) -> Result<Self, ark_serialize::SerializationError> {
-        let powers_of_g_vec = Vec::deserialize_with_mode(reader, compress, validate)?;
+        let powers_of_g_vec = Vec::deserialize_with_mode(&mut reader, compress, validate)?;
+        let powers_of_gamma_g = if H {
+            Vec::deserialize_with_mode(&mut reader, compress, validate)?
+        } else {
+            vec![]
+        };
         Ok(ProverKey {
             powers_of_g: ark_std::borrow::Cow::Owned(powers_of_g_vec),
+            powers_of_gamma_g,
         })
     }
 }
 
-impl<'a, C: Curve> Valid for ProverKey<'a, C> {
+impl<'a, C: Curve, const H: bool> Valid for ProverKey<'a, C, H> {
     fn check(&self) -> Result<(), ark_serialize::SerializationError> {
         match self.powers_of_g.clone() {
             Cow::Borrowed(powers) => powers.to_vec().check(),
             Cow::Owned(powers) => powers.check(),
+        }?;
+        if H {
+            self.powers_of_gamma_g.check()?;
         }
+        Ok(())
     }
 }
 
@@ -74,6 +112,12 @@ impl<'a, C: Curve> Valid for ProverKey<'a, C> {
 pub struct Proof<C: Curve> {
     pub eval: C::ScalarField,
     pub proof: C,
+    /// Evaluation of the blinding polynomial at the challenge, which is zero when not in hiding
+    /// mode.
+    pub random_v: C::ScalarField,
+    /// Commitment `σ(β)γG` to the fresh blinding polynomial `σ(X)` of the opening, which is zero
+    /// when not in hiding mode.
+    pub mask: C,
 }
 
 /// KZG implements the CommitmentScheme trait for the KZG commitment scheme.
@@ -84,7 +128,7 @@ pub struct KZG<'a, E: Pairing, const H: bool = false> {
 }
 
 impl<'a, E: Pairing<G1: Curve>, const H: bool> CommitmentScheme<E::G1, H> for KZG<'a, E, H> {
-    type ProverParams = ProverKey<'a, E::G1>;
+    type ProverParams = ProverKey<'a, E::G1, H>;
     type VerifierParams = VerifierKey<E>;
     type Proof = Proof<E::G1>;
     type ProverChallenge = E::ScalarField;
@@ -108,8 +152,17 @@ impl<'a, E: Pairing<G1: Curve>, const H: bool> CommitmentScheme<E::G1, H> for KZ
             KZG10::<E, DensePolynomial<E::ScalarField>>::setup(len, false, &mut rng)
                 .expect("Setup failed");
         let powers_of_g = universal_params.powers_of_g[..=len].to_vec();
-        let powers = ProverKey::<E::G1> {
+        let powers_of_gamma_g = if H {
+            vec![
+                universal_params.powers_of_gamma_g[&0],
+                universal_params.powers_of_gamma_g[&1],
+            ]
+        } else {
+            vec![]
+        };
+        let powers = ProverKey::<E::G1, H> {
             powers_of_g: ark_std::borrow::Cow::Owned(powers_of_g),
+            powers_of_gamma_g,
         };
         let vk = VerifierKey {
             g: universal_params.powers_of_g[0],
@@ -124,15 +177,16 @@ impl<'a, E: Pairing<G1: Curve>, const H: bool> CommitmentScheme<E::G1, H> for KZ
 
     /// commit implements the CommitmentScheme commit interface, adapting the implementation from
     /// https://github.com/arkworks-rs/poly-commit/tree/c724fa666e935bbba8db5a1421603bab542e15ab/poly-commit/src/kzg10/mod.rs#L178
-    /// with the main difference being the removal of the blinding factors and the no-dependency to
+    /// with the main differences being that the blinding polynomial is the constant `blind`
+    /// (instead of a random polynomial, which is sampled at each opening) and the no-dependency to
     /// the Pairing trait.
     fn commit(
         params: &Self::ProverParams,
         v: &[E::ScalarField],
-        _blind: &E::ScalarField,
+        blind: &E::ScalarField,
     ) -> Result<E::G1, Error> {
-        if !_blind.is_zero() || H {
-            return Err(Error::NotSupportedYet("hiding".to_string()));
+        if !H && (!blind.is_zero()) {
+            return Err(Error::BlindingNotZero);
         }
 
         let polynomial = poly_from_vec(v.to_vec())?;
@@ -144,35 +198,43 @@ impl<'a, E: Pairing<G1: Curve>, const H: bool> CommitmentScheme<E::G1, H> for KZ
             &params.powers_of_g[num_leading_zeros..],
             &plain_coeffs,
         );
-        Ok(commitment)
+        if !H {
+            return Ok(commitment);
+        }
+        // <v, [β^i G]> + r⋅γG
+        let blinding_poly = DensePolynomial::from_coefficients_vec(vec![*blind]);
+        Ok(commitment + commit_to_blinding_poly(params, &blinding_poly)?)
     }
 
     /// prove implements the CommitmentScheme prove interface, adapting the implementation from
     /// https://github.com/arkworks-rs/poly-commit/tree/c724fa666e935bbba8db5a1421603bab542e15ab/poly-commit/src/kzg10/mod.rs#L307
-    /// with the main difference being the removal of the blinding factors and the no-dependency to
-    /// the Pairing trait.
+    /// with the main differences being that in hiding mode the opened commitment is masked with
+    /// the commitment to a fresh blinding polynomial (see the module docs), and the no-dependency
+    /// to the Pairing trait.
+    ///
+    /// In hiding mode the `rng` is required to sample the blinding polynomial of the opening.
     fn prove(
         params: &Self::ProverParams,
         transcript: &mut impl Transcript<E::ScalarField>,
         cm: &E::G1,
         v: &[E::ScalarField],
-        _blind: &E::ScalarField,
-        _rng: Option<&mut dyn RngCore>,
+        blind: &E::ScalarField,
+        rng: Option<&mut dyn RngCore>,
     ) -> Result<Self::Proof, Error> {
         transcript.absorb_nonnative(cm);
         let challenge = transcript.get_challenge();
-        Self::prove_with_challenge(params, challenge, v, _blind, _rng)
+        Self::prove_with_challenge(params, challenge, v, blind, rng)
     }
 
     fn prove_with_challenge(
         params: &Self::ProverParams,
         challenge: Self::ProverChallenge,
         v: &[E::ScalarField],
-        _blind: &E::ScalarField,
-        _rng: Option<&mut dyn RngCore>,
+        blind: &E::ScalarField,
+        rng: Option<&mut dyn RngCore>,
     ) -> Result<Self::Proof, Error> {
-        if !_blind.is_zero() || H {
-            return Err(Error::NotSupportedYet("hiding".to_string()));
+        if !H && (!blind.is_zero()) {
+            return Err(Error::BlindingNotZero);
         }
 
         let polynomial = poly_from_vec(v.to_vec())?;
@@ -185,12 +247,7 @@ impl<'a, E: Pairing<G1: Curve>, const H: bool> CommitmentScheme<E::G1, H> for KZ
             -challenge,
             E::ScalarField::one(),
         ]);
-        let (witness_poly, remainder_poly) = DenseOrSparsePolynomial::from(&polynomial)
-            .divide_with_q_and_r(&DenseOrSparsePolynomial::from(&divisor))
-            // the panic inside `divide_with_q_and_r` should never be reached, since the divisor
-            // polynomial is constructed right before and is set to not be zero. And the `.unwrap`
-            // should not give an error.
-            .unwrap();
+        let (witness_poly, remainder_poly) = divide(&polynomial, &divisor);
 
         let eval = if remainder_poly.is_zero() {
             E::ScalarField::zero()
@@ -206,7 +263,39 @@ impl<'a, E: Pairing<G1: Curve>, const H: bool> CommitmentScheme<E::G1, H> for KZ
             &witness_coeffs,
         );
 
-        Ok(Proof { eval, proof })
+        if !H {
+            return Ok(Proof {
+                eval,
+                proof,
+                random_v: E::ScalarField::zero(),
+                mask: E::G1::zero(),
+            });
+        }
+
+        // sample the blinding polynomial σ(X) of this opening, and open cm + c⋅mask, with
+        // mask = σ(β)γG, whose blinding polynomial is ρ(X) = r + c⋅σ(X)
+        let rng = rng.ok_or(Error::MissingRandomness)?;
+        let (sigma_0, sigma_1) = (E::ScalarField::rand(rng), E::ScalarField::rand(rng));
+        let mask = commit_to_blinding_poly(
+            params,
+            &DensePolynomial::from_coefficients_vec(vec![sigma_0, sigma_1]),
+        )?;
+        let c = mask_challenge(&challenge, &eval, &mask);
+        let blinding_poly =
+            DensePolynomial::from_coefficients_vec(vec![*blind + c * sigma_0, c * sigma_1]);
+
+        // w = q(β)G + q_ρ(β)γG, where q_ρ(X) = (ρ(X) - ρ(z)) / (X - z), and the evaluation of
+        // the blinding polynomial is ρ(z)
+        let (blinding_witness_poly, _) = divide(&blinding_poly, &divisor);
+        let proof = proof + commit_to_blinding_poly(params, &blinding_witness_poly)?;
+        let random_v = blinding_poly.evaluate(&challenge);
+
+        Ok(Proof {
+            eval,
+            proof,
+            random_v,
+            mask,
+        })
     }
 
     fn verify(
@@ -226,11 +315,12 @@ impl<'a, E: Pairing<G1: Curve>, const H: bool> CommitmentScheme<E::G1, H> for KZ
         cm: &E::G1,
         proof: &Self::Proof,
     ) -> Result<(), Error> {
-        if H {
-            return Err(Error::NotSupportedYet("hiding".to_string()));
+        if !H && (!proof.random_v.is_zero() || !proof.mask.is_zero()) {
+            return Err(Error::BlindingNotZero);
         }
 
-        // verify the KZG proof using arkworks method
+        // verify the KZG proof of the masked commitment using arkworks method
+        let cm = *cm + proof.mask * mask_challenge(&challenge, &proof.eval, &proof.mask);
         let v = KZG10::<E, DensePolynomial<E::ScalarField>>::check(
             params, // vk
             &KZG10Commitment(cm.into_affine()),
@@ -238,7 +328,7 @@ impl<'a, E: Pairing<G1: Curve>, const H: bool> CommitmentScheme<E::G1, H> for KZ
             proof.eval,
             &KZG10Proof::<E> {
                 w: proof.proof.into_affine(),
-                random_v: None,
+                random_v: if H { Some(proof.random_v) } else { None },
             },
         )?;
         if !v {
@@ -246,6 +336,121 @@ impl<'a, E: Pairing<G1: Curve>, const H: bool> CommitmentScheme<E::G1, H> for KZ
         }
         Ok(())
     }
+
+    /// Verifies the openings of the commitments `cm_i` at the challenges `z_i` with a single
+    /// pairing check. Each opening satisfies `e(W_i, βH - z_i⋅H) = e(cm'_i - y_i⋅G - r_i⋅γG, H)`,
+    /// where `cm'_i = cm_i + c_i⋅mask_i` is the masked commitment, so taking a random `ρ` from the
+    /// transcript (after absorbing the commitments, the proofs, the masks, the challenges, the
+    /// evaluations and the evaluations of the blinding polynomials), all of them are implied by
+    /// `e(Σ ρ^i⋅W_i, βH) = e(Σ ρ^i⋅(cm'_i - y_i⋅G - r_i⋅γG + z_i⋅W_i), H)`.
+    ///
+    /// Compatible with the `checkMultiple` method of the KZG10 Solidity verifier, when the
+    /// transcript is a `KeccakTranscript`.
+    fn batch_verify_with_challenges(
+        params: &Self::VerifierParams,
+        transcript: &mut impl Transcript<E::ScalarField>,
+        challenges: &[Self::Challenge],
+        cms: &[E::G1],
+        proofs: &[Self::Proof],
+    ) -> Result<(), Error> {
+        check_batch_lengths(challenges.len(), cms.len(), proofs.len())?;
+        if !H
+            && proofs
+                .iter()
+                .any(|proof| !proof.random_v.is_zero() || !proof.mask.is_zero())
+        {
+            return Err(Error::BlindingNotZero);
+        }
+
+        for (cm, proof) in cms.iter().zip(proofs) {
+            transcript.absorb_nonnative(cm);
+            transcript.absorb_nonnative(&proof.proof);
+            transcript.absorb_nonnative(&proof.mask);
+        }
+        transcript.absorb(&challenges.to_vec());
+        transcript.absorb(&proofs.iter().map(|proof| proof.eval).collect::<Vec<_>>());
+        transcript.absorb(
+            &proofs
+                .iter()
+                .map(|proof| proof.random_v)
+                .collect::<Vec<_>>(),
+        );
+        let rho = transcript.get_challenge();
+
+        // accumulate Σ ρ^i⋅W_i, Σ ρ^i⋅(cm'_i + z_i⋅W_i), Σ ρ^i⋅y_i and Σ ρ^i⋅r_i
+        let mut rho_i = E::ScalarField::one();
+        let (mut w, mut c) = (E::G1::zero(), E::G1::zero());
+        let (mut y, mut r) = (E::ScalarField::zero(), E::ScalarField::zero());
+        for ((z, cm), proof) in challenges.iter().zip(cms).zip(proofs) {
+            let cm = *cm + proof.mask * mask_challenge(z, &proof.eval, &proof.mask);
+            let w_i = proof.proof * rho_i;
+            w += w_i;
+            c += cm * rho_i + w_i * z;
+            y += rho_i * proof.eval;
+            r += rho_i * proof.random_v;
+            rho_i *= rho;
+        }
+        c -= params.g * y + params.gamma_g * r;
+
+        // e(Σ ρ^i⋅(cm_i - y_i⋅G - r_i⋅γG + z_i⋅W_i), H) ⋅ e(-Σ ρ^i⋅W_i, βH) == 1
+        if !E::multi_pairing([c, -w], [params.h, params.beta_h]).is_zero() {
+            return Err(Error::CommitmentVerificationFail);
+        }
+        Ok(())
+    }
+}
+
+/// Returns the quotient and the remainder of the division of `p` by `divisor`.
+fn divide<F: PrimeField>(
+    p: &DensePolynomial<F>,
+    divisor: &DensePolynomial<F>,
+) -> (DensePolynomial<F>, DensePolynomial<F>) {
+    DenseOrSparsePolynomial::from(p)
+        .divide_with_q_and_r(&DenseOrSparsePolynomial::from(divisor))
+        // the panic inside `divide_with_q_and_r` should never be reached, since the divisor
+        // polynomial is constructed right before and is set to not be zero. And the `.unwrap`
+        // should not give an error.
+        .unwrap()
+}
+
+/// Returns the challenge `c` with which the mask of a hiding opening is added to the opened
+/// commitment, computed as `keccak256(z, y, mask)` reduced modulo the scalar field, over the
+/// 32-byte big-endian encodings of the opening point `z`, the evaluation `y` and the affine
+/// coordinates of the mask (`(0, 0)` for the point at infinity), so that it matches the
+/// `maskChallenge` method of the KZG10 Solidity verifier. Since `z` is derived after fixing the
+/// commitment, `c` binds the mask and the evaluation to it.
+fn mask_challenge<C: Curve<ScalarField: PrimeField>>(
+    z: &C::ScalarField,
+    eval: &C::ScalarField,
+    mask: &C,
+) -> C::ScalarField {
+    let (x, y) = mask.into_affine().xy().unwrap_or_default();
+    let mut hasher = Keccak256::new();
+    hasher.update(z.into_bigint().to_bytes_be());
+    hasher.update(eval.into_bigint().to_bytes_be());
+    for coordinate in [x, y] {
+        // the serialization is little-endian
+        let mut bytes = vec![];
+        coordinate
+            .serialize_uncompressed(&mut bytes)
+            .expect("serializing into a vector can not fail");
+        bytes.reverse();
+        hasher.update(bytes);
+    }
+    C::ScalarField::from_be_bytes_mod_order(&hasher.finalize())
+}
+
+/// Commits to the given (blinding) polynomial over the powers `[γβ^i G]`.
+fn commit_to_blinding_poly<C: Curve, const H: bool>(
+    params: &ProverKey<C, H>,
+    p: &DensePolynomial<C::ScalarField>,
+) -> Result<C, Error> {
+    check_degree_is_too_large(p.degree(), params.powers_of_gamma_g.len())?;
+    let (num_leading_zeros, coeffs) = skip_first_zero_coeffs_and_convert_to_bigints(p);
+    Ok(C::msm_bigint(
+        &params.powers_of_gamma_g[num_leading_zeros..],
+        &coeffs,
+    ))
 }
 
 fn check_degree_is_too_large(
@@ -284,28 +489,190 @@ fn convert_to_bigints<F: PrimeField>(p: &[F]) -> Vec<F::BigInt> {
 mod tests {
     use ark_bn254::{Bn254, Fr, G1Projective as G1};
     use ark_crypto_primitives::sponge::{poseidon::PoseidonSponge, CryptographicSponge};
-    use ark_std::{test_rng, UniformRand};
+    use ark_serialize::Compress;
+    use ark_std::test_rng;
 
     use super::*;
-    use crate::transcript::poseidon::poseidon_canonical_config;
+    use crate::transcript::{keccak::KeccakTranscript, poseidon::poseidon_canonical_config};
 
     #[test]
     fn test_kzg_commitment_scheme() -> Result<(), Error> {
+        let _ = test_kzg_commitment_scheme_opt::<false>()?;
+        let _ = test_kzg_commitment_scheme_opt::<true>()?;
+        Ok(())
+    }
+    fn test_kzg_commitment_scheme_opt<const hiding: bool>() -> Result<(), Error> {
         let mut rng = &mut test_rng();
         let poseidon_config = poseidon_canonical_config::<Fr>();
         let transcript_p = &mut PoseidonSponge::<Fr>::new(&poseidon_config);
         let transcript_v = &mut PoseidonSponge::<Fr>::new(&poseidon_config);
 
         let n = 10;
-        let (pk, vk): (ProverKey<G1>, VerifierKey<Bn254>) = KZG::<Bn254>::setup(&mut rng, n)?;
+        let (pk, vk): (ProverKey<G1, hiding>, VerifierKey<Bn254>) =
+            KZG::<Bn254, hiding>::setup(&mut rng, n)?;
 
         let v: Vec<Fr> = std::iter::repeat_with(|| Fr::rand(rng)).take(n).collect();
-        let cm = KZG::<Bn254>::commit(&pk, &v, &Fr::zero())?;
+        let r_blind: Fr = if hiding { Fr::rand(rng) } else { Fr::zero() };
+        let cm = KZG::<Bn254, hiding>::commit(&pk, &v, &r_blind)?;
 
-        let proof = KZG::<Bn254>::prove(&pk, transcript_p, &cm, &v, &Fr::zero(), None)?;
+        let proof =
+            KZG::<Bn254, hiding>::prove(&pk, transcript_p, &cm, &v, &r_blind, Some(&mut *rng))?;
 
         // verify the proof:
-        KZG::<Bn254>::verify(&vk, transcript_v, &cm, &proof)?;
+        KZG::<Bn254, hiding>::verify(&vk, transcript_v, &cm, &proof)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_kzg_batch_verify() -> Result<(), Error> {
+        let _ = test_kzg_batch_verify_opt::<false>()?;
+        let _ = test_kzg_batch_verify_opt::<true>()?;
+        Ok(())
+    }
+    fn test_kzg_batch_verify_opt<const hiding: bool>() -> Result<(), Error> {
+        let mut rng = &mut test_rng();
+        let n = 10;
+        let (pk, vk): (ProverKey<G1, hiding>, VerifierKey<Bn254>) =
+            KZG::<Bn254, hiding>::setup(&mut rng, n)?;
+
+        // open 3 different vectors at different challenges
+        let mut cms = vec![];
+        let mut challenges = vec![];
+        let mut proofs = vec![];
+        for _ in 0..3 {
+            let v: Vec<Fr> = std::iter::repeat_with(|| Fr::rand(rng)).take(n).collect();
+            let r_blind: Fr = if hiding { Fr::rand(rng) } else { Fr::zero() };
+            let challenge = Fr::rand(rng);
+            cms.push(KZG::<Bn254, hiding>::commit(&pk, &v, &r_blind)?);
+            proofs.push(KZG::<Bn254, hiding>::prove_with_challenge(
+                &pk,
+                challenge,
+                &v,
+                &r_blind,
+                Some(&mut *rng),
+            )?);
+            challenges.push(challenge);
+        }
+
+        KZG::<Bn254, hiding>::batch_verify_with_challenges(
+            &vk,
+            &mut KeccakTranscript::new(&()),
+            &challenges,
+            &cms,
+            &proofs,
+        )?;
+
+        // a single wrong evaluation makes the batched check fail
+        let mut bad_proofs = proofs.clone();
+        bad_proofs[1].eval += Fr::one();
+        assert!(KZG::<Bn254, hiding>::batch_verify_with_challenges(
+            &vk,
+            &mut KeccakTranscript::new(&()),
+            &challenges,
+            &cms,
+            &bad_proofs,
+        )
+        .is_err());
+
+        // and so does opening a commitment at a different challenge
+        let mut bad_challenges = challenges.clone();
+        bad_challenges.swap(0, 2);
+        assert!(KZG::<Bn254, hiding>::batch_verify_with_challenges(
+            &vk,
+            &mut KeccakTranscript::new(&()),
+            &bad_challenges,
+            &cms,
+            &proofs,
+        )
+        .is_err());
+
+        // the batch must contain as many challenges and proofs as commitments
+        assert!(KZG::<Bn254, hiding>::batch_verify_with_challenges(
+            &vk,
+            &mut KeccakTranscript::new(&()),
+            &challenges[..2],
+            &cms,
+            &proofs,
+        )
+        .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_kzg_hiding() -> Result<(), Error> {
+        let mut rng = &mut test_rng();
+        let n = 10;
+        let (pk, vk): (ProverKey<G1, true>, VerifierKey<Bn254>) =
+            KZG::<Bn254, true>::setup(&mut rng, n)?;
+
+        let v_1: Vec<Fr> = std::iter::repeat_with(|| Fr::rand(rng)).take(n).collect();
+        let v_2: Vec<Fr> = std::iter::repeat_with(|| Fr::rand(rng)).take(n).collect();
+        let (r_1, r_2) = (Fr::rand(rng), Fr::rand(rng));
+
+        // the blinding factor changes the commitment
+        let cm_1 = KZG::<Bn254, true>::commit(&pk, &v_1, &r_1)?;
+        assert_ne!(cm_1, KZG::<Bn254, true>::commit(&pk, &v_1, &Fr::zero())?);
+        // and the non-hiding mode does not accept blinding factors
+        let (pk_non_hiding, _) = KZG::<Bn254>::setup(&mut rng, n)?;
+        assert!(KZG::<Bn254>::commit(&pk_non_hiding, &v_1, &r_1).is_err());
+
+        // the commitment is homomorphic also in the blinding factors, as needed when folding
+        let cm_2 = KZG::<Bn254, true>::commit(&pk, &v_2, &r_2)?;
+        let c = Fr::rand(rng);
+        let v_3: Vec<Fr> = v_1.iter().zip(&v_2).map(|(a, b)| *a + c * b).collect();
+        let r_3 = r_1 + c * r_2;
+        let cm_3 = cm_1 + cm_2 * c;
+        assert_eq!(cm_3, KZG::<Bn254, true>::commit(&pk, &v_3, &r_3)?);
+
+        let challenge = Fr::rand(rng);
+        let proof =
+            KZG::<Bn254, true>::prove_with_challenge(&pk, challenge, &v_3, &r_3, Some(&mut *rng))?;
+        KZG::<Bn254, true>::verify_with_challenge(&vk, challenge, &cm_3, &proof)?;
+        // the opening does not reveal the blinding factor
+        assert_ne!(proof.random_v, r_3);
+        // and each opening uses a fresh blinding polynomial
+        let proof_2 =
+            KZG::<Bn254, true>::prove_with_challenge(&pk, challenge, &v_3, &r_3, Some(&mut *rng))?;
+        KZG::<Bn254, true>::verify_with_challenge(&vk, challenge, &cm_3, &proof_2)?;
+        assert_ne!(proof.random_v, proof_2.random_v);
+        assert_ne!(proof.mask, proof_2.mask);
+        // which is sampled from the given rng
+        assert!(
+            KZG::<Bn254, true>::prove_with_challenge(&pk, challenge, &v_3, &r_3, None).is_err()
+        );
+
+        // opening with a wrong blinding factor fails
+        let proof =
+            KZG::<Bn254, true>::prove_with_challenge(&pk, challenge, &v_3, &r_1, Some(&mut *rng))?;
+        assert!(KZG::<Bn254, true>::verify_with_challenge(&vk, challenge, &cm_3, &proof).is_err());
+        // and so does dropping the mask from the proof
+        let mut proof =
+            KZG::<Bn254, true>::prove_with_challenge(&pk, challenge, &v_3, &r_3, Some(&mut *rng))?;
+        proof.mask = G1::zero();
+        assert!(KZG::<Bn254, true>::verify_with_challenge(&vk, challenge, &cm_3, &proof).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_kzg_prover_key_serialization() -> Result<(), Error> {
+        let mut rng = &mut test_rng();
+        let n = 10;
+
+        // the serialization of the non-hiding keys only contains the powers of G
+        let (pk, _) = KZG::<Bn254>::setup(&mut rng, n)?;
+        let mut bytes = vec![];
+        pk.serialize_compressed(&mut bytes)?;
+        assert_eq!(bytes.len(), pk.powers_of_g.serialized_size(Compress::Yes));
+        assert_eq!(pk, ProverKey::<G1>::deserialize_compressed(&bytes[..])?);
+
+        let (pk, _) = KZG::<Bn254, true>::setup(&mut rng, n)?;
+        let mut bytes = vec![];
+        pk.serialize_compressed(&mut bytes)?;
+        assert_eq!(bytes.len(), pk.serialized_size(Compress::Yes));
+        assert_eq!(
+            pk,
+            ProverKey::<G1, true>::deserialize_compressed(&bytes[..])?
+        );
         Ok(())
     }
 }
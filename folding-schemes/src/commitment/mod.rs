@@ -5,9 +5,11 @@ use ark_std::rand::RngCore;
 use crate::transcript::Transcript;
 use crate::{Curve, Error};
 
+pub mod hyperkzg;
 pub mod ipa;
 pub mod kzg;
 pub mod pedersen;
+pub mod srs;
 
 /// CommitmentScheme defines the vector commitment scheme trait. Where `H` indicates if to use the
 /// commitment in hiding mode or not.
@@ -65,6 +67,49 @@ pub trait CommitmentScheme<C: Curve, const H: bool = false>: Clone + Debug {
         cm: &C,
         proof: &Self::Proof,
     ) -> Result<(), Error>;
+
+    /// verifies the openings of several commitments at their already computed challenges. By
+    /// default each opening is verified separately through `verify_with_challenge`, while the
+    /// schemes that support it (eg. KZG) override this method to check all the openings at once,
+    /// taking the coefficients of the random linear combination from the given transcript.
+    fn batch_verify_with_challenges(
+        params: &Self::VerifierParams,
+        _transcript: &mut impl Transcript<C::ScalarField>,
+        challenges: &[Self::Challenge],
+        cms: &[C],
+        proofs: &[Self::Proof],
+    ) -> Result<(), Error> {
+        check_batch_lengths(challenges.len(), cms.len(), proofs.len())?;
+        for ((challenge, cm), proof) in challenges.iter().zip(cms).zip(proofs) {
+            Self::verify_with_challenge(params, challenge.clone(), cm, proof)?;
+        }
+        Ok(())
+    }
+}
+
+/// checks that a batch of openings contains as many challenges and proofs as commitments
+pub(crate) fn check_batch_lengths(
+    n_challenges: usize,
+    n_cms: usize,
+    n_proofs: usize,
+) -> Result<(), Error> {
+    if n_challenges != n_cms {
+        return Err(Error::NotSameLength(
+            "challenges.len()".to_string(),
+            n_challenges,
+            "cms.len()".to_string(),
+            n_cms,
+        ));
+    }
+    if n_proofs != n_cms {
+        return Err(Error::NotSameLength(
+            "proofs.len()".to_string(),
+            n_proofs,
+            "cms.len()".to_string(),
+            n_cms,
+        ));
+    }
+    Ok(())
 }
 
 #[cfg(test)]
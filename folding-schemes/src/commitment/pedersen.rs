@@ -6,7 +6,10 @@ use ark_std::{marker::PhantomData, rand::RngCore, UniformRand, Zero};
 use super::CommitmentScheme;
 use crate::folding::circuits::CF2;
 use crate::transcript::Transcript;
-use crate::utils::vec::{vec_add, vec_scalar_mul};
+use crate::utils::{
+    hash_to_curve::{hash_to_curve, hash_to_curve_generators},
+    vec::{vec_add, vec_scalar_mul},
+};
 use crate::{Curve, Error};
 
 #[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
@@ -22,11 +25,40 @@ pub struct Params<C: Curve> {
     pub generators: Vec<C::Affine>,
 }
 
+impl<C: Curve> Params<C> {
+    /// Deterministically derives `len` generators (and `h`) from the given domain separation
+    /// `label` through `hash_to_curve`, so that anyone can reproduce the parameters and check that
+    /// no discrete-log relation between the generators is known.
+    pub fn from_label(label: &[u8], len: usize) -> Self {
+        Self {
+            h: hash_to_curve::<C>(label, b"h").into(),
+            generators: hash_to_curve_generators::<C>(label, len),
+        }
+    }
+
+    /// Recomputes the parameters from the given `label` and checks that they match `self`.
+    pub fn verify_label(&self, label: &[u8]) -> Result<(), Error> {
+        if *self != Self::from_label(label, self.generators.len()) {
+            return Err(Error::CommitmentParamsMismatch);
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Pedersen<C: Curve, const H: bool = false> {
     _c: PhantomData<C>,
 }
 
+impl<C: Curve, const H: bool> Pedersen<C, H> {
+    /// Same as `setup`, but deriving the generators from the given `label` (see
+    /// `Params::from_label`) instead of sampling them from an `rng`.
+    pub fn setup_from_label(label: &[u8], len: usize) -> Result<(Params<C>, Params<C>), Error> {
+        let p = Params::<C>::from_label(label, len.next_power_of_two());
+        Ok((p.clone(), p))
+    }
+}
+
 /// Implements the CommitmentScheme trait for Pedersen commitments
 impl<C: Curve, const H: bool> CommitmentScheme<C, H> for Pedersen<C, H> {
     type ProverParams = Params<C>;
@@ -242,6 +274,43 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_pedersen_setup_from_label() -> Result<(), Error> {
+        test_pedersen_setup_from_label_opt::<ark_bn254::G1Projective>()?;
+        test_pedersen_setup_from_label_opt::<ark_grumpkin::Projective>()?;
+        test_pedersen_setup_from_label_opt::<Projective>()?;
+        test_pedersen_setup_from_label_opt::<ark_vesta::Projective>()?;
+        Ok(())
+    }
+    fn test_pedersen_setup_from_label_opt<C: Curve>() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let n: usize = 10;
+        let label = b"sonobe-pedersen-test";
+
+        // two independent setups give the same params, which can be checked against the label
+        let (params, _) = Pedersen::<C>::setup_from_label(label, n)?;
+        assert_eq!(params, Pedersen::<C>::setup_from_label(label, n)?.0);
+        assert_eq!(params.generators.len(), n.next_power_of_two());
+        params.verify_label(label)?;
+        assert!(params.verify_label(b"another-label").is_err());
+        let mut wrong_params = params.clone();
+        wrong_params.generators[1] = C::Affine::rand(&mut rng);
+        assert!(wrong_params.verify_label(label).is_err());
+
+        // the params are usable by the commitment scheme
+        let poseidon_config = poseidon_canonical_config::<C::ScalarField>();
+        let mut transcript_p = PoseidonSponge::<C::ScalarField>::new(&poseidon_config);
+        let mut transcript_v = PoseidonSponge::<C::ScalarField>::new(&poseidon_config);
+        let v: Vec<C::ScalarField> = std::iter::repeat_with(|| C::ScalarField::rand(&mut rng))
+            .take(n)
+            .collect();
+        let r = C::ScalarField::rand(&mut rng);
+        let cm = Pedersen::<C, true>::commit(&params, &v, &r)?;
+        let proof = Pedersen::<C, true>::prove(&params, &mut transcript_p, &cm, &v, &r, None)?;
+        Pedersen::<C, true>::verify(&params, &mut transcript_v, &cm, &proof)?;
+        Ok(())
+    }
+
     #[test]
     fn test_pedersen_circuit() -> Result<(), Error> {
         let _ = test_pedersen_circuit_opt::<false>()?;
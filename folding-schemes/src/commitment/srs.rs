@@ -0,0 +1,518 @@
+/// Loaders of the KZG parameters from the transcripts of powers-of-tau ceremonies, to be used
+/// instead of `KZG::setup` (which samples the toxic waste from the given `rng`) in production.
+///
+/// Two formats are supported:
+/// - the `.ptau` files generated by [snarkjs](https://github.com/iden3/snarkjs) (eg. the ones
+///   from the Perpetual Powers of Tau ceremony), over the BN254 curve.
+/// - the `transcript.json` of the [Ethereum KZG
+///   ceremony](https://github.com/ethereum/kzg-ceremony-specs), whose points are decoded with the
+///   `CanonicalDeserialize` compressed encoding of the given pairing's curves (which for
+///   `ark_bls12_381` matches the ZCash encoding used by the ceremony).
+///
+/// In both cases the powers are truncated to the requested length, and it is checked that the
+/// loaded powers of G1 are consecutive powers of the secret contained in the G2 powers. The
+/// returned parameters can be then passed to the folding schemes through the `cs_pp` and `cs_vp`
+/// fields of their `PreprocessorParam`.
+///
+/// The ceremonies only contain the powers of `β`, and not the powers `[γβ^i G]` of an independent
+/// generator needed to blind the openings, so the parameters can only be loaded in non-hiding mode
+/// (`H=false`), and loading them in hiding mode returns an error.
+use ark_bn254::{Bn254, Fq, Fq2, G1Affine, G1Projective, G2Affine};
+use ark_ec::{pairing::Pairing, AffineRepr, VariableBaseMSM};
+use ark_ff::{Field, PrimeField};
+use ark_poly_commit::kzg10::VerifierKey;
+use ark_serialize::CanonicalDeserialize;
+use ark_std::{borrow::Cow, rand::RngCore, UniformRand, Zero};
+use num_bigint::BigUint;
+use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom};
+
+use super::kzg::ProverKey;
+use crate::{utils::hash_to_curve::hash_to_curve, Curve, Error};
+
+/// ptau section containing the header (field size, modulus and power of the ceremony)
+const PTAU_SECTION_HEADER: u32 = 1;
+/// ptau section containing the powers of tau in G1
+const PTAU_SECTION_TAU_G1: u32 = 2;
+/// ptau section containing the powers of tau in G2
+const PTAU_SECTION_TAU_G2: u32 = 3;
+
+/// Domain separation tag used to derive the generator `γG` of the loaded verifier keys
+pub const KZG_GAMMA_G_DST: &[u8] = b"sonobe-kzg-gamma-g";
+
+/// Returns the KZG parameters for vectors of length up to `len`, loaded from a snarkjs `.ptau`
+/// file over BN254.
+///
+/// The `rng` is only used for the random linear combination of the pairing check of the powers,
+/// so the returned parameters do not depend on it.
+pub fn kzg_params_from_ptau<R: Read + Seek, const H: bool>(
+    mut reader: R,
+    len: usize,
+    rng: impl RngCore,
+) -> Result<(ProverKey<'static, G1Projective, H>, VerifierKey<Bn254>), Error> {
+    check_non_hiding::<H>()?;
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    if magic != *b"ptau" {
+        return Err(Error::InvalidSRS("not a ptau file".to_string()));
+    }
+    let _version = read_u32(&mut reader)?;
+    let n_sections = read_u32(&mut reader)?;
+
+    // section type -> (position, size)
+    let mut sections = HashMap::new();
+    for _ in 0..n_sections {
+        let section_type = read_u32(&mut reader)?;
+        let size = read_u64(&mut reader)?;
+        let position = reader.stream_position()?;
+        sections.insert(section_type, (position, size));
+        reader.seek(SeekFrom::Current(size as i64))?;
+    }
+    let section = |section_type: u32| {
+        sections
+            .get(&section_type)
+            .copied()
+            .ok_or(Error::InvalidSRS(format!(
+                "missing section {} in the ptau file",
+                section_type
+            )))
+    };
+
+    // header
+    reader.seek(SeekFrom::Start(section(PTAU_SECTION_HEADER)?.0))?;
+    let n8 = read_u32(&mut reader)? as usize;
+    let mut q = vec![0u8; n8];
+    reader.read_exact(&mut q)?;
+    if BigUint::from_bytes_le(&q) != BigUint::from(Fq::MODULUS) {
+        return Err(Error::InvalidSRS(
+            "the ptau file is not over the BN254 curve".to_string(),
+        ));
+    }
+    let power = read_u32(&mut reader)?;
+
+    let len = len.next_power_of_two();
+    // the file contains 2^(power+1) - 1 powers of tau in G1
+    if (len + 1) as u64 > (1u64 << (power + 1)) - 1 {
+        return Err(Error::InvalidSRS(format!(
+            "the ptau file of power {} does not contain enough powers for length {}",
+            power, len
+        )));
+    }
+
+    // the field elements are stored in Montgomery form, ie. as `x⋅R` with `R = 2^(8⋅n8)`
+    let r_inv = Fq::from(2u64)
+        .pow([8 * n8 as u64])
+        .inverse()
+        .ok_or(Error::Other("error on computing inverse".to_string()))?;
+    let read_fq = |reader: &mut R| -> Result<Fq, Error> {
+        let mut bytes = vec![0u8; n8];
+        reader.read_exact(&mut bytes)?;
+        Ok(Fq::from_le_bytes_mod_order(&bytes) * r_inv)
+    };
+
+    let (position, size) = section(PTAU_SECTION_TAU_G1)?;
+    if size < ((len + 1) * 2 * n8) as u64 {
+        return Err(Error::InvalidSRS(
+            "the G1 section of the ptau file is too short".to_string(),
+        ));
+    }
+    reader.seek(SeekFrom::Start(position))?;
+    let powers_of_g = (0..=len)
+        .map(|_| {
+            let (x, y) = (read_fq(&mut reader)?, read_fq(&mut reader)?);
+            let p = if x.is_zero() && y.is_zero() {
+                G1Affine::identity()
+            } else {
+                G1Affine::new_unchecked(x, y)
+            };
+            if !p.is_on_curve() || !p.is_in_correct_subgroup_assuming_on_curve() {
+                return Err(Error::InvalidSRS("invalid G1 point".to_string()));
+            }
+            Ok(p)
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let (position, size) = section(PTAU_SECTION_TAU_G2)?;
+    if size < (2 * 4 * n8) as u64 {
+        return Err(Error::InvalidSRS(
+            "the G2 section of the ptau file is too short".to_string(),
+        ));
+    }
+    reader.seek(SeekFrom::Start(position))?;
+    let powers_of_h = (0..2)
+        .map(|_| {
+            let x = Fq2::new(read_fq(&mut reader)?, read_fq(&mut reader)?);
+            let y = Fq2::new(read_fq(&mut reader)?, read_fq(&mut reader)?);
+            let p = G2Affine::new_unchecked(x, y);
+            if !p.is_on_curve() || !p.is_in_correct_subgroup_assuming_on_curve() {
+                return Err(Error::InvalidSRS("invalid G2 point".to_string()));
+            }
+            Ok(p)
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    kzg_params_from_powers::<Bn254, H>(powers_of_g, &powers_of_h, len, rng)
+}
+
+/// Returns the KZG parameters for vectors of length up to `len`, loaded from the JSON transcript of
+/// the Ethereum KZG ceremony. From the sub-transcripts of the ceremony, the smallest one containing
+/// enough powers is used.
+///
+/// The `rng` is only used for the random linear combination of the pairing check of the powers,
+/// so the returned parameters do not depend on it.
+pub fn kzg_params_from_ethereum_ceremony<E: Pairing<G1: Curve>, const H: bool>(
+    transcript: &str,
+    len: usize,
+    rng: impl RngCore,
+) -> Result<(ProverKey<'static, E::G1, H>, VerifierKey<E>), Error> {
+    check_non_hiding::<H>()?;
+    let transcript: serde_json::Value =
+        serde_json::from_str(transcript).map_err(|e| Error::JSONSerdeError(e.to_string()))?;
+    let sub_transcripts = transcript["transcripts"]
+        .as_array()
+        .ok_or(Error::InvalidSRS("missing transcripts".to_string()))?;
+
+    let len = len.next_power_of_two();
+    let powers_of_tau = sub_transcripts
+        .iter()
+        .map(|t| &t["powersOfTau"])
+        .filter_map(|p| Some((p["G1Powers"].as_array()?, p["G2Powers"].as_array()?)))
+        .filter(|(g1_powers, _)| g1_powers.len() > len)
+        .min_by_key(|(g1_powers, _)| g1_powers.len());
+    let (g1_powers, g2_powers) = powers_of_tau.ok_or(Error::InvalidSRS(format!(
+        "the transcript does not contain enough powers for length {}",
+        len
+    )))?;
+    if g2_powers.len() < 2 {
+        return Err(Error::InvalidSRS(
+            "the transcript does not contain enough powers of G2".to_string(),
+        ));
+    }
+
+    let powers_of_g = g1_powers[..=len]
+        .iter()
+        .map(decode_hex_point::<E::G1Affine>)
+        .collect::<Result<Vec<_>, Error>>()?;
+    let powers_of_h = g2_powers[..2]
+        .iter()
+        .map(decode_hex_point::<E::G2Affine>)
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    kzg_params_from_powers::<E, H>(powers_of_g, &powers_of_h, len, rng)
+}
+
+/// Returns an error if the parameters are requested in hiding mode, which the ceremonies can not
+/// provide (see the module documentation).
+fn check_non_hiding<const H: bool>() -> Result<(), Error> {
+    if H {
+        return Err(Error::NotSupported(
+            "loading hiding KZG parameters from a powers-of-tau ceremony".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Builds the non-hiding KZG parameters from the powers `[β^i G]` and `[H, βH]`, checking that the
+/// powers of G1 are consecutive powers of the same `β` as the one in `βH`.
+///
+/// The ceremonies do not provide the generator `γG` of the verifier key, so it is derived through
+/// `hash_to_curve` from [`KZG_GAMMA_G_DST`]. This way nobody knows its discrete log with respect to
+/// `G`, and every party loading the same ceremony obtains the same parameters.
+fn kzg_params_from_powers<E: Pairing<G1: Curve>, const H: bool>(
+    powers_of_g: Vec<E::G1Affine>,
+    powers_of_h: &[E::G2Affine],
+    len: usize,
+    mut rng: impl RngCore,
+) -> Result<(ProverKey<'static, E::G1, H>, VerifierKey<E>), Error> {
+    if powers_of_g.len() != len + 1 || powers_of_h.len() < 2 {
+        return Err(Error::InvalidSRS("not enough powers".to_string()));
+    }
+    let (g, h, beta_h) = (powers_of_g[0], powers_of_h[0], powers_of_h[1]);
+    if g.is_zero() || h.is_zero() {
+        return Err(Error::InvalidSRS(
+            "the generators can not be zero".to_string(),
+        ));
+    }
+
+    // check that e(β^{i+1} G, H) == e(β^i G, βH) for all i, batched through a random linear
+    // combination: e(<ρ, [β^{i+1} G]>, H) == e(<ρ, [β^i G]>, βH)
+    let rho: Vec<E::ScalarField> = std::iter::repeat_with(|| E::ScalarField::rand(&mut rng))
+        .take(len)
+        .collect();
+    let lhs = E::G1::msm_unchecked(&powers_of_g[1..], &rho);
+    let rhs = E::G1::msm_unchecked(&powers_of_g[..len], &rho);
+    if !E::multi_pairing([lhs, -rhs], [h, beta_h]).is_zero() {
+        return Err(Error::InvalidSRS(
+            "the powers of G1 are not consecutive powers of the secret of G2".to_string(),
+        ));
+    }
+
+    let gamma_g = hash_to_curve::<E::G1>(KZG_GAMMA_G_DST, b"gamma_g");
+    let pk = ProverKey::<E::G1, H> {
+        powers_of_g: Cow::Owned(powers_of_g),
+        powers_of_gamma_g: vec![],
+    };
+    let vk = VerifierKey {
+        g,
+        gamma_g,
+        h,
+        beta_h,
+        prepared_h: h.into(),
+        prepared_beta_h: beta_h.into(),
+    };
+    Ok((pk, vk))
+}
+
+fn decode_hex_point<P: CanonicalDeserialize>(v: &serde_json::Value) -> Result<P, Error> {
+    let s = v.as_str().ok_or(Error::InvalidSRS(
+        "the points must be hex strings".to_string(),
+    ))?;
+    let s = s.strip_prefix("0x").unwrap_or(s);
+    if s.len() % 2 != 0 {
+        return Err(Error::InvalidSRS(format!("invalid hex string {}", s)));
+    }
+    let bytes = (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16))
+        .collect::<Result<Vec<u8>, _>>()
+        .map_err(|e| Error::InvalidSRS(e.to_string()))?;
+    Ok(P::deserialize_compressed(&bytes[..])?)
+}
+
+fn read_u32(reader: &mut impl Read) -> Result<u32, Error> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64(reader: &mut impl Read) -> Result<u64, Error> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use ark_bn254::Fr;
+    use ark_crypto_primitives::sponge::{poseidon::PoseidonSponge, CryptographicSponge};
+    use ark_ff::BigInteger;
+    use ark_grumpkin::Projective as Projective2;
+    use ark_poly::univariate::DensePolynomial;
+    use ark_poly_commit::kzg10::{UniversalParams, KZG10};
+    use ark_serialize::CanonicalSerialize;
+    use ark_std::test_rng;
+    use std::fmt::Write;
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::commitment::{kzg::KZG, pedersen::Pedersen, CommitmentScheme};
+    use crate::folding::nova::{Nova, PreprocessorParam};
+    use crate::frontend::{utils::CubicFCircuit, FCircuit};
+    use crate::transcript::poseidon::poseidon_canonical_config;
+    use crate::FoldingScheme;
+
+    fn write_fq(buf: &mut Vec<u8>, x: Fq) {
+        // ptau files store the field elements in Montgomery form
+        buf.extend((x * Fq::from(2u64).pow([256])).into_bigint().to_bytes_le());
+    }
+
+    fn write_section(buf: &mut Vec<u8>, section_type: u32, data: &[u8]) {
+        buf.extend(section_type.to_le_bytes());
+        buf.extend((data.len() as u64).to_le_bytes());
+        buf.extend(data);
+    }
+
+    /// Returns a ptau file of the given power, containing only the sections used by the loader,
+    /// with the given powers.
+    fn ptau_from_powers(power: u32, powers_of_g: &[G1Affine], powers_of_h: &[G2Affine]) -> Vec<u8> {
+        let mut header = vec![];
+        header.extend(32u32.to_le_bytes());
+        header.extend(Fq::MODULUS.to_bytes_le());
+        header.extend(power.to_le_bytes());
+        header.extend(power.to_le_bytes()); // ceremony power
+        let mut tau_g1 = vec![];
+        for p in powers_of_g {
+            write_fq(&mut tau_g1, p.x);
+            write_fq(&mut tau_g1, p.y);
+        }
+        let mut tau_g2 = vec![];
+        for p in powers_of_h {
+            for c in [p.x.c0, p.x.c1, p.y.c0, p.y.c1] {
+                write_fq(&mut tau_g2, c);
+            }
+        }
+
+        let mut ptau = b"ptau".to_vec();
+        ptau.extend(1u32.to_le_bytes()); // version
+        ptau.extend(3u32.to_le_bytes()); // number of sections
+                                         // the sections of a ptau file are not necessarily sorted
+        write_section(&mut ptau, PTAU_SECTION_TAU_G2, &tau_g2);
+        write_section(&mut ptau, PTAU_SECTION_HEADER, &header);
+        write_section(&mut ptau, PTAU_SECTION_TAU_G1, &tau_g1);
+        ptau
+    }
+
+    fn to_hex<P: CanonicalSerialize>(p: &P) -> Result<String, Error> {
+        let mut bytes = vec![];
+        p.serialize_compressed(&mut bytes)?;
+        Ok(bytes.iter().fold(String::from("0x"), |mut acc, b| {
+            let _ = write!(acc, "{:02x}", b);
+            acc
+        }))
+    }
+
+    /// Returns a transcript in the format of the Ethereum KZG ceremony, with a sub-transcript for
+    /// each of the given numbers of powers.
+    fn ethereum_ceremony_from_powers(
+        srs: &UniversalParams<Bn254>,
+        num_powers: &[usize],
+    ) -> Result<String, Error> {
+        let mut sub_transcripts = vec![];
+        for n in num_powers {
+            let g1_powers = srs.powers_of_g[..*n]
+                .iter()
+                .map(to_hex)
+                .collect::<Result<Vec<_>, Error>>()?;
+            let g2_powers = vec![to_hex(&srs.h)?, to_hex(&srs.beta_h)?];
+            sub_transcripts.push(serde_json::json!({
+                "numG1Powers": n,
+                "numG2Powers": 2,
+                "powersOfTau": { "G1Powers": g1_powers, "G2Powers": g2_powers },
+            }));
+        }
+        Ok(serde_json::json!({ "transcripts": sub_transcripts }).to_string())
+    }
+
+    fn test_kzg_params(
+        pk: &ProverKey<G1Projective>,
+        vk: &VerifierKey<Bn254>,
+        n: usize,
+    ) -> Result<(), Error> {
+        let mut rng = test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let transcript_p = &mut PoseidonSponge::<Fr>::new(&poseidon_config);
+        let transcript_v = &mut PoseidonSponge::<Fr>::new(&poseidon_config);
+
+        let v: Vec<Fr> = std::iter::repeat_with(|| Fr::rand(&mut rng))
+            .take(n)
+            .collect();
+        let cm = KZG::<Bn254>::commit(pk, &v, &Fr::zero())?;
+        let proof = KZG::<Bn254>::prove(pk, transcript_p, &cm, &v, &Fr::zero(), None)?;
+        KZG::<Bn254>::verify(vk, transcript_v, &cm, &proof)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_kzg_params_from_ptau() -> Result<(), Error> {
+        let mut rng = test_rng();
+        let n = 10;
+        let srs = KZG10::<Bn254, DensePolynomial<Fr>>::setup(16, false, &mut rng)?;
+        let powers_of_h = [srs.h, srs.beta_h];
+        let ptau = ptau_from_powers(4, &srs.powers_of_g, &powers_of_h);
+
+        let (pk, vk) = kzg_params_from_ptau::<_, false>(Cursor::new(&ptau), n, &mut rng)?;
+        // the powers are truncated to the next power of two of the length
+        assert_eq!(pk.powers_of_g.to_vec(), srs.powers_of_g[..=16].to_vec());
+        assert_eq!((vk.h, vk.beta_h), (srs.h, srs.beta_h));
+        test_kzg_params(&pk, &vk, n)?;
+
+        // the parameters do not depend on the rng, so that loading the same file twice results in
+        // the same keys (including the generator `γG`)
+        let (pk2, vk2) = kzg_params_from_ptau::<_, false>(Cursor::new(&ptau), n, &mut rng)?;
+        assert_eq!(pk2, pk);
+        assert_eq!(
+            (vk.g, vk.gamma_g, vk.h, vk.beta_h),
+            (vk2.g, vk2.gamma_g, vk2.h, vk2.beta_h)
+        );
+
+        // the ceremony can not provide hiding parameters, whose openings would otherwise reveal the
+        // blinding factor of the commitments
+        assert!(matches!(
+            kzg_params_from_ptau::<_, true>(Cursor::new(&ptau), n, &mut rng),
+            Err(Error::NotSupported(_))
+        ));
+
+        // the file does not contain enough powers
+        assert!(kzg_params_from_ptau::<_, false>(Cursor::new(&ptau), 17, &mut rng).is_err());
+
+        // the powers of G1 are not consecutive powers of tau
+        let mut powers_of_g = srs.powers_of_g.clone();
+        powers_of_g[3] = G1Affine::rand(&mut rng);
+        let ptau = ptau_from_powers(4, &powers_of_g, &powers_of_h);
+        assert!(kzg_params_from_ptau::<_, false>(Cursor::new(&ptau), n, &mut rng).is_err());
+
+        // not a ptau file
+        assert!(kzg_params_from_ptau::<_, false>(Cursor::new(&ptau[1..]), n, &mut rng).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_kzg_params_from_ethereum_ceremony() -> Result<(), Error> {
+        let mut rng = test_rng();
+        let srs = KZG10::<Bn254, DensePolynomial<Fr>>::setup(16, false, &mut rng)?;
+        let transcript = ethereum_ceremony_from_powers(&srs, &[17, 9])?;
+
+        // uses the smallest sub-transcript with enough powers
+        for (n, num_powers) in [(5, 9), (10, 17)] {
+            let (pk, vk) =
+                kzg_params_from_ethereum_ceremony::<Bn254, false>(&transcript, n, &mut rng)?;
+            assert_eq!(pk.powers_of_g.len(), num_powers);
+            test_kzg_params(&pk, &vk, n)?;
+        }
+        assert!(
+            kzg_params_from_ethereum_ceremony::<Bn254, false>(&transcript, 17, &mut rng).is_err()
+        );
+        assert!(matches!(
+            kzg_params_from_ethereum_ceremony::<Bn254, true>(&transcript, 10, &mut rng),
+            Err(Error::NotSupported(_))
+        ));
+
+        // the powers of G1 are not consecutive powers of tau
+        let mut srs = srs;
+        srs.powers_of_g[3] = G1Affine::rand(&mut rng);
+        let transcript = ethereum_ceremony_from_powers(&srs, &[17])?;
+        assert!(
+            kzg_params_from_ethereum_ceremony::<Bn254, false>(&transcript, 10, &mut rng).is_err()
+        );
+        Ok(())
+    }
+
+    // Runs Nova using the KZG parameters loaded from a ptau file
+    #[test]
+    fn test_nova_with_ptau_params() -> Result<(), Error> {
+        type N = Nova<
+            G1Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            KZG<'static, Bn254>,
+            Pedersen<Projective2>,
+            false,
+        >;
+        let mut rng = test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+
+        // get the length of the KZG parameters needed for the circuit
+        let mut prep_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        let len = N::preprocess(&mut rng, &prep_param)?
+            .0
+            .cs_pp
+            .powers_of_g
+            .len()
+            - 1;
+
+        let srs = KZG10::<Bn254, DensePolynomial<Fr>>::setup(len, false, &mut rng)?;
+        let ptau = ptau_from_powers(len.trailing_zeros(), &srs.powers_of_g, &[srs.h, srs.beta_h]);
+        let (cs_pp, cs_vp) = kzg_params_from_ptau(Cursor::new(&ptau), len, &mut rng)?;
+        prep_param.cs_pp = Some(cs_pp);
+        prep_param.cs_vp = Some(cs_vp);
+
+        let nova_params = N::preprocess(&mut rng, &prep_param)?;
+        let mut nova = N::init(&nova_params, F_circuit, vec![Fr::from(3_u32)])?;
+        for _ in 0..2 {
+            nova.prove_step(&mut rng, (), None)?;
+        }
+        N::verify(nova_params.1, nova.ivc_proof())?;
+        Ok(())
+    }
+}
@@ -238,6 +238,42 @@ impl<C: Curve> NIFSFullGadget<C> {
         })
     }
 
+    /// Folds two running instances, ie. unlike [`Self::fold_committed_instance`], it does not
+    /// assume that `ci2.cmE=0` and `ci2.u=1`. This is used when merging the CycleFold running
+    /// instances of two PCD nodes (see [`crate::folding::nova::pcd`]).
+    pub fn fold_running_committed_instances(
+        r_bits: Vec<Boolean<CF2<C>>>,
+        cmT: C::Var,
+        ci1: CycleFoldCommittedInstanceVar<C>,
+        ci2: CycleFoldCommittedInstanceVar<C>,
+    ) -> Result<CycleFoldCommittedInstanceVar<C>, SynthesisError> {
+        // r_nonnat is equal to r_bits just that in a different format
+        let r_nonnat = {
+            let mut bits = r_bits.clone();
+            bits.resize(CF1::<C>::MODULUS_BIT_SIZE as usize, Boolean::FALSE);
+            NonNativeUintVar::from(&bits)
+        };
+        Ok(CycleFoldCommittedInstanceVar {
+            // ci1.cmE + r * cmT + r^2 * ci2.cmE = (ci2.cmE * r + cmT) * r + ci1.cmE
+            cmE: (ci2.cmE.scalar_mul_le(r_bits.iter())? + cmT).scalar_mul_le(r_bits.iter())?
+                + ci1.cmE,
+            cmW: ci1.cmW + ci2.cmW.scalar_mul_le(r_bits.iter())?,
+            u: ci1
+                .u
+                .add_no_align(&r_nonnat.mul_no_align(&ci2.u)?)?
+                .modulo::<CF1<C>>()?,
+            x: ci1
+                .x
+                .iter()
+                .zip(ci2.x)
+                .map(|(a, b)| {
+                    a.add_no_align(&r_nonnat.mul_no_align(&b)?)?
+                        .modulo::<CF1<C>>()
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+        })
+    }
+
     pub fn verify(
         // assumes that r_bits is equal to r_nonnat just that in a different format
         r_bits: Vec<Boolean<CF2<C>>>,
@@ -688,6 +724,61 @@ pub mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_nifs_full_gadget_running_instances() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let mut transcript_v = PoseidonSponge::<Fr>::new(&poseidon_config);
+        let pp_hash = Fr::rand(&mut rng);
+
+        // prepare two running committed instances to test in-circuit
+        let ci: Vec<CommittedInstance<Projective>> = (0..2)
+            .into_iter()
+            .map(|_| CommittedInstance::<Projective> {
+                cmE: Projective::rand(&mut rng),
+                u: Fr::rand(&mut rng),
+                cmW: Projective::rand(&mut rng),
+                x: vec![Fr::rand(&mut rng); 1],
+            })
+            .collect();
+        let (ci1, ci2) = (ci[0].clone(), ci[1].clone());
+
+        let cmT = Projective::rand(&mut rng); // random only for testing
+        let (ci3, r_bits) = NIFS::<Projective, Pedersen<Projective>, PoseidonSponge<Fr>>::verify(
+            &mut transcript_v,
+            pp_hash,
+            &ci1,
+            &ci2,
+            &cmT,
+        )?;
+
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        let r_bitsVar = Vec::<Boolean<Fq>>::new_witness(cs.clone(), || Ok(r_bits))?;
+        let ci1Var = CycleFoldCommittedInstanceVar::<Projective>::new_witness(cs.clone(), || {
+            Ok(ci1.clone())
+        })?;
+        let ci2Var = CycleFoldCommittedInstanceVar::<Projective>::new_witness(cs.clone(), || {
+            Ok(ci2.clone())
+        })?;
+        let ci3Var = CycleFoldCommittedInstanceVar::<Projective>::new_witness(cs.clone(), || {
+            Ok(ci3.clone())
+        })?;
+        let cmTVar = GVar::new_witness(cs.clone(), || Ok(cmT))?;
+
+        let ci = NIFSFullGadget::<Projective>::fold_running_committed_instances(
+            r_bitsVar, cmTVar, ci1Var, ci2Var,
+        )?;
+        ci.cmE.enforce_equal(&ci3Var.cmE)?;
+        ci.u.enforce_equal_unaligned(&ci3Var.u)?;
+        ci.cmW.enforce_equal(&ci3Var.cmW)?;
+        for (x, y) in ci.x.iter().zip(ci3Var.x.iter()) {
+            x.enforce_equal_unaligned(y)?;
+        }
+        assert!(cs.is_satisfied()?);
+        Ok(())
+    }
+
     #[test]
     fn test_cyclefold_challenge_gadget() -> Result<(), Error> {
         let mut rng = ark_std::test_rng();
@@ -38,15 +38,12 @@ impl<F: PrimeField> CCS<F> {
     {
         let (w, x) = self.split_z(z);
 
-        // if the commitment scheme is set to be hiding, set the random blinding parameter
-        let r_w = if CS::is_hiding() {
-            F::rand(rng)
-        } else {
-            F::zero()
-        };
-        let C = CS::commit(cs_params, &w, &r_w)?;
-
-        Ok((CCCS::<C> { C, x }, Witness::<F> { w, r_w }))
+        // if the commitment scheme is set to be hiding, the witness is
+        // created with a random blinding factor
+        let W = Witness::<F>::new::<H>(w, &mut *rng);
+        let C = CS::commit(cs_params, &W.w, &W.r_w)?;
+
+        Ok((CCCS::<C> { C, x }, W))
     }
 
     /// Computes q(x) = \sum^q c_i * \prod_{j \in S_i} ( \sum_{y \in {0,1}^s'} M_j(x, y) * z(y) )
@@ -138,6 +135,10 @@ impl<C: Curve> CommittedInstanceOps<C> for CCCS<C> {
         vec![self.C]
     }
 
+    fn get_public_inputs(&self) -> &[CF1<C>] {
+        &self.x
+    }
+
     fn is_incoming(&self) -> bool {
         true
     }
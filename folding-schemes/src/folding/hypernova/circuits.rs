@@ -1,9 +1,5 @@
 /// Implementation of [HyperNova](https://eprint.iacr.org/2023/573.pdf) circuits
-use ark_crypto_primitives::sponge::{
-    constraints::{AbsorbGadget, CryptographicSpongeVar},
-    poseidon::{constraints::PoseidonSpongeVar, PoseidonConfig, PoseidonSponge},
-    CryptographicSponge,
-};
+use ark_crypto_primitives::sponge::{constraints::AbsorbGadget, CryptographicSponge};
 use ark_ff::PrimeField;
 use ark_r1cs_std::{
     alloc::{AllocVar, AllocationMode},
@@ -47,7 +43,7 @@ use crate::folding::{
     traits::{CommittedInstanceVarOps, Dummy},
 };
 use crate::frontend::FCircuit;
-use crate::transcript::{AbsorbNonNativeGadget, TranscriptVar};
+use crate::transcript::{AbsorbNonNativeGadget, Transcript, TranscriptVar};
 use crate::utils::virtual_polynomial::VPAuxInfo;
 use crate::{Curve, Error};
 
@@ -463,15 +459,20 @@ fn compute_c_gadget<F: PrimeField>(
 /// For multi-instance folding, one needs to specify the const generics below:
 /// * `MU` - the number of LCCCS instances to be folded
 /// * `NU` - the number of CCCS instances to be folded
+///
+/// The sponge `S` is used for the hashes and the transcript of the IVC, and `T` is its in-circuit
+/// counterpart.
 #[derive(Debug, Clone)]
 pub struct AugmentedFCircuit<
     C1: Curve,
     C2: Curve,
     FC: FCircuit<CF1<C1>>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
     const MU: usize,
     const NU: usize,
 > {
-    pub(super) poseidon_config: PoseidonConfig<CF1<C1>>,
+    pub(super) poseidon_config: S::Config,
     pub(super) ccs: CCS<C1::ScalarField>, // CCS of the AugmentedFCircuit
     pub(super) pp_hash: Option<CF1<C1>>,
     pub(super) i: Option<CF1<C1>>,
@@ -491,16 +492,20 @@ pub struct AugmentedFCircuit<
     pub(super) cf_u_i_cmW: Option<C2>, // input, cf_u_i.cmW
     pub(super) cf_U_i: Option<CycleFoldCommittedInstance<C2>>, // input, RelaxedR1CS CycleFold instance
     pub(super) cf_cmT: Option<C2>,
+    pub(super) _s: PhantomData<S>,
+    pub(super) _t: PhantomData<T>,
 }
 
-impl<C1, C2, FC, const MU: usize, const NU: usize> AugmentedFCircuit<C1, C2, FC, MU, NU>
+impl<C1, C2, FC, S, T, const MU: usize, const NU: usize> AugmentedFCircuit<C1, C2, FC, S, T, MU, NU>
 where
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
     C2: Curve,
     FC: FCircuit<CF1<C1>>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
 {
     pub fn default(
-        poseidon_config: &PoseidonConfig<CF1<C1>>,
+        poseidon_config: &S::Config,
         F_circuit: FC,
         ccs: CCS<C1::ScalarField>,
     ) -> Result<Self, Error> {
@@ -526,11 +531,13 @@ where
             cf_u_i_cmW: None,
             cf_U_i: None,
             cf_cmT: None,
+            _s: PhantomData,
+            _t: PhantomData,
         })
     }
 
     pub fn empty(
-        poseidon_config: &PoseidonConfig<CF1<C1>>,
+        poseidon_config: &S::Config,
         F: FC, // FCircuit
         ccs: Option<CCS<C1::ScalarField>>,
     ) -> Result<Self, Error> {
@@ -578,17 +585,10 @@ where
             let all_Ws = [vec![W_i.clone()], Ws].concat();
             let all_ws = [vec![w_i.clone()], ws].concat();
 
-            let mut transcript_p: PoseidonSponge<C1::ScalarField> =
-                PoseidonSponge::<C1::ScalarField>::new(&self.poseidon_config.clone());
+            let mut transcript_p = S::new(&self.poseidon_config);
             // since this is only for the number of constraints, no need to absorb the pp_hash here
-            let (nimfs_proof, U_i1, _, _) = NIMFS::<C1, PoseidonSponge<C1::ScalarField>>::prove(
-                &mut transcript_p,
-                &ccs,
-                &all_Us,
-                &all_us,
-                &all_Ws,
-                &all_ws,
-            )?;
+            let (nimfs_proof, U_i1, _, _) =
+                NIMFS::<C1, S>::prove(&mut transcript_p, &ccs, &all_Us, &all_us, &all_Ws, &all_ws)?;
 
             let augmented_f_circuit = Self {
                 poseidon_config: self.poseidon_config.clone(),
@@ -610,6 +610,8 @@ where
                 cf_u_i_cmW: None,
                 cf_U_i: None,
                 cf_cmT: None,
+                _s: PhantomData,
+                _t: PhantomData,
             };
 
             let cs: ConstraintSystem<C1::ScalarField>;
@@ -651,11 +653,13 @@ where
     }
 }
 
-impl<C1, C2, FC, const MU: usize, const NU: usize> AugmentedFCircuit<C1, C2, FC, MU, NU>
+impl<C1, C2, FC, S, T, const MU: usize, const NU: usize> AugmentedFCircuit<C1, C2, FC, S, T, MU, NU>
 where
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
     C2: Curve,
     FC: FCircuit<CF1<C1>>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
 {
     pub fn compute_next_state(
         self,
@@ -707,7 +711,7 @@ where
         })?;
         let cf_cmT = C2::Var::new_witness(cs.clone(), || Ok(self.cf_cmT.unwrap_or_else(C2::zero)))?;
 
-        let sponge = PoseidonSpongeVar::<C1::ScalarField>::new(cs.clone(), &self.poseidon_config);
+        let sponge = T::new(cs.clone(), &self.poseidon_config);
 
         let is_basecase = i.is_zero()?;
         let is_not_basecase = !&is_basecase;
@@ -736,7 +740,7 @@ where
         // Notice that NIMFSGadget::fold_committed_instance does not fold C. We set `U_i1.C` to
         // unconstrained witnesses `U_i1_C` respectively. Its correctness will be checked on the
         // other curve.
-        let mut transcript = PoseidonSpongeVar::new(cs.clone(), &self.poseidon_config);
+        let mut transcript = T::new(cs.clone(), &self.poseidon_config);
         transcript.absorb(&pp_hash)?;
         let (mut U_i1, rho_bits) = NIMFSGadget::<C1>::verify(
             cs.clone(),
@@ -857,12 +861,14 @@ where
     }
 }
 
-impl<C1, C2, FC, const MU: usize, const NU: usize> ConstraintSynthesizer<CF1<C1>>
-    for AugmentedFCircuit<C1, C2, FC, MU, NU>
+impl<C1, C2, FC, S, T, const MU: usize, const NU: usize> ConstraintSynthesizer<CF1<C1>>
+    for AugmentedFCircuit<C1, C2, FC, S, T, MU, NU>
 where
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
     C2: Curve,
     FC: FCircuit<CF1<C1>>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
 {
     fn generate_constraints(self, cs: ConstraintSystemRef<CF1<C1>>) -> Result<(), SynthesisError> {
         self.compute_next_state(cs).map(|_| ())
@@ -872,7 +878,11 @@ where
 #[cfg(test)]
 mod tests {
     use ark_bn254::{Fq, Fr, G1Projective as Projective};
-    use ark_crypto_primitives::sponge::Absorb;
+    use ark_crypto_primitives::sponge::{
+        constraints::CryptographicSpongeVar,
+        poseidon::{constraints::PoseidonSpongeVar, PoseidonSponge},
+        Absorb,
+    };
     use ark_ff::BigInteger;
     use ark_grumpkin::Projective as Projective2;
     use ark_std::{cmp::max, test_rng, time::Instant, UniformRand};
@@ -1177,12 +1187,15 @@ mod tests {
 
         let start = Instant::now();
         let F_circuit = CubicFCircuit::<Fr>::new(())?;
-        let mut augmented_f_circuit =
-            AugmentedFCircuit::<Projective, Projective2, CubicFCircuit<Fr>, MU, NU>::empty(
-                &poseidon_config,
-                F_circuit,
-                None,
-            )?;
+        let mut augmented_f_circuit = AugmentedFCircuit::<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            PoseidonSponge<Fr>,
+            PoseidonSpongeVar<Fr>,
+            MU,
+            NU,
+        >::empty(&poseidon_config, F_circuit, None)?;
         let ccs = augmented_f_circuit.ccs.clone();
         println!("AugmentedFCircuit & CCS generation: {:?}", start.elapsed());
         println!("CCS m x n: {} x {}", ccs.n_constraints(), ccs.n_variables());
@@ -1267,29 +1280,38 @@ mod tests {
                 // input in the AugmentedFCircuit
                 cf_u_i1_x = cf_U_i.hash_cyclefold(&sponge, pp_hash);
 
-                augmented_f_circuit =
-                    AugmentedFCircuit::<Projective, Projective2, CubicFCircuit<Fr>, MU, NU> {
-                        poseidon_config: poseidon_config.clone(),
-                        ccs: ccs.clone(),
-                        pp_hash: Some(pp_hash),
-                        i: Some(Fr::zero()),
-                        i_usize: Some(0),
-                        z_0: Some(z_0.clone()),
-                        z_i: Some(z_i.clone()),
-                        external_inputs: Some(()),
-                        U_i: Some(U_i.clone()),
-                        Us: Some(Us.clone()),
-                        u_i_C: Some(u_i.C),
-                        us: Some(us.clone()),
-                        U_i1_C: Some(U_i1.C),
-                        F: F_circuit,
-                        nimfs_proof: None,
-
-                        // cyclefold values
-                        cf_u_i_cmW: None,
-                        cf_U_i: None,
-                        cf_cmT: None,
-                    };
+                augmented_f_circuit = AugmentedFCircuit::<
+                    Projective,
+                    Projective2,
+                    CubicFCircuit<Fr>,
+                    PoseidonSponge<Fr>,
+                    PoseidonSpongeVar<Fr>,
+                    MU,
+                    NU,
+                > {
+                    poseidon_config: poseidon_config.clone(),
+                    ccs: ccs.clone(),
+                    pp_hash: Some(pp_hash),
+                    i: Some(Fr::zero()),
+                    i_usize: Some(0),
+                    z_0: Some(z_0.clone()),
+                    z_i: Some(z_i.clone()),
+                    external_inputs: Some(()),
+                    U_i: Some(U_i.clone()),
+                    Us: Some(Us.clone()),
+                    u_i_C: Some(u_i.C),
+                    us: Some(us.clone()),
+                    U_i1_C: Some(U_i1.C),
+                    F: F_circuit,
+                    nimfs_proof: None,
+
+                    // cyclefold values
+                    cf_u_i_cmW: None,
+                    cf_U_i: None,
+                    cf_cmT: None,
+                    _s: PhantomData,
+                    _t: PhantomData,
+                };
             } else {
                 let mut transcript_p: PoseidonSponge<Fr> =
                     PoseidonSponge::<Fr>::new(&poseidon_config.clone());
@@ -1351,29 +1373,38 @@ mod tests {
                 // AugmentedFCircuit
                 cf_u_i1_x = cf_U_i1.hash_cyclefold(&sponge, pp_hash);
 
-                augmented_f_circuit =
-                    AugmentedFCircuit::<Projective, Projective2, CubicFCircuit<Fr>, MU, NU> {
-                        poseidon_config: poseidon_config.clone(),
-                        ccs: ccs.clone(),
-                        pp_hash: Some(pp_hash),
-                        i: Some(iFr),
-                        i_usize: Some(i),
-                        z_0: Some(z_0.clone()),
-                        z_i: Some(z_i.clone()),
-                        external_inputs: Some(()),
-                        U_i: Some(U_i.clone()),
-                        Us: Some(Us.clone()),
-                        u_i_C: Some(u_i.C),
-                        us: Some(us.clone()),
-                        U_i1_C: Some(U_i1.C),
-                        F: F_circuit,
-                        nimfs_proof: Some(nimfs_proof),
-
-                        // cyclefold values
-                        cf_u_i_cmW: Some(cf_u_i.cmW),
-                        cf_U_i: Some(cf_U_i),
-                        cf_cmT: Some(cf_cmT),
-                    };
+                augmented_f_circuit = AugmentedFCircuit::<
+                    Projective,
+                    Projective2,
+                    CubicFCircuit<Fr>,
+                    PoseidonSponge<Fr>,
+                    PoseidonSpongeVar<Fr>,
+                    MU,
+                    NU,
+                > {
+                    poseidon_config: poseidon_config.clone(),
+                    ccs: ccs.clone(),
+                    pp_hash: Some(pp_hash),
+                    i: Some(iFr),
+                    i_usize: Some(i),
+                    z_0: Some(z_0.clone()),
+                    z_i: Some(z_i.clone()),
+                    external_inputs: Some(()),
+                    U_i: Some(U_i.clone()),
+                    Us: Some(Us.clone()),
+                    u_i_C: Some(u_i.C),
+                    us: Some(us.clone()),
+                    U_i1_C: Some(U_i1.C),
+                    F: F_circuit,
+                    nimfs_proof: Some(nimfs_proof),
+
+                    // cyclefold values
+                    cf_u_i_cmW: Some(cf_u_i.cmW),
+                    cf_U_i: Some(cf_U_i),
+                    cf_cmT: Some(cf_cmT),
+                    _s: PhantomData,
+                    _t: PhantomData,
+                };
 
                 // assign the next round instances
                 cf_W_i = cf_W_i1;
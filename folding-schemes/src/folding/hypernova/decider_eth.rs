@@ -1,4 +1,7 @@
 /// This file implements the HyperNova's onchain (Ethereum's EVM) decider.
+use ark_bn254::Bn254;
+use ark_crypto_primitives::sponge::CryptographicSponge;
+use ark_groth16::Groth16;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_snark::SNARK;
 use ark_std::rand::{CryptoRng, RngCore};
@@ -7,14 +10,18 @@ use core::marker::PhantomData;
 
 pub use super::decider_eth_circuit::DeciderEthCircuit;
 use super::decider_eth_circuit::DeciderHyperNovaGadget;
-use super::HyperNova;
+use super::{HyperNova, CCCS, LCCCS};
 use crate::commitment::{
-    kzg::Proof as KZGProof, pedersen::Params as PedersenParams, CommitmentScheme,
+    kzg::{Proof as KZGProof, KZG},
+    pedersen::Params as PedersenParams,
+    CommitmentScheme,
 };
 use crate::folding::circuits::decider::DeciderEnabledNIFS;
 use crate::folding::nova::decider_eth::VerifierParam;
 use crate::folding::traits::{Dummy, WitnessOps};
 use crate::frontend::FCircuit;
+use crate::transcript::keccak::KeccakTranscript;
+use crate::utils::eth::ToEth;
 use crate::{Curve, Error};
 use crate::{Decider as DeciderTrait, FoldingScheme};
 
@@ -208,25 +215,56 @@ where
             return Err(Error::SNARKVerificationFail);
         }
 
-        // 7.3. Verify the KZG proof
+        // 7.3. Verify the KZG proof, through the same batched check used by the other onchain
+        // deciders, which for a single opening is equivalent to the plain KZG check
         // we're at the Ethereum EVM case, so the CS1 is KZG commitments
-        CS1::verify_with_challenge(&cs_vp, proof.kzg_challenge, &C, &proof.kzg_proof)?;
+        CS1::batch_verify_with_challenges(
+            &cs_vp,
+            &mut KeccakTranscript::new(&()),
+            &[proof.kzg_challenge],
+            &[C],
+            core::slice::from_ref(&proof.kzg_proof),
+        )?;
 
         Ok(true)
     }
 }
 
+/// Prepares solidity calldata for calling the HyperNovaDecider contract
+#[allow(clippy::too_many_arguments)]
+pub fn prepare_calldata(
+    function_signature_check: [u8; 4],
+    i: ark_bn254::Fr,
+    z_0: Vec<ark_bn254::Fr>,
+    z_i: Vec<ark_bn254::Fr>,
+    running_instance: &LCCCS<ark_bn254::G1Projective>,
+    incoming_instance: &CCCS<ark_bn254::G1Projective>,
+    proof: Proof<ark_bn254::G1Projective, KZG<'static, Bn254>, Groth16<Bn254>>,
+) -> Result<Vec<u8>, Error> {
+    Ok([
+        function_signature_check.to_eth(),
+        i.to_eth(),   // i
+        z_0.to_eth(), // z_0
+        z_i.to_eth(), // z_i
+        running_instance.C.to_eth(),
+        incoming_instance.C.to_eth(),
+        proof.rho.to_eth(),             // rho
+        proof.snark_proof.to_eth(),     // pA, pB, pC
+        proof.kzg_challenge.to_eth(),   // challenge
+        proof.kzg_proof.eval.to_eth(),  // eval
+        proof.kzg_proof.proof.to_eth(), // kzg_proof
+    ]
+    .concat())
+}
+
 #[cfg(test)]
 pub mod tests {
-    use ark_bn254::{Bn254, Fr, G1Projective as Projective};
-    use ark_groth16::Groth16;
+    use ark_bn254::{Fr, G1Projective as Projective};
     use ark_grumpkin::Projective as Projective2;
     use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
 
     use super::*;
-    use crate::commitment::{kzg::KZG, pedersen::Pedersen};
-    use crate::folding::hypernova::cccs::CCCS;
-    use crate::folding::hypernova::lcccs::LCCCS;
+    use crate::commitment::pedersen::Pedersen;
     use crate::folding::hypernova::PreprocessorParam;
     use crate::folding::traits::CommittedInstanceOps;
     use crate::frontend::utils::CubicFCircuit;
@@ -0,0 +1,309 @@
+/// This file implements a HyperNova decider backed by the HyperKZG multilinear commitment, where
+/// the LCCCS evaluation claims of the folded instance are reduced in-circuit to an evaluation of
+/// the multilinear extension of the folded witness (see `DeciderHyperKZGCircuit`), which is then
+/// checked through a HyperKZG opening, so that the witness does not need to be allocated in the
+/// decider circuit.
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_snark::SNARK;
+use ark_std::rand::{CryptoRng, RngCore};
+use ark_std::{One, Zero};
+use core::marker::PhantomData;
+
+use super::decider_eth_circuit::DeciderHyperNovaGadget;
+pub use super::decider_hyperkzg_circuit::DeciderHyperKZGCircuit;
+use super::HyperNova;
+use crate::commitment::{
+    hyperkzg::Proof as HyperKZGProof, pedersen::Params as PedersenParams, CommitmentScheme,
+};
+use crate::folding::circuits::decider::DeciderEnabledNIFS;
+use crate::folding::nova::decider_eth::VerifierParam;
+use crate::folding::traits::Dummy;
+use crate::frontend::FCircuit;
+use crate::{Curve, Error};
+use crate::{Decider as DeciderTrait, FoldingScheme};
+
+#[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Proof<C1, CS1, S>
+where
+    C1: Curve,
+    CS1: CommitmentScheme<
+        C1,
+        ProverChallenge = Vec<C1::ScalarField>,
+        Challenge = Vec<C1::ScalarField>,
+    >,
+    S: SNARK<C1::ScalarField>,
+{
+    snark_proof: S::Proof,
+    cs_proof: CS1::Proof,
+    // rho used at the last fold, U_{i+1}=NIMFS.V(rho, U_i, u_i), it is checked in-circuit
+    rho: C1::ScalarField,
+    // the point at which the witness is opened is provided by the prover, but in-circuit it is
+    // checked to match the point of the sum-check
+    point: Vec<C1::ScalarField>,
+}
+
+/// Decider for HyperNova, where the folded witness is checked through a HyperKZG opening instead
+/// of in-circuit
+#[derive(Clone, Debug)]
+pub struct Decider<C1, C2, FC, CS1, CS2, S, FS, const MU: usize, const NU: usize> {
+    _c1: PhantomData<C1>,
+    _c2: PhantomData<C2>,
+    _fc: PhantomData<FC>,
+    _cs1: PhantomData<CS1>,
+    _cs2: PhantomData<CS2>,
+    _s: PhantomData<S>,
+    _fs: PhantomData<FS>,
+}
+
+impl<C1, C2, FC, CS1, CS2, S, FS, const MU: usize, const NU: usize> DeciderTrait<C1, C2, FC, FS>
+    for Decider<C1, C2, FC, CS1, CS2, S, FS, MU, NU>
+where
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    // CS1 is a HyperKZG commitment, where challenge is the point at which the MLE is evaluated
+    CS1: CommitmentScheme<
+        C1,
+        ProverChallenge = Vec<C1::ScalarField>,
+        Challenge = Vec<C1::ScalarField>,
+        Proof = HyperKZGProof<C1>,
+    >,
+    // enforce that the CS2 is Pedersen commitment scheme, since the CycleFold commitments are
+    // checked in-circuit
+    CS2: CommitmentScheme<C2, ProverParams = PedersenParams<C2>>,
+    S: SNARK<C1::ScalarField>,
+    FS: FoldingScheme<C1, C2, FC>,
+    // constrain FS into HyperNova, since this is a Decider specifically for HyperNova
+    HyperNova<C1, C2, FC, CS1, CS2, MU, NU, false>: From<FS>,
+    crate::folding::hypernova::ProverParams<C1, C2, CS1, CS2, false>:
+        From<<FS as FoldingScheme<C1, C2, FC>>::ProverParam>,
+    crate::folding::hypernova::VerifierParams<C1, C2, CS1, CS2, false>:
+        From<<FS as FoldingScheme<C1, C2, FC>>::VerifierParam>,
+{
+    type PreprocessorParam = ((FS::ProverParam, FS::VerifierParam), usize);
+    type ProverParam = (S::ProvingKey, CS1::ProverParams);
+    type Proof = Proof<C1, CS1, S>;
+    type VerifierParam = VerifierParam<C1, CS1::VerifierParams, S::VerifyingKey>;
+    type PublicInput = Vec<C1::ScalarField>;
+    type CommittedInstance = Vec<C1>;
+
+    fn preprocess(
+        mut rng: impl RngCore + CryptoRng,
+        ((pp, vp), state_len): Self::PreprocessorParam,
+    ) -> Result<(Self::ProverParam, Self::VerifierParam), Error> {
+        // get the FoldingScheme prover & verifier params from HyperNova
+        let hypernova_pp: <HyperNova<C1, C2, FC, CS1, CS2, MU, NU, false> as FoldingScheme<
+            C1,
+            C2,
+            FC,
+        >>::ProverParam = pp.into();
+        let hypernova_vp: <HyperNova<C1, C2, FC, CS1, CS2, MU, NU, false> as FoldingScheme<
+            C1,
+            C2,
+            FC,
+        >>::VerifierParam = vp.into();
+        let pp_hash = hypernova_vp.pp_hash()?;
+
+        let s = hypernova_vp.ccs.s;
+        let t = hypernova_vp.ccs.t;
+
+        let circuit = DeciderHyperKZGCircuit::<C1, C2>::dummy((
+            hypernova_vp.ccs,
+            hypernova_vp.cf_r1cs,
+            hypernova_pp.cf_cs_pp,
+            hypernova_pp.poseidon_config,
+            (s, t, MU, NU),
+            state_len,
+        ));
+
+        // get the SNARK specific setup for the circuit
+        let (snark_pk, snark_vk) = S::circuit_specific_setup(circuit, &mut rng)
+            .map_err(|e| Error::SNARKSetupFail(e.to_string()))?;
+
+        let pp = (snark_pk, hypernova_pp.cs_pp);
+
+        let vp = Self::VerifierParam {
+            pp_hash,
+            snark_vp: snark_vk,
+            cs_vp: hypernova_vp.cs_vp,
+        };
+        Ok((pp, vp))
+    }
+
+    fn prove(
+        mut rng: impl RngCore + CryptoRng,
+        pp: Self::ProverParam,
+        folding_scheme: FS,
+    ) -> Result<Self::Proof, Error> {
+        let (snark_pk, cs_pk): (S::ProvingKey, CS1::ProverParams) = pp;
+
+        let circuit = DeciderHyperKZGCircuit::<C1, C2>::try_from(HyperNova::from(folding_scheme))?;
+
+        let rho = circuit.rho;
+
+        // get the point that has been already computed when preparing the circuit inputs in the
+        // above `try_from` call
+        let point = circuit.sum_check_proof.point.clone();
+
+        // generate the HyperKZG proof of the evaluation of the MLE of W_{i+1} at `point`
+        let cs_proof = CS1::prove_with_challenge(
+            &cs_pk,
+            point.clone(),
+            &circuit.W_i1.w,
+            &C1::ScalarField::zero(),
+            None,
+        )?;
+
+        let snark_proof =
+            S::prove(&snark_pk, circuit, &mut rng).map_err(|e| Error::Other(e.to_string()))?;
+
+        Ok(Self::Proof {
+            snark_proof,
+            cs_proof,
+            rho,
+            point,
+        })
+    }
+
+    fn verify(
+        vp: Self::VerifierParam,
+        i: C1::ScalarField,
+        z_0: Vec<C1::ScalarField>,
+        z_i: Vec<C1::ScalarField>,
+        // we don't use the instances at the verifier level, since we check them in-circuit
+        running_commitments: &Self::CommittedInstance,
+        incoming_commitments: &Self::CommittedInstance,
+        proof: &Self::Proof,
+    ) -> Result<bool, Error> {
+        if i <= C1::ScalarField::one() {
+            return Err(Error::NotEnoughSteps);
+        }
+
+        let Self::VerifierParam {
+            pp_hash,
+            snark_vp,
+            cs_vp,
+        } = vp;
+
+        // 6.2. Fold the commitments
+        let C = DeciderHyperNovaGadget::fold_group_elements_native(
+            running_commitments,
+            incoming_commitments,
+            None,
+            proof.rho,
+        )?[0];
+
+        // Note: the NIMFS proof and the sum-check proof are checked inside the
+        // DeciderHyperKZGCircuit, which ensures that `proof.point` and `proof.cs_proof.eval` are
+        // the opening of W_{i+1} that implies the claims of U_{i+1}
+        let public_input: Vec<C1::ScalarField> = [
+            &[pp_hash, i][..],
+            &z_0,
+            &z_i,
+            &C.inputize_nonnative(),
+            &proof.point,
+            &[proof.cs_proof.eval, proof.rho],
+        ]
+        .concat();
+
+        let snark_v = S::verify(&snark_vp, &public_input, &proof.snark_proof)
+            .map_err(|e| Error::Other(e.to_string()))?;
+        if !snark_v {
+            return Err(Error::SNARKVerificationFail);
+        }
+
+        // 7. Verify the HyperKZG proof
+        CS1::verify_with_challenge(&cs_vp, proof.point.clone(), &C, &proof.cs_proof)?;
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use ark_bn254::{Bn254, Fr, G1Projective as Projective};
+    use ark_groth16::Groth16;
+    use ark_grumpkin::Projective as Projective2;
+
+    use super::*;
+    use crate::commitment::{hyperkzg::HyperKZG, pedersen::Pedersen};
+    use crate::folding::hypernova::PreprocessorParam;
+    use crate::folding::traits::CommittedInstanceOps;
+    use crate::frontend::utils::CubicFCircuit;
+    use crate::transcript::poseidon::poseidon_canonical_config;
+
+    #[test]
+    fn test_decider() -> Result<(), Error> {
+        const MU: usize = 1;
+        const NU: usize = 1;
+        // use HyperNova as FoldingScheme
+        type HN = HyperNova<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            HyperKZG<'static, Bn254>,
+            Pedersen<Projective2>,
+            MU,
+            NU,
+            false,
+        >;
+        type D = Decider<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            HyperKZG<'static, Bn254>,
+            Pedersen<Projective2>,
+            Groth16<Bn254>, // here we define the Snark to use in the decider
+            HN,             // here we define the FoldingScheme to use
+            MU,
+            NU,
+        >;
+
+        let mut rng = rand::rngs::OsRng;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let z_0 = vec![Fr::from(3_u32)];
+
+        let prep_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        let hypernova_params = HN::preprocess(&mut rng, &prep_param)?;
+
+        let mut hypernova = HN::init(&hypernova_params, F_circuit, z_0.clone())?;
+        hypernova.prove_step(&mut rng, (), Some((vec![], vec![])))?;
+        hypernova.prove_step(&mut rng, (), Some((vec![], vec![])))?; // do a 2nd step
+
+        // prepare the Decider prover & verifier params
+        let (decider_pp, decider_vp) =
+            D::preprocess(&mut rng, (hypernova_params, F_circuit.state_len()))?;
+
+        // decider proof generation
+        let proof = D::prove(rng, decider_pp, hypernova.clone())?;
+
+        // decider proof verification
+        let verified = D::verify(
+            decider_vp.clone(),
+            hypernova.i,
+            hypernova.z_0.clone(),
+            hypernova.z_i.clone(),
+            &hypernova.U_i.get_commitments(),
+            &hypernova.u_i.get_commitments(),
+            &proof,
+        )?;
+        assert!(verified);
+
+        // a proof with a wrong opening point does not verify
+        let mut bad_proof = proof;
+        bad_proof.point[0] += Fr::one();
+        assert!(D::verify(
+            decider_vp,
+            hypernova.i,
+            hypernova.z_0,
+            hypernova.z_i,
+            &hypernova.U_i.get_commitments(),
+            &hypernova.u_i.get_commitments(),
+            &bad_proof,
+        )
+        .is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,579 @@
+/// This file implements a HyperNova decider circuit that, instead of checking the CCS relation of
+/// the folded witness `W_{i+1}` in-circuit (as `DeciderEthCircuit` does), reduces the evaluation
+/// claims `v` of the folded LCCCS at `r_x` to a single evaluation of the multilinear extension of
+/// `W_{i+1}`, which is then proven outside of the circuit with the HyperKZG opening of `U_{i+1}.C`.
+///
+/// Since `v_j = Σ_y M̃_j(r_x, y) ⋅ z̃(y)` with `z = (u, x, w)`, for a random `γ` we have
+/// `Σ_j γ^j (v_j - P_j) = Σ_y ã(y) ⋅ w̃(y)`, where `P_j` is the contribution of the public part
+/// `(u, x)` of `z` and `ã(y) = Σ_j γ^j M̃_j(r_x, y + 1 + |x|)`. The circuit verifies the sum-check
+/// of the right hand side, which ends with the claim `ã(r_y) ⋅ w̃(r_y)` at a random point `r_y`,
+/// and computes `ã(r_y)` from the (constant) CCS matrices, so that the witness is never allocated
+/// in the circuit and `w̃(r_y)` together with `r_y` are the only values exposed to the verifier.
+use ark_crypto_primitives::sponge::{
+    constraints::CryptographicSpongeVar,
+    poseidon::{constraints::PoseidonSpongeVar, PoseidonConfig, PoseidonSponge},
+    CryptographicSponge,
+};
+use ark_poly::Polynomial;
+use ark_r1cs_std::{
+    alloc::AllocVar,
+    boolean::Boolean,
+    eq::EqGadget,
+    fields::{fp::FpVar, FieldVar},
+};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use ark_std::{log2, marker::PhantomData, One, Zero};
+use std::sync::Arc;
+
+use super::{
+    circuits::{CCCSVar, LCCCSVar},
+    decider_eth_circuit::DeciderHyperNovaGadget,
+    nimfs::{NIMFSProof, NIMFS},
+    HyperNova, Witness, CCCS, LCCCS,
+};
+use crate::arith::{
+    ccs::{circuits::CCSMatricesVar, CCS},
+    r1cs::R1CS,
+    Arith,
+};
+use crate::commitment::{pedersen::Params as PedersenParams, CommitmentScheme};
+use crate::folding::{
+    circuits::{
+        cyclefold::{CycleFoldCommittedInstance, CycleFoldCommittedInstanceVar, CycleFoldWitness},
+        decider::DeciderEnabledNIFS,
+        nonnative::affine::NonNativeAffineVar,
+        sum_check::{IOPProofVar, SumCheckVerifierGadget, VPAuxInfoVar},
+        CF1, CF2,
+    },
+    traits::{CommittedInstanceOps, CommittedInstanceVarOps, Dummy},
+};
+use crate::frontend::FCircuit;
+use crate::transcript::{Transcript, TranscriptVar};
+use crate::utils::{
+    gadgets::{eval_mle, MatrixGadget},
+    mle::dense_vec_to_dense_mle,
+    powers_of,
+    sum_check::{
+        structs::{IOPProof as SumCheckProof, IOPProverMessage},
+        IOPSumCheck, SumCheck,
+    },
+    virtual_polynomial::{build_eq_x_r_vec, VPAuxInfo, VirtualPolynomial},
+};
+use crate::{Curve, Error};
+
+/// Gadget that reduces the evaluation claims of an LCCCS to an evaluation of the multilinear
+/// extension of its witness, through a sum-check over the witness variables.
+/// It also offers the rust native implementation compatible with the gadget.
+pub struct WitnessEvalGadget {}
+
+impl WitnessEvalGadget {
+    /// Returns the number of variables of the multilinear extension of a witness of length
+    /// `n_witnesses`, which matches the number of variables of the HyperKZG openings.
+    pub fn n_vars(n_witnesses: usize) -> usize {
+        (log2(n_witnesses) as usize).max(1)
+    }
+
+    /// Returns the sum-check proof of `Σ_y ã(y) ⋅ w̃(y)` together with the evaluation `w̃(r_y)`,
+    /// where `r_y` is the point of the sum-check proof.
+    pub fn prove_native<C: Curve, T: Transcript<CF1<C>>>(
+        transcript: &mut T,
+        ccs: &CCS<CF1<C>>,
+        U: &LCCCS<C>,
+        W: &Witness<CF1<C>>,
+    ) -> Result<(SumCheckProof<CF1<C>>, CF1<C>), Error> {
+        for cm in U.get_commitments() {
+            transcript.absorb_nonnative(&cm);
+        }
+        let gamma = transcript.get_challenge();
+
+        let n_vars = Self::n_vars(ccs.n_witnesses());
+        let l = ccs.n_public_inputs();
+        let eq_r_x = build_eq_x_r_vec(&U.r_x)?;
+
+        // a[k] = Σ_j γ^j Σ_row eq(r_x, row) M_j[row][k + 1 + l]
+        let mut a = vec![CF1::<C>::zero(); 1 << n_vars];
+        for (M_j, gamma_j) in ccs.M.iter().zip(powers_of(gamma, ccs.t)) {
+            for (row, eq_r_x_row) in M_j.coeffs.iter().zip(&eq_r_x) {
+                for &(value, col) in row.iter() {
+                    if col > l {
+                        a[col - l - 1] += gamma_j * eq_r_x_row * value;
+                    }
+                }
+            }
+        }
+
+        let a = Arc::new(dense_vec_to_dense_mle(n_vars, &a));
+        let w = Arc::new(dense_vec_to_dense_mle(n_vars, &W.w));
+        let mut g = VirtualPolynomial::new_from_mle(&a, CF1::<C>::one());
+        g.mul_by_mle(w.clone(), CF1::<C>::one())?;
+
+        let sum_check_proof = IOPSumCheck::<CF1<C>, T>::prove(&g, transcript)
+            .map_err(|err| Error::SumCheckProveError(err.to_string()))?;
+        let eval = w.evaluate(&sum_check_proof.point);
+
+        Ok((sum_check_proof, eval))
+    }
+
+    /// Verifies the sum-check proof against the claims of `U`, and checks that its final claim
+    /// matches the given evaluation `eval` of the witness MLE. Returns the point `r_y` at which
+    /// the witness MLE needs to be opened.
+    pub fn verify_gadget<C: Curve>(
+        transcript: &mut PoseidonSpongeVar<CF1<C>>,
+        ccs: &CCSMatricesVar<CF1<C>>,
+        U: &LCCCSVar<C>,
+        sum_check_proof: &IOPProofVar<CF1<C>>,
+        eval: &FpVar<CF1<C>>,
+    ) -> Result<Vec<FpVar<CF1<C>>>, SynthesisError> {
+        for cm in U.get_commitments() {
+            transcript.absorb_nonnative(&cm)?;
+        }
+        let gamma = transcript.get_challenge()?;
+
+        let l = U.x.len();
+        let n_witnesses = ccs.M[0].n_cols - l - 1;
+        let aux_info = VPAuxInfoVar::<CF1<C>>::new_constant(
+            ConstraintSystemRef::None,
+            VPAuxInfo {
+                max_degree: 2,
+                num_variables: Self::n_vars(n_witnesses),
+                phantom: PhantomData::<CF1<C>>,
+            },
+        )?;
+        let (e_vars, r_y) =
+            SumCheckVerifierGadget::verify(sum_check_proof, &aux_info, transcript, Boolean::TRUE)?;
+
+        // eq(r_y, ·) over the boolean hypercube, with the same variable ordering as the one of
+        // `build_eq_x_r_vec`
+        let mut eq_r_y = vec![FpVar::<CF1<C>>::one()];
+        for r_i in r_y.iter() {
+            let hi = eq_r_y.iter().map(|e| e * r_i).collect::<Vec<_>>();
+            let lo = eq_r_y
+                .iter()
+                .zip(&hi)
+                .map(|(e, h)| e - h)
+                .collect::<Vec<_>>();
+            eq_r_y = [lo, hi].concat();
+        }
+
+        // split z = (u, x, w) into its public part, and the evaluations of eq(r_y, ·) placed at the
+        // positions of w, so that M_j z_x and M_j z_w are computed from linear combinations only
+        let z_x = [&[U.u.clone()][..], &U.x, &vec![FpVar::zero(); n_witnesses]].concat();
+        let z_w = [&vec![FpVar::zero(); l + 1][..], &eq_r_y[..n_witnesses]].concat();
+
+        let mut claim = FpVar::zero();
+        let mut a_r_y = FpVar::zero();
+        let mut gamma_j = FpVar::one();
+        for (M_j, v_j) in ccs.M.iter().zip(&U.v) {
+            let s = log2(M_j.n_rows) as usize;
+            let P_j = eval_mle(s, M_j.mul_vector(&z_x)?, U.r_x.clone());
+            let a_j = eval_mle(s, M_j.mul_vector(&z_w)?, U.r_x.clone());
+            claim += &gamma_j * (v_j - P_j);
+            a_r_y += &gamma_j * a_j;
+            gamma_j *= &gamma;
+        }
+
+        sum_check_proof.claim.enforce_equal(&claim)?;
+        e_vars
+            .last()
+            .ok_or(SynthesisError::Unsatisfiable)?
+            .enforce_equal(&(a_r_y * eval))?;
+
+        Ok(r_y)
+    }
+}
+
+/// Circuit for the HyperNova decider backed by the HyperKZG multilinear commitment. It performs
+/// the same checks as `DeciderEthCircuit`, except that `R_arith(W_{i+1}, U_{i+1})` and the KZG
+/// evaluation of `W_{i+1}` are replaced by the `WitnessEvalGadget` sum-check, so the circuit does
+/// not depend on the size of the witness but only on the number of its variables.
+pub struct DeciderHyperKZGCircuit<C1: Curve, C2: Curve> {
+    /// CCS of the Augmented Function circuit
+    pub ccs: CCS<CF1<C1>>,
+    /// R1CS of the CycleFold circuit
+    pub cf_r1cs: R1CS<CF1<C2>>,
+    /// CycleFold PedersenParams over C2
+    pub cf_pedersen_params: PedersenParams<C2>,
+    pub poseidon_config: PoseidonConfig<CF1<C1>>,
+    /// public params hash
+    pub pp_hash: CF1<C1>,
+    pub i: CF1<C1>,
+    /// initial state
+    pub z_0: Vec<CF1<C1>>,
+    /// current i-th state
+    pub z_i: Vec<CF1<C1>>,
+    /// HyperNova instances
+    pub U_i: LCCCS<C1>,
+    pub u_i: CCCS<C1>,
+    pub U_i1: LCCCS<C1>,
+    /// Folded witness, which is not allocated in the circuit but kept for the HyperKZG opening
+    pub W_i1: Witness<CF1<C1>>,
+
+    /// Helpers for folding verification
+    pub nimfs_proof: NIMFSProof<C1>,
+    pub rho: CF1<C1>,
+
+    /// CycleFold running instance
+    pub cf_U_i: CycleFoldCommittedInstance<C2>,
+    pub cf_W_i: CycleFoldWitness<C2>,
+
+    /// Sum-check proof, whose point is the one at which `W_{i+1}` is opened
+    pub sum_check_proof: SumCheckProof<CF1<C1>>,
+    /// Evaluation of the multilinear extension of `W_{i+1}` at the sum-check point
+    pub eval: CF1<C1>,
+}
+
+impl<C1: Curve, C2: Curve>
+    Dummy<(
+        CCS<CF1<C1>>,
+        R1CS<CF1<C2>>,
+        PedersenParams<C2>,
+        PoseidonConfig<CF1<C1>>,
+        (usize, usize, usize, usize),
+        usize,
+    )> for DeciderHyperKZGCircuit<C1, C2>
+{
+    fn dummy(
+        (ccs, cf_r1cs, cf_pedersen_params, poseidon_config, proof_config, state_len): (
+            CCS<CF1<C1>>,
+            R1CS<CF1<C2>>,
+            PedersenParams<C2>,
+            PoseidonConfig<CF1<C1>>,
+            (usize, usize, usize, usize),
+            usize,
+        ),
+    ) -> Self {
+        let n_vars = WitnessEvalGadget::n_vars(ccs.n_witnesses());
+        Self {
+            cf_pedersen_params,
+            poseidon_config,
+            pp_hash: Zero::zero(),
+            i: Zero::zero(),
+            z_0: vec![Zero::zero(); state_len],
+            z_i: vec![Zero::zero(); state_len],
+            U_i: LCCCS::dummy(&ccs),
+            u_i: CCCS::dummy(&ccs),
+            U_i1: LCCCS::dummy(&ccs),
+            W_i1: Witness::dummy(&ccs),
+            nimfs_proof: NIMFSProof::dummy(proof_config),
+            rho: Zero::zero(),
+            cf_U_i: CycleFoldCommittedInstance::dummy(&cf_r1cs),
+            cf_W_i: CycleFoldWitness::dummy(&cf_r1cs),
+            // use 'one()' instead of 'zero()' so that the dummy sum-check proof has the same
+            // in-circuit representation as an actual proof (see `NIMFSProof::dummy`)
+            sum_check_proof: SumCheckProof {
+                point: vec![One::one(); n_vars],
+                proofs: vec![
+                    IOPProverMessage {
+                        coeffs: vec![One::one(); 3]
+                    };
+                    n_vars
+                ],
+            },
+            eval: Zero::zero(),
+            ccs,
+            cf_r1cs,
+        }
+    }
+}
+
+impl<
+        C1: Curve,
+        C2: Curve,
+        FC: FCircuit<C1::ScalarField>,
+        CS1: CommitmentScheme<C1, H>,
+        // enforce that the CS2 is Pedersen commitment scheme, since the CycleFold commitments are
+        // checked in-circuit
+        CS2: CommitmentScheme<C2, H, ProverParams = PedersenParams<C2>>,
+        const MU: usize,
+        const NU: usize,
+        const H: bool,
+    > TryFrom<HyperNova<C1, C2, FC, CS1, CS2, MU, NU, H>> for DeciderHyperKZGCircuit<C1, C2>
+{
+    type Error = Error;
+
+    fn try_from(hn: HyperNova<C1, C2, FC, CS1, CS2, MU, NU, H>) -> Result<Self, Error> {
+        // compute the U_{i+1}, W_{i+1}, by folding the last running & incoming instances
+        let mut transcript = PoseidonSponge::<C1::ScalarField>::new(&hn.poseidon_config);
+        transcript.absorb(&hn.pp_hash);
+        let (nimfs_proof, U_i1, W_i1, rho) = NIMFS::<C1, PoseidonSponge<C1::ScalarField>>::prove(
+            &mut transcript,
+            &hn.ccs,
+            &[hn.U_i.clone()],
+            &[hn.u_i.clone()],
+            &[hn.W_i.clone()],
+            &[hn.w_i.clone()],
+        )?;
+
+        // reduce the claims of U_{i+1} to an evaluation of the MLE of W_{i+1}
+        let (sum_check_proof, eval) =
+            WitnessEvalGadget::prove_native(&mut transcript, &hn.ccs, &U_i1, &W_i1)?;
+
+        Ok(Self {
+            ccs: hn.ccs,
+            cf_r1cs: hn.cf_r1cs,
+            cf_pedersen_params: hn.cf_cs_pp,
+            poseidon_config: hn.poseidon_config,
+            pp_hash: hn.pp_hash,
+            i: hn.i,
+            z_0: hn.z_0,
+            z_i: hn.z_i,
+            U_i: hn.U_i,
+            u_i: hn.u_i,
+            U_i1,
+            W_i1,
+            nimfs_proof,
+            rho,
+            cf_U_i: hn.cf_U_i,
+            cf_W_i: hn.cf_W_i,
+            sum_check_proof,
+            eval,
+        })
+    }
+}
+
+impl<C1, C2> ConstraintSynthesizer<CF1<C1>> for DeciderHyperKZGCircuit<C1, C2>
+where
+    C1: Curve,
+    C2: Curve<ScalarField = CF2<C1>, BaseField = CF1<C1>>,
+{
+    fn generate_constraints(self, cs: ConstraintSystemRef<CF1<C1>>) -> Result<(), SynthesisError> {
+        // CCS's (sparse) matrices are constants in the circuit
+        let ccs = CCSMatricesVar::<CF1<C1>>::new_constant(cs.clone(), &self.ccs)?;
+
+        let pp_hash = FpVar::new_input(cs.clone(), || Ok(self.pp_hash))?;
+        let i = FpVar::new_input(cs.clone(), || Ok(self.i))?;
+        let z_0 = Vec::new_input(cs.clone(), || Ok(self.z_0))?;
+        let z_i = Vec::new_input(cs.clone(), || Ok(self.z_i))?;
+
+        let u_i = CCCSVar::<C1>::new_witness(cs.clone(), || Ok(self.u_i))?;
+        let U_i = LCCCSVar::<C1>::new_witness(cs.clone(), || Ok(self.U_i))?;
+        // here U_i1 = NIMFS.P(U_i, u_i)
+        let U_i1_commitments = Vec::<NonNativeAffineVar<C1>>::new_input(cs.clone(), || {
+            Ok(self.U_i1.get_commitments())
+        })?;
+        let U_i1 = LCCCSVar::<C1>::new_witness(cs.clone(), || Ok(self.U_i1))?;
+        U_i1.get_commitments().enforce_equal(&U_i1_commitments)?;
+
+        let cf_U_i =
+            CycleFoldCommittedInstanceVar::<C2>::new_witness(cs.clone(), || Ok(self.cf_U_i))?;
+
+        // allocate the inputs for the check 7
+        let point = Vec::<FpVar<CF1<C1>>>::new_input(cs.clone(), || {
+            Ok(self.sum_check_proof.point.clone())
+        })?;
+        let eval = FpVar::new_input(cs.clone(), || Ok(self.eval))?;
+        let sum_check_proof = IOPProofVar::new_witness(cs.clone(), || Ok(self.sum_check_proof))?;
+
+        // `sponge` is for digest computation.
+        let sponge = PoseidonSpongeVar::new(cs.clone(), &self.poseidon_config);
+        // `transcript` is for challenge generation.
+        let mut transcript = sponge.clone();
+
+        // NOTE: we use the same enumeration as in `GenericOnchainDeciderCircuit`, where the check 1
+        // is replaced by the check 7.
+
+        // 2. enforce `u_i` is an incoming instance
+        u_i.enforce_incoming()?;
+
+        // 3. u_i.x[0] == H(i, z_0, z_i, U_i), u_i.x[1] == H(cf_U_i)
+        let (u_i_x, U_i_vec) = U_i.hash(&sponge, &pp_hash, &i, &z_0, &z_i)?;
+        let (cf_u_i_x, _) = cf_U_i.hash(&sponge, pp_hash.clone())?;
+        u_i.get_public_inputs().enforce_equal(&[u_i_x, cf_u_i_x])?;
+
+        #[cfg(feature = "light-test")]
+        log::warn!("[WARNING]: Running with the 'light-test' feature, skipping the big part of the DeciderHyperKZGCircuit.\n           Only for testing purposes.");
+
+        // The following two checks (and their respective allocations) are disabled for normal
+        // tests since they take several millions of constraints and would take several minutes
+        // (and RAM) to run the test. It is active by default, and not active only when
+        // 'light-test' feature is used.
+        #[cfg(not(feature = "light-test"))]
+        {
+            // imports here instead of at the top of the file, so we avoid having multiple
+            // `#[cfg(not(test))]`
+            use crate::{
+                arith::{r1cs::circuits::R1CSMatricesVar, ArithRelationGadget},
+                commitment::pedersen::PedersenGadget,
+                folding::circuits::{
+                    cyclefold::CycleFoldWitnessVar, nonnative::uint::NonNativeUintVar,
+                },
+            };
+            use ark_r1cs_std::{convert::ToBitsGadget, groups::CurveVar};
+            let cf_W_i = CycleFoldWitnessVar::<C2>::new_witness(cs.clone(), || Ok(self.cf_W_i))?;
+            // 4. check Pedersen commitments of cf_U_i.{cmE, cmW}
+            let H = C2::Var::constant(self.cf_pedersen_params.h);
+            let G = self
+                .cf_pedersen_params
+                .generators
+                .iter()
+                .map(|&g| C2::Var::constant(g.into()))
+                .collect::<Vec<_>>();
+            let cf_W_i_E_bits = cf_W_i
+                .E
+                .iter()
+                .map(|E_i| E_i.to_bits_le())
+                .collect::<Result<Vec<_>, _>>()?;
+            let cf_W_i_W_bits = cf_W_i
+                .W
+                .iter()
+                .map(|W_i| W_i.to_bits_le())
+                .collect::<Result<Vec<_>, _>>()?;
+            PedersenGadget::<C2>::commit(&H, &G, &cf_W_i_E_bits, &cf_W_i.rE.to_bits_le()?)?
+                .enforce_equal(&cf_U_i.cmE)?;
+            PedersenGadget::<C2>::commit(&H, &G, &cf_W_i_W_bits, &cf_W_i.rW.to_bits_le()?)?
+                .enforce_equal(&cf_U_i.cmW)?;
+
+            let cf_r1cs = R1CSMatricesVar::<CF1<C2>, NonNativeUintVar<CF2<C2>>>::new_constant(
+                ConstraintSystemRef::None,
+                self.cf_r1cs,
+            )?;
+
+            // 5. enforce `cf_U_i` and `cf_W_i` satisfy `cf_r1cs`
+            cf_r1cs.enforce_relation(&cf_W_i, &cf_U_i)?;
+        }
+
+        // 6.1. partially enforce `NIMFS.V(U_i, u_i) = U_{i+1}`.
+        DeciderHyperNovaGadget::fold_field_elements_gadget(
+            &self.ccs,
+            &mut transcript,
+            pp_hash,
+            U_i,
+            U_i_vec,
+            u_i,
+            self.nimfs_proof,
+            self.rho,
+        )?
+        .enforce_partial_equal(&U_i1)?;
+
+        // 7. reduce the claims of `U_{i+1}` to the evaluation of the MLE of `W_{i+1}` at `point`,
+        // which is checked outside of the circuit through the HyperKZG opening of `U_{i+1}.C`
+        WitnessEvalGadget::verify_gadget(&mut transcript, &ccs, &U_i1, &sum_check_proof, &eval)?
+            .enforce_equal(&point)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use ark_bn254::{Bn254, Fr, G1Projective as Projective};
+    use ark_grumpkin::Projective as Projective2;
+    use ark_r1cs_std::R1CSVar;
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::{test_rng, UniformRand};
+
+    use super::*;
+    use crate::commitment::{hyperkzg::HyperKZG, pedersen::Pedersen};
+    use crate::folding::nova::PreprocessorParam;
+    use crate::frontend::utils::CubicFCircuit;
+    use crate::transcript::poseidon::poseidon_canonical_config;
+    use crate::FoldingScheme;
+
+    // checks that the native and in-circuit reductions of the LCCCS claims match
+    #[test]
+    fn test_witness_eval_gadget() -> Result<(), Error> {
+        let mut rng = test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let n_rows = 2_u32.pow(5) as usize;
+        let n_cols = 2_u32.pow(5) as usize;
+        let r1cs = R1CS::<Fr>::rand(&mut rng, n_rows, n_cols);
+        let ccs = CCS::from(r1cs);
+        let z: Vec<Fr> = (0..n_cols).map(|_| Fr::rand(&mut rng)).collect();
+
+        let (pedersen_params, _) = Pedersen::<Projective>::setup(&mut rng, ccs.n_witnesses())?;
+        let (lcccs, w) = ccs.to_lcccs::<_, Projective, Pedersen<Projective>, false>(
+            &mut rng,
+            &pedersen_params,
+            &z,
+        )?;
+
+        let mut transcript = PoseidonSponge::<Fr>::new(&poseidon_config);
+        let (sum_check_proof, eval) =
+            WitnessEvalGadget::prove_native(&mut transcript, &ccs, &lcccs, &w)?;
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let ccs_var = CCSMatricesVar::<Fr>::new_constant(cs.clone(), ccs.clone())?;
+        let lcccs_var = LCCCSVar::new_input(cs.clone(), || Ok(lcccs.clone()))?;
+        let sum_check_proof_var =
+            IOPProofVar::new_witness(cs.clone(), || Ok(sum_check_proof.clone()))?;
+        let eval_var = FpVar::new_witness(cs.clone(), || Ok(eval))?;
+        let mut transcript_var = PoseidonSpongeVar::<Fr>::new(cs.clone(), &poseidon_config);
+        let point_var = WitnessEvalGadget::verify_gadget(
+            &mut transcript_var,
+            &ccs_var,
+            &lcccs_var,
+            &sum_check_proof_var,
+            &eval_var,
+        )?;
+        assert!(cs.is_satisfied()?);
+        assert_eq!(point_var.value()?, sum_check_proof.point);
+
+        // a wrong evaluation of the witness does not satisfy the circuit
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let ccs_var = CCSMatricesVar::<Fr>::new_constant(cs.clone(), ccs)?;
+        let lcccs_var = LCCCSVar::new_input(cs.clone(), || Ok(lcccs))?;
+        let sum_check_proof_var = IOPProofVar::new_witness(cs.clone(), || Ok(sum_check_proof))?;
+        let eval_var = FpVar::new_witness(cs.clone(), || Ok(eval + Fr::one()))?;
+        let mut transcript_var = PoseidonSpongeVar::<Fr>::new(cs.clone(), &poseidon_config);
+        WitnessEvalGadget::verify_gadget(
+            &mut transcript_var,
+            &ccs_var,
+            &lcccs_var,
+            &sum_check_proof_var,
+            &eval_var,
+        )?;
+        assert!(!cs.is_satisfied()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_decider_hyperkzg_circuit() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let z_0 = vec![Fr::from(3_u32)];
+
+        const MU: usize = 1;
+        const NU: usize = 1;
+
+        type HN = HyperNova<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            HyperKZG<'static, Bn254>,
+            Pedersen<Projective2>,
+            MU,
+            NU,
+            false,
+        >;
+        let prep_param = PreprocessorParam::<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            HyperKZG<'static, Bn254>,
+            Pedersen<Projective2>,
+            false,
+        >::new(poseidon_config, F_circuit);
+        let hn_params = HN::preprocess(&mut rng, &prep_param)?;
+
+        // generate a HyperNova instance and do a step of it
+        let mut hypernova = HN::init(&hn_params, F_circuit, z_0.clone())?;
+        hypernova.prove_step(&mut rng, (), None)?;
+
+        let ivc_proof = hypernova.ivc_proof();
+        HN::verify(hn_params.1, ivc_proof)?;
+
+        // load the DeciderHyperKZGCircuit from the generated HyperNova instance
+        let decider_circuit =
+            DeciderHyperKZGCircuit::<Projective, Projective2>::try_from(hypernova)?;
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+
+        // generate the constraints and check that are satisfied by the inputs
+        decider_circuit.generate_constraints(cs.clone())?;
+        assert!(cs.is_satisfied()?);
+        dbg!(cs.num_constraints());
+        Ok(())
+    }
+}
@@ -3,13 +3,13 @@ use ark_ff::PrimeField;
 use ark_poly::Polynomial;
 use ark_serialize::CanonicalDeserialize;
 use ark_serialize::CanonicalSerialize;
-use ark_std::rand::Rng;
-use ark_std::Zero;
+use ark_std::rand::{Rng, RngCore};
+use ark_std::{UniformRand, Zero};
 
 use super::circuits::LCCCSVar;
 use super::Witness;
 use crate::arith::ccs::CCS;
-use crate::arith::{Arith, ArithRelation};
+use crate::arith::{Arith, ArithRelation, ArithSampler};
 use crate::commitment::CommitmentScheme;
 use crate::folding::circuits::CF1;
 use crate::folding::traits::Inputize;
@@ -45,13 +45,10 @@ impl<F: PrimeField> CCS<F> {
         C: Curve<ScalarField = F>,
     {
         let (w, x) = self.split_z(z);
-        // if the commitment scheme is set to be hiding, set the random blinding parameter
-        let r_w = if CS::is_hiding() {
-            F::rand(rng)
-        } else {
-            F::zero()
-        };
-        let C = CS::commit(cs_params, &w, &r_w)?;
+        // if the commitment scheme is set to be hiding, the witness is
+        // created with a random blinding factor
+        let W = Witness::<F>::new::<H>(w, &mut *rng);
+        let C = CS::commit(cs_params, &W.w, &W.r_w)?;
 
         let r_x: Vec<F> = (0..self.s).map(|_| F::rand(rng)).collect();
 
@@ -73,7 +70,7 @@ impl<F: PrimeField> CCS<F> {
                 r_x,
                 v,
             },
-            Witness::<F> { w, r_w },
+            W,
         ))
     }
 }
@@ -116,6 +113,57 @@ impl<C: Curve> ArithRelation<Witness<CF1<C>>, LCCCS<C>> for CCS<CF1<C>> {
     }
 }
 
+impl<C: Curve> ArithSampler<C, Witness<CF1<C>>, LCCCS<C>> for CCS<CF1<C>> {
+    fn sample_witness_instance<CS: CommitmentScheme<C, H>, const H: bool>(
+        &self,
+        params: &CS::ProverParams,
+        mut rng: impl RngCore,
+    ) -> Result<(Witness<CF1<C>>, LCCCS<C>), Error> {
+        // Implements sampling a (committed) LCCCS, see appendix D.4 of
+        // https://eprint.iacr.org/2023/573.pdf. Notice that any z satisfies
+        // the LCCCS relation as long as the v_j values are computed from it,
+        // so we just need to sample u, x, w and r_x at random.
+        let u = CF1::<C>::rand(&mut rng);
+        let x = (0..self.n_public_inputs())
+            .map(|_| CF1::<C>::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let witness = Witness::new::<H>(
+            (0..self.n_witnesses())
+                .map(|_| CF1::<C>::rand(&mut rng))
+                .collect(),
+            &mut rng,
+        );
+        let r_x = (0..self.s)
+            .map(|_| CF1::<C>::rand(&mut rng))
+            .collect::<Vec<_>>();
+
+        let z = [&[u][..], &x, &witness.w].concat();
+        let v = self
+            .M
+            .iter()
+            .map(|M_j| {
+                let Mz = dense_vec_to_dense_mle(self.s, &mat_vec_mul(M_j, &z)?);
+                Ok(Mz.evaluate(&r_x))
+            })
+            .collect::<Result<_, Error>>()?;
+
+        let lcccs = LCCCS {
+            C: CS::commit(params, &witness.w, &witness.r_w)?,
+            u,
+            x,
+            r_x,
+            v,
+        };
+
+        debug_assert!(
+            self.check_relation(&witness, &lcccs).is_ok(),
+            "Sampled a non satisfiable LCCCS"
+        );
+
+        Ok((witness, lcccs))
+    }
+}
+
 impl<C: Curve> Absorb for LCCCS<C> {
     fn to_sponge_bytes(&self, dest: &mut Vec<u8>) {
         C::ScalarField::batch_to_sponge_bytes(&self.to_sponge_field_elements_as_vec(), dest);
@@ -137,6 +185,10 @@ impl<C: Curve> CommittedInstanceOps<C> for LCCCS<C> {
         vec![self.C]
     }
 
+    fn get_public_inputs(&self) -> &[CF1<C>] {
+        &self.x
+    }
+
     fn is_incoming(&self) -> bool {
         false
     }
@@ -1,7 +1,6 @@
 /// Implements the scheme described in [HyperNova](https://eprint.iacr.org/2023/573.pdf)
-use ark_crypto_primitives::sponge::{
-    poseidon::{PoseidonConfig, PoseidonSponge},
-    CryptographicSponge,
+use ark_crypto_primitives::sponge::poseidon::{
+    constraints::PoseidonSpongeVar, PoseidonConfig, PoseidonSponge,
 };
 use ark_ff::{BigInteger, PrimeField};
 use ark_r1cs_std::R1CSVar;
@@ -11,11 +10,19 @@ use ark_std::{cmp::max, fmt::Debug, marker::PhantomData, rand::RngCore, One, Zer
 
 pub mod cccs;
 pub mod circuits;
+// offchain decider
+pub mod decider;
+pub mod decider_circuits;
+// onchain decider
 pub mod decider_eth;
 pub mod decider_eth_circuit;
+// decider backed by the HyperKZG multilinear commitment
+pub mod decider_hyperkzg;
+pub mod decider_hyperkzg_circuit;
 pub mod lcccs;
 pub mod nimfs;
 pub mod utils;
+pub mod zk;
 
 use cccs::CCCS;
 use circuits::AugmentedFCircuit;
@@ -35,11 +42,12 @@ use crate::folding::{
         fold_cyclefold_circuit, CycleFoldCircuit, CycleFoldCommittedInstance, CycleFoldConfig,
         CycleFoldWitness,
     },
+    circuits::CF1,
     nova::{get_r1cs_from_cs, PreprocessorParam},
     traits::{CommittedInstanceOps, Dummy, WitnessOps},
 };
 use crate::frontend::FCircuit;
-use crate::transcript::poseidon::poseidon_canonical_config;
+use crate::transcript::{CanonicalConfig, Transcript, TranscriptVar};
 use crate::utils::pp_hash;
 use crate::{Curve, Error, FoldingScheme, MultiFolding};
 
@@ -69,16 +77,23 @@ pub struct Witness<F: PrimeField> {
 }
 
 impl<F: PrimeField> Witness<F> {
-    pub fn new(w: Vec<F>) -> Self {
-        // note: at the current version, we don't use the blinding factors and we set them to 0
-        // always.
-        Self { w, r_w: F::zero() }
+    /// Creates a new witness, where the blinding factor `r_w` is sampled at
+    /// random if `H` is set (i.e., if the commitment scheme is hiding), and is
+    /// zero otherwise.
+    pub fn new<const H: bool>(w: Vec<F>, mut rng: impl RngCore) -> Self {
+        let r_w = if H { F::rand(&mut rng) } else { F::zero() };
+        Self { w, r_w }
     }
 }
 
 impl<F: PrimeField> Dummy<&CCS<F>> for Witness<F> {
     fn dummy(ccs: &CCS<F>) -> Self {
-        Self::new(vec![F::zero(); ccs.n_witnesses()])
+        // the dummy witness is not blinded, so that it matches the dummy
+        // instances, whose commitments are set to zero
+        Self {
+            w: vec![F::zero(); ccs.n_witnesses()],
+            r_w: F::zero(),
+        }
     }
 }
 
@@ -90,17 +105,18 @@ impl<F: PrimeField> WitnessOps<F> for Witness<F> {
     }
 }
 
-/// Proving parameters for HyperNova-based IVC
+/// Proving parameters for HyperNova-based IVC, where `SC` is the configuration of the sponge used
+/// by the IVC (Poseidon by default).
 #[derive(Debug, Clone)]
-pub struct ProverParams<C1, C2, CS1, CS2, const H: bool>
+pub struct ProverParams<C1, C2, CS1, CS2, const H: bool, SC = PoseidonConfig<CF1<C1>>>
 where
     C1: Curve,
     C2: Curve,
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
 {
-    /// Poseidon sponge configuration
-    pub poseidon_config: PoseidonConfig<C1::ScalarField>,
+    /// Sponge configuration
+    pub poseidon_config: SC,
     /// Proving parameters of the underlying commitment scheme over C1
     pub cs_pp: CS1::ProverParams,
     /// Proving parameters of the underlying commitment scheme over C2
@@ -116,7 +132,8 @@ impl<
         CS1: CommitmentScheme<C1, H>,
         CS2: CommitmentScheme<C2, H>,
         const H: bool,
-    > CanonicalSerialize for ProverParams<C1, C2, CS1, CS2, H>
+        SC,
+    > CanonicalSerialize for ProverParams<C1, C2, CS1, CS2, H, SC>
 {
     fn serialize_with_mode<W: std::io::prelude::Write>(
         &self,
@@ -140,9 +157,10 @@ pub struct VerifierParams<
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
     const H: bool,
+    SC = PoseidonConfig<CF1<C1>>,
 > {
-    /// Poseidon sponge configuration
-    pub poseidon_config: PoseidonConfig<C1::ScalarField>,
+    /// Sponge configuration
+    pub poseidon_config: SC,
     /// CCS of the Augmented step circuit
     pub ccs: CCS<C1::ScalarField>,
     /// R1CS of the CycleFold circuit
@@ -153,7 +171,8 @@ pub struct VerifierParams<
     pub cf_cs_vp: CS2::VerifierParams,
 }
 
-impl<C1, C2, CS1, CS2, const H: bool> CanonicalSerialize for VerifierParams<C1, C2, CS1, CS2, H>
+impl<C1, C2, CS1, CS2, const H: bool, SC> CanonicalSerialize
+    for VerifierParams<C1, C2, CS1, CS2, H, SC>
 where
     C1: Curve,
     C2: Curve,
@@ -174,12 +193,13 @@ where
     }
 }
 
-impl<C1, C2, CS1, CS2, const H: bool> VerifierParams<C1, C2, CS1, CS2, H>
+impl<C1, C2, CS1, CS2, const H: bool, SC> VerifierParams<C1, C2, CS1, CS2, H, SC>
 where
     C1: Curve,
     C2: Curve,
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
+    SC: CanonicalSerialize,
 {
     /// returns the hash of the public parameters of HyperNova
     pub fn pp_hash(&self) -> Result<C1::ScalarField, Error> {
@@ -217,20 +237,36 @@ where
 /// For multi-instance folding, one needs to specify the const generics below:
 /// * `MU` - the number of LCCCS instances to be folded
 /// * `NU` - the number of CCCS instances to be folded
-#[derive(Clone, Debug)]
-pub struct HyperNova<C1, C2, FC, CS1, CS2, const MU: usize, const NU: usize, const H: bool>
-where
+///
+/// The sponge `S` (Poseidon by default) is used for the transcripts and the hashes of the IVC,
+/// and `T` is its in-circuit counterpart, used in the augmented circuit.
+#[derive(Clone)]
+pub struct HyperNova<
+    C1,
+    C2,
+    FC,
+    CS1,
+    CS2,
+    const MU: usize,
+    const NU: usize,
+    const H: bool,
+    S = PoseidonSponge<CF1<C1>>,
+    T = PoseidonSpongeVar<CF1<C1>>,
+> where
     C1: Curve,
     C2: Curve,
     FC: FCircuit<C1::ScalarField>,
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
 {
     /// CCS of the Augmented Function circuit
     pub ccs: CCS<C1::ScalarField>,
     /// R1CS of the CycleFold circuit
     pub cf_r1cs: R1CS<C2::ScalarField>,
-    pub poseidon_config: PoseidonConfig<C1::ScalarField>,
+    /// Sponge configuration
+    pub poseidon_config: S::Config,
     /// CommitmentScheme::ProverParams over C1
     pub cs_pp: CS1::ProverParams,
     /// CycleFold CommitmentScheme::ProverParams, over C2
@@ -253,16 +289,54 @@ where
     /// CycleFold running instance
     pub cf_W_i: CycleFoldWitness<C2>,
     pub cf_U_i: CycleFoldCommittedInstance<C2>,
+
+    _s: PhantomData<S>,
+    _t: PhantomData<T>,
+}
+
+impl<C1, C2, FC, CS1, CS2, const MU: usize, const NU: usize, const H: bool, S, T> Debug
+    for HyperNova<C1, C2, FC, CS1, CS2, MU, NU, H, S, T>
+where
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
+{
+    fn fmt(&self, f: &mut ark_std::fmt::Formatter<'_>) -> ark_std::fmt::Result {
+        f.debug_struct("HyperNova")
+            .field("ccs", &self.ccs)
+            .field("cf_r1cs", &self.cf_r1cs)
+            .field("poseidon_config", &self.poseidon_config)
+            .field("cs_pp", &self.cs_pp)
+            .field("cf_cs_pp", &self.cf_cs_pp)
+            .field("F", &self.F)
+            .field("pp_hash", &self.pp_hash)
+            .field("i", &self.i)
+            .field("z_0", &self.z_0)
+            .field("z_i", &self.z_i)
+            .field("W_i", &self.W_i)
+            .field("U_i", &self.U_i)
+            .field("w_i", &self.w_i)
+            .field("u_i", &self.u_i)
+            .field("cf_W_i", &self.cf_W_i)
+            .field("cf_U_i", &self.cf_U_i)
+            .finish()
+    }
 }
 
-impl<C1, C2, FC, CS1, CS2, const MU: usize, const NU: usize, const H: bool> MultiFolding<C1, C2, FC>
-    for HyperNova<C1, C2, FC, CS1, CS2, MU, NU, H>
+impl<C1, C2, FC, CS1, CS2, const MU: usize, const NU: usize, const H: bool, S, T>
+    MultiFolding<C1, C2, FC> for HyperNova<C1, C2, FC, CS1, CS2, MU, NU, H, S, T>
 where
     C1: Curve,
     C2: Curve,
     FC: FCircuit<C1::ScalarField>,
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
 {
     type RunningInstance = (LCCCS<C1>, Witness<C1::ScalarField>);
@@ -314,14 +388,16 @@ where
     }
 }
 
-impl<C1, C2, FC, CS1, CS2, const MU: usize, const NU: usize, const H: bool>
-    HyperNova<C1, C2, FC, CS1, CS2, MU, NU, H>
+impl<C1, C2, FC, CS1, CS2, const MU: usize, const NU: usize, const H: bool, S, T>
+    HyperNova<C1, C2, FC, CS1, CS2, MU, NU, H, S, T>
 where
     C1: Curve,
     C2: Curve,
     FC: FCircuit<C1::ScalarField>,
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
 {
     /// internal helper for new_running_instance & new_incoming_instance methods, returns the R1CS
@@ -337,7 +413,7 @@ where
         let (_, cf_U_i): (CycleFoldWitness<C2>, CycleFoldCommittedInstance<C2>) =
             self.cf_r1cs.dummy_witness_instance();
 
-        let sponge = PoseidonSponge::<C1::ScalarField>::new(&self.poseidon_config);
+        let sponge = S::new(&self.poseidon_config);
 
         u_i.x = vec![
             U_i.hash(
@@ -354,7 +430,7 @@ where
         // compute u_{i+1}.x
         let U_i1 = LCCCS::dummy(&self.ccs);
 
-        let augmented_f_circuit = AugmentedFCircuit::<C1, C2, FC, MU, NU> {
+        let augmented_f_circuit = AugmentedFCircuit::<C1, C2, FC, S, T, MU, NU> {
             poseidon_config: self.poseidon_config.clone(),
             ccs: self.ccs.clone(),
             pp_hash: Some(self.pp_hash),
@@ -375,6 +451,8 @@ where
             cf_u_i_cmW: None,
             cf_U_i: None,
             cf_cmT: None,
+            _s: PhantomData,
+            _t: PhantomData,
         };
 
         let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
@@ -396,20 +474,22 @@ where
     }
 }
 
-impl<C1, C2, FC, CS1, CS2, const MU: usize, const NU: usize, const H: bool>
-    FoldingScheme<C1, C2, FC> for HyperNova<C1, C2, FC, CS1, CS2, MU, NU, H>
+impl<C1, C2, FC, CS1, CS2, const MU: usize, const NU: usize, const H: bool, S, T>
+    FoldingScheme<C1, C2, FC> for HyperNova<C1, C2, FC, CS1, CS2, MU, NU, H, S, T>
 where
     C1: Curve,
     C2: Curve,
     FC: FCircuit<C1::ScalarField>,
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
 {
     /// Reuse Nova's PreprocessorParam.
-    type PreprocessorParam = PreprocessorParam<C1, C2, FC, CS1, CS2, H>;
-    type ProverParam = ProverParams<C1, C2, CS1, CS2, H>;
-    type VerifierParam = VerifierParams<C1, C2, CS1, CS2, H>;
+    type PreprocessorParam = PreprocessorParam<C1, C2, FC, CS1, CS2, H, S::Config>;
+    type ProverParam = ProverParams<C1, C2, CS1, CS2, H, S::Config>;
+    type VerifierParam = VerifierParams<C1, C2, CS1, CS2, H, S::Config>;
     type RunningInstance = (LCCCS<C1>, Witness<C1::ScalarField>);
     type IncomingInstance = (CCCS<C1>, Witness<C1::ScalarField>);
     type MultiCommittedInstanceWithWitness =
@@ -423,14 +503,14 @@ where
         validate: ark_serialize::Validate,
         fc_params: FC::Params,
     ) -> Result<Self::ProverParam, Error> {
-        let poseidon_config = poseidon_canonical_config::<C1::ScalarField>();
+        let poseidon_config = S::Config::canonical_config();
 
         // generate the r1cs & cf_r1cs needed for the VerifierParams. In this way we avoid needing
         // to serialize them, saving significant space in the VerifierParams serialized size.
 
         // main circuit R1CS:
         let f_circuit = FC::new(fc_params)?;
-        let augmented_F_circuit = AugmentedFCircuit::<C1, C2, FC, MU, NU>::empty(
+        let augmented_F_circuit = AugmentedFCircuit::<C1, C2, FC, S, T, MU, NU>::empty(
             &poseidon_config,
             f_circuit.clone(),
             None,
@@ -454,14 +534,14 @@ where
         validate: ark_serialize::Validate,
         fc_params: FC::Params,
     ) -> Result<Self::VerifierParam, Error> {
-        let poseidon_config = poseidon_canonical_config::<C1::ScalarField>();
+        let poseidon_config = S::Config::canonical_config();
 
         // generate the r1cs & cf_r1cs needed for the VerifierParams. In this way we avoid needing
         // to serialize them, saving significant space in the VerifierParams serialized size.
 
         // main circuit R1CS:
         let f_circuit = FC::new(fc_params)?;
-        let augmented_F_circuit = AugmentedFCircuit::<C1, C2, FC, MU, NU>::empty(
+        let augmented_F_circuit = AugmentedFCircuit::<C1, C2, FC, S, T, MU, NU>::empty(
             &poseidon_config,
             f_circuit.clone(),
             None,
@@ -492,7 +572,7 @@ where
             return Err(Error::CantBeZero("mu,nu".to_string()));
         }
 
-        let augmented_f_circuit = AugmentedFCircuit::<C1, C2, FC, MU, NU>::empty(
+        let augmented_f_circuit = AugmentedFCircuit::<C1, C2, FC, S, T, MU, NU>::empty(
             &prep_param.poseidon_config,
             prep_param.F.clone(),
             None,
@@ -521,13 +601,13 @@ where
             )?,
         };
 
-        let pp = ProverParams::<C1, C2, CS1, CS2, H> {
+        let pp = ProverParams {
             poseidon_config: prep_param.poseidon_config.clone(),
             cs_pp,
             cf_cs_pp,
             ccs: Some(ccs.clone()),
         };
-        let vp = VerifierParams::<C1, C2, CS1, CS2, H> {
+        let vp = VerifierParams {
             poseidon_config: prep_param.poseidon_config.clone(),
             ccs,
             cf_r1cs,
@@ -549,11 +629,11 @@ where
         }
 
         // `sponge` is for digest computation.
-        let sponge = PoseidonSponge::<C1::ScalarField>::new(&pp.poseidon_config);
+        let sponge = S::new(&pp.poseidon_config);
 
         // prepare the HyperNova's AugmentedFCircuit and CycleFold's circuits and obtain its CCS
         // and R1CS respectively
-        let augmented_f_circuit = AugmentedFCircuit::<C1, C2, FC, MU, NU>::empty(
+        let augmented_f_circuit = AugmentedFCircuit::<C1, C2, FC, S, T, MU, NU>::empty(
             &pp.poseidon_config,
             F.clone(),
             pp.ccs.clone(),
@@ -598,6 +678,8 @@ where
             // cyclefold running instance
             cf_W_i: cf_W_dummy,
             cf_U_i: cf_U_dummy,
+            _s: PhantomData,
+            _t: PhantomData,
         })
     }
 
@@ -609,9 +691,13 @@ where
         other_instances: Option<Self::MultiCommittedInstanceWithWitness>,
     ) -> Result<(), Error> {
         // ensure that commitments are blinding if user has specified so.
-
+        // Notice that at i=0 both w_i and W_i are the dummy witnesses, and at
+        // i=1 W_i is still the dummy witness set at the base case, so the
+        // blinding factors are only checked for the non-dummy witnesses.
         if H {
             let blinding_commitments = if self.i == C1::ScalarField::zero() {
+                vec![]
+            } else if self.i == C1::ScalarField::one() {
                 vec![self.w_i.r_w]
             } else {
                 vec![self.w_i.r_w, self.W_i.r_w]
@@ -661,7 +747,7 @@ where
             (vec![], vec![], vec![], vec![])
         };
 
-        let augmented_f_circuit: AugmentedFCircuit<C1, C2, FC, MU, NU>;
+        let augmented_f_circuit: AugmentedFCircuit<C1, C2, FC, S, T, MU, NU>;
 
         if self.z_i.len() != self.F.state_len() {
             return Err(Error::NotSameLength(
@@ -692,14 +778,13 @@ where
             i_usize = usize::from_le_bytes(i_bytes);
         }
 
-        let (U_i1, mut W_i1);
+        let (U_i1, W_i1);
 
         if self.i == C1::ScalarField::zero() {
             W_i1 = Witness::<C1::ScalarField>::dummy(&self.ccs);
-            W_i1.r_w = self.W_i.r_w;
             U_i1 = LCCCS::dummy(&self.ccs);
 
-            augmented_f_circuit = AugmentedFCircuit::<C1, C2, FC, MU, NU> {
+            augmented_f_circuit = AugmentedFCircuit::<C1, C2, FC, S, T, MU, NU> {
                 poseidon_config: self.poseidon_config.clone(),
                 ccs: self.ccs.clone(),
                 pp_hash: Some(self.pp_hash),
@@ -720,10 +805,11 @@ where
                 cf_u_i_cmW: None,
                 cf_U_i: None,
                 cf_cmT: None,
+                _s: PhantomData,
+                _t: PhantomData,
             };
         } else {
-            let mut transcript_p: PoseidonSponge<C1::ScalarField> =
-                PoseidonSponge::<C1::ScalarField>::new(&self.poseidon_config);
+            let mut transcript_p = S::new(&self.poseidon_config);
             transcript_p.absorb(&self.pp_hash);
 
             let (all_Us, all_us, all_Ws, all_ws) = (
@@ -734,7 +820,7 @@ where
             );
 
             let (rho, nimfs_proof);
-            (nimfs_proof, U_i1, W_i1, rho) = NIMFS::<C1, PoseidonSponge<C1::ScalarField>>::prove(
+            (nimfs_proof, U_i1, W_i1, rho) = NIMFS::<C1, S>::prove(
                 &mut transcript_p,
                 &self.ccs,
                 &all_Us,
@@ -773,7 +859,7 @@ where
                     &mut rng,
                 )?;
 
-            augmented_f_circuit = AugmentedFCircuit::<C1, C2, FC, MU, NU> {
+            augmented_f_circuit = AugmentedFCircuit::<C1, C2, FC, S, T, MU, NU> {
                 poseidon_config: self.poseidon_config.clone(),
                 ccs: self.ccs.clone(),
                 pp_hash: Some(self.pp_hash),
@@ -794,6 +880,8 @@ where
                 cf_u_i_cmW: Some(cf_u_i.cmW),
                 cf_U_i: Some(self.cf_U_i.clone()),
                 cf_cmT: Some(cf_cmT),
+                _s: PhantomData,
+                _t: PhantomData,
             };
 
             // assign the next round instances
@@ -881,7 +969,7 @@ where
         let (pp, vp) = params;
 
         let f_circuit = FC::new(fcircuit_params)?;
-        let augmented_f_circuit = AugmentedFCircuit::<C1, C2, FC, MU, NU>::empty(
+        let augmented_f_circuit = AugmentedFCircuit::<C1, C2, FC, S, T, MU, NU>::empty(
             &pp.poseidon_config,
             f_circuit.clone(),
             None,
@@ -908,6 +996,8 @@ where
             U_i,
             cf_W_i,
             cf_U_i,
+            _s: PhantomData,
+            _t: PhantomData,
         })
     }
 
@@ -933,7 +1023,7 @@ where
             return Ok(());
         }
         // `sponge` is for digest computation.
-        let sponge = PoseidonSponge::<C1::ScalarField>::new(&vp.poseidon_config);
+        let sponge = S::new(&vp.poseidon_config);
 
         if u_i.x.len() != 2 || U_i.x.len() != 2 {
             return Err(Error::IVCVerificationFail);
@@ -966,11 +1056,10 @@ where
 }
 
 #[cfg(test)]
-mod tests {
+pub mod tests {
     use crate::commitment::kzg::KZG;
     use ark_bn254::{Bn254, Fr, G1Projective as Projective};
     use ark_grumpkin::Projective as Projective2;
-    use ark_std::UniformRand;
 
     use super::*;
     use crate::commitment::pedersen::Pedersen;
@@ -1001,6 +1090,12 @@ mod tests {
         Ok(())
     }
 
+    const MU: usize = 2;
+    const NU: usize = 3;
+
+    type HN<CS1, CS2, const H: bool> =
+        HyperNova<Projective, Projective2, CubicFCircuit<Fr>, CS1, CS2, MU, NU, H>;
+
     #[allow(clippy::type_complexity)]
     // test_ivc allowing to choose the CommitmentSchemes
     pub fn test_ivc_opt<
@@ -1010,15 +1105,9 @@ mod tests {
     >(
         poseidon_config: PoseidonConfig<Fr>,
         F_circuit: CubicFCircuit<Fr>,
-    ) -> Result<(), Error> {
+    ) -> Result<HN<CS1, CS2, H>, Error> {
         let mut rng = ark_std::test_rng();
 
-        const MU: usize = 2;
-        const NU: usize = 3;
-
-        type HN<CS1, CS2, const H: bool> =
-            HyperNova<Projective, Projective2, CubicFCircuit<Fr>, CS1, CS2, MU, NU, H>;
-
         let prep_param =
             PreprocessorParam::<Projective, Projective2, CubicFCircuit<Fr>, CS1, CS2, H>::new(
                 poseidon_config.clone(),
@@ -1029,14 +1118,6 @@ mod tests {
         let z_0 = vec![Fr::from(3_u32)];
         let mut hypernova = HN::init(&hypernova_params, F_circuit, z_0.clone())?;
 
-        let (w_i_blinding, W_i_blinding) = if H {
-            (Fr::rand(&mut rng), Fr::rand(&mut rng))
-        } else {
-            (Fr::zero(), Fr::zero())
-        };
-        hypernova.w_i.r_w = w_i_blinding;
-        hypernova.W_i.r_w = W_i_blinding;
-
         let num_steps: usize = 3;
         for _ in 0..num_steps {
             // prepare some new instances to fold in the multifolding step
@@ -1057,11 +1138,17 @@ mod tests {
         }
         assert_eq!(Fr::from(num_steps as u32), hypernova.i);
 
+        if H {
+            // the witnesses of the non-dummy instances are blinded
+            assert!(!hypernova.w_i.r_w.is_zero());
+            assert!(!hypernova.W_i.r_w.is_zero());
+        }
+
         let ivc_proof = hypernova.ivc_proof();
         HN::verify(
             hypernova_params.1.clone(), // verifier_params
             ivc_proof,
         )?;
-        Ok(())
+        Ok(hypernova)
     }
 }
@@ -0,0 +1,241 @@
+/// Implements the zero-knowledge layer for HyperNova's IVC, as described in appendix D.4 of
+/// [HyperNova](https://eprint.iacr.org/2023/573.pdf), following the same structure as Nova's one
+/// (see [`crate::folding::nova::zk`]).
+///
+/// As in Nova, this covers the Use-case-3 described in [`crate::folding::nova::zk`]: the user
+/// wants to generate a zk-proof of the IVC state to an IVC verifier, without any SNARK proof
+/// involved.
+///
+/// Since the multi-folding scheme of HyperNova can fold several LCCCS and CCCS instances at once,
+/// instead of folding twice as in Nova, the prover samples a random satisfying LCCCS
+/// instance-witness pair `(U_r, W_r)` (see the [`ArithSampler`] implementation for CCS), and folds
+/// the running instance `U_i`, the random instance `U_r` and the incoming instance `u_i` in a
+/// single multi-folding step. The resulting witness `W_i'` is then blinded by `W_r`, and can be
+/// sent to the verifier together with the NIMFS proof.
+///
+/// Notice that the NIMFS proof (the sum-check messages and the sigmas and thetas values) is sent
+/// in the clear.
+use ark_crypto_primitives::sponge::{
+    poseidon::{PoseidonConfig, PoseidonSponge},
+    CryptographicSponge,
+};
+use ark_std::{rand::RngCore, Zero};
+
+use super::{
+    cccs::CCCS,
+    lcccs::LCCCS,
+    nimfs::{NIMFSProof, NIMFS},
+    HyperNova, Witness,
+};
+use crate::{
+    arith::{ccs::CCS, r1cs::R1CS, ArithRelation, ArithSampler},
+    commitment::CommitmentScheme,
+    folding::{
+        circuits::cyclefold::{CycleFoldCommittedInstance, CycleFoldWitness},
+        traits::CommittedInstanceOps,
+    },
+    frontend::FCircuit,
+    Curve, Error,
+};
+
+pub struct RandomizedIVCProof<C1: Curve, C2: Curve> {
+    pub U_i: LCCCS<C1>,
+    pub u_i: CCCS<C1>,
+    pub U_r: LCCCS<C1>,
+    pub pi: NIMFSProof<C1>, // proof of folding U_i, U_r and u_i
+    pub W_i_prime: Witness<C1::ScalarField>,
+    pub cf_U_i: CycleFoldCommittedInstance<C2>,
+    pub cf_W_i: CycleFoldWitness<C2>,
+}
+
+impl<C1: Curve, C2: Curve> RandomizedIVCProof<C1, C2> {
+    /// Compute a zero-knowledge proof of a HyperNova IVC proof
+    /// It implements the prover of appendix D.4 in https://eprint.iacr.org/2023/573.pdf
+    pub fn new<
+        FC: FCircuit<C1::ScalarField>,
+        CS1: CommitmentScheme<C1, true>,
+        CS2: CommitmentScheme<C2, true>,
+        const MU: usize,
+        const NU: usize,
+    >(
+        hypernova: &HyperNova<C1, C2, FC, CS1, CS2, MU, NU, true>,
+        mut rng: impl RngCore,
+    ) -> Result<RandomizedIVCProof<C1, C2>, Error>
+    where
+        C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    {
+        let mut transcript = PoseidonSponge::<C1::ScalarField>::new(&hypernova.poseidon_config);
+        transcript.absorb(&hypernova.pp_hash);
+
+        // 1. Sample a satisfying LCCCS instance-witness pair (U_r, W_r)
+        let (W_r, U_r) = hypernova
+            .ccs
+            .sample_witness_instance::<CS1, true>(&hypernova.cs_pp, &mut rng)?;
+
+        // 2. Fold the instance-witness pairs (U_i, W_i), (U_r, W_r) and (u_i, w_i)
+        let (pi, _, W_i_prime, _) = NIMFS::<C1, PoseidonSponge<C1::ScalarField>>::prove(
+            &mut transcript,
+            &hypernova.ccs,
+            &[hypernova.U_i.clone(), U_r.clone()],
+            &[hypernova.u_i.clone()],
+            &[hypernova.W_i.clone(), W_r],
+            &[hypernova.w_i.clone()],
+        )?;
+
+        Ok(RandomizedIVCProof {
+            U_i: hypernova.U_i.clone(),
+            u_i: hypernova.u_i.clone(),
+            U_r,
+            pi,
+            W_i_prime,
+            cf_U_i: hypernova.cf_U_i.clone(),
+            cf_W_i: hypernova.cf_W_i.clone(),
+        })
+    }
+
+    /// Verify a zero-knowledge proof of a HyperNova IVC proof
+    /// It implements the verifier of appendix D.4 in https://eprint.iacr.org/2023/573.pdf
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify(
+        ccs: &CCS<C1::ScalarField>,
+        cf_r1cs: &R1CS<C2::ScalarField>,
+        pp_hash: C1::ScalarField,
+        poseidon_config: &PoseidonConfig<C1::ScalarField>,
+        i: C1::ScalarField,
+        z_0: Vec<C1::ScalarField>,
+        z_i: Vec<C1::ScalarField>,
+        proof: &RandomizedIVCProof<C1, C2>,
+    ) -> Result<(), Error>
+    where
+        C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    {
+        // Handles case where i=0
+        if i == C1::ScalarField::zero() {
+            if z_0 == z_i {
+                return Ok(());
+            } else {
+                return Err(Error::zkIVCVerificationFail);
+            }
+        }
+
+        // 1. Check that u_i.x is correct - including the cyclefold running instance
+        // a. Check length
+        if proof.u_i.x.len() != 2 || proof.U_i.x.len() != 2 {
+            return Err(Error::IVCVerificationFail);
+        }
+
+        // b. Check computed hashes are correct
+        let sponge = PoseidonSponge::<C1::ScalarField>::new(poseidon_config);
+        let expected_u_i_x = proof.U_i.hash(&sponge, pp_hash, i, &z_0, &z_i);
+        if expected_u_i_x != proof.u_i.x[0] {
+            return Err(Error::zkIVCVerificationFail);
+        }
+
+        let expected_cf_u_i_x = proof.cf_U_i.hash_cyclefold(&sponge, pp_hash);
+        if expected_cf_u_i_x != proof.u_i.x[1] {
+            return Err(Error::IVCVerificationFail);
+        }
+
+        // 2. Obtain the U^{\prime}_i folded instance
+        let mut transcript = PoseidonSponge::<C1::ScalarField>::new(poseidon_config);
+        transcript.absorb(&pp_hash);
+        let U_i_prime = NIMFS::<C1, PoseidonSponge<C1::ScalarField>>::verify(
+            &mut transcript,
+            ccs,
+            &[proof.U_i.clone(), proof.U_r.clone()],
+            &[proof.u_i.clone()],
+            proof.pi.clone(),
+        )?;
+
+        // 3. Check that W^{\prime}_i is a satisfying witness
+        ccs.check_relation(&proof.W_i_prime, &U_i_prime)?;
+
+        // 4. Check that the cyclefold instance-witness pair satisfies the cyclefold relaxed r1cs
+        cf_r1cs.check_relation(&proof.cf_W_i, &proof.cf_U_i)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::commitment::pedersen::Pedersen;
+    use crate::folding::hypernova::tests::test_ivc_opt;
+    use crate::frontend::utils::CubicFCircuit;
+    use crate::transcript::poseidon::poseidon_canonical_config;
+    use ark_bn254::{Fr, G1Projective as Projective};
+    use ark_grumpkin::Projective as Projective2;
+    use ark_std::One;
+    use rand::rngs::OsRng;
+
+    // Tests zk proof generation and verification for a valid HyperNova IVC proof
+    #[test]
+    fn test_zk_hypernova_ivc() -> Result<(), Error> {
+        let mut rng = OsRng;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let hypernova = test_ivc_opt::<
+            Pedersen<Projective, true>,
+            Pedersen<Projective2, true>,
+            true,
+        >(poseidon_config, F_circuit)?;
+
+        let proof = RandomizedIVCProof::new(&hypernova, &mut rng)?;
+        RandomizedIVCProof::verify(
+            &hypernova.ccs,
+            &hypernova.cf_r1cs,
+            hypernova.pp_hash,
+            &hypernova.poseidon_config,
+            hypernova.i,
+            hypernova.z_0.clone(),
+            hypernova.z_i.clone(),
+            &proof,
+        )?;
+
+        // the randomized witness should not match the original running witness
+        assert_ne!(proof.W_i_prime.w, hypernova.W_i.w);
+
+        // verification fails with a wrong state
+        let verify = RandomizedIVCProof::verify(
+            &hypernova.ccs,
+            &hypernova.cf_r1cs,
+            hypernova.pp_hash,
+            &hypernova.poseidon_config,
+            hypernova.i,
+            hypernova.z_0.clone(),
+            hypernova.z_0,
+            &proof,
+        );
+        assert!(verify.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_zk_hypernova_verification_fails_with_wrong_witness() -> Result<(), Error> {
+        let mut rng = OsRng;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let hypernova = test_ivc_opt::<
+            Pedersen<Projective, true>,
+            Pedersen<Projective2, true>,
+            true,
+        >(poseidon_config, F_circuit)?;
+
+        // proof verification fails with an incorrect randomized witness
+        let mut incorrect_proof = RandomizedIVCProof::new(&hypernova, &mut rng)?;
+        incorrect_proof.W_i_prime.w[0] += Fr::one();
+        let verify = RandomizedIVCProof::verify(
+            &hypernova.ccs,
+            &hypernova.cf_r1cs,
+            hypernova.pp_hash,
+            &hypernova.poseidon_config,
+            hypernova.i,
+            hypernova.z_0,
+            hypernova.z_i,
+            &incorrect_proof,
+        );
+        assert!(verify.is_err());
+        Ok(())
+    }
+}
@@ -1,7 +1,10 @@
 pub mod circuits;
 pub mod hypernova;
+pub mod mova;
 pub mod nova;
+pub mod ova;
 pub mod protogalaxy;
+pub mod supernova;
 pub mod traits;
 
 #[cfg(test)]
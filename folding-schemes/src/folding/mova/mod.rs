@@ -0,0 +1,144 @@
+/// Implements the IVC scheme obtained by using the [Mova](https://eprint.iacr.org/2024/1220.pdf)
+/// NIFS inside the Nova's IVC construction, together with
+/// [CycleFold](https://eprint.iacr.org/2023/1192.pdf).
+///
+/// Unlike Nova, Mova does not commit to the error term `E` nor to the cross term `T`, but instead
+/// keeps track of the evaluation of the MLE of `E` at a random point `rE`. Hence at each step only
+/// the commitment to the witness `W` needs to be computed, and only its folding is delegated to
+/// the CycleFold circuit.
+///
+/// The IVC and its AugmentedFCircuit are shared with Nova (see
+/// [`crate::folding::nova::GenericNova`]), and the NIFS implementation for Mova and its gadget are
+/// in nova/nifs/mova.rs and nova/nifs/mova_circuits.rs.
+use ark_crypto_primitives::sponge::poseidon::{constraints::PoseidonSpongeVar, PoseidonSponge};
+
+use crate::folding::{
+    circuits::CF1,
+    nova::{
+        nifs::mova::{CommittedInstance, Witness, NIFS},
+        GenericNova,
+    },
+};
+
+/// IVCProof of Mova+CycleFold, which has the same structure as Nova's one, but containing Mova's
+/// witnesses and committed instances.
+pub type IVCProof<C1, C2> =
+    crate::folding::nova::IVCProof<C1, C2, Witness<C1>, CommittedInstance<C1>>;
+
+/// Implements Mova+CycleFold's IVC, following the FoldingScheme trait.
+/// The `H` const generic specifies whether the homorphic commitment scheme is blinding
+/// The sponge `S` (Poseidon by default) is used for the transcripts and the hashes of the IVC,
+/// and `T` is its in-circuit counterpart, used in the augmented circuit.
+pub type Mova<
+    C1,
+    C2,
+    FC,
+    CS1,
+    CS2,
+    const H: bool = false,
+    S = PoseidonSponge<CF1<C1>>,
+    T = PoseidonSpongeVar<CF1<C1>>,
+> = GenericNova<C1, C2, FC, CS1, CS2, NIFS<C1, CS1, S, H>, H, S, T>;
+
+#[cfg(test)]
+pub mod tests {
+    use crate::commitment::kzg::KZG;
+    use ark_bn254::{Bn254, Fr, G1Projective as Projective};
+    use ark_grumpkin::Projective as Projective2;
+
+    use ark_crypto_primitives::sponge::poseidon::PoseidonConfig;
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+    use super::*;
+    use crate::commitment::{pedersen::Pedersen, CommitmentScheme};
+    use crate::folding::nova::PreprocessorParam;
+    use crate::frontend::{utils::CubicFCircuit, FCircuit};
+    use crate::transcript::poseidon::poseidon_canonical_config;
+    use crate::{Error, FoldingScheme};
+
+    /// This test tests the Mova+CycleFold IVC, and by consequence it is also testing the
+    /// AugmentedFCircuit
+    #[test]
+    fn test_ivc() -> Result<(), Error> {
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+
+        // run the test using Pedersen commitments on both sides of the curve cycle
+        test_ivc_opt::<Pedersen<Projective>, Pedersen<Projective2>, false>(
+            poseidon_config.clone(),
+            F_circuit,
+            3,
+        )?;
+
+        test_ivc_opt::<Pedersen<Projective, true>, Pedersen<Projective2, true>, true>(
+            poseidon_config.clone(),
+            F_circuit,
+            3,
+        )?;
+
+        // run the test using KZG for the commitments on the main curve, and Pedersen for the
+        // commitments on the secondary curve
+        test_ivc_opt::<KZG<Bn254>, Pedersen<Projective2>, false>(poseidon_config, F_circuit, 3)?;
+        Ok(())
+    }
+
+    // test_ivc allowing to choose the CommitmentSchemes
+    fn test_ivc_opt<
+        CS1: CommitmentScheme<Projective, H>,
+        CS2: CommitmentScheme<Projective2, H>,
+        const H: bool,
+    >(
+        poseidon_config: PoseidonConfig<Fr>,
+        F_circuit: CubicFCircuit<Fr>,
+        num_steps: usize,
+    ) -> Result<(), Error> {
+        type M<CS1, CS2, const H: bool> =
+            Mova<Projective, Projective2, CubicFCircuit<Fr>, CS1, CS2, H>;
+
+        let mut rng = ark_std::test_rng();
+
+        let prep_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        let mova_params = M::<CS1, CS2, H>::preprocess(&mut rng, &prep_param)?;
+
+        let z_0 = vec![Fr::from(3_u32)];
+        let mut mova = M::<CS1, CS2, H>::init(&mova_params, F_circuit, z_0.clone())?;
+
+        for _ in 0..num_steps {
+            mova.prove_step(&mut rng, (), None)?;
+        }
+        assert_eq!(Fr::from(num_steps as u32), mova.i);
+
+        // serialize the Mova Verifier params
+        let mut mova_vp_serialized = vec![];
+        mova_params
+            .1
+            .serialize_compressed(&mut mova_vp_serialized)?;
+        // deserialize the Mova Verifier params
+        let mova_vp_deserialized = M::<CS1, CS2, H>::vp_deserialize_with_mode(
+            &mut mova_vp_serialized.as_slice(),
+            ark_serialize::Compress::Yes,
+            ark_serialize::Validate::Yes,
+            (), // fcircuit_params
+        )?;
+
+        let ivc_proof = mova.ivc_proof();
+
+        // serialize IVCProof
+        let mut ivc_proof_serialized = vec![];
+        ivc_proof.serialize_compressed(&mut ivc_proof_serialized)?;
+        // deserialize IVCProof
+        let ivc_proof_deserialized = IVCProof::<Projective, Projective2>::deserialize_compressed(
+            ivc_proof_serialized.as_slice(),
+        )?;
+
+        // verify the deserialized IVCProof with the deserialized VerifierParams
+        M::<CS1, CS2, H>::verify(mova_vp_deserialized, ivc_proof_deserialized.clone())?;
+
+        // restore the IVC from the IVCProof and continue folding
+        let mut mova = M::<CS1, CS2, H>::from_ivc_proof(ivc_proof_deserialized, (), mova_params)?;
+        mova.prove_step(&mut rng, (), None)?;
+        assert_eq!(Fr::from(num_steps as u32 + 1), mova.i);
+        Ok(())
+    }
+}
@@ -1,8 +1,4 @@
 /// contains [Nova](https://eprint.iacr.org/2021/370.pdf) related circuits
-use ark_crypto_primitives::sponge::{
-    constraints::CryptographicSpongeVar,
-    poseidon::{constraints::PoseidonSpongeVar, PoseidonConfig, PoseidonSponge},
-};
 use ark_ff::PrimeField;
 use ark_r1cs_std::{
     alloc::AllocVar,
@@ -13,15 +9,10 @@ use ark_r1cs_std::{
     R1CSVar,
 };
 use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
-use ark_std::{fmt::Debug, One, Zero};
+use ark_std::{fmt::Debug, marker::PhantomData, One, Zero};
 
-use super::{
-    nifs::{
-        nova_circuits::{CommittedInstanceVar, NIFSGadget},
-        NIFSGadgetTrait,
-    },
-    CommittedInstance, NovaCycleFoldConfig,
-};
+use super::nifs::NovaNIFSGadgetTrait;
+use super::NovaCycleFoldConfig;
 use crate::folding::circuits::{
     cyclefold::{
         CycleFoldChallengeGadget, CycleFoldCommittedInstance, CycleFoldCommittedInstanceVar,
@@ -30,9 +21,9 @@ use crate::folding::circuits::{
     nonnative::{affine::NonNativeAffineVar, uint::NonNativeUintVar},
     CF1,
 };
-use crate::folding::traits::{CommittedInstanceVarOps, Dummy};
+use crate::folding::traits::{CommittedInstanceOps, CommittedInstanceVarOps, Dummy};
 use crate::frontend::FCircuit;
-use crate::transcript::AbsorbNonNativeGadget;
+use crate::transcript::{AbsorbNonNativeGadget, Transcript, TranscriptVar};
 use crate::Curve;
 
 /// `AugmentedFCircuit` enhances the original step function `F`, so that it can
@@ -41,69 +32,103 @@ use crate::Curve;
 /// The method for converting `F` to `AugmentedFCircuit` (`F'`) is defined in
 /// [Nova](https://eprint.iacr.org/2021/370.pdf), where `AugmentedFCircuit` not
 /// only invokes `F`, but also adds additional constraints for verifying the
-/// correct folding of primary instances (i.e., Nova's `CommittedInstance`s over
-/// `C1`).
+/// correct folding of primary instances (i.e., the `CommittedInstance`s over
+/// `C1` of the NIFS gadget `G`, which can be Nova's or any of its variants).
 ///
 /// Furthermore, to reduce circuit size over `C2`, we implement the constraints
 /// defined in [CycleFold](https://eprint.iacr.org/2023/1192.pdf). These extra
 /// constraints verify the correct folding of CycleFold instances.
+///
+/// The sponge `S` (and its in-circuit counterpart `T`) is used both for the hashes of the
+/// instances and for the challenges of the NIFS and CycleFold verifiers.
 #[derive(Debug, Clone)]
-pub struct AugmentedFCircuit<C1: Curve, C2: Curve, FC: FCircuit<CF1<C1>>> {
-    pub(super) poseidon_config: PoseidonConfig<CF1<C1>>,
+pub struct AugmentedFCircuit<
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<CF1<C1>>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
+    G: NovaNIFSGadgetTrait<C1, S, T>,
+> {
+    pub(super) poseidon_config: S::Config,
+    // number of constraints of the circuit, which is used to obtain the dummy values whose size
+    // depends on it (see [`NovaNIFSGadgetTrait::dummy_running_instance`])
+    pub(super) n_constraints: usize,
+    // whether the initial running instance hashed in the base case is the witness U_i, chosen by
+    // the prover, instead of the constant U_{\bot} (see [`super::zk::BlindedInstance`])
+    pub(super) blinded_base_case: bool,
     pub(super) pp_hash: Option<CF1<C1>>,
     pub(super) i: Option<CF1<C1>>,
     pub(super) i_usize: Option<usize>,
     pub(super) z_0: Option<Vec<C1::ScalarField>>,
     pub(super) z_i: Option<Vec<C1::ScalarField>>,
     pub(super) external_inputs: Option<FC::ExternalInputs>,
-    pub(super) u_i_cmW: Option<C1>,
-    pub(super) U_i: Option<CommittedInstance<C1>>,
-    pub(super) U_i1_cmE: Option<C1>,
-    pub(super) U_i1_cmW: Option<C1>,
-    pub(super) cmT: Option<C1>,
+    // only the commitments of u_i and U_i1 are used, the rest of their values are computed
+    // in-circuit
+    pub(super) u_i: Option<G::CommittedInstance>,
+    pub(super) U_i: Option<G::CommittedInstance>,
+    pub(super) U_i1: Option<G::CommittedInstance>,
+    pub(super) proof: Option<G::Proof>,
     pub(super) F: FC, // F circuit
 
     // cyclefold verifier on C1
-    // Here 'cf1, cf2' are for each of the CycleFold circuits, corresponding to the fold of cmW and
-    // cmE respectively
-    pub(super) cf1_u_i_cmW: Option<C2>, // input
-    pub(super) cf2_u_i_cmW: Option<C2>, // input
+    // Here `cf_u_i_cmW` and `cf_cmT` contain a value for each of the CycleFold circuits, eg. in
+    // Nova they correspond to the fold of cmW and cmE respectively
+    pub(super) cf_u_i_cmW: Option<Vec<C2>>, // input
     pub(super) cf_U_i: Option<CycleFoldCommittedInstance<C2>>, // input
-    pub(super) cf1_cmT: Option<C2>,
-    pub(super) cf2_cmT: Option<C2>,
+    pub(super) cf_cmT: Option<Vec<C2>>,
+    pub(super) _s: PhantomData<S>,
+    pub(super) _t: PhantomData<T>,
 }
 
-impl<C1: Curve, C2: Curve, FC: FCircuit<CF1<C1>>> AugmentedFCircuit<C1, C2, FC> {
-    pub fn empty(poseidon_config: &PoseidonConfig<CF1<C1>>, F_circuit: FC) -> Self {
+impl<C1, C2, FC, S, T, G> AugmentedFCircuit<C1, C2, FC, S, T, G>
+where
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<CF1<C1>>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
+    G: NovaNIFSGadgetTrait<C1, S, T>,
+{
+    pub fn empty(
+        poseidon_config: &S::Config,
+        F_circuit: FC,
+        n_constraints: usize,
+        blinded_base_case: bool,
+    ) -> Self {
         Self {
             poseidon_config: poseidon_config.clone(),
+            n_constraints,
+            blinded_base_case,
             pp_hash: None,
             i: None,
             i_usize: None,
             z_0: None,
             z_i: None,
             external_inputs: None,
-            u_i_cmW: None,
+            u_i: None,
             U_i: None,
-            U_i1_cmE: None,
-            U_i1_cmW: None,
-            cmT: None,
+            U_i1: None,
+            proof: None,
             F: F_circuit,
             // cyclefold values
-            cf1_u_i_cmW: None,
-            cf2_u_i_cmW: None,
+            cf_u_i_cmW: None,
             cf_U_i: None,
-            cf1_cmT: None,
-            cf2_cmT: None,
+            cf_cmT: None,
+            _s: PhantomData,
+            _t: PhantomData,
         }
     }
 }
 
-impl<C1, C2, FC> AugmentedFCircuit<C1, C2, FC>
+impl<C1, C2, FC, S, T, G> AugmentedFCircuit<C1, C2, FC, S, T, G>
 where
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
     C2: Curve,
     FC: FCircuit<CF1<C1>>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
+    G: NovaNIFSGadgetTrait<C1, S, T>,
 {
     pub fn compute_next_state(
         self,
@@ -129,31 +154,32 @@ where
             Ok(self.external_inputs.unwrap_or_default())
         })?;
 
-        let u_dummy = CommittedInstance::dummy(2);
-        let U_i = CommittedInstanceVar::<C1>::new_witness(cs.clone(), || {
+        let u_dummy = G::dummy_running_instance(self.n_constraints);
+        let U_i = G::CommittedInstanceVar::new_witness(cs.clone(), || {
             Ok(self.U_i.unwrap_or(u_dummy.clone()))
         })?;
-        let U_i1_cmE = NonNativeAffineVar::new_witness(cs.clone(), || {
-            Ok(self.U_i1_cmE.unwrap_or_else(C1::zero))
-        })?;
-        let U_i1_cmW = NonNativeAffineVar::new_witness(cs.clone(), || {
-            Ok(self.U_i1_cmW.unwrap_or_else(C1::zero))
+        let U_i1_cms = Vec::<NonNativeAffineVar<C1>>::new_witness(cs.clone(), || {
+            Ok(self.U_i1.unwrap_or(u_dummy.clone()).get_commitments())
         })?;
 
-        let cmT =
-            NonNativeAffineVar::new_witness(cs.clone(), || Ok(self.cmT.unwrap_or_else(C1::zero)))?;
+        let proof = G::ProofVar::new_witness(cs.clone(), || {
+            Ok(self
+                .proof
+                .unwrap_or_else(|| G::dummy_proof(self.n_constraints)))
+        })?;
 
         let cf_u_dummy = CycleFoldCommittedInstance::dummy(NovaCycleFoldConfig::<C1>::IO_LEN);
         let cf_U_i = CycleFoldCommittedInstanceVar::<C2>::new_witness(cs.clone(), || {
             Ok(self.cf_U_i.unwrap_or(cf_u_dummy.clone()))
         })?;
-        let cf1_cmT =
-            C2::Var::new_witness(cs.clone(), || Ok(self.cf1_cmT.unwrap_or_else(C2::zero)))?;
-        let cf2_cmT =
-            C2::Var::new_witness(cs.clone(), || Ok(self.cf2_cmT.unwrap_or_else(C2::zero)))?;
+        let cf_cmT = Vec::<C2::Var>::new_witness(cs.clone(), || {
+            Ok(self
+                .cf_cmT
+                .unwrap_or(vec![C2::zero(); G::N_CYCLEFOLD_CIRCUITS]))
+        })?;
 
         // `sponge` is for digest computation.
-        let sponge = PoseidonSpongeVar::<C1::ScalarField>::new(cs.clone(), &self.poseidon_config);
+        let sponge = T::new(cs.clone(), &self.poseidon_config);
         // `transcript` is for challenge generation.
         let mut transcript = sponge.clone();
 
@@ -166,39 +192,27 @@ where
         // u_i.x[1] = H(cf_U_i)
         let (cf_u_i_x, cf_U_i_vec) = cf_U_i.clone().hash(&sponge, pp_hash.clone())?;
 
-        // P.2. Construct u_i
-        let u_i = CommittedInstanceVar {
-            // u_i.cmE = cm(0)
-            cmE: NonNativeAffineVar::new_constant(cs.clone(), C1::zero())?,
-            // u_i.u = 1
-            u: FpVar::one(),
-            // u_i.cmW is provided by the prover as witness
-            cmW: NonNativeAffineVar::new_witness(cs.clone(), || {
-                Ok(self.u_i_cmW.unwrap_or(C1::zero()))
-            })?,
-            // u_i.x is computed in step 1
-            x: vec![u_i_x, cf_u_i_x],
-        };
+        // P.2. Construct u_i, whose commitments are provided by the prover as witnesses, and
+        // whose public inputs u_i.x are computed in step 1
+        let u_i = G::new_incoming_var(
+            cs.clone(),
+            self.u_i.as_ref().unwrap_or(&u_dummy),
+            vec![u_i_x, cf_u_i_x],
+        )?;
 
         // P.3. nifs.verify, obtains U_{i+1} by folding u_i & U_i.
-        // Notice that NIFSGadget::verify does not fold cmE & cmW.
-        // We set `U_i1.cmE` and `U_i1.cmW` to unconstrained witnesses `U_i1_cmE` and `U_i1_cmW`
-        // respectively.
+        // Notice that NIFSGadget::verify does not fold the commitments.
+        // We set the commitments of `U_i1` to the unconstrained witnesses `U_i1_cms`.
         // The correctness of them will be checked on the other curve.
-        let (mut U_i1, r_bits) = NIFSGadget::<
-            C1,
-            PoseidonSponge<C1::ScalarField>,
-            PoseidonSpongeVar<C1::ScalarField>,
-        >::verify(
+        let (mut U_i1, r_bits) = G::verify(
             &mut transcript,
             pp_hash.clone(),
             U_i.clone(),
             U_i_vec,
             u_i.clone(),
-            Some(cmT.clone()),
+            Some(proof.clone()),
         )?;
-        U_i1.cmE = U_i1_cmE;
-        U_i1.cmW = U_i1_cmW;
+        G::set_commitments(&mut U_i1, U_i1_cms)?;
 
         // convert r_bits to a `NonNativeFieldVar`
         let r_nonnat = {
@@ -215,8 +229,8 @@ where
             .F
             .generate_step_constraints(cs.clone(), i_usize, z_i, external_inputs)?;
 
-        // Base case: u_{i+1}.x[0] == H((i+1, z_0, z_{i+1}, U_{\bot})
-        // Non-base case: u_{i+1}.x[0] == H((i+1, z_0, z_{i+1}, U_{i+1})
+        // Base case: u_{i+1}.x[0] == H(i+1, z_0, z_{i+1}, U_{\bot})
+        // Non-base case: u_{i+1}.x[0] == H(i+1, z_0, z_{i+1}, U_{i+1})
         let (u_i1_x, _) = U_i1.clone().hash(
             &sponge,
             &pp_hash,
@@ -224,13 +238,18 @@ where
             &z_0,
             &z_i1,
         )?;
-        let (u_i1_x_base, _) = CommittedInstanceVar::new_constant(cs.clone(), u_dummy)?.hash(
-            &sponge,
-            &pp_hash,
-            &FpVar::<CF1<C1>>::one(),
-            &z_0,
-            &z_i1,
-        )?;
+        let U_base = if self.blinded_base_case {
+            // In the blinded base case, U_{\bot} is replaced by the witness U_i, so the prover can
+            // choose it to be a random satisfying instance (see `crate::folding::nova::zk`). This
+            // does not affect soundness, since U_i becomes the running instance U_1, which is
+            // folded into all the following running instances: if it is not satisfiable, neither
+            // is the last running instance, and the IVC verifier (or the Decider) rejects it.
+            U_i.clone()
+        } else {
+            G::CommittedInstanceVar::new_constant(cs.clone(), u_dummy)?
+        };
+        let (u_i1_x_base, _) =
+            U_base.hash(&sponge, &pp_hash, &FpVar::<CF1<C1>>::one(), &z_0, &z_i1)?;
         let x = is_basecase.select(&u_i1_x_base, &u_i1_x)?;
         // This line "converts" `x` from a witness to a public input.
         // Instead of directly modifying the constraint system, we explicitly
@@ -244,72 +263,62 @@ where
         FpVar::new_input(cs.clone(), || x.value())?.enforce_equal(&x)?;
 
         // CycleFold part
-        // C.1. Compute cf1_u_i.x and cf2_u_i.x
-        let cfW_x = vec![
-            r_nonnat.clone(),
-            U_i.cmW.x,
-            U_i.cmW.y,
-            u_i.cmW.x,
-            u_i.cmW.y,
-            U_i1.cmW.x,
-            U_i1.cmW.y,
-        ];
-        let cfE_x = vec![
-            r_nonnat, U_i.cmE.x, U_i.cmE.y, cmT.x, cmT.y, U_i1.cmE.x, U_i1.cmE.y,
-        ];
-
-        // ensure that cf1_u & cf2_u have as public inputs the cmW & cmE from main instances U_i,
-        // u_i, U_i+1 coordinates of the commitments
-        // C.2. Construct `cf1_u_i` and `cf2_u_i`
-        let cf1_u_i = CycleFoldCommittedInstanceVar {
-            // cf1_u_i.cmE = 0
-            cmE: C2::Var::zero(),
-            // cf1_u_i.u = 1
-            u: NonNativeUintVar::new_constant(cs.clone(), C1::BaseField::one())?,
-            // cf1_u_i.cmW is provided by the prover as witness
-            cmW: C2::Var::new_witness(cs.clone(), || Ok(self.cf1_u_i_cmW.unwrap_or(C2::zero())))?,
-            // cf1_u_i.x is computed in step 1
-            x: cfW_x,
-        };
-        let cf2_u_i = CycleFoldCommittedInstanceVar {
-            // cf2_u_i.cmE = 0
-            cmE: C2::Var::zero(),
-            // cf2_u_i.u = 1
-            u: NonNativeUintVar::new_constant(cs.clone(), C1::BaseField::one())?,
-            // cf2_u_i.cmW is provided by the prover as witness
-            cmW: C2::Var::new_witness(cs.clone(), || Ok(self.cf2_u_i_cmW.unwrap_or(C2::zero())))?,
-            // cf2_u_i.x is computed in step 1
-            x: cfE_x,
-        };
-
-        // C.3. nifs.verify, obtains cf1_U_{i+1} by folding cf1_u_i & cf_U_i, and then cf_U_{i+1}
-        // by folding cf2_u_i & cf1_U_{i+1}.
-
-        // compute cf1_r = H(cf1_u_i, cf_U_i, cf1_cmT)
-        // cf_r_bits is denoted by rho* in the paper.
-        let cf1_r_bits = CycleFoldChallengeGadget::<C2>::get_challenge_gadget(
-            &mut transcript,
-            pp_hash.clone(),
-            cf_U_i_vec,
-            cf1_u_i.clone(),
-            cf1_cmT.clone(),
-        )?;
-        // Fold cf1_u_i & cf_U_i into cf1_U_{i+1}
-        let cf1_U_i1 =
-            NIFSFullGadget::<C2>::fold_committed_instance(cf1_r_bits, cf1_cmT, cf_U_i, cf1_u_i)?;
+        let cf_u_i_cmW = Vec::<C2::Var>::new_witness(cs.clone(), || {
+            Ok(self
+                .cf_u_i_cmW
+                .unwrap_or(vec![C2::zero(); G::N_CYCLEFOLD_CIRCUITS]))
+        })?;
 
-        // same for cf2_r:
-        let cf2_r_bits = CycleFoldChallengeGadget::<C2>::get_challenge_gadget(
-            &mut transcript,
-            pp_hash.clone(),
-            cf1_U_i1.to_native_sponge_field_elements()?,
-            cf2_u_i.clone(),
-            cf2_cmT.clone(),
-        )?;
-        let cf_U_i1 = NIFSFullGadget::<C2>::fold_committed_instance(
-            cf2_r_bits, cf2_cmT, cf1_U_i1, // the output from NIFS.V(cf1_r, cf_U, cfE_u)
-            cf2_u_i,
-        )?;
+        // The CycleFold instances are folded sequentially into the CycleFold running instance,
+        // starting from cf_U_i, whose sponge representation was computed in step P.1.
+        let mut cf_U_i1 = cf_U_i;
+        let mut cf_U_i1_vec = Some(cf_U_i_vec);
+        for ((points, cf_u_i_cmW), cf_cmT) in G::cyclefold_points_var(&U_i, &u_i, &proof, &U_i1)
+            .into_iter()
+            .zip(cf_u_i_cmW)
+            .zip(cf_cmT)
+        {
+            // C.1. Compute cf_u_i.x, ensuring that cf_u_i has as public inputs the challenge r
+            // and the coordinates of the commitments from the main instances U_i, u_i, U_i+1
+            // that are folded in the CycleFold circuit
+            let cf_x = [
+                vec![r_nonnat.clone()],
+                points.into_iter().flat_map(|p| [p.x, p.y]).collect(),
+            ]
+            .concat();
+
+            // C.2. Construct cf_u_i
+            let cf_u_i = CycleFoldCommittedInstanceVar {
+                // cf_u_i.cmE = 0
+                cmE: C2::Var::zero(),
+                // cf_u_i.u = 1
+                u: NonNativeUintVar::new_constant(cs.clone(), C1::BaseField::one())?,
+                // cf_u_i.cmW is provided by the prover as witness
+                cmW: cf_u_i_cmW,
+                // cf_u_i.x is computed in step C.1
+                x: cf_x,
+            };
+
+            // C.3. nifs.verify, obtains the next cf_U_{i+1} by folding cf_u_i & the current
+            // cf_U_{i+1}.
+
+            // compute cf_r = H(cf_u_i, cf_U_{i+1}, cf_cmT)
+            // cf_r_bits is denoted by rho* in the paper.
+            let cf_U_vec = match cf_U_i1_vec.take() {
+                Some(cf_U_vec) => cf_U_vec,
+                None => cf_U_i1.to_native_sponge_field_elements()?,
+            };
+            let cf_r_bits = CycleFoldChallengeGadget::<C2>::get_challenge_gadget(
+                &mut transcript,
+                pp_hash.clone(),
+                cf_U_vec,
+                cf_u_i.clone(),
+                cf_cmT.clone(),
+            )?;
+            // Fold cf_u_i & cf_U_{i+1} into the next cf_U_{i+1}
+            cf_U_i1 =
+                NIFSFullGadget::<C2>::fold_committed_instance(cf_r_bits, cf_cmT, cf_U_i1, cf_u_i)?;
+        }
 
         // Back to Primary Part
         // P.4.b compute and check the second output of F'
@@ -335,11 +344,14 @@ where
     }
 }
 
-impl<C1, C2, FC> ConstraintSynthesizer<CF1<C1>> for AugmentedFCircuit<C1, C2, FC>
+impl<C1, C2, FC, S, T, G> ConstraintSynthesizer<CF1<C1>> for AugmentedFCircuit<C1, C2, FC, S, T, G>
 where
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
     C2: Curve,
     FC: FCircuit<CF1<C1>>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
+    G: NovaNIFSGadgetTrait<C1, S, T>,
 {
     fn generate_constraints(self, cs: ConstraintSystemRef<CF1<C1>>) -> Result<(), SynthesisError> {
         self.compute_next_state(cs).map(|_| ())
@@ -351,14 +363,19 @@ pub mod tests {
     use super::*;
     use ark_bn254::{Fr, G1Projective as Projective};
     use ark_crypto_primitives::sponge::{
-        constraints::AbsorbGadget, poseidon::PoseidonSponge, CryptographicSponge,
+        constraints::{AbsorbGadget, CryptographicSpongeVar},
+        poseidon::{constraints::PoseidonSpongeVar, PoseidonSponge},
+        CryptographicSponge,
     };
     use ark_ff::BigInteger;
 
     use ark_relations::r1cs::ConstraintSystem;
     use ark_std::UniformRand;
 
-    use crate::folding::nova::nifs::nova::ChallengeGadget;
+    use crate::folding::nova::{
+        nifs::{nova::ChallengeGadget, nova_circuits::CommittedInstanceVar},
+        CommittedInstance,
+    };
     use crate::transcript::poseidon::poseidon_canonical_config;
     use crate::Error;
 
@@ -9,9 +9,12 @@ use ark_std::rand::{CryptoRng, RngCore};
 use ark_std::{One, Zero};
 use core::marker::PhantomData;
 
-use super::decider_circuits::{DeciderCircuit1, DeciderCircuit2};
-use super::decider_eth_circuit::DeciderNovaGadget;
-use super::Nova;
+use super::decider_circuits::{DeciderCircuit1, DeciderCircuit2, RandomizedDeciderCircuit1};
+use super::decider_eth_circuit::{
+    DeciderNovaGadget, DeciderRandomizedNovaGadget, RandomizedFoldingProof,
+};
+use super::{zk::RandomizedNova, Nova};
+use crate::arith::Arith;
 use crate::commitment::CommitmentScheme;
 use crate::folding::circuits::cyclefold::CycleFoldCommittedInstance;
 use crate::folding::circuits::decider::DeciderEnabledNIFS;
@@ -49,6 +52,32 @@ where
     cs2_challenges: [C2::ScalarField; 2],
 }
 
+/// Decider proof of a [`RandomizedNova`], see [`Decider::prove_randomized`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RandomizedProof<C1, C2, CS1, CS2, S1, S2>
+where
+    C1: Curve,
+    C2: Curve,
+    CS1: CommitmentScheme<C1, true>,
+    CS2: CommitmentScheme<C2, true>,
+    S1: SNARK<C1::ScalarField>,
+    S2: SNARK<C2::ScalarField>,
+{
+    c1_snark_proof: S1::Proof,
+    c2_snark_proof: S2::Proof,
+    cs1_proofs: [CS1::Proof; 2],
+    cs2_proofs: [CS2::Proof; 2],
+    // folding proof and challenges of the two last folds, U_f=NIFS.V(r, U_i, u_i, cmT) and
+    // U_{i+1}=NIFS.V(r', U_f, U_r, cmT'), which are checked in-circuit
+    folding_proof: RandomizedFoldingProof<C1>,
+    r: C1::ScalarField,
+    r_prime: C1::ScalarField,
+    // cyclefold committed instance
+    cf_U_final: CycleFoldCommittedInstance<C2>,
+    cs1_challenges: [C1::ScalarField; 2],
+    cs2_challenges: [C2::ScalarField; 2],
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
 pub struct ProverParam<CS1_ProvingKey, S1_ProvingKey, CS2_ProvingKey, S2_ProvingKey>
 where
@@ -327,6 +356,251 @@ where
     }
 }
 
+/// Decider for the randomized Nova instances of the zk layer (Use-case-2 in [`super::zk`]), which
+/// allows to delegate the Decider proof generation to an untrusted server, since it only receives
+/// the blinded witness of the randomized instance.
+impl<C1, C2, FC, CS1, CS2, S1, S2, FS> Decider<C1, C2, FC, CS1, CS2, S1, S2, FS>
+where
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<
+        C1,
+        true,
+        ProverChallenge = C1::ScalarField,
+        Challenge = C1::ScalarField,
+        Proof = crate::commitment::kzg::Proof<C1>,
+    >,
+    CS2: CommitmentScheme<
+        C2,
+        true,
+        ProverChallenge = C2::ScalarField,
+        Challenge = C2::ScalarField,
+        Proof = crate::commitment::kzg::Proof<C2>,
+    >,
+    S1: SNARK<C1::ScalarField>,
+    S2: SNARK<C2::ScalarField>,
+    FS: FoldingScheme<C1, C2, FC>,
+    // constrain FS into Nova, since this is a Decider specifically for Nova
+    Nova<C1, C2, FC, CS1, CS2, true>: From<FS>,
+    crate::folding::nova::ProverParams<C1, C2, CS1, CS2, true>:
+        From<<FS as FoldingScheme<C1, C2, FC>>::ProverParam>,
+    crate::folding::nova::VerifierParams<C1, C2, CS1, CS2, true>:
+        From<<FS as FoldingScheme<C1, C2, FC>>::VerifierParam>,
+{
+    /// Same as `preprocess`, but for the RandomizedDeciderCircuit1.
+    #[allow(clippy::type_complexity)]
+    pub fn preprocess_randomized(
+        mut rng: impl RngCore + CryptoRng,
+        ((pp, vp), state_len): ((FS::ProverParam, FS::VerifierParam), usize),
+    ) -> Result<
+        (
+            ProverParam<CS1::ProverParams, S1::ProvingKey, CS2::ProverParams, S2::ProvingKey>,
+            VerifierParam<
+                C1,
+                CS1::VerifierParams,
+                S1::VerifyingKey,
+                CS2::VerifierParams,
+                S2::VerifyingKey,
+            >,
+        ),
+        Error,
+    > {
+        // get the FoldingScheme prover & verifier params from Nova
+        let nova_pp: <Nova<C1, C2, FC, CS1, CS2, true> as FoldingScheme<C1, C2, FC>>::ProverParam =
+            pp.into();
+        let nova_vp: <Nova<C1, C2, FC, CS1, CS2, true> as FoldingScheme<
+            C1,
+            C2,
+            FC,
+        >>::VerifierParam = vp.into();
+        let pp_hash = nova_vp.pp_hash()?;
+
+        let io_len = nova_vp.r1cs.n_public_inputs();
+        let circuit1 = RandomizedDeciderCircuit1::<C1, C2>::dummy((
+            nova_vp.r1cs,
+            &nova_vp.cf_r1cs,
+            nova_vp.poseidon_config,
+            io_len, // U_r's public inputs length
+            (),
+            state_len,
+            2, // Nova's running CommittedInstance contains 2 commitments
+        ));
+        let circuit2 = DeciderCircuit2::<C2>::dummy((
+            nova_vp.cf_r1cs,
+            poseidon_canonical_config::<C2::ScalarField>(),
+            2, // Nova's running CommittedInstance contains 2 commitments
+        ));
+
+        // get the Groth16 specific setup for the circuits
+        let (c1_g16_pk, c1_g16_vk) = S1::circuit_specific_setup(circuit1, &mut rng)
+            .map_err(|e| Error::SNARKSetupFail(e.to_string()))?;
+        let (c2_g16_pk, c2_g16_vk) = S2::circuit_specific_setup(circuit2, &mut rng)
+            .map_err(|e| Error::SNARKSetupFail(e.to_string()))?;
+
+        let pp = ProverParam {
+            c1_snark_pp: c1_g16_pk,
+            c1_cs_pp: nova_pp.cs_pp,
+            c2_snark_pp: c2_g16_pk,
+            c2_cs_pp: nova_pp.cf_cs_pp,
+        };
+        let vp = VerifierParam {
+            pp_hash,
+            c1_snark_vp: c1_g16_vk,
+            c1_cs_vp: nova_vp.cs_vp,
+            c2_snark_vp: c2_g16_vk,
+            c2_cs_vp: nova_vp.cf_cs_vp,
+        };
+        Ok((pp, vp))
+    }
+
+    /// Same as `prove`, but for a [`RandomizedNova`], so that the prover does not learn the
+    /// unblinded witnesses of the IVC.
+    pub fn prove_randomized(
+        mut rng: impl RngCore + CryptoRng,
+        pp: ProverParam<CS1::ProverParams, S1::ProvingKey, CS2::ProverParams, S2::ProvingKey>,
+        randomized_nova: RandomizedNova<C1, C2, FC, CS1, CS2>,
+    ) -> Result<RandomizedProof<C1, C2, CS1, CS2, S1, S2>, Error> {
+        let circuit2 = DeciderCircuit2::<C2>::try_from(randomized_nova.nova.clone())?;
+        let circuit1 = RandomizedDeciderCircuit1::<C1, C2>::try_from(randomized_nova)?;
+
+        let folding_proof = circuit1.proof.clone();
+        let (r, r_prime) = circuit1.randomness;
+        let cf_U_final = circuit1.cf_U_i.clone();
+
+        let c1_kzg_challenges = circuit1.kzg_challenges.clone();
+        let c1_kzg_proofs = circuit1
+            .W_i1
+            .get_openings()
+            .iter()
+            .zip(&c1_kzg_challenges)
+            .map(|((v, r), &c)| CS1::prove_with_challenge(&pp.c1_cs_pp, c, v, r, Some(&mut rng)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let c2_kzg_challenges = circuit2.kzg_challenges.clone();
+        let c2_kzg_proofs = circuit2
+            .cf_W_i
+            .get_openings()
+            .iter()
+            .zip(&c2_kzg_challenges)
+            .map(|((v, r), &c)| CS2::prove_with_challenge(&pp.c2_cs_pp, c, v, r, Some(&mut rng)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let c1_snark_proof = S1::prove(&pp.c1_snark_pp, circuit1, &mut rng)
+            .map_err(|e| Error::Other(e.to_string()))?;
+        let c2_snark_proof = S2::prove(&pp.c2_snark_pp, circuit2, &mut rng)
+            .map_err(|e| Error::Other(e.to_string()))?;
+
+        Ok(RandomizedProof {
+            c1_snark_proof,
+            c2_snark_proof,
+            cs1_proofs: c1_kzg_proofs
+                .try_into()
+                .map_err(|e: Vec<_>| Error::NotExpectedLength(e.len(), 2))?,
+            cs2_proofs: c2_kzg_proofs
+                .try_into()
+                .map_err(|e: Vec<_>| Error::NotExpectedLength(e.len(), 2))?,
+            folding_proof,
+            r,
+            r_prime,
+            cf_U_final,
+            cs1_challenges: c1_kzg_challenges
+                .try_into()
+                .map_err(|e: Vec<_>| Error::NotExpectedLength(e.len(), 2))?,
+            cs2_challenges: c2_kzg_challenges
+                .try_into()
+                .map_err(|e: Vec<_>| Error::NotExpectedLength(e.len(), 2))?,
+        })
+    }
+
+    /// Same as `verify`, but for the proofs generated by `prove_randomized`.
+    pub fn verify_randomized(
+        vp: VerifierParam<
+            C1,
+            CS1::VerifierParams,
+            S1::VerifyingKey,
+            CS2::VerifierParams,
+            S2::VerifyingKey,
+        >,
+        i: C1::ScalarField,
+        z_0: Vec<C1::ScalarField>,
+        z_i: Vec<C1::ScalarField>,
+        running_commitments: &[C1],
+        incoming_commitments: &[C1],
+        proof: &RandomizedProof<C1, C2, CS1, CS2, S1, S2>,
+    ) -> Result<bool, Error> {
+        if i <= C1::ScalarField::one() {
+            return Err(Error::NotEnoughSteps);
+        }
+
+        // 6.2. Fold the commitments, including the ones of the random instance U_r
+        let U_final_commitments = DeciderRandomizedNovaGadget::fold_group_elements_native(
+            running_commitments,
+            incoming_commitments,
+            Some(proof.folding_proof.clone()),
+            (proof.r, proof.r_prime),
+        )?;
+        let cf_U = proof.cf_U_final.clone();
+
+        // snark proof 1
+        let c1_public_input = [
+            &[vp.pp_hash, i][..],
+            &z_0,
+            &z_i,
+            &U_final_commitments.inputize_nonnative(),
+            &cf_U.inputize_nonnative(),
+            &proof.cs1_challenges,
+            &proof.cs1_proofs.iter().map(|p| p.eval).collect::<Vec<_>>(),
+            &proof.folding_proof.inputize(),
+        ]
+        .concat();
+
+        let c1_snark_v = S1::verify(&vp.c1_snark_vp, &c1_public_input, &proof.c1_snark_proof)
+            .map_err(|e| Error::Other(e.to_string()))?;
+        if !c1_snark_v {
+            return Err(Error::SNARKVerificationFail);
+        }
+
+        // snark proof 2
+        // migrate pp_hash from C1::Fr to C1::Fq
+        let pp_hash_Fq =
+            C2::ScalarField::from_le_bytes_mod_order(&vp.pp_hash.into_bigint().to_bytes_le());
+        let c2_public_input: Vec<C2::ScalarField> = [
+            &[pp_hash_Fq][..],
+            &cf_U.inputize(),
+            &proof.cs2_challenges,
+            &proof.cs2_proofs.iter().map(|p| p.eval).collect::<Vec<_>>(),
+        ]
+        .concat();
+
+        let c2_snark_v = S2::verify(&vp.c2_snark_vp, &c2_public_input, &proof.c2_snark_proof)
+            .map_err(|e| Error::Other(e.to_string()))?;
+        if !c2_snark_v {
+            return Err(Error::SNARKVerificationFail);
+        }
+
+        // 7.3. check C1 commitments (main instance commitments)
+        for ((cm, &c), pi) in U_final_commitments
+            .iter()
+            .zip(&proof.cs1_challenges)
+            .zip(&proof.cs1_proofs)
+        {
+            CS1::verify_with_challenge(&vp.c1_cs_vp, c, cm, pi)?;
+        }
+
+        // 4.3. check C2 commitments (CycleFold instance commitments)
+        for ((cm, &c), pi) in cf_U
+            .get_commitments()
+            .iter()
+            .zip(&proof.cs2_challenges)
+            .zip(&proof.cs2_proofs)
+        {
+            CS2::verify_with_challenge(&vp.c2_cs_vp, c, cm, pi)?;
+        }
+
+        Ok(true)
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use ark_groth16::Groth16;
@@ -339,7 +613,7 @@ pub mod tests {
 
     use super::*;
     use crate::commitment::kzg::KZG;
-    use crate::folding::nova::PreprocessorParam;
+    use crate::folding::nova::{PreprocessorParam, Witness};
     use crate::frontend::utils::CubicFCircuit;
     use crate::transcript::poseidon::poseidon_canonical_config;
 
@@ -412,4 +686,72 @@ pub mod tests {
         println!("Decider verify, {:?}", start.elapsed());
         Ok(())
     }
+
+    // Tests the Decider for the randomized Nova instances, where the Decider prover only gets the
+    // RandomizedNova (which does not contain the unblinded witnesses) from the user
+    #[test]
+    fn test_decider_randomized() -> Result<(), Error> {
+        // use Nova as FoldingScheme
+        type N = Nova<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            KZG<'static, MNT4, true>,
+            KZG<'static, MNT6, true>,
+            true,
+        >;
+        type D = Decider<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            KZG<'static, MNT4, true>,
+            KZG<'static, MNT6, true>,
+            Groth16<MNT4>,
+            Groth16<MNT6>,
+            N, // here we define the FoldingScheme to use
+        >;
+
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let z_0 = vec![Fr::from(3_u32)];
+
+        let prep_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        let nova_params = N::preprocess(&mut rng, &prep_param)?;
+
+        let mut nova = N::init(&nova_params, F_circuit, z_0)?;
+        nova.prove_step(&mut rng, (), None)?;
+        nova.prove_step(&mut rng, (), None)?;
+
+        let mut rng = rand::rngs::OsRng;
+
+        // prepare the Decider prover & verifier params
+        let (decider_pp, decider_vp) =
+            D::preprocess_randomized(&mut rng, (nova_params, F_circuit.state_len()))?;
+
+        // the user randomizes the Nova instance, and sends it to the Decider prover
+        let randomized_nova = RandomizedNova::new(&nova, &mut rng)?;
+        let dummy_witness = Witness::<Projective>::dummy(&nova.r1cs);
+        assert_eq!(randomized_nova.nova.W_i, dummy_witness);
+        assert_eq!(randomized_nova.nova.w_i, dummy_witness);
+
+        // decider proof generation
+        let start = Instant::now();
+        let proof = D::prove_randomized(rng, decider_pp, randomized_nova)?;
+        println!("Decider prove, {:?}", start.elapsed());
+
+        // decider proof verification
+        let verified = D::verify_randomized(
+            decider_vp,
+            nova.i,
+            nova.z_0,
+            nova.z_i,
+            &nova.U_i.get_commitments(),
+            &nova.u_i.get_commitments(),
+            &proof,
+        )?;
+        assert!(verified);
+        Ok(())
+    }
 }
@@ -8,8 +8,9 @@ use ark_r1cs_std::fields::fp::FpVar;
 use core::marker::PhantomData;
 
 use super::{
-    decider_eth_circuit::DeciderNovaGadget,
+    decider_eth_circuit::{DeciderNovaGadget, DeciderRandomizedNovaGadget},
     nifs::{nova::NIFS, NIFSTrait},
+    zk::RandomizedNova,
     CommittedInstance, Nova, Witness,
 };
 use crate::folding::{circuits::CF1, traits::WitnessOps};
@@ -101,6 +102,72 @@ impl<
     }
 }
 
+/// Same as [`DeciderCircuit1`], but for the randomized Nova instances of the zk layer (see
+/// [`RandomizedNova`]), so that the Decider prover only receives the blinded witness.
+pub type RandomizedDeciderCircuit1<C1, C2> = GenericOffchainDeciderCircuit1<
+    C1,
+    C2,
+    CommittedInstance<C1>,
+    CommittedInstance<C1>,
+    Witness<C1>,
+    R1CS<CF1<C1>>,
+    R1CSMatricesVar<CF1<C1>, FpVar<CF1<C1>>>,
+    DeciderRandomizedNovaGadget,
+>;
+
+impl<
+        C1: Curve,
+        C2: Curve,
+        FC: FCircuit<C1::ScalarField>,
+        CS1: CommitmentScheme<C1, true>,
+        CS2: CommitmentScheme<C2, true>,
+    > TryFrom<RandomizedNova<C1, C2, FC, CS1, CS2>> for RandomizedDeciderCircuit1<C1, C2>
+{
+    type Error = Error;
+
+    fn try_from(randomized_nova: RandomizedNova<C1, C2, FC, CS1, CS2>) -> Result<Self, Error> {
+        // compute the randomized U_{i+1}, the witness W_{i+1} is provided by the RandomizedNova
+        let (U_i1, proof, randomness, mut transcript) = randomized_nova.fold_instances()?;
+        let RandomizedNova {
+            nova,
+            W_i_prime: W_i1,
+            ..
+        } = randomized_nova;
+
+        // compute the KZG challenges used as inputs in the circuit
+        let kzg_challenges = KZGChallengesGadget::get_challenges_native(&mut transcript, &U_i1);
+
+        // get KZG evals
+        let kzg_evaluations = W_i1
+            .get_openings()
+            .iter()
+            .zip(&kzg_challenges)
+            .map(|((v, _), &c)| EvalGadget::evaluate_native(v, c))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            _avar: PhantomData,
+            arith: nova.r1cs,
+            poseidon_config: nova.poseidon_config,
+            pp_hash: nova.pp_hash,
+            i: nova.i,
+            z_0: nova.z_0,
+            z_i: nova.z_i,
+            U_i: nova.U_i,
+            W_i: nova.W_i,
+            u_i: nova.u_i,
+            w_i: nova.w_i,
+            U_i1,
+            W_i1,
+            proof,
+            randomness,
+            cf_U_i: nova.cf_U_i,
+            kzg_challenges,
+            kzg_evaluations,
+        })
+    }
+}
+
 /// Circuit that implements part of the in-circuit checks needed for the offchain verification over
 /// the Curve1's BaseField (=Curve2's ScalarField).
 pub type DeciderCircuit2<C2> = GenericOffchainDeciderCircuit2<C2>;
@@ -150,6 +217,23 @@ impl<
     }
 }
 
+/// The CycleFold instance-witness pair of a RandomizedNova is the one of the original Nova, so the
+/// DeciderCircuit2 is the same for both.
+impl<
+        C1: Curve,
+        C2: Curve,
+        FC: FCircuit<C1::ScalarField>,
+        CS1: CommitmentScheme<C1, true>,
+        CS2: CommitmentScheme<C2, true>,
+    > TryFrom<RandomizedNova<C1, C2, FC, CS1, CS2>> for DeciderCircuit2<C2>
+{
+    type Error = Error;
+
+    fn try_from(randomized_nova: RandomizedNova<C1, C2, FC, CS1, CS2>) -> Result<Self, Error> {
+        Self::try_from(randomized_nova.nova)
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use ark_pallas::{Fq, Fr, Projective};
@@ -3,21 +3,26 @@
 /// More details can be found at the documentation page:
 /// https://privacy-scaling-explorations.github.io/sonobe-docs/design/nova-decider-onchain.html
 use ark_bn254::Bn254;
-use ark_groth16::Groth16;
+use ark_crypto_primitives::sponge::CryptographicSponge;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
-use ark_snark::SNARK;
+use ark_snark::{UniversalSetupSNARK, SNARK};
 use ark_std::{
     rand::{CryptoRng, RngCore},
     One, Zero,
 };
 use core::marker::PhantomData;
 
-pub use super::decider_eth_circuit::DeciderEthCircuit;
-use super::decider_eth_circuit::DeciderNovaGadget;
-use super::{CommittedInstance, Nova};
+pub use super::decider_eth_circuit::{DeciderEthCircuit, RandomizedDeciderEthCircuit};
+use super::decider_eth_circuit::{
+    DeciderNovaGadget, DeciderRandomizedNovaGadget, RandomizedFoldingProof,
+};
+use super::{zk::RandomizedNova, CommittedInstance, Nova};
+use crate::arith::Arith;
 use crate::folding::circuits::decider::DeciderEnabledNIFS;
-use crate::folding::traits::{InputizeNonNative, WitnessOps};
+use crate::folding::traits::{Inputize, InputizeNonNative, WitnessOps};
 use crate::frontend::FCircuit;
+use crate::snark::index_error;
+use crate::transcript::keccak::KeccakTranscript;
 use crate::utils::eth::ToEth;
 use crate::{
     commitment::{
@@ -48,6 +53,24 @@ where
     kzg_challenges: [C::ScalarField; 2],
 }
 
+/// Decider proof of a [`RandomizedNova`], see [`Decider::prove_randomized`].
+#[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct RandomizedProof<C, CS, S>
+where
+    C: Curve,
+    CS: CommitmentScheme<C, true, ProverChallenge = C::ScalarField, Challenge = C::ScalarField>,
+    S: SNARK<C::ScalarField>,
+{
+    snark_proof: S::Proof,
+    kzg_proofs: [CS::Proof; 2],
+    // folding proof and challenges of the two last folds, U_f=NIFS.V(r, U_i, u_i, cmT) and
+    // U_{i+1}=NIFS.V(r', U_f, U_r, cmT'), which are checked in-circuit
+    folding_proof: RandomizedFoldingProof<C>,
+    r: C::ScalarField,
+    r_prime: C::ScalarField,
+    kzg_challenges: [C::ScalarField; 2],
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
 pub struct VerifierParam<C1, CS_VerifyingKey, S_VerifyingKey>
 where
@@ -230,31 +253,288 @@ where
             return Err(Error::SNARKVerificationFail);
         }
 
-        // 7.3. Verify the KZG proofs
-        for ((cm, &c), pi) in U_final_commitments
+        // 7.3. Verify the KZG proofs, batched into a single pairing check whose random linear
+        // combination is taken from a Keccak transcript, as done by the Solidity verifier
+        CS1::batch_verify_with_challenges(
+            &cs_vp,
+            &mut KeccakTranscript::new(&()),
+            &proof.kzg_challenges,
+            &U_final_commitments,
+            &proof.kzg_proofs,
+        )?;
+
+        Ok(true)
+    }
+}
+
+/// Decider for the randomized Nova instances of the zk layer (Use-case-2 in [`super::zk`]), which
+/// allows to delegate the Decider proof generation to an untrusted server, since it only receives
+/// the blinded witness of the randomized instance.
+impl<C1, C2, FC, CS1, CS2, S, FS> Decider<C1, C2, FC, CS1, CS2, S, FS>
+where
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    // CS1 is a KZG commitment, where challenge is C1::Fr elem
+    CS1: CommitmentScheme<
+        C1,
+        true,
+        ProverChallenge = C1::ScalarField,
+        Challenge = C1::ScalarField,
+        Proof = KZGProof<C1>,
+    >,
+    // enforce that the CS2 is Pedersen commitment scheme, since we're at Ethereum's EVM decider
+    CS2: CommitmentScheme<C2, true, ProverParams = PedersenParams<C2>>,
+    S: SNARK<C1::ScalarField>,
+    FS: FoldingScheme<C1, C2, FC>,
+    // constrain FS into Nova, since this is a Decider specifically for Nova
+    Nova<C1, C2, FC, CS1, CS2, true>: From<FS>,
+    crate::folding::nova::ProverParams<C1, C2, CS1, CS2, true>:
+        From<<FS as FoldingScheme<C1, C2, FC>>::ProverParam>,
+    crate::folding::nova::VerifierParams<C1, C2, CS1, CS2, true>:
+        From<<FS as FoldingScheme<C1, C2, FC>>::VerifierParam>,
+{
+    /// Same as `preprocess`, but for the RandomizedDeciderEthCircuit.
+    #[allow(clippy::type_complexity)]
+    pub fn preprocess_randomized(
+        mut rng: impl RngCore + CryptoRng,
+        ((pp, vp), state_len): ((FS::ProverParam, FS::VerifierParam), usize),
+    ) -> Result<
+        (
+            (S::ProvingKey, CS1::ProverParams),
+            VerifierParam<C1, CS1::VerifierParams, S::VerifyingKey>,
+        ),
+        Error,
+    > {
+        // get the FoldingScheme prover & verifier params from Nova
+        let nova_pp: <Nova<C1, C2, FC, CS1, CS2, true> as FoldingScheme<C1, C2, FC>>::ProverParam =
+            pp.into();
+        let nova_vp: <Nova<C1, C2, FC, CS1, CS2, true> as FoldingScheme<
+                    C1,
+                    C2,
+                    FC,
+                >>::VerifierParam = vp.into();
+
+        let pp_hash = nova_vp.pp_hash()?;
+
+        let io_len = nova_vp.r1cs.n_public_inputs();
+        let circuit = RandomizedDeciderEthCircuit::<C1, C2>::dummy((
+            nova_vp.r1cs,
+            nova_vp.cf_r1cs,
+            nova_pp.cf_cs_pp,
+            nova_pp.poseidon_config,
+            io_len, // U_r's public inputs length
+            (),
+            state_len,
+            2, // Nova's running CommittedInstance contains 2 commitments
+        ));
+
+        // get the Groth16 specific setup for the circuit
+        let (g16_pk, g16_vk) = S::circuit_specific_setup(circuit, &mut rng)
+            .map_err(|e| Error::SNARKSetupFail(e.to_string()))?;
+
+        let pp = (g16_pk, nova_pp.cs_pp);
+        let vp = VerifierParam {
+            pp_hash,
+            snark_vp: g16_vk,
+            cs_vp: nova_vp.cs_vp,
+        };
+        Ok((pp, vp))
+    }
+
+    /// Same as `prove`, but for a [`RandomizedNova`], so that the prover does not learn the
+    /// unblinded witnesses of the IVC.
+    pub fn prove_randomized(
+        mut rng: impl RngCore + CryptoRng,
+        pp: (S::ProvingKey, CS1::ProverParams),
+        randomized_nova: RandomizedNova<C1, C2, FC, CS1, CS2>,
+    ) -> Result<RandomizedProof<C1, CS1, S>, Error> {
+        let (snark_pk, cs_pk): (S::ProvingKey, CS1::ProverParams) = pp;
+
+        let circuit = RandomizedDeciderEthCircuit::<C1, C2>::try_from(randomized_nova)?;
+
+        let folding_proof = circuit.proof.clone();
+        let (r, r_prime) = circuit.randomness;
+
+        // get the challenges that have been already computed when preparing the circuit inputs in
+        // the above `try_from` call
+        let kzg_challenges = circuit.kzg_challenges.clone();
+
+        // generate KZG proofs
+        let kzg_proofs = circuit
+            .W_i1
+            .get_openings()
             .iter()
-            .zip(&proof.kzg_challenges)
-            .zip(&proof.kzg_proofs)
-        {
-            // we're at the Ethereum EVM case, so the CS1 is KZG commitments
-            CS1::verify_with_challenge(&cs_vp, c, cm, pi)?;
+            .zip(&kzg_challenges)
+            .map(|((v, r), &c)| CS1::prove_with_challenge(&cs_pk, c, v, r, Some(&mut rng)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let snark_proof =
+            S::prove(&snark_pk, circuit, &mut rng).map_err(|e| Error::Other(e.to_string()))?;
+
+        Ok(RandomizedProof {
+            snark_proof,
+            folding_proof,
+            r,
+            r_prime,
+            kzg_proofs: kzg_proofs
+                .try_into()
+                .map_err(|e: Vec<_>| Error::NotExpectedLength(e.len(), 2))?,
+            kzg_challenges: kzg_challenges
+                .try_into()
+                .map_err(|e: Vec<_>| Error::NotExpectedLength(e.len(), 2))?,
+        })
+    }
+
+    /// Same as `verify`, but for the proofs generated by `prove_randomized`.
+    pub fn verify_randomized(
+        vp: VerifierParam<C1, CS1::VerifierParams, S::VerifyingKey>,
+        i: C1::ScalarField,
+        z_0: Vec<C1::ScalarField>,
+        z_i: Vec<C1::ScalarField>,
+        running_commitments: &[C1],
+        incoming_commitments: &[C1],
+        proof: &RandomizedProof<C1, CS1, S>,
+    ) -> Result<bool, Error> {
+        if i <= C1::ScalarField::one() {
+            return Err(Error::NotEnoughSteps);
+        }
+
+        let VerifierParam {
+            pp_hash,
+            snark_vp,
+            cs_vp,
+        } = vp;
+
+        // 6.2. Fold the commitments, including the ones of the random instance U_r
+        let U_final_commitments = DeciderRandomizedNovaGadget::fold_group_elements_native(
+            running_commitments,
+            incoming_commitments,
+            Some(proof.folding_proof.clone()),
+            (proof.r, proof.r_prime),
+        )?;
+
+        let public_input = [
+            &[pp_hash, i][..],
+            &z_0,
+            &z_i,
+            &U_final_commitments.inputize_nonnative(),
+            &proof.kzg_challenges,
+            &proof.kzg_proofs.iter().map(|p| p.eval).collect::<Vec<_>>(),
+            &proof.folding_proof.inputize(),
+        ]
+        .concat();
+
+        let snark_v = S::verify(&snark_vp, &public_input, &proof.snark_proof)
+            .map_err(|e| Error::Other(e.to_string()))?;
+        if !snark_v {
+            return Err(Error::SNARKVerificationFail);
         }
 
+        // 7.3. Verify the KZG proofs, batched into a single pairing check whose random linear
+        // combination is taken from a Keccak transcript, as done by the Solidity verifier
+        CS1::batch_verify_with_challenges(
+            &cs_vp,
+            &mut KeccakTranscript::new(&()),
+            &proof.kzg_challenges,
+            &U_final_commitments,
+            &proof.kzg_proofs,
+        )?;
+
         Ok(true)
     }
 }
 
+/// Decider whose SNARK has a universal setup (eg. [`crate::snark::plonk::Plonk`]), which instead
+/// of running a circuit-specific setup for the decider circuit indexes it with the given universal
+/// parameters, so that the same SRS can be reused by the deciders of different `FCircuit`s. The
+/// resulting parameters are used with the usual `prove` and `verify` methods.
+impl<C1, C2, FC, CS1, CS2, S, FS> Decider<C1, C2, FC, CS1, CS2, S, FS>
+where
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    // CS1 is a KZG commitment, where challenge is C1::Fr elem
+    CS1: CommitmentScheme<
+        C1,
+        ProverChallenge = C1::ScalarField,
+        Challenge = C1::ScalarField,
+        Proof = KZGProof<C1>,
+    >,
+    // enforce that the CS2 is Pedersen commitment scheme, since we're at Ethereum's EVM decider
+    CS2: CommitmentScheme<C2, ProverParams = PedersenParams<C2>>,
+    S: UniversalSetupSNARK<C1::ScalarField>,
+    FS: FoldingScheme<C1, C2, FC>,
+    // constrain FS into Nova, since this is a Decider specifically for Nova
+    Nova<C1, C2, FC, CS1, CS2, false>: From<FS>,
+    crate::folding::nova::ProverParams<C1, C2, CS1, CS2, false>:
+        From<<FS as FoldingScheme<C1, C2, FC>>::ProverParam>,
+    crate::folding::nova::VerifierParams<C1, C2, CS1, CS2, false>:
+        From<<FS as FoldingScheme<C1, C2, FC>>::VerifierParam>,
+{
+    /// Same as `preprocess`, but indexing the decider circuit with the universal parameters
+    /// `snark_pp` of the SNARK instead of running its circuit-specific setup.
+    #[allow(clippy::type_complexity)]
+    pub fn preprocess_universal(
+        mut rng: impl RngCore + CryptoRng,
+        ((pp, vp), state_len): <Self as DeciderTrait<C1, C2, FC, FS>>::PreprocessorParam,
+        snark_pp: &S::PublicParameters,
+    ) -> Result<
+        (
+            <Self as DeciderTrait<C1, C2, FC, FS>>::ProverParam,
+            <Self as DeciderTrait<C1, C2, FC, FS>>::VerifierParam,
+        ),
+        Error,
+    > {
+        // get the FoldingScheme prover & verifier params from Nova
+        let nova_pp: <Nova<C1, C2, FC, CS1, CS2, false> as FoldingScheme<C1, C2, FC>>::ProverParam =
+            pp.into();
+        let nova_vp: <Nova<C1, C2, FC, CS1, CS2, false> as FoldingScheme<
+                    C1,
+                    C2,
+                    FC,
+                >>::VerifierParam = vp.into();
+
+        let pp_hash = nova_vp.pp_hash()?;
+
+        let circuit = DeciderEthCircuit::<C1, C2>::dummy((
+            nova_vp.r1cs,
+            nova_vp.cf_r1cs,
+            nova_pp.cf_cs_pp,
+            nova_pp.poseidon_config,
+            (),
+            (),
+            state_len,
+            2, // Nova's running CommittedInstance contains 2 commitments
+        ));
+
+        // index the circuit with the universal parameters
+        let (snark_pk, snark_vk) = S::index(snark_pp, circuit, &mut rng).map_err(index_error)?;
+
+        let pp = (snark_pk, nova_pp.cs_pp);
+        let vp = VerifierParam {
+            pp_hash,
+            snark_vp: snark_vk,
+            cs_vp: nova_vp.cs_vp,
+        };
+        Ok((pp, vp))
+    }
+}
+
 /// Prepares solidity calldata for calling the NovaDecider contract
 #[allow(clippy::too_many_arguments)]
-pub fn prepare_calldata(
+pub fn prepare_calldata<S>(
     function_signature_check: [u8; 4],
     i: ark_bn254::Fr,
     z_0: Vec<ark_bn254::Fr>,
     z_i: Vec<ark_bn254::Fr>,
     running_instance: &CommittedInstance<ark_bn254::G1Projective>,
     incoming_instance: &CommittedInstance<ark_bn254::G1Projective>,
-    proof: Proof<ark_bn254::G1Projective, KZG<'static, Bn254>, Groth16<Bn254>>,
-) -> Result<Vec<u8>, Error> {
+    proof: Proof<ark_bn254::G1Projective, KZG<'static, Bn254>, S>,
+) -> Result<Vec<u8>, Error>
+where
+    S: SNARK<ark_bn254::Fr, Proof: ToEth>,
+{
     Ok([
         function_signature_check.to_eth(),
         i.to_eth(),   // i
@@ -263,14 +543,18 @@ pub fn prepare_calldata(
         running_instance.cmW.to_eth(),
         running_instance.cmE.to_eth(),
         incoming_instance.cmW.to_eth(),
-        proof.cmT.to_eth(),                 // cmT
-        proof.r.to_eth(),                   // r
-        proof.snark_proof.to_eth(),         // pA, pB, pC
-        proof.kzg_challenges.to_eth(),      // challenge_W, challenge_E
-        proof.kzg_proofs[0].eval.to_eth(),  // eval W
-        proof.kzg_proofs[1].eval.to_eth(),  // eval E
-        proof.kzg_proofs[0].proof.to_eth(), // W kzg_proof
-        proof.kzg_proofs[1].proof.to_eth(), // E kzg_proof
+        proof.cmT.to_eth(),                    // cmT
+        proof.r.to_eth(),                      // r
+        proof.snark_proof.to_eth(),            // pA, pB, pC (Groth16) or the PLONK proof
+        proof.kzg_challenges.to_eth(),         // challenge_W, challenge_E
+        proof.kzg_proofs[0].eval.to_eth(),     // eval W
+        proof.kzg_proofs[1].eval.to_eth(),     // eval E
+        proof.kzg_proofs[0].random_v.to_eth(), // blinding eval W
+        proof.kzg_proofs[1].random_v.to_eth(), // blinding eval E
+        proof.kzg_proofs[0].proof.to_eth(),    // W kzg_proof
+        proof.kzg_proofs[1].proof.to_eth(),    // E kzg_proof
+        proof.kzg_proofs[0].mask.to_eth(),     // W kzg_proof mask
+        proof.kzg_proofs[1].mask.to_eth(),     // E kzg_proof mask
     ]
     .concat())
 }
@@ -278,14 +562,16 @@ pub fn prepare_calldata(
 #[cfg(test)]
 pub mod tests {
     use ark_bn254::{Fr, G1Projective as Projective};
+    use ark_groth16::Groth16;
     use ark_grumpkin::Projective as Projective2;
     use std::time::Instant;
 
     use super::*;
     use crate::commitment::pedersen::Pedersen;
-    use crate::folding::nova::{PreprocessorParam, ProverParams as NovaProverParams};
+    use crate::folding::nova::{PreprocessorParam, ProverParams as NovaProverParams, Witness};
     use crate::folding::traits::CommittedInstanceOps;
     use crate::frontend::utils::CubicFCircuit;
+    use crate::snark::plonk::Plonk;
     use crate::transcript::poseidon::poseidon_canonical_config;
 
     #[test]
@@ -364,6 +650,147 @@ pub mod tests {
         Ok(())
     }
 
+    // Tests the Decider with a universal-setup SNARK (PLONK), whose parameters are obtained by
+    // indexing the decider circuit with a KZG SRS instead of running a circuit-specific setup
+    #[test]
+    fn test_decider_universal() -> Result<(), Error> {
+        // use Nova as FoldingScheme
+        type N = Nova<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            KZG<'static, Bn254>,
+            Pedersen<Projective2>,
+            false,
+        >;
+        type D = Decider<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            KZG<'static, Bn254>,
+            Pedersen<Projective2>,
+            Plonk<Bn254>, // here we define the Snark to use in the decider
+            N,            // here we define the FoldingScheme to use
+        >;
+
+        let mut rng = rand::rngs::OsRng;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let z_0 = vec![Fr::from(3_u32)];
+
+        let preprocessor_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        let nova_params = N::preprocess(&mut rng, &preprocessor_param)?;
+
+        let mut nova = N::init(&nova_params, F_circuit, z_0.clone())?;
+        nova.prove_step(&mut rng, (), None)?;
+        nova.prove_step(&mut rng, (), None)?; // do a 2nd step
+
+        // the universal SRS is sized to the decider circuit here, while in practice it would be
+        // loaded from a powers of tau ceremony (see `commitment::srs`) and shared among deciders
+        let bound = Plonk::<Bn254>::computation_bound(
+            DeciderEthCircuit::<Projective, Projective2>::try_from(nova.clone())?,
+        )?;
+        let srs = Plonk::<Bn254>::universal_setup(&bound, &mut rng)?;
+
+        // prepare the Decider prover & verifier params
+        let (decider_pp, decider_vp) =
+            D::preprocess_universal(&mut rng, (nova_params, F_circuit.state_len()), &srs)?;
+
+        // decider proof generation
+        let proof = D::prove(rng, decider_pp, nova.clone())?;
+
+        // decider proof verification
+        let verified = D::verify(
+            decider_vp,
+            nova.i,
+            nova.z_0,
+            nova.z_i,
+            &nova.U_i.get_commitments(),
+            &nova.u_i.get_commitments(),
+            &proof,
+        )?;
+        assert!(verified);
+        Ok(())
+    }
+
+    // Tests the Decider for the randomized Nova instances, where the Decider prover only gets the
+    // RandomizedNova (which does not contain the unblinded witnesses) from the user
+    #[test]
+    fn test_decider_randomized() -> Result<(), Error> {
+        // use Nova as FoldingScheme
+        type N = Nova<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            KZG<'static, Bn254, true>,
+            Pedersen<Projective2, true>,
+            true,
+        >;
+        type D = Decider<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            KZG<'static, Bn254, true>,
+            Pedersen<Projective2, true>,
+            Groth16<Bn254>, // here we define the Snark to use in the decider
+            N,              // here we define the FoldingScheme to use
+        >;
+
+        let mut rng = rand::rngs::OsRng;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let z_0 = vec![Fr::from(3_u32)];
+
+        let preprocessor_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        let nova_params = N::preprocess(&mut rng, &preprocessor_param)?;
+        let mut nova = N::init(&nova_params, F_circuit, z_0)?;
+
+        // prepare the Decider prover & verifier params
+        let (decider_pp, decider_vp) =
+            D::preprocess_randomized(&mut rng, (nova_params, F_circuit.state_len()))?;
+
+        nova.prove_step(&mut rng, (), None)?;
+        nova.prove_step(&mut rng, (), None)?;
+
+        // the user randomizes the Nova instance, and sends it to the Decider prover
+        let randomized_nova = RandomizedNova::new(&nova, &mut rng)?;
+        let dummy_witness = Witness::<Projective>::dummy(&nova.r1cs);
+        assert_eq!(randomized_nova.nova.W_i, dummy_witness);
+        assert_eq!(randomized_nova.nova.w_i, dummy_witness);
+
+        // decider proof generation
+        let start = Instant::now();
+        let proof = D::prove_randomized(rng, decider_pp, randomized_nova)?;
+        println!("Decider prove, {:?}", start.elapsed());
+
+        // decider proof verification
+        let verified = D::verify_randomized(
+            decider_vp.clone(),
+            nova.i,
+            nova.z_0.clone(),
+            nova.z_i.clone(),
+            &nova.U_i.get_commitments(),
+            &nova.u_i.get_commitments(),
+            &proof,
+        )?;
+        assert!(verified);
+
+        // verification fails with a wrong state
+        let verified = D::verify_randomized(
+            decider_vp,
+            nova.i,
+            nova.z_0.clone(),
+            nova.z_0,
+            &nova.U_i.get_commitments(),
+            &nova.u_i.get_commitments(),
+            &proof,
+        );
+        assert!(verified.is_err());
+        Ok(())
+    }
+
     // Test to check the serialization and deserialization of diverse Decider related parameters.
     // This test is the same test as `test_decider` but it serializes values and then uses the
     // deserialized values to continue the checks.
@@ -3,7 +3,7 @@
 /// More details can be found at the documentation page:
 /// https://privacy-scaling-explorations.github.io/sonobe-docs/design/nova-decider-onchain.html
 use ark_crypto_primitives::sponge::{
-    constraints::CryptographicSpongeVar,
+    constraints::{AbsorbGadget, CryptographicSpongeVar},
     poseidon::{constraints::PoseidonSpongeVar, PoseidonSponge},
     CryptographicSponge,
 };
@@ -13,11 +13,13 @@ use ark_r1cs_std::{
     fields::fp::FpVar,
 };
 use ark_relations::r1cs::{Namespace, SynthesisError};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::{borrow::Borrow, marker::PhantomData};
 
 use super::{
     nifs::nova_circuits::{CommittedInstanceVar, NIFSGadget},
     nifs::{nova::NIFS, NIFSGadgetTrait, NIFSTrait},
+    zk::RandomizedNova,
     CommittedInstance, Nova, Witness,
 };
 use crate::commitment::{pedersen::Params as PedersenParams, CommitmentScheme};
@@ -25,7 +27,7 @@ use crate::folding::{
     circuits::{
         decider::on_chain::GenericOnchainDeciderCircuit, nonnative::affine::NonNativeAffineVar, CF1,
     },
-    traits::{WitnessOps, WitnessVarOps},
+    traits::{Dummy, Inputize, WitnessOps, WitnessVarOps},
 };
 use crate::frontend::FCircuit;
 use crate::{
@@ -198,6 +200,182 @@ impl<C: Curve>
     }
 }
 
+/// Decider circuit for the randomized Nova instances of the zk layer (see [`RandomizedNova`]),
+/// where the folded instance `U_{i+1}` is further folded with a random satisfying instance `U_r`,
+/// so that the Decider prover only receives the blinded witness.
+pub type RandomizedDeciderEthCircuit<C1, C2> = GenericOnchainDeciderCircuit<
+    C1,
+    C2,
+    CommittedInstance<C1>,
+    CommittedInstance<C1>,
+    Witness<C1>,
+    R1CS<CF1<C1>>,
+    R1CSMatricesVar<CF1<C1>, FpVar<CF1<C1>>>,
+    DeciderRandomizedNovaGadget,
+>;
+
+/// returns an instance of the RandomizedDeciderEthCircuit from the given RandomizedNova struct
+impl<
+        C1: Curve,
+        C2: Curve,
+        FC: FCircuit<C1::ScalarField>,
+        CS1: CommitmentScheme<C1, true>,
+        // enforce that the CS2 is Pedersen commitment scheme, since we're at Ethereum's EVM decider
+        CS2: CommitmentScheme<C2, true, ProverParams = PedersenParams<C2>>,
+    > TryFrom<RandomizedNova<C1, C2, FC, CS1, CS2>> for RandomizedDeciderEthCircuit<C1, C2>
+{
+    type Error = Error;
+
+    fn try_from(randomized_nova: RandomizedNova<C1, C2, FC, CS1, CS2>) -> Result<Self, Error> {
+        // compute the randomized U_{i+1}, notice that the witness W_{i+1} is already provided by
+        // the RandomizedNova, since the unblinded witnesses are not available here
+        let (U_i1, proof, randomness, mut transcript) = randomized_nova.fold_instances()?;
+        let RandomizedNova {
+            nova,
+            W_i_prime: W_i1,
+            ..
+        } = randomized_nova;
+
+        // compute the KZG challenges used as inputs in the circuit
+        let kzg_challenges = KZGChallengesGadget::get_challenges_native(&mut transcript, &U_i1);
+
+        // get KZG evals
+        let kzg_evaluations = W_i1
+            .get_openings()
+            .iter()
+            .zip(&kzg_challenges)
+            .map(|((v, _), &c)| EvalGadget::evaluate_native(v, c))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            _avar: PhantomData,
+            arith: nova.r1cs,
+            cf_arith: nova.cf_r1cs,
+            cf_pedersen_params: nova.cf_cs_pp,
+            poseidon_config: nova.poseidon_config,
+            pp_hash: nova.pp_hash,
+            i: nova.i,
+            z_0: nova.z_0,
+            z_i: nova.z_i,
+            U_i: nova.U_i,
+            W_i: nova.W_i,
+            u_i: nova.u_i,
+            w_i: nova.w_i,
+            U_i1,
+            W_i1,
+            proof,
+            randomness,
+            cf_U_i: nova.cf_U_i,
+            cf_W_i: nova.cf_W_i,
+            kzg_challenges,
+            kzg_evaluations,
+        })
+    }
+}
+
+/// Proof of the two folds done by [`RandomizedNova`]: `U_f = NIFS.V(U_i, u_i, cmT)` and
+/// `U_{i+1} = NIFS.V(U_f, U_r, cmT')`. All its values are public inputs of the Decider circuit.
+#[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct RandomizedFoldingProof<C: Curve> {
+    pub cmT: C,
+    // commitments of the intermediate folded instance U_f
+    pub cmE: C,
+    pub cmW: C,
+    // random satisfying instance
+    pub U_r: CommittedInstance<C>,
+    pub cmT_prime: C,
+}
+
+impl<C: Curve> Dummy<usize> for RandomizedFoldingProof<C> {
+    fn dummy(io_len: usize) -> Self {
+        Self {
+            cmT: C::zero(),
+            cmE: C::zero(),
+            cmW: C::zero(),
+            U_r: CommittedInstance::dummy(io_len),
+            cmT_prime: C::zero(),
+        }
+    }
+}
+
+impl<C: Curve> Inputize<CF1<C>> for RandomizedFoldingProof<C> {
+    /// Returns the internal representation in the same order as how the value
+    /// is allocated in `DeciderRandomizedNovaGadget::fold_field_elements_gadget`.
+    fn inputize(&self) -> Vec<CF1<C>> {
+        [
+            &self.cmT.inputize_nonnative()[..],
+            &self.cmE.inputize_nonnative(),
+            &self.cmW.inputize_nonnative(),
+            &self.U_r.inputize(),
+            &self.cmT_prime.inputize_nonnative(),
+        ]
+        .concat()
+    }
+}
+
+/// Same as [`DeciderNovaGadget`], but additionally folds the resulting instance with the random
+/// satisfying instance `U_r` of [`RandomizedNova`].
+pub struct DeciderRandomizedNovaGadget;
+
+impl<C: Curve>
+    DeciderEnabledNIFS<C, CommittedInstance<C>, CommittedInstance<C>, Witness<C>, R1CS<CF1<C>>>
+    for DeciderRandomizedNovaGadget
+{
+    type ProofDummyCfg = usize;
+    type Proof = RandomizedFoldingProof<C>;
+    type RandomnessDummyCfg = ();
+    type Randomness = (CF1<C>, CF1<C>);
+
+    fn fold_field_elements_gadget(
+        _arith: &R1CS<CF1<C>>,
+        transcript: &mut PoseidonSpongeVar<CF1<C>>,
+        pp_hash: FpVar<CF1<C>>,
+        U: CommittedInstanceVar<C>,
+        U_vec: Vec<FpVar<CF1<C>>>,
+        u: CommittedInstanceVar<C>,
+        proof: RandomizedFoldingProof<C>,
+        _randomness: (CF1<C>, CF1<C>),
+    ) -> Result<CommittedInstanceVar<C>, SynthesisError> {
+        let cs = transcript.cs();
+        let cmT = NonNativeAffineVar::new_input(cs.clone(), || Ok(proof.cmT))?;
+        let (mut U_f, _) = NIFSGadget::verify(transcript, pp_hash.clone(), U, U_vec, u, Some(cmT))?;
+        // the commitments of U_f are folded natively by the verifier, but they are needed
+        // in-circuit since they are absorbed when computing the challenge of the second fold
+        U_f.cmE = NonNativeAffineVar::new_input(cs.clone(), || Ok(proof.cmE))?;
+        U_f.cmW = NonNativeAffineVar::new_input(cs.clone(), || Ok(proof.cmW))?;
+        let U_r = CommittedInstanceVar::new_input(cs.clone(), || Ok(proof.U_r))?;
+        let cmT_prime = NonNativeAffineVar::new_input(cs, || Ok(proof.cmT_prime))?;
+        let U_f_vec = U_f.to_sponge_field_elements()?;
+        let (new_U, _) =
+            NIFSGadget::verify(transcript, pp_hash, U_f, U_f_vec, U_r, Some(cmT_prime))?;
+        Ok(new_U)
+    }
+
+    fn fold_group_elements_native(
+        U_commitments: &[C],
+        u_commitments: &[C],
+        proof: Option<Self::Proof>,
+        (r, r_prime): Self::Randomness,
+    ) -> Result<Vec<C>, Error> {
+        let proof = proof.ok_or(Error::Empty)?;
+        // U_f = NIFS.V(U_i, u_i), whose commitments must match the ones used in-circuit
+        let U_f_commitments = DeciderNovaGadget::fold_group_elements_native(
+            U_commitments,
+            u_commitments,
+            Some(proof.cmT),
+            r,
+        )?;
+        if U_f_commitments != [proof.cmW, proof.cmE] {
+            return Err(Error::NotEqual);
+        }
+        // U_{i+1} = NIFS.V(U_f, U_r), notice that U_r is a relaxed instance, so its cmE is not
+        // zero
+        let cmW = proof.cmW + proof.U_r.cmW.mul(r_prime);
+        let cmE = proof.cmE + proof.cmT_prime.mul(r_prime) + proof.U_r.cmE.mul(r_prime * r_prime);
+        Ok(vec![cmW, cmE])
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use ark_pallas::{Fr, Projective};
@@ -255,4 +433,50 @@ pub mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_randomized_decider_circuit() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let z_0 = vec![Fr::from(3_u32)];
+
+        type N = Nova<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            Pedersen<Projective, true>,
+            Pedersen<Projective2, true>,
+            true,
+        >;
+
+        let prep_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        let nova_params = N::preprocess(&mut rng, &prep_param)?;
+
+        let mut nova = N::init(&nova_params, F_circuit, z_0)?;
+        nova.prove_step(&mut rng, (), None)?;
+        nova.prove_step(&mut rng, (), None)?;
+
+        // randomize the Nova instance, and load the RandomizedDeciderEthCircuit from it, as the
+        // Decider prover would do
+        let randomized_nova = RandomizedNova::new(&nova, &mut rng)?;
+        let decider_circuit =
+            RandomizedDeciderEthCircuit::<Projective, Projective2>::try_from(randomized_nova)?;
+
+        // the circuit does not contain the unblinded witnesses
+        let dummy_witness = Witness::<Projective>::dummy(&nova.r1cs);
+        assert_eq!(decider_circuit.W_i, dummy_witness);
+        assert_eq!(decider_circuit.w_i, dummy_witness);
+        let non_randomized_circuit = DeciderEthCircuit::<Projective, Projective2>::try_from(nova)?;
+        assert_ne!(decider_circuit.W_i1.W, non_randomized_circuit.W_i1.W);
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+
+        // generate the constraints and check that are satisfied by the inputs
+        decider_circuit.generate_constraints(cs.clone())?;
+        assert!(cs.is_satisfied()?);
+
+        Ok(())
+    }
 }
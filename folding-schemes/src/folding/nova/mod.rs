@@ -3,10 +3,14 @@
 ///
 /// The structure of the Nova code is the following:
 /// - NIFS implementation for Nova (nifs.rs), Mova (mova.rs), Ova (ova.rs)
-/// - IVC and the Decider (offchain Decider & onchain Decider) implementations for Nova
+/// - IVC implementation, generic over the NIFS being used (so that it is shared by Nova and its
+///   variants, eg. Ova), and the Decider (offchain Decider & onchain Decider) implementations for
+///   Nova
+/// - PCD implementation (pcd.rs), which merges Nova running instances of adjacent step ranges,
+///   allowing to prove the IVC steps in a tree-shaped manner
 use ark_crypto_primitives::sponge::{
-    poseidon::{PoseidonConfig, PoseidonSponge},
-    Absorb, CryptographicSponge,
+    poseidon::{constraints::PoseidonSpongeVar, PoseidonConfig, PoseidonSponge},
+    Absorb,
 };
 use ark_ff::{BigInteger, PrimeField};
 use ark_r1cs_std::R1CSVar;
@@ -16,7 +20,7 @@ use ark_std::{cmp::max, fmt::Debug, marker::PhantomData, rand::RngCore, One, Uni
 
 use crate::folding::{circuits::CF1, traits::Dummy};
 use crate::frontend::FCircuit;
-use crate::transcript::{poseidon::poseidon_canonical_config, Transcript};
+use crate::transcript::{CanonicalConfig, Transcript, TranscriptVar};
 use crate::utils::vec::is_zero_vec;
 use crate::FoldingScheme;
 use crate::{
@@ -43,7 +47,7 @@ pub mod zk;
 pub mod nifs;
 
 use circuits::AugmentedFCircuit;
-use nifs::{nova::NIFS, nova_circuits::CommittedInstanceVar, NIFSTrait};
+use nifs::{nova::NIFS, nova_circuits::CommittedInstanceVar, NovaNIFSGadgetTrait, NovaNIFSTrait};
 
 // offchain decider
 pub mod decider;
@@ -51,6 +55,9 @@ pub mod decider_circuits;
 // onchain decider
 pub mod decider_eth;
 pub mod decider_eth_circuit;
+// proof-carrying data
+pub mod pcd;
+pub mod pcd_circuits;
 
 use super::traits::{CommittedInstanceOps, Inputize, WitnessOps};
 
@@ -117,6 +124,10 @@ impl<C: Curve> CommittedInstanceOps<C> for CommittedInstance<C> {
         vec![self.cmW, self.cmE]
     }
 
+    fn get_public_inputs(&self) -> &[CF1<C>] {
+        &self.x
+    }
+
     fn is_incoming(&self) -> bool {
         self.cmE == C::zero() && self.u == One::one()
     }
@@ -146,18 +157,17 @@ pub struct Witness<C: Curve> {
 
 impl<C: Curve> Witness<C> {
     pub fn new<const H: bool>(w: Vec<C::ScalarField>, e_len: usize, mut rng: impl RngCore) -> Self {
-        let (rW, rE) = if H {
-            (
-                C::ScalarField::rand(&mut rng),
-                C::ScalarField::rand(&mut rng),
-            )
+        let rW = if H {
+            C::ScalarField::rand(&mut rng)
         } else {
-            (C::ScalarField::zero(), C::ScalarField::zero())
+            C::ScalarField::zero()
         };
 
         Self {
             E: vec![C::ScalarField::zero(); e_len],
-            rE,
+            // E is zero, so its commitment is zero (see `commit`), and rE must be zero too so that
+            // the folded E keeps matching the folded cmE in hiding mode
+            rE: C::ScalarField::zero(),
             W: w,
             rW,
         }
@@ -201,16 +211,25 @@ impl<C: Curve> WitnessOps<C::ScalarField> for Witness<C> {
     }
 }
 
+/// Preprocessing parameters for Nova-based IVC, where `SC` is the configuration of the sponge used
+/// by the IVC (Poseidon by default).
 #[derive(Debug, Clone)]
-pub struct PreprocessorParam<C1, C2, FC, CS1, CS2, const H: bool = false>
-where
+pub struct PreprocessorParam<
+    C1,
+    C2,
+    FC,
+    CS1,
+    CS2,
+    const H: bool = false,
+    SC = PoseidonConfig<CF1<C1>>,
+> where
     C1: Curve,
     C2: Curve,
     FC: FCircuit<C1::ScalarField>,
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
 {
-    pub poseidon_config: PoseidonConfig<C1::ScalarField>,
+    pub poseidon_config: SC,
     pub F: FC,
     // cs params if not provided, will be generated at the preprocess method
     pub cs_pp: Option<CS1::ProverParams>,
@@ -219,7 +238,7 @@ where
     pub cf_cs_vp: Option<CS2::VerifierParams>,
 }
 
-impl<C1, C2, FC, CS1, CS2, const H: bool> PreprocessorParam<C1, C2, FC, CS1, CS2, H>
+impl<C1, C2, FC, CS1, CS2, const H: bool, SC> PreprocessorParam<C1, C2, FC, CS1, CS2, H, SC>
 where
     C1: Curve,
     C2: Curve,
@@ -227,7 +246,7 @@ where
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
 {
-    pub fn new(poseidon_config: PoseidonConfig<C1::ScalarField>, F: FC) -> Self {
+    pub fn new(poseidon_config: SC, F: FC) -> Self {
         Self {
             poseidon_config,
             F,
@@ -241,42 +260,38 @@ where
 
 /// Proving parameters for Nova-based IVC
 #[derive(Debug, Clone)]
-pub struct ProverParams<C1, C2, CS1, CS2, const H: bool = false>
+pub struct ProverParams<C1, C2, CS1, CS2, const H: bool = false, SC = PoseidonConfig<CF1<C1>>>
 where
     C1: Curve,
     C2: Curve,
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
 {
-    /// Poseidon sponge configuration
-    pub poseidon_config: PoseidonConfig<C1::ScalarField>,
+    /// Sponge configuration
+    pub poseidon_config: SC,
     /// Proving parameters of the underlying commitment scheme over C1
     pub cs_pp: CS1::ProverParams,
     /// Proving parameters of the underlying commitment scheme over C2
     pub cf_cs_pp: CS2::ProverParams,
 }
 
-impl<C1, C2, CS1, CS2, const H: bool> Valid for ProverParams<C1, C2, CS1, CS2, H>
+impl<C1, C2, CS1, CS2, const H: bool, SC> Valid for ProverParams<C1, C2, CS1, CS2, H, SC>
 where
     C1: Curve,
     C2: Curve,
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
+    SC: Valid,
 {
     fn check(&self) -> Result<(), ark_serialize::SerializationError> {
-        self.poseidon_config.full_rounds.check()?;
-        self.poseidon_config.partial_rounds.check()?;
-        self.poseidon_config.alpha.check()?;
-        self.poseidon_config.ark.check()?;
-        self.poseidon_config.mds.check()?;
-        self.poseidon_config.rate.check()?;
-        self.poseidon_config.capacity.check()?;
+        self.poseidon_config.check()?;
         self.cs_pp.check()?;
         self.cf_cs_pp.check()?;
         Ok(())
     }
 }
-impl<C1, C2, CS1, CS2, const H: bool> CanonicalSerialize for ProverParams<C1, C2, CS1, CS2, H>
+impl<C1, C2, CS1, CS2, const H: bool, SC> CanonicalSerialize
+    for ProverParams<C1, C2, CS1, CS2, H, SC>
 where
     C1: Curve,
     C2: Curve,
@@ -296,12 +311,14 @@ where
         self.cs_pp.serialized_size(compress) + self.cf_cs_pp.serialized_size(compress)
     }
 }
-impl<C1, C2, CS1, CS2, const H: bool> CanonicalDeserialize for ProverParams<C1, C2, CS1, CS2, H>
+impl<C1, C2, CS1, CS2, const H: bool, SC> CanonicalDeserialize
+    for ProverParams<C1, C2, CS1, CS2, H, SC>
 where
     C1: Curve,
     C2: Curve,
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
+    SC: CanonicalConfig + Valid + Sync,
 {
     fn deserialize_with_mode<R: std::io::prelude::Read>(
         mut reader: R,
@@ -311,7 +328,7 @@ where
         let cs_pp = CS1::ProverParams::deserialize_with_mode(&mut reader, compress, validate)?;
         let cf_cs_pp = CS2::ProverParams::deserialize_with_mode(&mut reader, compress, validate)?;
         Ok(ProverParams {
-            poseidon_config: poseidon_canonical_config::<C1::ScalarField>(),
+            poseidon_config: SC::canonical_config(),
             cs_pp,
             cf_cs_pp,
         })
@@ -320,15 +337,15 @@ where
 
 /// Verification parameters for Nova-based IVC
 #[derive(Debug, Clone)]
-pub struct VerifierParams<C1, C2, CS1, CS2, const H: bool = false>
+pub struct VerifierParams<C1, C2, CS1, CS2, const H: bool = false, SC = PoseidonConfig<CF1<C1>>>
 where
     C1: Curve,
     C2: Curve,
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
 {
-    /// Poseidon sponge configuration
-    pub poseidon_config: PoseidonConfig<C1::ScalarField>,
+    /// Sponge configuration
+    pub poseidon_config: SC,
     /// R1CS of the Augmented step circuit
     pub r1cs: R1CS<C1::ScalarField>,
     /// R1CS of the CycleFold circuit
@@ -339,20 +356,23 @@ where
     pub cf_cs_vp: CS2::VerifierParams,
 }
 
-impl<C1, C2, CS1, CS2, const H: bool> Valid for VerifierParams<C1, C2, CS1, CS2, H>
+impl<C1, C2, CS1, CS2, const H: bool, SC> Valid for VerifierParams<C1, C2, CS1, CS2, H, SC>
 where
     C1: Curve,
     C2: Curve,
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
+    SC: Valid,
 {
     fn check(&self) -> Result<(), ark_serialize::SerializationError> {
+        self.poseidon_config.check()?;
         self.cs_vp.check()?;
         self.cf_cs_vp.check()?;
         Ok(())
     }
 }
-impl<C1, C2, CS1, CS2, const H: bool> CanonicalSerialize for VerifierParams<C1, C2, CS1, CS2, H>
+impl<C1, C2, CS1, CS2, const H: bool, SC> CanonicalSerialize
+    for VerifierParams<C1, C2, CS1, CS2, H, SC>
 where
     C1: Curve,
     C2: Curve,
@@ -373,12 +393,13 @@ where
     }
 }
 
-impl<C1, C2, CS1, CS2, const H: bool> VerifierParams<C1, C2, CS1, CS2, H>
+impl<C1, C2, CS1, CS2, const H: bool, SC> VerifierParams<C1, C2, CS1, CS2, H, SC>
 where
     C1: Curve,
     C2: Curve,
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
+    SC: CanonicalSerialize,
 {
     /// returns the hash of the public parameters of Nova
     pub fn pp_hash(&self) -> Result<C1::ScalarField, Error> {
@@ -393,10 +414,12 @@ where
 }
 
 #[derive(PartialEq, Eq, Debug, Clone, CanonicalSerialize, CanonicalDeserialize)]
-pub struct IVCProof<C1, C2>
+pub struct IVCProof<C1, C2, Wit = Witness<C1>, CI = CommittedInstance<C1>>
 where
     C1: Curve,
     C2: Curve,
+    Wit: CanonicalSerialize + CanonicalDeserialize,
+    CI: CanonicalSerialize + CanonicalDeserialize,
 {
     // current step of the IVC
     pub i: C1::ScalarField,
@@ -405,33 +428,52 @@ where
     // current state
     pub z_i: Vec<C1::ScalarField>,
     // running instance
-    pub W_i: Witness<C1>,
-    pub U_i: CommittedInstance<C1>,
+    pub W_i: Wit,
+    pub U_i: CI,
     // incoming instance
-    pub w_i: Witness<C1>,
-    pub u_i: CommittedInstance<C1>,
+    pub w_i: Wit,
+    pub u_i: CI,
     // CycleFold instances
     pub cf_W_i: CycleFoldWitness<C2>,
     pub cf_U_i: CycleFoldCommittedInstance<C2>,
 }
 
-/// Implements Nova+CycleFold's IVC, described in [Nova](https://eprint.iacr.org/2021/370.pdf) and
-/// [CycleFold](https://eprint.iacr.org/2023/1192.pdf), following the FoldingScheme trait
+/// Implements the Nova+CycleFold's IVC, described in [Nova](https://eprint.iacr.org/2021/370.pdf)
+/// and [CycleFold](https://eprint.iacr.org/2023/1192.pdf), following the FoldingScheme trait,
+/// generic over the NIFS `N` being used, so that it is shared by Nova and its variants (see
+/// [`Nova`], [`crate::folding::ova::Ova`] and [`crate::folding::mova::Mova`]).
 /// The `H` const generic specifies whether the homorphic commitment scheme is blinding
-#[derive(Clone, Debug)]
-pub struct Nova<C1, C2, FC, CS1, CS2, const H: bool = false>
-where
+/// The sponge `S` (Poseidon by default) is used for the transcripts and the hashes of the IVC,
+/// and `T` is its in-circuit counterpart, used in the augmented circuit.
+/// The `B` const generic specifies whether the initial running instance can be chosen by the
+/// prover instead of being the dummy instance U_{\bot}, which is needed to blind the first step
+/// (see [`zk::BlindedInstance`]).
+pub struct GenericNova<
+    C1,
+    C2,
+    FC,
+    CS1,
+    CS2,
+    N,
+    const H: bool = false,
+    S = PoseidonSponge<CF1<C1>>,
+    T = PoseidonSpongeVar<CF1<C1>>,
+    const B: bool = false,
+> where
     C1: Curve,
     C2: Curve,
     FC: FCircuit<C1::ScalarField>,
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
+    N: NovaNIFSTrait<C1, CS1, S, H>,
 {
     /// R1CS of the Augmented Function circuit
     pub r1cs: R1CS<C1::ScalarField>,
     /// R1CS of the CycleFold circuit
     pub cf_r1cs: R1CS<C2::ScalarField>,
-    pub poseidon_config: PoseidonConfig<C1::ScalarField>,
+    pub poseidon_config: S::Config,
     /// CommitmentScheme::ProverParams over C1
     pub cs_pp: CS1::ProverParams,
     /// CycleFold CommitmentScheme::ProverParams, over C2
@@ -446,34 +488,127 @@ where
     /// current i-th state
     pub z_i: Vec<C1::ScalarField>,
     /// Nova instances
-    pub w_i: Witness<C1>,
-    pub u_i: CommittedInstance<C1>,
-    pub W_i: Witness<C1>,
-    pub U_i: CommittedInstance<C1>,
+    pub w_i: N::Witness,
+    pub u_i: N::CommittedInstance,
+    pub W_i: N::Witness,
+    pub U_i: N::CommittedInstance,
 
     /// CycleFold running instance
     pub cf_W_i: CycleFoldWitness<C2>,
     pub cf_U_i: CycleFoldCommittedInstance<C2>,
+
+    _s: PhantomData<S>,
+    _t: PhantomData<T>,
+}
+
+/// Implements Nova+CycleFold's IVC, described in [Nova](https://eprint.iacr.org/2021/370.pdf) and
+/// [CycleFold](https://eprint.iacr.org/2023/1192.pdf), following the FoldingScheme trait
+/// The `H` const generic specifies whether the homorphic commitment scheme is blinding
+pub type Nova<
+    C1,
+    C2,
+    FC,
+    CS1,
+    CS2,
+    const H: bool = false,
+    S = PoseidonSponge<CF1<C1>>,
+    T = PoseidonSpongeVar<CF1<C1>>,
+    const B: bool = false,
+> = GenericNova<C1, C2, FC, CS1, CS2, NIFS<C1, CS1, S, H>, H, S, T, B>;
+
+// `Clone` and `Debug` are implemented manually instead of derived, since deriving them would
+// require the NIFS type `N` to implement them too.
+impl<C1, C2, FC, CS1, CS2, N, const H: bool, S, T, const B: bool> Clone
+    for GenericNova<C1, C2, FC, CS1, CS2, N, H, S, T, B>
+where
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
+    N: NovaNIFSTrait<C1, CS1, S, H>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            r1cs: self.r1cs.clone(),
+            cf_r1cs: self.cf_r1cs.clone(),
+            poseidon_config: self.poseidon_config.clone(),
+            cs_pp: self.cs_pp.clone(),
+            cf_cs_pp: self.cf_cs_pp.clone(),
+            F: self.F.clone(),
+            pp_hash: self.pp_hash,
+            i: self.i,
+            z_0: self.z_0.clone(),
+            z_i: self.z_i.clone(),
+            w_i: self.w_i.clone(),
+            u_i: self.u_i.clone(),
+            W_i: self.W_i.clone(),
+            U_i: self.U_i.clone(),
+            cf_W_i: self.cf_W_i.clone(),
+            cf_U_i: self.cf_U_i.clone(),
+            _s: PhantomData,
+            _t: PhantomData,
+        }
+    }
 }
 
-impl<C1, C2, FC, CS1, CS2, const H: bool> FoldingScheme<C1, C2, FC>
-    for Nova<C1, C2, FC, CS1, CS2, H>
+impl<C1, C2, FC, CS1, CS2, N, const H: bool, S, T, const B: bool> Debug
+    for GenericNova<C1, C2, FC, CS1, CS2, N, H, S, T, B>
 where
     C1: Curve,
     C2: Curve,
     FC: FCircuit<C1::ScalarField>,
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
+    N: NovaNIFSTrait<C1, CS1, S, H>,
+{
+    fn fmt(&self, f: &mut ark_std::fmt::Formatter<'_>) -> ark_std::fmt::Result {
+        f.debug_struct("GenericNova")
+            .field("r1cs", &self.r1cs)
+            .field("cf_r1cs", &self.cf_r1cs)
+            .field("poseidon_config", &self.poseidon_config)
+            .field("cs_pp", &self.cs_pp)
+            .field("cf_cs_pp", &self.cf_cs_pp)
+            .field("F", &self.F)
+            .field("pp_hash", &self.pp_hash)
+            .field("i", &self.i)
+            .field("z_0", &self.z_0)
+            .field("z_i", &self.z_i)
+            .field("w_i", &self.w_i)
+            .field("u_i", &self.u_i)
+            .field("W_i", &self.W_i)
+            .field("U_i", &self.U_i)
+            .field("cf_W_i", &self.cf_W_i)
+            .field("cf_U_i", &self.cf_U_i)
+            .finish()
+    }
+}
+
+impl<C1, C2, FC, CS1, CS2, N, const H: bool, S, T, const B: bool> FoldingScheme<C1, C2, FC>
+    for GenericNova<C1, C2, FC, CS1, CS2, N, H, S, T, B>
+where
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
+    N: NovaNIFSTrait<C1, CS1, S, H>,
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
 {
-    type PreprocessorParam = PreprocessorParam<C1, C2, FC, CS1, CS2, H>;
-    type ProverParam = ProverParams<C1, C2, CS1, CS2, H>;
-    type VerifierParam = VerifierParams<C1, C2, CS1, CS2, H>;
-    type RunningInstance = (CommittedInstance<C1>, Witness<C1>);
-    type IncomingInstance = (CommittedInstance<C1>, Witness<C1>);
+    type PreprocessorParam = PreprocessorParam<C1, C2, FC, CS1, CS2, H, S::Config>;
+    type ProverParam = ProverParams<C1, C2, CS1, CS2, H, S::Config>;
+    type VerifierParam = VerifierParams<C1, C2, CS1, CS2, H, S::Config>;
+    type RunningInstance = (N::CommittedInstance, N::Witness);
+    type IncomingInstance = (N::CommittedInstance, N::Witness);
     type MultiCommittedInstanceWithWitness = ();
     type CFInstance = (CycleFoldCommittedInstance<C2>, CycleFoldWitness<C2>);
-    type IVCProof = IVCProof<C1, C2>;
+    type IVCProof = IVCProof<C1, C2, N::Witness, N::CommittedInstance>;
 
     fn pp_deserialize_with_mode<R: std::io::prelude::Read>(
         reader: R,
@@ -491,28 +626,13 @@ where
         validate: ark_serialize::Validate,
         fc_params: FC::Params,
     ) -> Result<Self::VerifierParam, Error> {
-        let poseidon_config = poseidon_canonical_config::<C1::ScalarField>();
+        let poseidon_config = S::Config::canonical_config();
 
         // generate the r1cs & cf_r1cs needed for the VerifierParams. In this way we avoid needing
         // to serialize them, saving significant space in the VerifierParams serialized size.
-
-        // main circuit R1CS:
         let f_circuit = FC::new(fc_params)?;
-        let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
-        let augmented_F_circuit =
-            AugmentedFCircuit::<C1, C2, FC>::empty(&poseidon_config, f_circuit.clone());
-        augmented_F_circuit.generate_constraints(cs.clone())?;
-        cs.finalize();
-        let cs = cs.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
-        let r1cs = extract_r1cs::<C1::ScalarField>(&cs)?;
-
-        // CycleFold circuit R1CS
-        let cs2 = ConstraintSystem::<C1::BaseField>::new_ref();
-        let cf_circuit = NovaCycleFoldCircuit::<C1>::empty();
-        cf_circuit.generate_constraints(cs2.clone())?;
-        cs2.finalize();
-        let cs2 = cs2.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
-        let cf_r1cs = extract_r1cs::<C1::BaseField>(&cs2)?;
+        let (r1cs, cf_r1cs) =
+            get_r1cs::<C1, C2, FC, S, T, N::Gadget<T>, B>(&poseidon_config, f_circuit)?;
 
         let cs_vp = CS1::VerifierParams::deserialize_with_mode(&mut reader, compress, validate)?;
         let cf_cs_vp = CS2::VerifierParams::deserialize_with_mode(&mut reader, compress, validate)?;
@@ -530,19 +650,20 @@ where
         mut rng: impl RngCore,
         prep_param: &Self::PreprocessorParam,
     ) -> Result<(Self::ProverParam, Self::VerifierParam), Error> {
-        let (r1cs, cf_r1cs) =
-            get_r1cs::<C1, C2, FC>(&prep_param.poseidon_config, prep_param.F.clone())?;
+        let (r1cs, cf_r1cs) = get_r1cs::<C1, C2, FC, S, T, N::Gadget<T>, B>(
+            &prep_param.poseidon_config,
+            prep_param.F.clone(),
+        )?;
 
         // if cs params exist, use them, if not, generate new ones
         let (cs_pp, cs_vp) = match (&prep_param.cs_pp, &prep_param.cs_vp) {
             (Some(cs_pp), Some(cs_vp)) => (cs_pp.clone(), cs_vp.clone()),
             _ => CS1::setup(
                 &mut rng,
-                // `CS1` is for committing to Nova's witness vector `w` and
-                // error term `e`, where the length of `e` is the number of
-                // constraints, so we set `len` to the maximum of `e` and `w`'s
-                // lengths.
-                max(r1cs.n_constraints(), r1cs.n_witnesses()),
+                // `CS1` is for committing to the NIFS' witness vectors, whose maximum length is
+                // given by the NIFS (eg. in Nova `w` and `e` are committed separately, so `len` is
+                // the maximum of `e` and `w`'s lengths).
+                N::commitment_len(&r1cs),
             )?,
         };
         let (cf_cs_pp, cf_cs_vp) = match (&prep_param.cf_cs_pp, &prep_param.cf_cs_vp) {
@@ -557,12 +678,12 @@ where
             )?,
         };
 
-        let prover_params = ProverParams::<C1, C2, CS1, CS2, H> {
+        let prover_params = ProverParams::<C1, C2, CS1, CS2, H, S::Config> {
             poseidon_config: prep_param.poseidon_config.clone(),
             cs_pp: cs_pp.clone(),
             cf_cs_pp: cf_cs_pp.clone(),
         };
-        let verifier_params = VerifierParams::<C1, C2, CS1, CS2, H> {
+        let verifier_params = VerifierParams::<C1, C2, CS1, CS2, H, S::Config> {
             poseidon_config: prep_param.poseidon_config.clone(),
             r1cs,
             cf_r1cs,
@@ -582,29 +703,15 @@ where
         let (pp, vp) = params;
 
         // prepare the circuit to obtain its R1CS
-        let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
-        let cs2 = ConstraintSystem::<C1::BaseField>::new_ref();
-
-        let augmented_F_circuit =
-            AugmentedFCircuit::<C1, C2, FC>::empty(&pp.poseidon_config, F.clone());
-        let cf_circuit = NovaCycleFoldCircuit::<C1>::empty();
-
-        augmented_F_circuit.generate_constraints(cs.clone())?;
-        cs.finalize();
-        let cs = cs.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
-        let r1cs = extract_r1cs::<C1::ScalarField>(&cs)?;
-
-        cf_circuit.generate_constraints(cs2.clone())?;
-        cs2.finalize();
-        let cs2 = cs2.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
-        let cf_r1cs = extract_r1cs::<C1::BaseField>(&cs2)?;
+        let (r1cs, cf_r1cs) =
+            get_r1cs::<C1, C2, FC, S, T, N::Gadget<T>, B>(&pp.poseidon_config, F.clone())?;
 
         // compute the public params hash
         let pp_hash = vp.pp_hash()?;
 
         // setup the dummy instances
-        let (W_dummy, U_dummy) = r1cs.dummy_witness_instance();
-        let (w_dummy, u_dummy) = r1cs.dummy_witness_instance();
+        let (W_dummy, U_dummy) = (N::Witness::dummy(&r1cs), N::CommittedInstance::dummy(&r1cs));
+        let (w_dummy, u_dummy) = (N::Witness::dummy(&r1cs), N::CommittedInstance::dummy(&r1cs));
         let (cf_W_dummy, cf_U_dummy) = cf_r1cs.dummy_witness_instance();
 
         // W_dummy=W_0 is a 'dummy witness', all zeroes, but with the size corresponding to the
@@ -627,206 +734,25 @@ where
             // cyclefold running instance
             cf_W_i: cf_W_dummy,
             cf_U_i: cf_U_dummy,
+            _s: PhantomData,
+            _t: PhantomData,
         })
     }
 
     /// Implements IVC.P of Nova+CycleFold
     fn prove_step(
         &mut self,
-        mut rng: impl RngCore,
+        rng: impl RngCore,
         external_inputs: FC::ExternalInputs,
         // Nova does not support multi-instances folding (by design)
         _other_instances: Option<Self::MultiCommittedInstanceWithWitness>,
     ) -> Result<(), Error> {
-        // ensure that commitments are blinding if user has specified so.
-        if H && self.i >= C1::ScalarField::one() {
-            let blinding_commitments = if self.i == C1::ScalarField::one() {
-                // blinding values of the running instances are zero at the first iteration
-                vec![self.w_i.rW, self.w_i.rE]
-            } else {
-                vec![self.w_i.rW, self.w_i.rE, self.W_i.rW, self.W_i.rE]
-            };
-            if blinding_commitments.contains(&C1::ScalarField::zero()) {
-                return Err(Error::IncorrectBlinding(
-                    H,
-                    format!("{:?}", blinding_commitments),
-                ));
-            }
-        }
-        // `sponge` is for digest computation.
-        let sponge = PoseidonSponge::<C1::ScalarField>::new(&self.poseidon_config);
-        // `transcript` is for challenge generation.
-        let mut transcript = sponge.clone();
-
-        let augmented_F_circuit: AugmentedFCircuit<C1, C2, FC>;
-
         // Nova does not support (by design) multi-instances folding
         if _other_instances.is_some() {
             return Err(Error::NoMultiInstances);
         }
 
-        if self.z_i.len() != self.F.state_len() {
-            return Err(Error::NotSameLength(
-                "z_i.len()".to_string(),
-                self.z_i.len(),
-                "F.state_len()".to_string(),
-                self.F.state_len(),
-            ));
-        }
-
-        if self.i > C1::ScalarField::from_le_bytes_mod_order(&usize::MAX.to_le_bytes()) {
-            return Err(Error::MaxStep);
-        }
-
-        let i_usize;
-
-        #[cfg(target_pointer_width = "64")]
-        {
-            let mut i_bytes: [u8; 8] = [0; 8];
-            i_bytes.copy_from_slice(&self.i.into_bigint().to_bytes_le()[..8]);
-            i_usize = usize::from_le_bytes(i_bytes);
-        }
-
-        #[cfg(target_pointer_width = "32")]
-        {
-            let mut i_bytes: [u8; 4] = [0; 4];
-            i_bytes.copy_from_slice(&self.i.into_bigint().to_bytes_le()[..4]);
-            i_usize = usize::from_le_bytes(i_bytes);
-        }
-
-        // fold Nova instances
-        let (W_i1, U_i1, cmT, r_bits): (Witness<C1>, CommittedInstance<C1>, C1, Vec<bool>) =
-            NIFS::<C1, CS1, PoseidonSponge<C1::ScalarField>, H>::prove(
-                &self.cs_pp,
-                &self.r1cs,
-                &mut transcript,
-                self.pp_hash,
-                &self.W_i,
-                &self.U_i,
-                &self.w_i,
-                &self.u_i,
-            )?;
-
-        if self.i == C1::ScalarField::zero() {
-            // base case
-            augmented_F_circuit = AugmentedFCircuit::<C1, C2, FC> {
-                poseidon_config: self.poseidon_config.clone(),
-                pp_hash: Some(self.pp_hash),
-                i: Some(C1::ScalarField::zero()), // = i=0
-                i_usize: Some(0),
-                z_0: Some(self.z_0.clone()), // = z_i
-                z_i: Some(self.z_i.clone()),
-                external_inputs: Some(external_inputs.clone()),
-                u_i_cmW: Some(self.u_i.cmW), // = dummy
-                U_i: Some(self.U_i.clone()), // = dummy
-                U_i1_cmE: Some(U_i1.cmE),
-                U_i1_cmW: Some(U_i1.cmW),
-                cmT: Some(cmT),
-                F: self.F.clone(),
-                cf1_u_i_cmW: None,
-                cf2_u_i_cmW: None,
-                cf_U_i: None,
-                cf1_cmT: None,
-                cf2_cmT: None,
-            };
-
-            #[cfg(test)]
-            {
-                let r_Fr = C1::ScalarField::from_bigint(BigInteger::from_bits_le(&r_bits))
-                    .ok_or(Error::OutOfBounds)?;
-                let expected =
-                    NIFS::<C1, CS1, PoseidonSponge<C1::ScalarField>, H>::fold_committed_instances(
-                        r_Fr, &self.U_i, &self.u_i, &cmT,
-                    );
-                assert_eq!(U_i1, expected);
-            }
-        } else {
-            // CycleFold part:
-            let cfW_circuit = NovaCycleFoldCircuit::<C1> {
-                r_bits: Some(r_bits.clone()),
-                points: Some(vec![self.U_i.clone().cmW, self.u_i.clone().cmW]),
-            };
-            let cfE_circuit = NovaCycleFoldCircuit::<C1> {
-                r_bits: Some(r_bits.clone()),
-                points: Some(vec![self.U_i.clone().cmE, cmT]),
-            };
-
-            // fold self.cf_U_i + cfW_U -> folded running with cfW
-            let (cfW_u_i, cfW_W_i1, cfW_U_i1, cfW_cmT) = self.fold_cyclefold_circuit(
-                &mut transcript,
-                self.cf_W_i.clone(), // CycleFold running instance witness
-                self.cf_U_i.clone(), // CycleFold running instance
-                cfW_circuit,
-                &mut rng,
-            )?;
-            // fold [the output from folding self.cf_U_i + cfW_U] + cfE_U = folded_running_with_cfW + cfE
-            let (cfE_u_i, cf_W_i1, cf_U_i1, cf_cmT) = self.fold_cyclefold_circuit(
-                &mut transcript,
-                cfW_W_i1,
-                cfW_U_i1.clone(),
-                cfE_circuit,
-                &mut rng,
-            )?;
-
-            augmented_F_circuit = AugmentedFCircuit::<C1, C2, FC> {
-                poseidon_config: self.poseidon_config.clone(),
-                pp_hash: Some(self.pp_hash),
-                i: Some(self.i),
-                i_usize: Some(i_usize),
-                z_0: Some(self.z_0.clone()),
-                z_i: Some(self.z_i.clone()),
-                external_inputs: Some(external_inputs.clone()),
-                u_i_cmW: Some(self.u_i.cmW),
-                U_i: Some(self.U_i.clone()),
-                U_i1_cmE: Some(U_i1.cmE),
-                U_i1_cmW: Some(U_i1.cmW),
-                cmT: Some(cmT),
-                F: self.F.clone(),
-                // cyclefold values
-                cf1_u_i_cmW: Some(cfW_u_i.cmW),
-                cf2_u_i_cmW: Some(cfE_u_i.cmW),
-                cf_U_i: Some(self.cf_U_i.clone()),
-                cf1_cmT: Some(cfW_cmT),
-                cf2_cmT: Some(cf_cmT),
-            };
-
-            self.cf_W_i = cf_W_i1;
-            self.cf_U_i = cf_U_i1;
-        }
-
-        let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
-
-        let z_i1 = augmented_F_circuit
-            .compute_next_state(cs.clone())?
-            .value()?;
-
-        #[cfg(test)]
-        assert!(cs.is_satisfied()?);
-
-        let cs = cs.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
-        let (w_i1, x_i1) = extract_w_x::<C1::ScalarField>(&cs);
-
-        #[cfg(test)]
-        if x_i1.len() != 2 {
-            return Err(Error::NotExpectedLength(x_i1.len(), 2));
-        }
-
-        // set values for next iteration
-        self.i += C1::ScalarField::one();
-        self.z_i = z_i1;
-        self.w_i = Witness::<C1>::new::<H>(w_i1, self.r1cs.n_constraints(), &mut rng);
-        self.u_i = self.w_i.commit::<CS1, H>(&self.cs_pp, x_i1)?;
-        self.W_i = W_i1;
-        self.U_i = U_i1;
-
-        #[cfg(test)]
-        {
-            self.u_i.check_incoming()?;
-            self.r1cs.check_relation(&self.w_i, &self.u_i)?;
-            self.r1cs.check_relation(&self.W_i, &self.U_i)?;
-        }
-
-        Ok(())
+        self.prove_step_opt(rng, external_inputs, None)
     }
 
     fn state(&self) -> Vec<C1::ScalarField> {
@@ -848,7 +774,7 @@ where
     }
 
     fn from_ivc_proof(
-        ivc_proof: IVCProof<C1, C2>,
+        ivc_proof: Self::IVCProof,
         fcircuit_params: FC::Params,
         params: (Self::ProverParam, Self::VerifierParam),
     ) -> Result<Self, Error> {
@@ -866,21 +792,8 @@ where
         let (pp, vp) = params;
 
         let f_circuit = FC::new(fcircuit_params)?;
-        let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
-        let cs2 = ConstraintSystem::<C1::BaseField>::new_ref();
-        let augmented_F_circuit =
-            AugmentedFCircuit::<C1, C2, FC>::empty(&pp.poseidon_config, f_circuit.clone());
-        let cf_circuit = NovaCycleFoldCircuit::<C1>::empty();
-
-        augmented_F_circuit.generate_constraints(cs.clone())?;
-        cs.finalize();
-        let cs = cs.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
-        let r1cs = extract_r1cs::<C1::ScalarField>(&cs)?;
-
-        cf_circuit.generate_constraints(cs2.clone())?;
-        cs2.finalize();
-        let cs2 = cs2.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
-        let cf_r1cs = extract_r1cs::<C1::BaseField>(&cs2)?;
+        let (r1cs, cf_r1cs) =
+            get_r1cs::<C1, C2, FC, S, T, N::Gadget<T>, B>(&pp.poseidon_config, f_circuit.clone())?;
 
         Ok(Self {
             r1cs,
@@ -899,10 +812,12 @@ where
             U_i,
             cf_W_i,
             cf_U_i,
+            _s: PhantomData,
+            _t: PhantomData,
         })
     }
 
-    /// Implements IVC.V of Nov.clone()a+CycleFold. Notice that this method does not include the
+    /// Implements IVC.V of Nova+CycleFold. Notice that this method does not include the
     /// commitments verification, which is done in the Decider.
     fn verify(vp: Self::VerifierParam, ivc_proof: Self::IVCProof) -> Result<(), Error> {
         let Self::IVCProof {
@@ -917,7 +832,7 @@ where
             cf_U_i,
         } = ivc_proof;
 
-        let sponge = PoseidonSponge::<C1::ScalarField>::new(&vp.poseidon_config);
+        let sponge = S::new(&vp.poseidon_config);
 
         if num_steps == C1::ScalarField::zero() {
             if z_0 != z_i {
@@ -926,7 +841,7 @@ where
             return Ok(());
         }
 
-        if u_i.x.len() != 2 || U_i.x.len() != 2 {
+        if u_i.get_public_inputs().len() != 2 || U_i.get_public_inputs().len() != 2 {
             return Err(Error::IVCVerificationFail);
         }
 
@@ -935,21 +850,17 @@ where
         // check that u_i's output points to the running instance
         // u_i.X[0] == H(i, z_0, z_i, U_i)
         let expected_u_i_x = U_i.hash(&sponge, pp_hash, num_steps, &z_0, &z_i);
-        if expected_u_i_x != u_i.x[0] {
+        if expected_u_i_x != u_i.get_public_inputs()[0] {
             return Err(Error::IVCVerificationFail);
         }
         // u_i.X[1] == H(cf_U_i)
         let expected_cf_u_i_x = cf_U_i.hash_cyclefold(&sponge, pp_hash);
-        if expected_cf_u_i_x != u_i.x[1] {
+        if expected_cf_u_i_x != u_i.get_public_inputs()[1] {
             return Err(Error::IVCVerificationFail);
         }
 
-        // check R1CS satisfiability, which is equivalent to checking if `u_i`
-        // is an incoming instance and if `w_i` and `u_i` satisfy RelaxedR1CS
-        u_i.check_incoming()?;
-        vp.r1cs.check_relation(&w_i, &u_i)?;
-        // check RelaxedR1CS satisfiability
-        vp.r1cs.check_relation(&W_i, &U_i)?;
+        // check the satisfiability of the incoming and running instances
+        N::check_instances(&vp.r1cs, &W_i, &U_i, &w_i, &u_i)?;
 
         // check CycleFold RelaxedR1CS satisfiability
         vp.cf_r1cs.check_relation(&cf_W_i, &cf_U_i)?;
@@ -958,20 +869,208 @@ where
     }
 }
 
-impl<C1, C2, FC, CS1, CS2, const H: bool> Nova<C1, C2, FC, CS1, CS2, H>
+impl<C1, C2, FC, CS1, CS2, N, const H: bool, S, T, const B: bool>
+    GenericNova<C1, C2, FC, CS1, CS2, N, H, S, T, B>
 where
     C1: Curve,
     C2: Curve,
     FC: FCircuit<C1::ScalarField>,
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
+    N: NovaNIFSTrait<C1, CS1, S, H>,
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
 {
+    /// Implements [`FoldingScheme::prove_step`]. If `folded` is given, it contains the folded
+    /// witness `W_{i+1}` and the proof of folding `u_i` into `U_i`, which have been computed by a
+    /// party knowing `W_i` and `w_i` (see [`zk::BlindedInstance`]), so only the folded instance is
+    /// computed, and the prover does not need the witnesses `W_i` and `w_i`.
+    pub(crate) fn prove_step_opt(
+        &mut self,
+        mut rng: impl RngCore,
+        external_inputs: FC::ExternalInputs,
+        folded: Option<(N::Witness, N::Proof)>,
+    ) -> Result<(), Error> {
+        // ensure that commitments are blinding if user has specified so.
+        if H && self.i >= C1::ScalarField::one() {
+            let blinding_commitments = if let Some((W_i1, _)) = &folded {
+                // the witnesses being folded are not known, check the given folded one instead
+                N::blinding_factors(W_i1)
+            } else if self.i == C1::ScalarField::one() {
+                // blinding values of the running instances are zero at the first iteration
+                N::blinding_factors(&self.w_i)
+            } else {
+                [
+                    N::blinding_factors(&self.w_i),
+                    N::blinding_factors(&self.W_i),
+                ]
+                .concat()
+            };
+            if blinding_commitments.contains(&C1::ScalarField::zero()) {
+                return Err(Error::IncorrectBlinding(
+                    H,
+                    format!("{:?}", blinding_commitments),
+                ));
+            }
+        }
+        // `transcript` is for challenge generation.
+        let mut transcript = S::new(&self.poseidon_config);
+
+        if self.z_i.len() != self.F.state_len() {
+            return Err(Error::NotSameLength(
+                "z_i.len()".to_string(),
+                self.z_i.len(),
+                "F.state_len()".to_string(),
+                self.F.state_len(),
+            ));
+        }
+
+        if self.i > C1::ScalarField::from_le_bytes_mod_order(&usize::MAX.to_le_bytes()) {
+            return Err(Error::MaxStep);
+        }
+
+        let i_usize;
+
+        #[cfg(target_pointer_width = "64")]
+        {
+            let mut i_bytes: [u8; 8] = [0; 8];
+            i_bytes.copy_from_slice(&self.i.into_bigint().to_bytes_le()[..8]);
+            i_usize = usize::from_le_bytes(i_bytes);
+        }
+
+        #[cfg(target_pointer_width = "32")]
+        {
+            let mut i_bytes: [u8; 4] = [0; 4];
+            i_bytes.copy_from_slice(&self.i.into_bigint().to_bytes_le()[..4]);
+            i_usize = usize::from_le_bytes(i_bytes);
+        }
+
+        let mut augmented_F_circuit = AugmentedFCircuit::<C1, C2, FC, S, T, N::Gadget<T>> {
+            poseidon_config: self.poseidon_config.clone(),
+            n_constraints: self.r1cs.n_constraints(),
+            blinded_base_case: B,
+            pp_hash: Some(self.pp_hash),
+            i: Some(self.i),
+            i_usize: Some(i_usize),
+            z_0: Some(self.z_0.clone()),
+            z_i: Some(self.z_i.clone()),
+            external_inputs: Some(external_inputs),
+            u_i: Some(self.u_i.clone()),
+            U_i: Some(self.U_i.clone()),
+            U_i1: None,
+            proof: None,
+            F: self.F.clone(),
+            // cyclefold values
+            cf_u_i_cmW: None,
+            cf_U_i: None,
+            cf_cmT: None,
+            _s: PhantomData,
+            _t: PhantomData,
+        };
+
+        let (W_i1, U_i1) = if self.i == C1::ScalarField::zero() {
+            // base case: the running instance U_1 is the initial running instance, which is the
+            // dummy instance U_{\bot} unless it has been replaced by a random one (see
+            // [`zk::BlindedInstance`]), and the circuit binds it to u_1.x. So the instances are not
+            // folded (notice that depending on the NIFS, folding the dummy instances might not
+            // result in a dummy instance, eg. in Ova).
+            augmented_F_circuit.U_i1 = Some(self.U_i.clone());
+
+            (self.W_i.clone(), self.U_i.clone())
+        } else {
+            // fold the primary instances, or if the folded witness and the folding proof are
+            // given, just compute the folded instance
+            let (W_i1, U_i1, proof, r_bits) = match folded {
+                None => N::prove(
+                    &self.cs_pp,
+                    &self.r1cs,
+                    &mut transcript,
+                    self.pp_hash,
+                    &self.W_i,
+                    &self.U_i,
+                    &self.w_i,
+                    &self.u_i,
+                )?,
+                Some((W_i1, proof)) => {
+                    let (U_i1, r_bits) =
+                        N::verify(&mut transcript, self.pp_hash, &self.U_i, &self.u_i, &proof)?;
+                    (W_i1, U_i1, proof, r_bits)
+                }
+            };
+
+            // CycleFold part:
+            // fold self.cf_U_i with the CycleFold instance of each of the CycleFold circuits,
+            // sequentially
+            let (mut cf_W_i1, mut cf_U_i1) = (self.cf_W_i.clone(), self.cf_U_i.clone());
+            let mut cf_u_i_cmW = vec![];
+            let mut cf_cmT = vec![];
+            for points in N::cyclefold_points(&self.U_i, &self.u_i, &proof) {
+                let cf_circuit = NovaCycleFoldCircuit::<C1> {
+                    r_bits: Some(r_bits.clone()),
+                    points: Some(points),
+                };
+                let cf_u_i;
+                let cf_cmT_i;
+                (cf_u_i, cf_W_i1, cf_U_i1, cf_cmT_i) = self.fold_cyclefold_circuit(
+                    &mut transcript,
+                    cf_W_i1,
+                    cf_U_i1,
+                    cf_circuit,
+                    &mut rng,
+                )?;
+                cf_u_i_cmW.push(cf_u_i.cmW);
+                cf_cmT.push(cf_cmT_i);
+            }
+
+            augmented_F_circuit.U_i1 = Some(U_i1.clone());
+            augmented_F_circuit.proof = Some(proof);
+            augmented_F_circuit.cf_u_i_cmW = Some(cf_u_i_cmW);
+            augmented_F_circuit.cf_U_i = Some(self.cf_U_i.clone());
+            augmented_F_circuit.cf_cmT = Some(cf_cmT);
+
+            self.cf_W_i = cf_W_i1;
+            self.cf_U_i = cf_U_i1;
+
+            (W_i1, U_i1)
+        };
+
+        let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
+
+        let z_i1 = augmented_F_circuit
+            .compute_next_state(cs.clone())?
+            .value()?;
+
+        #[cfg(test)]
+        assert!(cs.is_satisfied()?);
+
+        let cs = cs.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
+        let (w_i1, x_i1) = extract_w_x::<C1::ScalarField>(&cs);
+
+        #[cfg(test)]
+        if x_i1.len() != 2 {
+            return Err(Error::NotExpectedLength(x_i1.len(), 2));
+        }
+
+        // set values for next iteration
+        self.i += C1::ScalarField::one();
+        self.z_i = z_i1;
+        (self.w_i, self.u_i) =
+            N::new_incoming(&mut rng, &self.cs_pp, &self.r1cs, w_i1, x_i1, &W_i1, &U_i1)?;
+        self.W_i = W_i1;
+        self.U_i = U_i1;
+
+        #[cfg(test)]
+        N::check_instances(&self.r1cs, &self.W_i, &self.U_i, &self.w_i, &self.u_i)?;
+
+        Ok(())
+    }
+
     // folds the given cyclefold circuit and its instances
     #[allow(clippy::type_complexity)]
-    fn fold_cyclefold_circuit<T: Transcript<C1::ScalarField>>(
+    fn fold_cyclefold_circuit(
         &self,
-        transcript: &mut T,
+        transcript: &mut S,
         cf_W_i: CycleFoldWitness<C2>, // witness of the running instance
         cf_U_i: CycleFoldCommittedInstance<C2>, // running instance
         cf_circuit: NovaCycleFoldCircuit<C1>,
@@ -1010,10 +1109,18 @@ pub fn get_r1cs_from_cs<F: PrimeField>(
     Ok(r1cs)
 }
 
-/// helper method to get the R1CS for both the AugmentedFCircuit and the CycleFold circuit
+/// helper method to get the R1CS for both the AugmentedFCircuit and the CycleFold circuit, where
+/// `G` is the NIFS gadget used in the AugmentedFCircuit, and `S` and `T` are the sponge and its
+/// in-circuit counterpart. `B` specifies whether the base case of the AugmentedFCircuit allows a
+/// prover-chosen initial running instance (see [`GenericNova`]).
+///
+/// Depending on the NIFS, the size of the AugmentedFCircuit might depend on its own number of
+/// constraints (eg. in Mova, the evaluation point of the MLE of the error term has `log2` of the
+/// number of constraints coordinates), so we iterate until reaching the fixed point, which for
+/// Nova and Ova is reached at the first iteration.
 #[allow(clippy::type_complexity)]
-pub fn get_r1cs<C1, C2, FC>(
-    poseidon_config: &PoseidonConfig<C1::ScalarField>,
+pub fn get_r1cs<C1, C2, FC, S, T, G, const B: bool>(
+    poseidon_config: &S::Config,
     F_circuit: FC,
 ) -> Result<(R1CS<C1::ScalarField>, R1CS<C2::ScalarField>), Error>
 where
@@ -1021,10 +1128,27 @@ where
     C2: Curve,
     FC: FCircuit<C1::ScalarField>,
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
+    G: NovaNIFSGadgetTrait<C1, S, T>,
 {
-    let augmented_F_circuit = AugmentedFCircuit::<C1, C2, FC>::empty(poseidon_config, F_circuit);
+    let mut n_constraints = 0;
+    let r1cs = loop {
+        let augmented_F_circuit = AugmentedFCircuit::<C1, C2, FC, S, T, G>::empty(
+            poseidon_config,
+            F_circuit.clone(),
+            n_constraints,
+            B,
+        );
+        let r1cs = get_r1cs_from_cs::<C1::ScalarField>(augmented_F_circuit)?;
+        if G::dummy_running_instance(r1cs.n_constraints())
+            == G::dummy_running_instance(n_constraints)
+        {
+            break r1cs;
+        }
+        n_constraints = r1cs.n_constraints();
+    };
     let cf_circuit = NovaCycleFoldCircuit::<C1>::empty();
-    let r1cs = get_r1cs_from_cs::<C1::ScalarField>(augmented_F_circuit)?;
     let cf_r1cs = get_r1cs_from_cs::<C2::ScalarField>(cf_circuit)?;
     Ok((r1cs, cf_r1cs))
 }
@@ -1037,8 +1161,13 @@ pub mod tests {
 
     use super::*;
     use crate::commitment::pedersen::Pedersen;
+    use crate::folding::nova::nifs::NIFSTrait;
     use crate::frontend::utils::CubicFCircuit;
     use crate::transcript::poseidon::poseidon_canonical_config;
+    use crate::transcript::poseidon2::{
+        poseidon2_canonical_config, Poseidon2Sponge, Poseidon2SpongeVar,
+    };
+    use ark_crypto_primitives::sponge::CryptographicSponge;
 
     /// This test tests the Nova+CycleFold IVC, and by consequence it is also testing the
     /// AugmentedFCircuit
@@ -1064,6 +1193,13 @@ pub mod tests {
         // run the test using KZG for the commitments on the main curve, and Pedersen for the
         // commitments on the secondary curve
         let _ = test_ivc_opt::<KZG<Bn254>, Pedersen<Projective2>, false>(
+            poseidon_config.clone(),
+            F_circuit,
+            3,
+        )?;
+
+        // and the same in hiding mode
+        let _ = test_ivc_opt::<KZG<Bn254, true>, Pedersen<Projective2, true>, true>(
             poseidon_config,
             F_circuit,
             3,
@@ -1071,6 +1207,144 @@ pub mod tests {
         Ok(())
     }
 
+    /// Runs the Nova+CycleFold IVC using Poseidon2 as the sponge for the transcripts and hashes,
+    /// both natively and in the augmented circuit.
+    #[test]
+    fn test_ivc_poseidon2() -> Result<(), Error> {
+        type N = Nova<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+            Poseidon2Sponge<Fr>,
+            Poseidon2SpongeVar<Fr>,
+        >;
+
+        let mut rng = ark_std::test_rng();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+
+        let prep_param = PreprocessorParam::new(poseidon2_canonical_config::<Fr>(), F_circuit);
+        let nova_params = N::preprocess(&mut rng, &prep_param)?;
+
+        let num_steps = 3;
+        let mut nova = N::init(&nova_params, F_circuit, vec![Fr::from(3_u32)])?;
+        for _ in 0..num_steps {
+            nova.prove_step(&mut rng, (), None)?;
+        }
+        assert_eq!(Fr::from(num_steps as u32), nova.i);
+
+        // only the commitment params are serialized, the sponge config is rebuilt from its
+        // canonical constructor when deserializing
+        let mut nova_vp_serialized = vec![];
+        nova_params
+            .1
+            .serialize_compressed(&mut nova_vp_serialized)?;
+        assert_eq!(
+            nova_vp_serialized.len(),
+            nova_params.1.cs_vp.compressed_size() + nova_params.1.cf_cs_vp.compressed_size()
+        );
+        let nova_vp_deserialized = N::vp_deserialize_with_mode(
+            &mut nova_vp_serialized.as_slice(),
+            ark_serialize::Compress::Yes,
+            ark_serialize::Validate::Yes,
+            (), // fcircuit_params
+        )?;
+
+        N::verify(nova_vp_deserialized, nova.ivc_proof())?;
+        Ok(())
+    }
+
+    /// Checks that the first step, which does not fold the initial instances, results in the same
+    /// running instance as folding the dummy instances with the NIFS, both with and without
+    /// hiding commitments.
+    #[test]
+    fn test_base_case() -> Result<(), Error> {
+        test_base_case_opt::<Pedersen<Projective>, Pedersen<Projective2>, false>()?;
+        test_base_case_opt::<Pedersen<Projective, true>, Pedersen<Projective2, true>, true>()?;
+        Ok(())
+    }
+
+    fn test_base_case_opt<
+        CS1: CommitmentScheme<Projective, H>,
+        CS2: CommitmentScheme<Projective2, H>,
+        const H: bool,
+    >() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+
+        let prep_param =
+            PreprocessorParam::<Projective, Projective2, CubicFCircuit<Fr>, CS1, CS2, H>::new(
+                poseidon_config.clone(),
+                F_circuit,
+            );
+        let params = Nova::<Projective, Projective2, CubicFCircuit<Fr>, CS1, CS2, H>::preprocess(
+            &mut rng,
+            &prep_param,
+        )?;
+        let mut nova = Nova::<Projective, Projective2, CubicFCircuit<Fr>, CS1, CS2, H>::init(
+            &params,
+            F_circuit,
+            vec![Fr::from(3_u32)],
+        )?;
+
+        // fold the dummy instances as done by the NIFS prover
+        let mut transcript = PoseidonSponge::<Fr>::new(&poseidon_config);
+        let (W_1, U_1, _, _) = NIFS::<Projective, CS1, PoseidonSponge<Fr>, H>::prove(
+            &params.0.cs_pp,
+            &nova.r1cs,
+            &mut transcript,
+            nova.pp_hash,
+            &nova.W_i,
+            &nova.U_i,
+            &nova.w_i,
+            &nova.u_i,
+        )?;
+
+        nova.prove_step(&mut rng, (), None)?;
+        assert_eq!(nova.W_i, W_1);
+        assert_eq!(nova.U_i, U_1);
+        Ok(())
+    }
+
+    /// Checks that setting the initial running instance (which, when the base case allows a
+    /// prover-chosen one, the circuit hashes without checking it) to an unsatisfiable instance is
+    /// detected, both by the prover's checks and by the IVC verifier.
+    #[test]
+    fn test_unsatisfiable_initial_running_instance() -> Result<(), Error> {
+        type N = Nova<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+            PoseidonSponge<Fr>,
+            PoseidonSpongeVar<Fr>,
+            true,
+        >;
+        let mut rng = ark_std::test_rng();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let prep_param = PreprocessorParam::new(poseidon_canonical_config::<Fr>(), F_circuit);
+        let nova_params = N::preprocess(&mut rng, &prep_param)?;
+        let mut nova = N::init(&nova_params, F_circuit, vec![Fr::from(3_u32)])?;
+
+        // a non-dummy running instance, which is not satisfied by the dummy witness W_i
+        nova.U_i.u = Fr::one();
+        nova.U_i.x = vec![Fr::rand(&mut rng), Fr::rand(&mut rng)];
+        nova.U_i.cmW = Projective::rand(&mut rng);
+        assert_ne!(nova.U_i, CommittedInstance::dummy(&nova.r1cs));
+        assert!(nova.r1cs.check_relation(&nova.W_i, &nova.U_i).is_err());
+
+        // the circuit accepts it, but the check of the instances at the end of the step fails
+        assert!(nova.prove_step(&mut rng, (), None).is_err());
+        // and so does the verification of the resulting IVC proof
+        assert!(N::verify(nova_params.1, nova.ivc_proof()).is_err());
+        Ok(())
+    }
+
     // test_ivc allowing to choose the CommitmentSchemes
     #[allow(clippy::type_complexity)]
     pub(crate) fn test_ivc_opt<
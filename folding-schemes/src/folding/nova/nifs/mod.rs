@@ -8,18 +8,20 @@
 /// - [Mova](https://eprint.iacr.org/2024/1220.pdf)
 use ark_crypto_primitives::sponge::{constraints::AbsorbGadget, Absorb, CryptographicSponge};
 use ark_r1cs_std::{alloc::AllocVar, boolean::Boolean, fields::fp::FpVar};
-use ark_relations::r1cs::SynthesisError;
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::fmt::Debug;
-use ark_std::rand::RngCore;
+use ark_std::{cmp::max, rand::RngCore};
 
-use crate::arith::r1cs::R1CS;
+use crate::arith::{r1cs::R1CS, Arith};
 use crate::commitment::CommitmentScheme;
-use crate::folding::circuits::CF1;
-use crate::folding::traits::{CommittedInstanceOps, CommittedInstanceVarOps};
+use crate::folding::circuits::{nonnative::affine::NonNativeAffineVar, CF1};
+use crate::folding::traits::{CommittedInstanceOps, CommittedInstanceVarOps, Dummy};
 use crate::transcript::{Transcript, TranscriptVar};
 use crate::{Curve, Error};
 
 pub mod mova;
+pub mod mova_circuits;
 pub mod nova;
 pub mod nova_circuits;
 pub mod ova;
@@ -122,6 +124,143 @@ pub trait NIFSGadgetTrait<C: Curve, S: CryptographicSponge, T: TranscriptVar<CF1
     ) -> Result<(Self::CommittedInstanceVar, Vec<Boolean<CF1<C>>>), SynthesisError>;
 }
 
+/// Extends the [`NIFSTrait`] with the parts of the Nova+CycleFold IVC that depend on the NIFS
+/// being used, so that Nova and its variants (eg. Ova, Mova) share the same IVC implementation
+/// ([`crate::folding::nova::GenericNova`]) and the same augmented circuit
+/// ([`crate::folding::nova::circuits::AugmentedFCircuit`]).
+pub trait NovaNIFSTrait<
+    C: Curve,
+    CS: CommitmentScheme<C, H>,
+    S: Transcript<CF1<C>>,
+    const H: bool = false,
+>:
+    NIFSTrait<
+    C,
+    CS,
+    S,
+    H,
+    CommittedInstance: CommittedInstanceOps<C>
+                           + Dummy<usize>
+                           + for<'a> Dummy<&'a R1CS<CF1<C>>>
+                           + Eq
+                           + CanonicalSerialize
+                           + CanonicalDeserialize,
+    Witness: for<'a> Dummy<&'a R1CS<CF1<C>>> + Eq + CanonicalSerialize + CanonicalDeserialize,
+>
+{
+    /// In-circuit counterpart of the NIFS, used in the augmented circuit, where `T` is the
+    /// in-circuit counterpart of the transcript `S`.
+    type Gadget<T: TranscriptVar<CF1<C>, S>>: NovaNIFSGadgetTrait<
+        C,
+        S,
+        T,
+        CommittedInstance = Self::CommittedInstance,
+        Proof = Self::Proof,
+    >;
+
+    /// Returns the maximum length of the vectors committed with `CS` for the given R1CS, so that
+    /// the commitment scheme can be set up accordingly.
+    fn commitment_len(r1cs: &R1CS<CF1<C>>) -> usize {
+        // by default, the witness vector `w` and the error term `e` are committed separately,
+        // where the length of `e` is the number of constraints
+        max(r1cs.n_constraints(), r1cs.n_witnesses())
+    }
+
+    /// Returns the blinding factors used in the commitments of the given witness.
+    fn blinding_factors(w: &Self::Witness) -> Vec<CF1<C>>;
+
+    /// Returns, for each of the CycleFold circuits used at each step, the points `[P_1, P_2]` (taken
+    /// from the running instance `U_i`, the incoming instance `u_i` and the NIFS proof) for which
+    /// the CycleFold circuit computes `P_1 + r * P_2`.
+    fn cyclefold_points(
+        U_i: &Self::CommittedInstance,
+        u_i: &Self::CommittedInstance,
+        proof: &Self::Proof,
+    ) -> Vec<Vec<C>>;
+
+    /// Generates the incoming witness and committed instance from the witness `w` and the public
+    /// inputs `x` of the augmented circuit, where `(W_i, U_i)` is the running instance with which
+    /// they are going to be folded in the next step.
+    fn new_incoming(
+        mut rng: impl RngCore,
+        params: &CS::ProverParams,
+        r1cs: &R1CS<CF1<C>>,
+        w: Vec<CF1<C>>,
+        x: Vec<CF1<C>>,
+        _W_i: &Self::Witness,
+        _U_i: &Self::CommittedInstance,
+    ) -> Result<(Self::Witness, Self::CommittedInstance), Error> {
+        let w_i = Self::new_witness(w, r1cs.n_constraints(), &mut rng);
+        let u_i = Self::new_instance(&mut rng, params, &w_i, x, vec![])?;
+        Ok((w_i, u_i))
+    }
+
+    /// Checks that the incoming instance `(w_i, u_i)` and the running instance `(W_i, U_i)`
+    /// satisfy the relations that the IVC verifier is able to check.
+    fn check_instances(
+        r1cs: &R1CS<CF1<C>>,
+        W_i: &Self::Witness,
+        U_i: &Self::CommittedInstance,
+        w_i: &Self::Witness,
+        u_i: &Self::CommittedInstance,
+    ) -> Result<(), Error>;
+}
+
+/// In-circuit counterpart of the [`NovaNIFSTrait`], which defines how the augmented circuit
+/// ([`crate::folding::nova::circuits::AugmentedFCircuit`]) handles the committed instances of the
+/// NIFS being used.
+pub trait NovaNIFSGadgetTrait<C: Curve, S: CryptographicSponge, T: TranscriptVar<CF1<C>, S>>:
+    NIFSGadgetTrait<
+    C,
+    S,
+    T,
+    CommittedInstance: Dummy<usize> + PartialEq,
+    CommittedInstanceVar: CommittedInstanceVarOps<C, PointVar = NonNativeAffineVar<C>>,
+>
+{
+    /// Number of CycleFold circuits used at each step to fold the commitments of the instances.
+    const N_CYCLEFOLD_CIRCUITS: usize;
+
+    /// Returns the dummy running instance `U_{\bot}` of an augmented circuit with `n_constraints`
+    /// constraints, which has to match the one obtained from its R1CS through
+    /// [`Dummy<&R1CS>`](Dummy). By default its size does not depend on the number of constraints.
+    fn dummy_running_instance(_n_constraints: usize) -> Self::CommittedInstance {
+        Self::CommittedInstance::dummy(2)
+    }
+
+    /// Returns the NIFS proof used in the augmented circuit when no proof is given (ie. when
+    /// obtaining its R1CS, and at the base case), for an augmented circuit with `n_constraints`
+    /// constraints.
+    fn dummy_proof(n_constraints: usize) -> Self::Proof;
+
+    /// Allocates the incoming instance `u_i`, whose commitments are provided by the prover as
+    /// witnesses, and whose public inputs `x` have been computed in-circuit.
+    fn new_incoming_var(
+        cs: ConstraintSystemRef<CF1<C>>,
+        u_i: &Self::CommittedInstance,
+        x: Vec<FpVar<CF1<C>>>,
+    ) -> Result<Self::CommittedInstanceVar, SynthesisError>;
+
+    /// Sets the commitments of `U`, given in the same order as returned by
+    /// [`CommittedInstanceVarOps::get_commitments`]. This is used to set the commitments of the
+    /// folded instance, which are not computed by [`NIFSGadgetTrait::verify`], since their folding
+    /// is delegated to the CycleFold circuits.
+    fn set_commitments(
+        U: &mut Self::CommittedInstanceVar,
+        cms: Vec<NonNativeAffineVar<C>>,
+    ) -> Result<(), SynthesisError>;
+
+    /// In-circuit counterpart of [`NovaNIFSTrait::cyclefold_points`], which additionally returns
+    /// the point `P_3 = P_1 + r * P_2` from the folded instance `U_i1`, so that for each of the
+    /// CycleFold circuits it returns `[P_1, P_2, P_3]`.
+    fn cyclefold_points_var(
+        U_i: &Self::CommittedInstanceVar,
+        u_i: &Self::CommittedInstanceVar,
+        proof: &Self::ProofVar,
+        U_i1: &Self::CommittedInstanceVar,
+    ) -> Vec<Vec<NonNativeAffineVar<C>>>;
+}
+
 /// These tests are the generic tests so that in the tests of Nova, Mova, Ova, we just need to
 /// instantiate these tests to test both the NIFSTrait and NIFSGadgetTrait implementations for each
 /// of the schemes.
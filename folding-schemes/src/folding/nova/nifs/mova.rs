@@ -1,21 +1,23 @@
 /// This module contains the implementation the NIFSTrait for the
 /// [Mova](https://eprint.iacr.org/2024/1220.pdf) NIFS (Non-Interactive Folding Scheme).
 use ark_crypto_primitives::sponge::Absorb;
-use ark_ff::PrimeField;
-use ark_poly::Polynomial;
+use ark_ff::{BigInteger, PrimeField};
+use ark_poly::{univariate::DensePolynomial, Polynomial};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::{log2, marker::PhantomData, rand::RngCore, One, UniformRand, Zero};
 
 use super::{
+    mova_circuits::{CommittedInstanceVar, NIFSGadget},
     nova::NIFS as NovaNIFS,
     pointvsline::{PointVsLine, PointVsLineProof, PointvsLineEvaluationClaim},
-    NIFSTrait,
+    NIFSTrait, NovaNIFSTrait,
 };
 use crate::arith::{r1cs::R1CS, Arith, ArithRelation};
 use crate::commitment::CommitmentScheme;
+use crate::constants::NOVA_N_BITS_RO;
 use crate::folding::circuits::CF1;
-use crate::folding::traits::Dummy;
-use crate::transcript::Transcript;
+use crate::folding::traits::{CommittedInstanceOps, Dummy, Inputize};
+use crate::transcript::{Transcript, TranscriptVar};
 use crate::utils::{
     mle::dense_vec_to_dense_mle,
     vec::{is_zero_vec, vec_add, vec_scalar_mul},
@@ -34,9 +36,8 @@ pub struct CommittedInstance<C: Curve> {
 }
 
 impl<C: Curve> Absorb for CommittedInstance<C> {
-    fn to_sponge_bytes(&self, _dest: &mut Vec<u8>) {
-        // This is never called
-        unimplemented!()
+    fn to_sponge_bytes(&self, dest: &mut Vec<u8>) {
+        C::ScalarField::batch_to_sponge_bytes(&self.to_sponge_field_elements_as_vec(), dest);
     }
 
     fn to_sponge_field_elements<F: PrimeField>(&self, dest: &mut Vec<F>) {
@@ -60,6 +61,51 @@ impl<C: Curve> Dummy<usize> for CommittedInstance<C> {
     }
 }
 
+impl<C: Curve> Dummy<&R1CS<CF1<C>>> for CommittedInstance<C> {
+    fn dummy(r1cs: &R1CS<CF1<C>>) -> Self {
+        Self {
+            // the evaluation point has one coordinate per variable of the MLE of E, whose length
+            // is the number of constraints
+            rE: vec![C::ScalarField::zero(); log2(r1cs.n_constraints()) as usize],
+            mleE: C::ScalarField::zero(),
+            u: C::ScalarField::zero(),
+            cmW: C::zero(),
+            x: vec![C::ScalarField::zero(); r1cs.n_public_inputs()],
+        }
+    }
+}
+
+impl<C: Curve> CommittedInstanceOps<C> for CommittedInstance<C> {
+    type Var = CommittedInstanceVar<C>;
+
+    fn get_commitments(&self) -> Vec<C> {
+        vec![self.cmW]
+    }
+
+    fn get_public_inputs(&self) -> &[CF1<C>] {
+        &self.x
+    }
+
+    fn is_incoming(&self) -> bool {
+        self.mleE == C::ScalarField::zero() && self.u == One::one()
+    }
+}
+
+impl<C: Curve> Inputize<CF1<C>> for CommittedInstance<C> {
+    /// Returns the internal representation in the same order as how the value
+    /// is allocated in `CommittedInstanceVar::new_input`.
+    fn inputize(&self) -> Vec<CF1<C>> {
+        [
+            &[self.u][..],
+            &self.x,
+            &self.rE,
+            &[self.mleE],
+            &self.cmW.inputize_nonnative(),
+        ]
+        .concat()
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
 pub struct Witness<C: Curve> {
     pub E: Vec<C::ScalarField>,
@@ -122,6 +168,26 @@ pub struct Proof<C: Curve> {
     pub mleT: C::ScalarField,
 }
 
+impl<C: Curve> Dummy<usize> for Proof<C> {
+    /// Returns a proof for instances whose error terms are all zeros, where `n_vars` is the
+    /// number of variables of the MLE of `E`.
+    fn dummy(n_vars: usize) -> Self {
+        // h1 and h2 have degree n_vars
+        let h = DensePolynomial {
+            coeffs: vec![C::ScalarField::zero(); n_vars + 1],
+        };
+        Self {
+            h_proof: PointVsLineProof {
+                h1: h.clone(),
+                h2: h,
+            },
+            mleE1_prime: C::ScalarField::zero(),
+            mleE2_prime: C::ScalarField::zero(),
+            mleT: C::ScalarField::zero(),
+        }
+    }
+}
+
 /// Implements the Non-Interactive Folding Scheme described in section 4 of
 /// [Mova](https://eprint.iacr.org/2024/1220.pdf).
 /// `H` specifies whether the NIFS will use a blinding factor
@@ -246,7 +312,9 @@ impl<C: Curve, CS: CommitmentScheme<C, H>, T: Transcript<C::ScalarField>, const
 
         transcript.absorb(&mleT_evaluated);
 
-        let alpha: C::ScalarField = transcript.get_challenge();
+        let alpha_bits = transcript.squeeze_bits(NOVA_N_BITS_RO);
+        let alpha = C::ScalarField::from_bigint(BigInteger::from_bits_le(&alpha_bits))
+            .ok_or(Error::OutOfBounds)?;
 
         let ci = Self::fold_committed_instance(
             alpha,
@@ -265,13 +333,7 @@ impl<C: Curve, CS: CommitmentScheme<C, H>, T: Transcript<C::ScalarField>, const
             mleE2_prime,
             mleT: mleT_evaluated,
         };
-        Ok((
-            w,
-            ci,
-            proof,
-            vec![], // r_bits, returned to be passed as inputs to the circuit, not used at the
-                    // current impl status
-        ))
+        Ok((w, ci, proof, alpha_bits))
     }
 
     /// [Mova](https://eprint.iacr.org/2024/1220.pdf)'s section 4. It verifies the results from the proof
@@ -300,7 +362,9 @@ impl<C: Curve, CS: CommitmentScheme<C, H>, T: Transcript<C::ScalarField>, const
         transcript.absorb(&proof.mleE2_prime);
         transcript.absorb(&proof.mleT);
 
-        let alpha: C::ScalarField = transcript.get_challenge();
+        let alpha_bits = transcript.squeeze_bits(NOVA_N_BITS_RO);
+        let alpha = C::ScalarField::from_bigint(BigInteger::from_bits_le(&alpha_bits))
+            .ok_or(Error::OutOfBounds)?;
 
         Ok((
             Self::fold_committed_instance(
@@ -312,7 +376,7 @@ impl<C: Curve, CS: CommitmentScheme<C, H>, T: Transcript<C::ScalarField>, const
                 &proof.mleE2_prime,
                 &proof.mleT,
             )?,
-            vec![],
+            alpha_bits,
         ))
     }
 }
@@ -351,6 +415,62 @@ impl<C: Curve, CS: CommitmentScheme<C, H>, T: Transcript<C::ScalarField>, const
     }
 }
 
+impl<C: Curve, CS: CommitmentScheme<C, H>, S: Transcript<C::ScalarField>, const H: bool>
+    NovaNIFSTrait<C, CS, S, H> for NIFS<C, CS, S, H>
+{
+    type Gadget<T: TranscriptVar<CF1<C>, S>> = NIFSGadget<C, S, T>;
+
+    fn commitment_len(r1cs: &R1CS<C::ScalarField>) -> usize {
+        // only the witness vector `w` is committed, since instead of committing to the error
+        // term `e`, the instance contains the evaluation of its MLE
+        r1cs.n_witnesses()
+    }
+
+    fn blinding_factors(w: &Self::Witness) -> Vec<C::ScalarField> {
+        vec![w.rW]
+    }
+
+    fn cyclefold_points(
+        U_i: &Self::CommittedInstance,
+        u_i: &Self::CommittedInstance,
+        _proof: &Self::Proof,
+    ) -> Vec<Vec<C>> {
+        vec![vec![U_i.cmW, u_i.cmW]]
+    }
+
+    fn new_incoming(
+        mut rng: impl RngCore,
+        params: &CS::ProverParams,
+        r1cs: &R1CS<C::ScalarField>,
+        w: Vec<C::ScalarField>,
+        x: Vec<C::ScalarField>,
+        _W_i: &Self::Witness,
+        _U_i: &Self::CommittedInstance,
+    ) -> Result<(Self::Witness, Self::CommittedInstance), Error> {
+        let w_i = Self::new_witness(w, r1cs.n_constraints(), &mut rng);
+        // passing an empty `aux` samples a fresh random evaluation point `rE` for u_i, and since
+        // the error term of w_i is zero, u_i.mleE is zero too
+        let u_i = Self::new_instance(&mut rng, params, &w_i, x, vec![])?;
+        Ok((w_i, u_i))
+    }
+
+    fn check_instances(
+        r1cs: &R1CS<C::ScalarField>,
+        W_i: &Self::Witness,
+        U_i: &Self::CommittedInstance,
+        w_i: &Self::Witness,
+        u_i: &Self::CommittedInstance,
+    ) -> Result<(), Error> {
+        // check R1CS satisfiability, which is equivalent to checking if `u_i` is an incoming
+        // instance and if `w_i` and `u_i` satisfy RelaxedR1CS
+        u_i.check_incoming()?;
+        r1cs.check_relation(w_i, u_i)?;
+        // check RelaxedR1CS satisfiability, which for Mova also checks that `U_i.mleE` is the
+        // evaluation of the MLE of `W_i.E` at `U_i.rE`
+        r1cs.check_relation(W_i, U_i)
+    }
+}
+
 impl<C: Curve> ArithRelation<Witness<C>, CommittedInstance<C>> for R1CS<CF1<C>> {
     type Evaluation = Vec<CF1<C>>;
 
@@ -364,10 +484,20 @@ impl<C: Curve> ArithRelation<Witness<C>, CommittedInstance<C>> for R1CS<CF1<C>>
 
     fn check_evaluation(
         w: &Witness<C>,
-        _u: &CommittedInstance<C>,
+        u: &CommittedInstance<C>,
         e: Self::Evaluation,
     ) -> Result<(), Error> {
-        (w.E == e).then_some(()).ok_or(Error::NotSatisfied)
+        if w.E != e {
+            return Err(Error::NotSatisfied);
+        }
+        // besides E, the committed instance carries the claimed evaluation `mleE` of the MLE of E
+        // at `rE`, which also needs to be checked
+        let n_vars = log2(w.E.len()) as usize;
+        if u.rE.len() != n_vars {
+            return Err(Error::NotExpectedLength(u.rE.len(), n_vars));
+        }
+        let mleE = dense_vec_to_dense_mle(n_vars, &w.E).evaluate(&u.rE);
+        (mleE == u.mleE).then_some(()).ok_or(Error::NotSatisfied)
     }
 }
 
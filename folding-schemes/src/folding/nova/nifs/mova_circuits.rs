@@ -0,0 +1,443 @@
+/// contains [Mova](https://eprint.iacr.org/2024/1220.pdf) NIFS related circuits
+use ark_crypto_primitives::sponge::{constraints::AbsorbGadget, CryptographicSponge};
+use ark_ff::PrimeField;
+use ark_r1cs_std::{
+    alloc::{AllocVar, AllocationMode},
+    boolean::Boolean,
+    eq::EqGadget,
+    fields::{fp::FpVar, FieldVar},
+    uint8::UInt8,
+};
+use ark_relations::r1cs::{ConstraintSystemRef, Namespace, SynthesisError};
+use ark_std::{fmt::Debug, log2, Zero};
+use core::{borrow::Borrow, marker::PhantomData};
+
+use super::mova::{CommittedInstance, Proof};
+use super::{NIFSGadgetTrait, NovaNIFSGadgetTrait};
+use crate::constants::NOVA_N_BITS_RO;
+use crate::folding::circuits::{
+    nonnative::affine::NonNativeAffineVar, sum_check::DensePolynomialVar, CF1,
+};
+use crate::folding::traits::{CommittedInstanceVarOps, Dummy};
+use crate::transcript::{AbsorbNonNativeGadget, TranscriptVar};
+use crate::Curve;
+
+/// CommittedInstanceVar contains the u, x, rE, mleE and cmW values which are folded on the main
+/// Mova constraints field (E1::Fr, where E1 is the main curve). As in Nova, cmW is represented
+/// non-natively over the constraint field.
+#[derive(Debug, Clone)]
+pub struct CommittedInstanceVar<C: Curve> {
+    pub u: FpVar<C::ScalarField>,
+    pub x: Vec<FpVar<C::ScalarField>>,
+    pub rE: Vec<FpVar<C::ScalarField>>,
+    pub mleE: FpVar<C::ScalarField>,
+    pub cmW: NonNativeAffineVar<C>,
+}
+
+impl<C: Curve> AllocVar<CommittedInstance<C>, CF1<C>> for CommittedInstanceVar<C> {
+    fn new_variable<T: Borrow<CommittedInstance<C>>>(
+        cs: impl Into<Namespace<CF1<C>>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        f().and_then(|val| {
+            let cs = cs.into();
+
+            let u = FpVar::<C::ScalarField>::new_variable(cs.clone(), || Ok(val.borrow().u), mode)?;
+            let x: Vec<FpVar<C::ScalarField>> =
+                Vec::new_variable(cs.clone(), || Ok(val.borrow().x.clone()), mode)?;
+            let rE: Vec<FpVar<C::ScalarField>> =
+                Vec::new_variable(cs.clone(), || Ok(val.borrow().rE.clone()), mode)?;
+            let mleE =
+                FpVar::<C::ScalarField>::new_variable(cs.clone(), || Ok(val.borrow().mleE), mode)?;
+
+            let cmW =
+                NonNativeAffineVar::<C>::new_variable(cs.clone(), || Ok(val.borrow().cmW), mode)?;
+
+            Ok(Self {
+                u,
+                x,
+                rE,
+                mleE,
+                cmW,
+            })
+        })
+    }
+}
+
+impl<C: Curve> AbsorbGadget<C::ScalarField> for CommittedInstanceVar<C> {
+    fn to_sponge_bytes(&self) -> Result<Vec<UInt8<C::ScalarField>>, SynthesisError> {
+        FpVar::batch_to_sponge_bytes(&self.to_sponge_field_elements()?)
+    }
+
+    fn to_sponge_field_elements(&self) -> Result<Vec<FpVar<C::ScalarField>>, SynthesisError> {
+        Ok([
+            vec![self.u.clone()],
+            self.x.clone(),
+            self.rE.clone(),
+            vec![self.mleE.clone()],
+            self.cmW.to_native_sponge_field_elements()?,
+        ]
+        .concat())
+    }
+}
+
+impl<C: Curve> CommittedInstanceVarOps<C> for CommittedInstanceVar<C> {
+    type PointVar = NonNativeAffineVar<C>;
+
+    fn get_commitments(&self) -> Vec<Self::PointVar> {
+        vec![self.cmW.clone()]
+    }
+
+    fn get_public_inputs(&self) -> &[FpVar<CF1<C>>] {
+        &self.x
+    }
+
+    fn enforce_incoming(&self) -> Result<(), SynthesisError> {
+        self.mleE.enforce_equal(&FpVar::zero())?;
+        self.u.enforce_equal(&FpVar::one())
+    }
+
+    fn enforce_partial_equal(&self, other: &Self) -> Result<(), SynthesisError> {
+        self.u.enforce_equal(&other.u)?;
+        self.x.enforce_equal(&other.x)?;
+        self.rE.enforce_equal(&other.rE)?;
+        self.mleE.enforce_equal(&other.mleE)
+    }
+}
+
+/// In-circuit representation of the Mova [`Proof`], which contains the Points-vs-Line proof
+/// polynomials `h1`, `h2` and the evaluations used to fold the error terms.
+#[derive(Debug, Clone)]
+pub struct ProofVar<C: Curve> {
+    pub h1: DensePolynomialVar<CF1<C>>,
+    pub h2: DensePolynomialVar<CF1<C>>,
+    pub mleE1_prime: FpVar<CF1<C>>,
+    pub mleE2_prime: FpVar<CF1<C>>,
+    pub mleT: FpVar<CF1<C>>,
+}
+
+impl<C: Curve> AllocVar<Proof<C>, CF1<C>> for ProofVar<C> {
+    fn new_variable<T: Borrow<Proof<C>>>(
+        cs: impl Into<Namespace<CF1<C>>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        f().and_then(|val| {
+            let cs = cs.into();
+            let val = val.borrow();
+
+            let h1 = DensePolynomialVar::new_variable(cs.clone(), || Ok(&val.h_proof.h1), mode)?;
+            let h2 = DensePolynomialVar::new_variable(cs.clone(), || Ok(&val.h_proof.h2), mode)?;
+            let mleE1_prime = FpVar::new_variable(cs.clone(), || Ok(val.mleE1_prime), mode)?;
+            let mleE2_prime = FpVar::new_variable(cs.clone(), || Ok(val.mleE2_prime), mode)?;
+            let mleT = FpVar::new_variable(cs.clone(), || Ok(val.mleT), mode)?;
+
+            Ok(Self {
+                h1,
+                h2,
+                mleE1_prime,
+                mleE2_prime,
+                mleT,
+            })
+        })
+    }
+}
+
+/// In-circuit counterpart of [`super::pointvsline::PointVsLine::verify`].
+pub struct PointVsLineGadget<C: Curve, S: CryptographicSponge, T: TranscriptVar<CF1<C>, S>> {
+    _c: PhantomData<C>,
+    _s: PhantomData<S>,
+    _t: PhantomData<T>,
+}
+
+impl<C, S, T> PointVsLineGadget<C, S, T>
+where
+    C: Curve,
+    S: CryptographicSponge,
+    T: TranscriptVar<CF1<C>, S>,
+{
+    /// Checks the Points-vs-Line proof contained in `proof`, and returns the new evaluation point
+    /// `rE' = l(beta)`.
+    pub fn verify(
+        transcript: &mut T,
+        ci1: &CommittedInstanceVar<C>,
+        ci2: &CommittedInstanceVar<C>,
+        proof: &ProofVar<C>,
+    ) -> Result<Vec<FpVar<CF1<C>>>, SynthesisError> {
+        if ci1.rE.len() != ci2.rE.len() {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+
+        proof.h1.eval_at_zero().enforce_equal(&ci1.mleE)?;
+        proof.h2.eval_at_one().enforce_equal(&ci2.mleE)?;
+
+        transcript.absorb(&proof.h1.coeffs)?;
+        transcript.absorb(&proof.h2.coeffs)?;
+
+        let beta_scalar = FpVar::<CF1<C>>::constant(CF1::<C>::from_le_bytes_mod_order(b"beta"));
+        transcript.absorb(&beta_scalar)?;
+        let beta = transcript.get_challenge()?;
+
+        proof.h1.evaluate(&beta).enforce_equal(&proof.mleE1_prime)?;
+        proof.h2.evaluate(&beta).enforce_equal(&proof.mleE2_prime)?;
+
+        // rE' = l(beta) = r1 + beta * (r1 - r2)
+        Ok(ci1
+            .rE
+            .iter()
+            .zip(&ci2.rE)
+            .map(|(r1, r2)| r1 + &beta * (r1 - r2))
+            .collect())
+    }
+}
+
+/// Implements the circuit that does the checks of the Non-Interactive Folding Scheme Verifier
+/// described in section 4 of [Mova](https://eprint.iacr.org/2024/1220.pdf), where the cmW check
+/// is delegated to the NIFSCycleFoldGadget.
+pub struct NIFSGadget<C: Curve, S: CryptographicSponge, T: TranscriptVar<CF1<C>, S>> {
+    _c: PhantomData<C>,
+    _s: PhantomData<S>,
+    _t: PhantomData<T>,
+}
+
+impl<C, S, T> NIFSGadgetTrait<C, S, T> for NIFSGadget<C, S, T>
+where
+    C: Curve,
+    S: CryptographicSponge,
+    T: TranscriptVar<CF1<C>, S>,
+{
+    type CommittedInstance = CommittedInstance<C>;
+    type CommittedInstanceVar = CommittedInstanceVar<C>;
+    type Proof = Proof<C>;
+    type ProofVar = ProofVar<C>;
+
+    fn verify(
+        transcript: &mut T,
+        pp_hash: FpVar<CF1<C>>,
+        U_i: Self::CommittedInstanceVar,
+        // U_i_vec is passed to reuse the already computed U_i_vec from previous methods
+        U_i_vec: Vec<FpVar<CF1<C>>>,
+        u_i: Self::CommittedInstanceVar,
+        proof: Option<Self::ProofVar>,
+    ) -> Result<(Self::CommittedInstanceVar, Vec<Boolean<CF1<C>>>), SynthesisError> {
+        // unlike Nova and Ova, Mova's verifier can not work without the proof
+        let proof = proof.ok_or(SynthesisError::AssignmentMissing)?;
+
+        transcript.absorb(&pp_hash)?;
+        transcript.absorb(&U_i_vec)?;
+        transcript.absorb(&u_i)?;
+
+        let rE_prime = PointVsLineGadget::<C, S, T>::verify(transcript, &U_i, &u_i, &proof)?;
+
+        transcript.absorb(&proof.mleE1_prime)?;
+        transcript.absorb(&proof.mleE2_prime)?;
+        transcript.absorb(&proof.mleT)?;
+
+        let alpha_bits = transcript.squeeze_bits(NOVA_N_BITS_RO)?;
+        let alpha = Boolean::le_bits_to_fp(&alpha_bits)?;
+
+        Ok((
+            Self::CommittedInstanceVar {
+                cmW: NonNativeAffineVar::new_constant(ConstraintSystemRef::None, C::zero())?,
+                // ci3.mleE = E1' + alpha * mleT + alpha^2 * E2'
+                mleE: &proof.mleE1_prime
+                    + &alpha * &proof.mleT
+                    + &alpha * &alpha * &proof.mleE2_prime,
+                // ci3.u = U_i.u + alpha * u_i.u
+                u: U_i.u + &alpha * u_i.u,
+                // ci3.x = U_i.x + alpha * u_i.x
+                x: U_i
+                    .x
+                    .iter()
+                    .zip(u_i.x)
+                    .map(|(a, b)| a + &alpha * &b)
+                    .collect::<Vec<FpVar<CF1<C>>>>(),
+                rE: rE_prime,
+            },
+            alpha_bits,
+        ))
+    }
+}
+
+impl<C, S, T> NovaNIFSGadgetTrait<C, S, T> for NIFSGadget<C, S, T>
+where
+    C: Curve,
+    S: CryptographicSponge,
+    T: TranscriptVar<CF1<C>, S>,
+{
+    // a single CycleFold circuit for cmW, since Mova does not commit to the error term
+    const N_CYCLEFOLD_CIRCUITS: usize = 1;
+
+    fn dummy_running_instance(n_constraints: usize) -> CommittedInstance<C> {
+        // the evaluation point rE has one coordinate per variable of the MLE of E, whose length
+        // is the number of constraints. Notice that the size of the dummy proof depends on the
+        // same number of variables.
+        CommittedInstance {
+            rE: vec![CF1::<C>::zero(); log2(n_constraints) as usize],
+            mleE: CF1::<C>::zero(),
+            u: CF1::<C>::zero(),
+            cmW: C::zero(),
+            x: vec![CF1::<C>::zero(); 2],
+        }
+    }
+
+    fn dummy_proof(n_constraints: usize) -> Proof<C> {
+        Proof::dummy(log2(n_constraints) as usize)
+    }
+
+    fn new_incoming_var(
+        cs: ConstraintSystemRef<CF1<C>>,
+        u_i: &CommittedInstance<C>,
+        x: Vec<FpVar<CF1<C>>>,
+    ) -> Result<CommittedInstanceVar<C>, SynthesisError> {
+        Ok(CommittedInstanceVar {
+            // u_i.u = 1
+            u: FpVar::one(),
+            x,
+            // u_i.rE is provided by the prover as witness
+            rE: Vec::new_witness(cs.clone(), || Ok(u_i.rE.clone()))?,
+            // u_i.mleE = 0, since the error term of an incoming instance is zero
+            mleE: FpVar::zero(),
+            // u_i.cmW is provided by the prover as witness
+            cmW: NonNativeAffineVar::new_witness(cs, || Ok(u_i.cmW))?,
+        })
+    }
+
+    fn set_commitments(
+        U: &mut CommittedInstanceVar<C>,
+        cms: Vec<NonNativeAffineVar<C>>,
+    ) -> Result<(), SynthesisError> {
+        let [cmW] = cms.try_into().map_err(|_| SynthesisError::Unsatisfiable)?;
+        U.cmW = cmW;
+        Ok(())
+    }
+
+    fn cyclefold_points_var(
+        U_i: &CommittedInstanceVar<C>,
+        u_i: &CommittedInstanceVar<C>,
+        _proof: &ProofVar<C>,
+        U_i1: &CommittedInstanceVar<C>,
+    ) -> Vec<Vec<NonNativeAffineVar<C>>> {
+        vec![vec![U_i.cmW.clone(), u_i.cmW.clone(), U_i1.cmW.clone()]]
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_crypto_primitives::sponge::poseidon::constraints::PoseidonSpongeVar;
+    use ark_crypto_primitives::sponge::poseidon::PoseidonSponge;
+    use ark_pallas::{Fr, Projective};
+    use ark_r1cs_std::R1CSVar;
+    use ark_std::{cmp::max, rand::RngCore, UniformRand};
+
+    use crate::arith::{
+        r1cs::tests::{get_test_r1cs, get_test_z},
+        Arith,
+    };
+    use crate::commitment::{pedersen::Pedersen, CommitmentScheme};
+    use crate::folding::nova::nifs::{
+        mova::NIFS,
+        tests::{
+            test_committed_instance_hash_opt, test_committed_instance_to_sponge_preimage_opt,
+            test_nifs_gadget_opt,
+        },
+        NIFSTrait,
+    };
+    use crate::transcript::poseidon::poseidon_canonical_config;
+    use crate::Error;
+
+    type N = NIFS<Projective, Pedersen<Projective>, PoseidonSponge<Fr>>;
+
+    fn random_committed_instance(rng: &mut impl RngCore) -> CommittedInstance<Projective> {
+        CommittedInstance::<Projective> {
+            rE: vec![Fr::rand(rng); 2],
+            mleE: Fr::rand(rng),
+            u: Fr::rand(rng),
+            cmW: Projective::rand(rng),
+            x: vec![Fr::rand(rng); 1],
+        }
+    }
+
+    #[test]
+    fn test_nifs_gadget() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let r1cs = get_test_r1cs();
+        let (pedersen_params, _) =
+            Pedersen::<Projective>::setup(&mut rng, max(r1cs.n_constraints(), r1cs.n_witnesses()))?;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        // unlike in Nova and Ova, the Mova proof is only valid for the transcript it was generated
+        // with, so we use the same `pp_hash` that `test_nifs_gadget_opt` samples internally
+        let pp_hash = Fr::rand(&mut ark_std::test_rng());
+
+        // prepare a running instance with a non-zero error term, by folding two fresh instances
+        let mut new_instance = |i: usize| -> Result<_, Error> {
+            let (w, x) = r1cs.split_z(&get_test_z(i));
+            let w = N::new_witness(w, r1cs.n_constraints(), ark_std::test_rng());
+            let u = N::new_instance(&mut rng, &pedersen_params, &w, x, vec![])?;
+            Ok((w, u))
+        };
+        let (W_0, U_0) = new_instance(3)?;
+        let (w_1, u_1) = new_instance(4)?;
+        let (w_2, u_2) = new_instance(5)?;
+
+        let mut transcript = PoseidonSponge::<Fr>::new(&poseidon_config);
+        let (W_1, U_1, _, _) = N::prove(
+            &pedersen_params,
+            &r1cs,
+            &mut transcript,
+            pp_hash,
+            &W_0,
+            &U_0,
+            &w_1,
+            &u_1,
+        )?;
+
+        let mut transcript = PoseidonSponge::<Fr>::new(&poseidon_config);
+        let (_, _, proof, _) = N::prove(
+            &pedersen_params,
+            &r1cs,
+            &mut transcript,
+            pp_hash,
+            &W_1,
+            &U_1,
+            &w_2,
+            &u_2,
+        )?;
+
+        let (ci_out, ciVar_out) = test_nifs_gadget_opt::<
+            N,
+            NIFSGadget<Projective, PoseidonSponge<Fr>, PoseidonSpongeVar<Fr>>,
+        >(vec![U_1, u_2], proof)?;
+        assert_eq!(ciVar_out.u.value()?, ci_out.u);
+        assert_eq!(ciVar_out.x.value()?, ci_out.x);
+        assert_eq!(ciVar_out.rE.value()?, ci_out.rE);
+        assert_eq!(ciVar_out.mleE.value()?, ci_out.mleE);
+        Ok(())
+    }
+
+    #[test]
+    fn test_committed_instance_to_sponge_preimage() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let ci = random_committed_instance(&mut rng);
+
+        test_committed_instance_to_sponge_preimage_opt::<
+            N,
+            NIFSGadget<Projective, PoseidonSponge<Fr>, PoseidonSpongeVar<Fr>>,
+        >(ci)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_committed_instance_hash() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let ci = random_committed_instance(&mut rng);
+
+        test_committed_instance_hash_opt::<
+            N,
+            NIFSGadget<Projective, PoseidonSponge<Fr>, PoseidonSpongeVar<Fr>>,
+        >(ci)?;
+        Ok(())
+    }
+}
@@ -8,8 +8,9 @@ use ark_std::rand::RngCore;
 use ark_std::Zero;
 use std::marker::PhantomData;
 
-use super::NIFSTrait;
-use crate::arith::r1cs::R1CS;
+use super::nova_circuits::NIFSGadget;
+use super::{NIFSTrait, NovaNIFSTrait};
+use crate::arith::{r1cs::R1CS, ArithRelation};
 use crate::commitment::CommitmentScheme;
 use crate::constants::NOVA_N_BITS_RO;
 use crate::folding::circuits::{
@@ -18,6 +19,7 @@ use crate::folding::circuits::{
     CF1,
 };
 use crate::folding::nova::{CommittedInstance, Witness};
+use crate::folding::traits::CommittedInstanceOps;
 use crate::transcript::{Transcript, TranscriptVar};
 use crate::utils::vec::{hadamard, mat_vec_mul, vec_add, vec_scalar_mul, vec_sub};
 use crate::{Curve, Error};
@@ -276,13 +278,50 @@ impl<C: Curve, CS: CommitmentScheme<C, H>, T: Transcript<C::ScalarField>, const
     }
 }
 
+impl<C: Curve, CS: CommitmentScheme<C, H>, S: Transcript<C::ScalarField>, const H: bool>
+    NovaNIFSTrait<C, CS, S, H> for NIFS<C, CS, S, H>
+{
+    type Gadget<T: TranscriptVar<CF1<C>, S>> = NIFSGadget<C, S, T>;
+
+    fn blinding_factors(w: &Self::Witness) -> Vec<C::ScalarField> {
+        // the E of the incoming instances is zero and cmT is committed with rT=0, so cmE is not
+        // blinded along the IVC, only when randomizing the final instance (see `nova::zk`)
+        vec![w.rW]
+    }
+
+    fn cyclefold_points(
+        U_i: &Self::CommittedInstance,
+        u_i: &Self::CommittedInstance,
+        cmT: &Self::Proof,
+    ) -> Vec<Vec<C>> {
+        // Nova folds cmW and cmE in two separate CycleFold circuits, where for the latter only the
+        // `cmT` term is computed in the CycleFold circuit, since `u_i.cmE` is always zero.
+        vec![vec![U_i.cmW, u_i.cmW], vec![U_i.cmE, *cmT]]
+    }
+
+    fn check_instances(
+        r1cs: &R1CS<C::ScalarField>,
+        W_i: &Self::Witness,
+        U_i: &Self::CommittedInstance,
+        w_i: &Self::Witness,
+        u_i: &Self::CommittedInstance,
+    ) -> Result<(), Error> {
+        // check R1CS satisfiability, which is equivalent to checking if `u_i`
+        // is an incoming instance and if `w_i` and `u_i` satisfy RelaxedR1CS
+        u_i.check_incoming()?;
+        r1cs.check_relation(w_i, u_i)?;
+        // check RelaxedR1CS satisfiability
+        r1cs.check_relation(W_i, U_i)
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
     use ark_crypto_primitives::sponge::poseidon::PoseidonSponge;
     use ark_pallas::{Fr, Projective};
 
-    use crate::arith::{r1cs::tests::get_test_r1cs, ArithRelation};
+    use crate::arith::r1cs::tests::get_test_r1cs;
     use crate::commitment::pedersen::Pedersen;
     use crate::folding::nova::nifs::tests::test_nifs_opt;
 
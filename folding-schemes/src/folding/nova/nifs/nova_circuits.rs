@@ -5,13 +5,14 @@ use ark_r1cs_std::{
     boolean::Boolean,
     eq::EqGadget,
     fields::{fp::FpVar, FieldVar},
+    select::CondSelectGadget,
     uint8::UInt8,
 };
 use ark_relations::r1cs::{ConstraintSystemRef, Namespace, SynthesisError};
 use ark_std::{fmt::Debug, Zero};
 use core::{borrow::Borrow, marker::PhantomData};
 
-use super::NIFSGadgetTrait;
+use super::{NIFSGadgetTrait, NovaNIFSGadgetTrait};
 use crate::folding::traits::CommittedInstanceVarOps;
 use crate::transcript::TranscriptVar;
 use crate::{
@@ -75,6 +76,29 @@ impl<C: Curve> AbsorbGadget<C::ScalarField> for CommittedInstanceVar<C> {
     }
 }
 
+impl<C: Curve> CondSelectGadget<CF1<C>> for CommittedInstanceVar<C> {
+    fn conditionally_select(
+        cond: &Boolean<CF1<C>>,
+        true_value: &Self,
+        false_value: &Self,
+    ) -> Result<Self, SynthesisError> {
+        if true_value.x.len() != false_value.x.len() {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        Ok(Self {
+            u: cond.select(&true_value.u, &false_value.u)?,
+            x: true_value
+                .x
+                .iter()
+                .zip(&false_value.x)
+                .map(|(t, f)| cond.select(t, f))
+                .collect::<Result<Vec<_>, _>>()?,
+            cmE: cond.select(&true_value.cmE, &false_value.cmE)?,
+            cmW: cond.select(&true_value.cmW, &false_value.cmW)?,
+        })
+    }
+}
+
 impl<C: Curve> CommittedInstanceVarOps<C> for CommittedInstanceVar<C> {
     type PointVar = NonNativeAffineVar<C>;
 
@@ -156,6 +180,59 @@ where
     }
 }
 
+impl<C, S, T> NovaNIFSGadgetTrait<C, S, T> for NIFSGadget<C, S, T>
+where
+    C: Curve,
+    S: CryptographicSponge,
+    T: TranscriptVar<CF1<C>, S>,
+{
+    // one CycleFold circuit for cmW and another one for cmE
+    const N_CYCLEFOLD_CIRCUITS: usize = 2;
+
+    fn dummy_proof(_n_constraints: usize) -> C {
+        // cmT = 0
+        C::zero()
+    }
+
+    fn new_incoming_var(
+        cs: ConstraintSystemRef<CF1<C>>,
+        u_i: &CommittedInstance<C>,
+        x: Vec<FpVar<CF1<C>>>,
+    ) -> Result<CommittedInstanceVar<C>, SynthesisError> {
+        Ok(CommittedInstanceVar {
+            // u_i.cmE = cm(0)
+            cmE: NonNativeAffineVar::new_constant(cs.clone(), C::zero())?,
+            // u_i.u = 1
+            u: FpVar::one(),
+            // u_i.cmW is provided by the prover as witness
+            cmW: NonNativeAffineVar::new_witness(cs, || Ok(u_i.cmW))?,
+            x,
+        })
+    }
+
+    fn set_commitments(
+        U: &mut CommittedInstanceVar<C>,
+        cms: Vec<NonNativeAffineVar<C>>,
+    ) -> Result<(), SynthesisError> {
+        let [cmW, cmE] = cms.try_into().map_err(|_| SynthesisError::Unsatisfiable)?;
+        U.cmW = cmW;
+        U.cmE = cmE;
+        Ok(())
+    }
+
+    fn cyclefold_points_var(
+        U_i: &CommittedInstanceVar<C>,
+        u_i: &CommittedInstanceVar<C>,
+        cmT: &NonNativeAffineVar<C>,
+        U_i1: &CommittedInstanceVar<C>,
+    ) -> Vec<Vec<NonNativeAffineVar<C>>> {
+        vec![
+            vec![U_i.cmW.clone(), u_i.cmW.clone(), U_i1.cmW.clone()],
+            vec![U_i.cmE.clone(), cmT.clone(), U_i1.cmE.clone()],
+        ]
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
@@ -9,14 +9,14 @@ use ark_std::{One, UniformRand, Zero};
 use std::marker::PhantomData;
 
 use super::nova::ChallengeGadget;
-use super::ova_circuits::CommittedInstanceVar;
-use super::NIFSTrait;
-use crate::arith::{r1cs::R1CS, Arith};
+use super::ova_circuits::{CommittedInstanceVar, NIFSGadget};
+use super::{NIFSTrait, NovaNIFSTrait};
+use crate::arith::{r1cs::R1CS, Arith, ArithRelation};
 use crate::commitment::CommitmentScheme;
 use crate::folding::traits::{CommittedInstanceOps, Inputize};
 use crate::folding::{circuits::CF1, traits::Dummy};
-use crate::transcript::Transcript;
-use crate::utils::vec::{hadamard, mat_vec_mul, vec_scalar_mul, vec_sub};
+use crate::transcript::{Transcript, TranscriptVar};
+use crate::utils::vec::{hadamard, mat_vec_mul, vec_add, vec_scalar_mul, vec_sub};
 use crate::{Curve, Error};
 
 /// A CommittedInstance in [Ova](https://hackmd.io/V4838nnlRKal9ZiTHiGYzw) is represented by `W` or
@@ -31,6 +31,22 @@ pub struct CommittedInstance<C: Curve> {
     pub cmWE: C,
 }
 
+impl<C: Curve> Dummy<usize> for CommittedInstance<C> {
+    fn dummy(io_len: usize) -> Self {
+        Self {
+            u: CF1::<C>::zero(),
+            x: vec![CF1::<C>::zero(); io_len],
+            cmWE: C::zero(),
+        }
+    }
+}
+
+impl<C: Curve> Dummy<&R1CS<CF1<C>>> for CommittedInstance<C> {
+    fn dummy(r1cs: &R1CS<CF1<C>>) -> Self {
+        Self::dummy(r1cs.n_public_inputs())
+    }
+}
+
 impl<C: Curve> Absorb for CommittedInstance<C> {
     fn to_sponge_bytes(&self, dest: &mut Vec<u8>) {
         C::ScalarField::batch_to_sponge_bytes(&self.to_sponge_field_elements_as_vec(), dest);
@@ -50,6 +66,10 @@ impl<C: Curve> CommittedInstanceOps<C> for CommittedInstance<C> {
         vec![self.cmWE]
     }
 
+    fn get_public_inputs(&self) -> &[CF1<C>] {
+        &self.x
+    }
+
     fn is_incoming(&self) -> bool {
         self.u == One::one()
     }
@@ -196,6 +216,7 @@ impl<C: Curve, CS: CommitmentScheme<C, H>, T: Transcript<C::ScalarField>, const
 
         let proof = C::ScalarField::zero();
         let (ci, _r_bits_v) = Self::verify(&mut transcript_v, pp_hash, U_i, u_i, &proof)?;
+
         #[cfg(test)]
         assert_eq!(_r_bits_v, r_bits);
 
@@ -229,6 +250,62 @@ impl<C: Curve, CS: CommitmentScheme<C, H>, T: Transcript<C::ScalarField>, const
     }
 }
 
+impl<C: Curve, CS: CommitmentScheme<C, H>, S: Transcript<C::ScalarField>, const H: bool>
+    NovaNIFSTrait<C, CS, S, H> for NIFS<C, CS, S, H>
+{
+    type Gadget<T: TranscriptVar<CF1<C>, S>> = NIFSGadget<C, S, T>;
+
+    fn commitment_len(r1cs: &R1CS<C::ScalarField>) -> usize {
+        // the witness vector `w` is committed concatenated with the error term `e` (or the cross
+        // term `t`), where the length of `e` is the number of constraints
+        r1cs.n_witnesses() + r1cs.n_constraints()
+    }
+
+    fn blinding_factors(w: &Self::Witness) -> Vec<C::ScalarField> {
+        vec![w.rW]
+    }
+
+    fn cyclefold_points(
+        U_i: &Self::CommittedInstance,
+        u_i: &Self::CommittedInstance,
+        _proof: &Self::Proof,
+    ) -> Vec<Vec<C>> {
+        vec![vec![U_i.cmWE, u_i.cmWE]]
+    }
+
+    fn new_incoming(
+        mut rng: impl RngCore,
+        params: &CS::ProverParams,
+        r1cs: &R1CS<C::ScalarField>,
+        w: Vec<C::ScalarField>,
+        x: Vec<C::ScalarField>,
+        W_i: &Self::Witness,
+        U_i: &Self::CommittedInstance,
+    ) -> Result<(Self::Witness, Self::CommittedInstance), Error> {
+        let w_i = Self::new_witness(w, r1cs.n_constraints(), &mut rng);
+        // u_i commits to w_i concatenated with the cross term between w_i and the running
+        // instance U_i, which is the one that u_i is going to be folded with
+        let t = compute_T(r1cs, W_i, U_i, &w_i, &x)?;
+        let u_i = Self::new_instance(&mut rng, params, &w_i, x, t)?;
+        Ok((w_i, u_i))
+    }
+
+    fn check_instances(
+        r1cs: &R1CS<C::ScalarField>,
+        _W_i: &Self::Witness,
+        _U_i: &Self::CommittedInstance,
+        w_i: &Self::Witness,
+        u_i: &Self::CommittedInstance,
+    ) -> Result<(), Error> {
+        // check R1CS satisfiability, which is equivalent to checking if `u_i` is an incoming
+        // instance and if `w_i` and `u_i.x` satisfy the (plain) R1CS. Since the error term of
+        // the running instance is only contained in the commitment `cmWE`, its relaxed R1CS
+        // check is done in the Decider.
+        u_i.check_incoming()?;
+        r1cs.check_relation(&w_i.w, &u_i.x)
+    }
+}
+
 /// Computes the E parameter (error terms) for the given R1CS and the instance's z and u. This
 /// method is used by the verifier to obtain E in order to check the RelaxedR1CS relation.
 pub fn compute_E<C: Curve>(
@@ -249,15 +326,38 @@ pub fn compute_E<C: Curve>(
     vec_sub(&Az_Bz, &uCz)
 }
 
+/// Computes the cross term `t` between the running instance `(W_i, U_i)` and the incoming witness
+/// `w_i` with public inputs `x_i`. In Ova, `t` is committed together with `w_i` when creating the
+/// incoming instance (see [`NIFS::new_instance`]), so that folding `U_i` with it results in a
+/// commitment to the folded `w || e`.
+pub fn compute_T<C: Curve>(
+    r1cs: &R1CS<C::ScalarField>,
+    W_i: &Witness<C>,
+    U_i: &CommittedInstance<C>,
+    w_i: &Witness<C>,
+    x_i: &[C::ScalarField],
+) -> Result<Vec<C::ScalarField>, Error> {
+    let u_i = C::ScalarField::one();
+    let z1: Vec<C::ScalarField> = [&[U_i.u][..], &U_i.x, &W_i.w].concat();
+    let z2: Vec<C::ScalarField> = [&[u_i][..], x_i, &w_i.w].concat();
+
+    // e(z1 + z2) = e(z1) + t + e(z2), notice that e(z2) is zero for a satisfying incoming witness
+    let e1 = compute_E::<C>(r1cs, &z1, U_i.u)?;
+    let e2 = compute_E::<C>(r1cs, &z2, u_i)?;
+    let e = compute_E::<C>(r1cs, &vec_add(&z1, &z2)?, U_i.u + u_i)?;
+
+    vec_sub(&vec_sub(&e, &e1)?, &e2)
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
+    use ark_crypto_primitives::sponge::poseidon::PoseidonSponge;
     use ark_pallas::{Fr, Projective};
 
-    use crate::arith::{r1cs::tests::get_test_r1cs, ArithRelation};
+    use crate::arith::r1cs::tests::{get_test_r1cs, get_test_z};
     use crate::commitment::pedersen::Pedersen;
     use crate::folding::nova::nifs::tests::test_nifs_opt;
-    use ark_crypto_primitives::sponge::poseidon::PoseidonSponge;
 
     // Simple auxiliary structure mainly used to help pass a witness for which we can check
     // easily an R1CS relation.
@@ -300,4 +400,34 @@ pub mod tests {
         r1cs.check_relation(&TestingWitness::<Projective> { e, w: W.w.clone() }, &U)?;
         Ok(())
     }
+
+    #[test]
+    fn test_compute_T() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let r1cs = get_test_r1cs();
+
+        // running instance with a random `u`, so that its error term is not zero
+        let (w, x) = r1cs.split_z(&get_test_z(3));
+        let W_i = Witness::<Projective> { w, rW: Fr::zero() };
+        let U_i = CommittedInstance::<Projective> {
+            u: Fr::rand(&mut rng),
+            x,
+            cmWE: Projective::zero(),
+        };
+        // incoming witness
+        let (w, x_i) = r1cs.split_z(&get_test_z(4));
+        let w_i = Witness::<Projective> { w, rW: Fr::zero() };
+
+        let t = compute_T(&r1cs, &W_i, &U_i, &w_i, &x_i)?;
+
+        // check that e(z1 + r * z2) = e1 + r * t
+        let r = Fr::rand(&mut rng);
+        let z1: Vec<Fr> = [&[U_i.u][..], &U_i.x, &W_i.w].concat();
+        let z2: Vec<Fr> = [&[Fr::one()][..], &x_i, &w_i.w].concat();
+        let e1 = compute_E::<Projective>(&r1cs, &z1, U_i.u)?;
+        let e =
+            compute_E::<Projective>(&r1cs, &vec_add(&z1, &vec_scalar_mul(&z2, &r))?, U_i.u + r)?;
+        assert_eq!(e, vec_add(&e1, &vec_scalar_mul(&t, &r))?);
+        Ok(())
+    }
 }
@@ -8,11 +8,11 @@ use ark_r1cs_std::{
     uint8::UInt8,
 };
 use ark_relations::r1cs::{ConstraintSystemRef, Namespace, SynthesisError};
-use ark_std::fmt::Debug;
+use ark_std::{fmt::Debug, Zero};
 use core::{borrow::Borrow, marker::PhantomData};
 
 use super::ova::CommittedInstance;
-use super::NIFSGadgetTrait;
+use super::{NIFSGadgetTrait, NovaNIFSGadgetTrait};
 use crate::folding::traits::CommittedInstanceVarOps;
 use crate::transcript::TranscriptVar;
 use crate::{
@@ -142,6 +142,53 @@ where
     }
 }
 
+impl<C, S, T> NovaNIFSGadgetTrait<C, S, T> for NIFSGadget<C, S, T>
+where
+    C: Curve,
+    S: CryptographicSponge,
+    T: TranscriptVar<CF1<C>, S>,
+{
+    // a single CycleFold circuit for cmWE
+    const N_CYCLEFOLD_CIRCUITS: usize = 1;
+
+    fn dummy_proof(_n_constraints: usize) -> C::ScalarField {
+        // Ova's proof is not used
+        C::ScalarField::zero()
+    }
+
+    fn new_incoming_var(
+        cs: ConstraintSystemRef<CF1<C>>,
+        u_i: &CommittedInstance<C>,
+        x: Vec<FpVar<CF1<C>>>,
+    ) -> Result<CommittedInstanceVar<C>, SynthesisError> {
+        Ok(CommittedInstanceVar {
+            // u_i.u = 1
+            u: FpVar::one(),
+            x,
+            // u_i.cmWE is provided by the prover as witness
+            cmWE: NonNativeAffineVar::new_witness(cs, || Ok(u_i.cmWE))?,
+        })
+    }
+
+    fn set_commitments(
+        U: &mut CommittedInstanceVar<C>,
+        cms: Vec<NonNativeAffineVar<C>>,
+    ) -> Result<(), SynthesisError> {
+        let [cmWE] = cms.try_into().map_err(|_| SynthesisError::Unsatisfiable)?;
+        U.cmWE = cmWE;
+        Ok(())
+    }
+
+    fn cyclefold_points_var(
+        U_i: &CommittedInstanceVar<C>,
+        u_i: &CommittedInstanceVar<C>,
+        _proof: &FpVar<CF1<C>>,
+        U_i1: &CommittedInstanceVar<C>,
+    ) -> Vec<Vec<NonNativeAffineVar<C>>> {
+        vec![vec![U_i.cmWE.clone(), u_i.cmWE.clone(), U_i1.cmWE.clone()]]
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
@@ -53,8 +53,8 @@ impl<C: Curve, T: Transcript<C::ScalarField>> PointVsLine<C, T> {
             .map(|(&r1, r2)| r1 - r2)
             .collect();
 
-        let h1 = compute_h(&mleE1, &ci1.rE, &r1_sub_r2)?;
-        let h2 = compute_h(&mleE2, &ci1.rE, &r1_sub_r2)?;
+        let h1 = pad_h(compute_h(&mleE1, &ci1.rE, &r1_sub_r2)?, n_vars)?;
+        let h2 = pad_h(compute_h(&mleE2, &ci1.rE, &r1_sub_r2)?, n_vars)?;
 
         transcript.absorb(&h1.coeffs());
         transcript.absorb(&h2.coeffs());
@@ -89,6 +89,15 @@ impl<C: Curve, T: Transcript<C::ScalarField>> PointVsLine<C, T> {
         Vec<<C>::ScalarField>, // rE=rE1'=rE2'.
         Error,
     > {
+        // h1 and h2 are expected to be padded to degree n_vars, see `pad_h`
+        let n_coeffs = ci1.rE.len() + 1;
+        if proof.h1.coeffs.len() != n_coeffs {
+            return Err(Error::NotExpectedLength(proof.h1.coeffs.len(), n_coeffs));
+        }
+        if proof.h2.coeffs.len() != n_coeffs {
+            return Err(Error::NotExpectedLength(proof.h2.coeffs.len(), n_coeffs));
+        }
+
         if proof.h1.evaluate(&C::ScalarField::zero()) != ci1.mleE {
             return Err(Error::NotEqual);
         }
@@ -158,6 +167,20 @@ fn compute_h<F: PrimeField>(
     Ok(poly.swap_remove(0))
 }
 
+/// Pads the coefficients of `h` with zeros up to degree `n_vars` (the maximum degree of `h`), so
+/// that the amount of coefficients that are absorbed by the transcript does not depend on the
+/// actual degree of `h`. This keeps the in-circuit verifier of constant size.
+fn pad_h<F: PrimeField>(h: DensePolynomial<F>, n_vars: usize) -> Result<DensePolynomial<F>, Error> {
+    let mut coeffs = h.coeffs;
+    if coeffs.len() > n_vars + 1 {
+        return Err(Error::NotExpectedLength(coeffs.len(), n_vars + 1));
+    }
+    coeffs.resize(n_vars + 1, F::zero());
+    // notice that we don't use `DensePolynomial::from_coefficients_vec`, since it would remove
+    // the trailing zeros
+    Ok(DensePolynomial { coeffs })
+}
+
 fn compute_l<F: PrimeField>(r1: &[F], r1_sub_r2: &[F], x: F) -> Result<Vec<F>, Error> {
     if r1.len() != r1_sub_r2.len() {
         return Err(Error::NotEqual);
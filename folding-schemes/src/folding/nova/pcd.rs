@@ -0,0 +1,707 @@
+/// Implements a Nova-based proof-carrying data (PCD) scheme, where the computation `z_n = F^n(z_0)`
+/// is proven in a tree-shaped manner instead of sequentially, so that different parts of it can be
+/// proven in parallel (and by different parties).
+///
+/// Each node of the tree proves that `z_end = F^{i_end - i_start}(z_start)`:
+/// - a leaf proves a single step of `F`, ie. it covers the steps `i..i+1` (see
+///   [`NovaPCD::prove_leaf`]).
+/// - an inner node merges two adjacent nodes `L` and `R`, ie. nodes covering the steps `a..m` and
+///   `m..b`, into a node covering the steps `a..b` (see [`NovaPCD::prove_merge`]). To do so, it
+///   folds the incoming instance of each child into its running instance, and then folds the two
+///   resulting running instances into one. The CycleFold running instances of the children are
+///   merged as well.
+///
+/// The verifier checks that the root of the tree covers the steps `0..n` (see
+/// [`NovaPCD::verify`]).
+///
+/// The circuit of the nodes is [`PCDNodeCircuit`], which is shared by the leaves and the inner
+/// nodes. Notice that the constraints of `F` are also generated in the inner nodes, at the step `m`
+/// and with the state `z_m` at the boundary between the children, even if their output is
+/// discarded. So the prover of an inner node has to provide external inputs for which `F` is
+/// satisfiable at that step and state, eg. the ones used for the step `m` in the leaf of `R`.
+///
+/// This is only implemented for Nova. A PCD for ProtoGalaxy (or for the rest of the Nova variants)
+/// would need its own node circuit, folding the two running instances with the multi-instance
+/// folding of the scheme, and it is out of the scope of this module.
+use ark_crypto_primitives::sponge::poseidon::{constraints::PoseidonSpongeVar, PoseidonSponge};
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::R1CSVar;
+use ark_relations::r1cs::ConstraintSystem;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{cmp::max, marker::PhantomData, rand::RngCore, One, Zero};
+
+use super::{
+    get_r1cs_from_cs,
+    nifs::{nova::NIFS, NIFSTrait},
+    pcd_circuits::{NodeInstance, PCDCycleFoldCircuit, PCDCycleFoldConfig, PCDNodeCircuit},
+    CommittedInstance, PreprocessorParam, ProverParams, VerifierParams, Witness,
+};
+use crate::arith::{
+    r1cs::{extract_w_x, R1CS},
+    Arith, ArithRelation,
+};
+use crate::commitment::CommitmentScheme;
+use crate::folding::{
+    circuits::{
+        cyclefold::{
+            fold_cyclefold_circuit, CycleFoldChallengeGadget, CycleFoldCommittedInstance,
+            CycleFoldWitness,
+        },
+        CF1,
+    },
+    traits::{CommittedInstanceOps, Dummy},
+};
+use crate::frontend::FCircuit;
+use crate::transcript::{Transcript, TranscriptVar};
+use crate::{Curve, Error};
+
+/// Returns `H(i_start, i_end, z_start, z_end, U)`, which is the first public input of the incoming
+/// instance of a node. This is the native counterpart of the hash computed in-circuit by
+/// [`PCDNodeCircuit`].
+pub(super) fn node_hash<C: Curve, T: Transcript<CF1<C>>>(
+    sponge: &T,
+    pp_hash: CF1<C>,
+    i_start: CF1<C>,
+    i_end: CF1<C>,
+    z_start: &[CF1<C>],
+    z_end: &[CF1<C>],
+    U: &CommittedInstance<C>,
+) -> CF1<C> {
+    let mut sponge = sponge.clone();
+    sponge.absorb(&pp_hash);
+    sponge.absorb(&i_start);
+    sponge.absorb(&i_end);
+    sponge.absorb(&z_start);
+    sponge.absorb(&z_end);
+    sponge.absorb(&U);
+    sponge.squeeze_field_elements(1)[0]
+}
+
+/// Proof of a node of the PCD tree, which proves that `z_end = F^{i_end - i_start}(z_start)`.
+#[derive(PartialEq, Eq, Debug, Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct PCDProof<C1: Curve, C2: Curve> {
+    // steps covered by the node, ie. `i_start..i_end`
+    pub i_start: C1::ScalarField,
+    pub i_end: C1::ScalarField,
+    // states before and after the steps covered by the node
+    pub z_start: Vec<C1::ScalarField>,
+    pub z_end: Vec<C1::ScalarField>,
+    // running instance
+    pub W: Witness<C1>,
+    pub U: CommittedInstance<C1>,
+    // incoming instance
+    pub w: Witness<C1>,
+    pub u: CommittedInstance<C1>,
+    // CycleFold running instance
+    pub cf_W: CycleFoldWitness<C2>,
+    pub cf_U: CycleFoldCommittedInstance<C2>,
+}
+
+impl<C1: Curve, C2: Curve> PCDProof<C1, C2> {
+    /// Returns the public values of the node, which are used by its parent's circuit.
+    fn instance(&self) -> NodeInstance<C1, C2> {
+        NodeInstance {
+            i_start: self.i_start,
+            i_end: self.i_end,
+            z_start: self.z_start.clone(),
+            z_end: self.z_end.clone(),
+            U: self.U.clone(),
+            u: self.u.clone(),
+            cf_U: self.cf_U.clone(),
+        }
+    }
+}
+
+/// Implements the Nova-based PCD described in the module documentation.
+/// The `H` const generic specifies whether the homorphic commitment scheme is blinding
+/// The sponge `S` (Poseidon by default) is used for the transcripts and the hashes of the nodes,
+/// and `T` is its in-circuit counterpart, used in the node circuit.
+pub struct NovaPCD<
+    C1,
+    C2,
+    FC,
+    CS1,
+    CS2,
+    const H: bool = false,
+    S = PoseidonSponge<CF1<C1>>,
+    T = PoseidonSpongeVar<CF1<C1>>,
+> where
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
+{
+    /// R1CS of the PCD node circuit
+    pub r1cs: R1CS<C1::ScalarField>,
+    /// R1CS of the CycleFold circuit
+    pub cf_r1cs: R1CS<C2::ScalarField>,
+    pub poseidon_config: S::Config,
+    /// CommitmentScheme::ProverParams over C1
+    pub cs_pp: CS1::ProverParams,
+    /// CycleFold CommitmentScheme::ProverParams, over C2
+    pub cf_cs_pp: CS2::ProverParams,
+    /// F circuit, the circuit that is being proven
+    pub F: FC,
+    /// public params hash
+    pub pp_hash: C1::ScalarField,
+
+    _s: PhantomData<S>,
+    _t: PhantomData<T>,
+}
+
+impl<C1, C2, FC, CS1, CS2, const H: bool, S, T> NovaPCD<C1, C2, FC, CS1, CS2, H, S, T>
+where
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
+{
+    /// Generates the R1CS of the node and CycleFold circuits, and the parameters of the
+    /// commitment schemes if they are not given.
+    #[allow(clippy::type_complexity)]
+    pub fn preprocess(
+        mut rng: impl RngCore,
+        prep_param: &PreprocessorParam<C1, C2, FC, CS1, CS2, H, S::Config>,
+    ) -> Result<
+        (
+            ProverParams<C1, C2, CS1, CS2, H, S::Config>,
+            VerifierParams<C1, C2, CS1, CS2, H, S::Config>,
+        ),
+        Error,
+    > {
+        let (r1cs, cf_r1cs) =
+            get_pcd_r1cs::<C1, C2, FC, S, T>(&prep_param.poseidon_config, prep_param.F.clone())?;
+
+        // if cs params exist, use them, if not, generate new ones
+        let (cs_pp, cs_vp) = match (&prep_param.cs_pp, &prep_param.cs_vp) {
+            (Some(cs_pp), Some(cs_vp)) => (cs_pp.clone(), cs_vp.clone()),
+            _ => CS1::setup(&mut rng, max(r1cs.n_constraints(), r1cs.n_witnesses()))?,
+        };
+        let (cf_cs_pp, cf_cs_vp) = match (&prep_param.cf_cs_pp, &prep_param.cf_cs_vp) {
+            (Some(cf_cs_pp), Some(cf_cs_vp)) => (cf_cs_pp.clone(), cf_cs_vp.clone()),
+            _ => CS2::setup(
+                &mut rng,
+                max(cf_r1cs.n_constraints(), cf_r1cs.n_witnesses()),
+            )?,
+        };
+
+        let prover_params = ProverParams::<C1, C2, CS1, CS2, H, S::Config> {
+            poseidon_config: prep_param.poseidon_config.clone(),
+            cs_pp,
+            cf_cs_pp,
+        };
+        let verifier_params = VerifierParams::<C1, C2, CS1, CS2, H, S::Config> {
+            poseidon_config: prep_param.poseidon_config.clone(),
+            r1cs,
+            cf_r1cs,
+            cs_vp,
+            cf_cs_vp,
+        };
+        Ok((prover_params, verifier_params))
+    }
+
+    #[allow(clippy::type_complexity)]
+    pub fn init(
+        params: &(
+            ProverParams<C1, C2, CS1, CS2, H, S::Config>,
+            VerifierParams<C1, C2, CS1, CS2, H, S::Config>,
+        ),
+        F: FC,
+    ) -> Result<Self, Error> {
+        let (pp, vp) = params;
+
+        // prepare the circuit to obtain its R1CS
+        let (r1cs, cf_r1cs) = get_pcd_r1cs::<C1, C2, FC, S, T>(&pp.poseidon_config, F.clone())?;
+
+        Ok(Self {
+            r1cs,
+            cf_r1cs,
+            poseidon_config: pp.poseidon_config.clone(),
+            cs_pp: pp.cs_pp.clone(),
+            cf_cs_pp: pp.cf_cs_pp.clone(),
+            F,
+            pp_hash: vp.pp_hash()?,
+            _s: PhantomData,
+            _t: PhantomData,
+        })
+    }
+
+    /// Proves the `i`-th step of the computation, ie. generates a leaf of the PCD tree covering the
+    /// steps `i..i+1`, whose initial state is `z_i`.
+    pub fn prove_leaf(
+        &self,
+        mut rng: impl RngCore,
+        i: usize,
+        z_i: Vec<C1::ScalarField>,
+        external_inputs: FC::ExternalInputs,
+    ) -> Result<PCDProof<C1, C2>, Error> {
+        if z_i.len() != self.F.state_len() {
+            return Err(Error::NotSameLength(
+                "z_i.len()".to_string(),
+                z_i.len(),
+                "F.state_len()".to_string(),
+                self.F.state_len(),
+            ));
+        }
+
+        let i_start = C1::ScalarField::from(i as u64);
+        let circuit = PCDNodeCircuit::<C1, C2, FC, S, T> {
+            poseidon_config: self.poseidon_config.clone(),
+            pp_hash: Some(self.pp_hash),
+            is_leaf: Some(true),
+            i: Some(i_start),
+            i_usize: Some(i),
+            z_i: Some(z_i.clone()),
+            external_inputs: Some(external_inputs),
+            children: None,
+            cmT: None,
+            U_folded: None,
+            F: self.F.clone(),
+            cf_merge_cmT: None,
+            cf_u_cmW: None,
+            cf_cmT: None,
+            _s: PhantomData,
+            _t: PhantomData,
+        };
+        let (z_end, w, u) = self.compute_node(&mut rng, circuit)?;
+
+        // the running instances of a leaf are the dummy ones, which are the ones bound to u.x
+        let (W, U) = (
+            Witness::dummy(&self.r1cs),
+            CommittedInstance::dummy(&self.r1cs),
+        );
+        let (cf_W, cf_U) = self.cf_r1cs.dummy_witness_instance();
+
+        Ok(PCDProof {
+            i_start,
+            i_end: i_start + C1::ScalarField::one(),
+            z_start: z_i,
+            z_end,
+            W,
+            U,
+            w,
+            u,
+            cf_W,
+            cf_U,
+        })
+    }
+
+    /// Merges the adjacent nodes `left` and `right`, ie. `right` has to start at the step (and
+    /// state) where `left` ends, into a node covering the steps of both.
+    ///
+    /// The `external_inputs` are the ones with which `F` is evaluated (and its output discarded)
+    /// in the circuit of the node, at the step `m` and state `z_m` where `right` starts, so they
+    /// have to make `F` satisfiable there.
+    pub fn prove_merge(
+        &self,
+        mut rng: impl RngCore,
+        left: &PCDProof<C1, C2>,
+        right: &PCDProof<C1, C2>,
+        external_inputs: FC::ExternalInputs,
+    ) -> Result<PCDProof<C1, C2>, Error> {
+        if left.i_end != right.i_start || left.z_end != right.z_start {
+            return Err(Error::NotEqual);
+        }
+
+        let mut transcript = S::new(&self.poseidon_config);
+
+        // fold the primary instances: u_L into U_L, u_R into U_R, and then the resulting U_R'
+        // into U_L'
+        let (W_L1, U_L1, cmT_L, r_L_bits) = NIFS::<C1, CS1, S, H>::prove(
+            &self.cs_pp,
+            &self.r1cs,
+            &mut transcript,
+            self.pp_hash,
+            &left.W,
+            &left.U,
+            &left.w,
+            &left.u,
+        )?;
+        let (W_R1, U_R1, cmT_R, r_R_bits) = NIFS::<C1, CS1, S, H>::prove(
+            &self.cs_pp,
+            &self.r1cs,
+            &mut transcript,
+            self.pp_hash,
+            &right.W,
+            &right.U,
+            &right.w,
+            &right.u,
+        )?;
+        let (W, U, cmT_M, r_M_bits) = NIFS::<C1, CS1, S, H>::prove(
+            &self.cs_pp,
+            &self.r1cs,
+            &mut transcript,
+            self.pp_hash,
+            &W_L1,
+            &U_L1,
+            &W_R1,
+            &U_R1,
+        )?;
+
+        // CycleFold part:
+        // merge the CycleFold running instances of the children
+        let (cf_T, cf_merge_cmT) =
+            NIFS::<C2, CS2, PoseidonSponge<C2::ScalarField>, H>::compute_cyclefold_cmT(
+                &self.cf_cs_pp,
+                &self.cf_r1cs,
+                &left.cf_W,
+                &left.cf_U,
+                &right.cf_W,
+                &right.cf_U,
+            )?;
+        let cf_r_bits = CycleFoldChallengeGadget::<C2>::get_challenge_native(
+            &mut transcript,
+            self.pp_hash,
+            left.cf_U.clone(),
+            right.cf_U.clone(),
+            cf_merge_cmT,
+        );
+        let cf_r = C2::ScalarField::from_bigint(BigInteger::from_bits_le(&cf_r_bits))
+            .ok_or(Error::OutOfBounds)?;
+        let mut cf_W = NIFS::<C2, CS2, PoseidonSponge<C2::ScalarField>, H>::fold_witness(
+            cf_r,
+            &left.cf_W,
+            &right.cf_W,
+            &cf_T,
+        )?;
+        let mut cf_U =
+            NIFS::<C2, CS2, PoseidonSponge<C2::ScalarField>, H>::fold_committed_instances(
+                cf_r,
+                &left.cf_U,
+                &right.cf_U,
+                &cf_merge_cmT,
+            );
+
+        // fold the CycleFold circuits of the three folds into cf_U, sequentially, in the same
+        // order as in PCDNodeCircuit
+        let cf_inputs = [
+            (&r_L_bits, vec![left.U.cmW, left.u.cmW, C1::zero()]),
+            (&r_L_bits, vec![left.U.cmE, cmT_L, left.u.cmE]),
+            (&r_R_bits, vec![right.U.cmW, right.u.cmW, C1::zero()]),
+            (&r_R_bits, vec![right.U.cmE, cmT_R, right.u.cmE]),
+            (&r_M_bits, vec![U_L1.cmW, U_R1.cmW, C1::zero()]),
+            (&r_M_bits, vec![U_L1.cmE, cmT_M, U_R1.cmE]),
+        ];
+        let mut cf_u_cmW = vec![];
+        let mut cf_cmT = vec![];
+        for (r_bits, points) in cf_inputs {
+            let cf_circuit = PCDCycleFoldCircuit::<C1> {
+                r_bits: Some(r_bits.clone()),
+                points: Some(points),
+            };
+            let cf_u;
+            let cf_cmT_i;
+            (cf_u, cf_W, cf_U, cf_cmT_i) =
+                fold_cyclefold_circuit::<PCDCycleFoldConfig<C1>, C2, CS2, H>(
+                    &mut transcript,
+                    self.cf_r1cs.clone(),
+                    self.cf_cs_pp.clone(),
+                    self.pp_hash,
+                    cf_W,
+                    cf_U,
+                    cf_circuit,
+                    &mut rng,
+                )?;
+            cf_u_cmW.push(cf_u.cmW);
+            cf_cmT.push(cf_cmT_i);
+        }
+
+        // step at the boundary between the children
+        let m = right.i_start.into_bigint().as_ref()[0] as usize;
+        let circuit = PCDNodeCircuit::<C1, C2, FC, S, T> {
+            poseidon_config: self.poseidon_config.clone(),
+            pp_hash: Some(self.pp_hash),
+            is_leaf: Some(false),
+            i: Some(right.i_start),
+            i_usize: Some(m),
+            z_i: Some(right.z_start.clone()),
+            external_inputs: Some(external_inputs),
+            children: Some([left.instance(), right.instance()]),
+            cmT: Some([cmT_L, cmT_R, cmT_M]),
+            U_folded: Some([U_L1, U_R1, U.clone()]),
+            F: self.F.clone(),
+            cf_merge_cmT: Some(cf_merge_cmT),
+            cf_u_cmW: Some(cf_u_cmW),
+            cf_cmT: Some(cf_cmT),
+            _s: PhantomData,
+            _t: PhantomData,
+        };
+        let (_, w, u) = self.compute_node(&mut rng, circuit)?;
+
+        Ok(PCDProof {
+            i_start: left.i_start,
+            i_end: right.i_end,
+            z_start: left.z_start.clone(),
+            z_end: right.z_end.clone(),
+            W,
+            U,
+            w,
+            u,
+            cf_W,
+            cf_U,
+        })
+    }
+
+    // generates the constraints of the given node circuit, returning the final state of the node
+    // together with its incoming instance-witness pair
+    #[allow(clippy::type_complexity)]
+    fn compute_node(
+        &self,
+        mut rng: impl RngCore,
+        circuit: PCDNodeCircuit<C1, C2, FC, S, T>,
+    ) -> Result<(Vec<C1::ScalarField>, Witness<C1>, CommittedInstance<C1>), Error> {
+        let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
+
+        let z_end = circuit.compute_next_state(cs.clone())?.value()?;
+
+        #[cfg(test)]
+        assert!(cs.is_satisfied()?);
+
+        let cs = cs.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
+        let (w, x) = extract_w_x::<C1::ScalarField>(&cs);
+
+        #[cfg(test)]
+        if x.len() != 2 {
+            return Err(Error::NotExpectedLength(x.len(), 2));
+        }
+
+        let w = Witness::<C1>::new::<H>(w, self.r1cs.n_constraints(), &mut rng);
+        let u = w.commit::<CS1, H>(&self.cs_pp, x)?;
+        Ok((z_end, w, u))
+    }
+
+    /// Verifies that the given node is the root of a PCD tree proving `z_n = F^n(z_0)`, ie. that
+    /// it covers the steps `0..n` starting from `z_0`.
+    pub fn verify(
+        vp: &VerifierParams<C1, C2, CS1, CS2, H, S::Config>,
+        z_0: &[C1::ScalarField],
+        num_steps: usize,
+        proof: &PCDProof<C1, C2>,
+    ) -> Result<(), Error> {
+        if proof.i_start != C1::ScalarField::zero()
+            || proof.i_end != C1::ScalarField::from(num_steps as u64)
+            || proof.z_start != z_0
+        {
+            return Err(Error::IVCVerificationFail);
+        }
+
+        if proof.u.get_public_inputs().len() != 2 || proof.U.get_public_inputs().len() != 2 {
+            return Err(Error::IVCVerificationFail);
+        }
+
+        let sponge = S::new(&vp.poseidon_config);
+        let pp_hash = vp.pp_hash()?;
+
+        // check that u's output points to the running instance
+        // u.X[0] == H(i_start, i_end, z_start, z_end, U)
+        let expected_u_x = node_hash(
+            &sponge,
+            pp_hash,
+            proof.i_start,
+            proof.i_end,
+            &proof.z_start,
+            &proof.z_end,
+            &proof.U,
+        );
+        if expected_u_x != proof.u.get_public_inputs()[0] {
+            return Err(Error::IVCVerificationFail);
+        }
+        // u.X[1] == H(cf_U)
+        let expected_cf_u_x = proof.cf_U.hash_cyclefold(&sponge, pp_hash);
+        if expected_cf_u_x != proof.u.get_public_inputs()[1] {
+            return Err(Error::IVCVerificationFail);
+        }
+
+        // check the satisfiability of the incoming and running instances
+        proof.u.check_incoming()?;
+        vp.r1cs.check_relation(&proof.w, &proof.u)?;
+        vp.r1cs.check_relation(&proof.W, &proof.U)?;
+
+        // check CycleFold RelaxedR1CS satisfiability
+        vp.cf_r1cs.check_relation(&proof.cf_W, &proof.cf_U)?;
+
+        Ok(())
+    }
+}
+
+/// helper method to get the R1CS for both the PCD node circuit and the CycleFold circuit
+#[allow(clippy::type_complexity)]
+pub fn get_pcd_r1cs<C1, C2, FC, S, T>(
+    poseidon_config: &S::Config,
+    F_circuit: FC,
+) -> Result<(R1CS<C1::ScalarField>, R1CS<C2::ScalarField>), Error>
+where
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
+{
+    let node_circuit = PCDNodeCircuit::<C1, C2, FC, S, T>::empty(poseidon_config, F_circuit);
+    let cf_circuit = PCDCycleFoldCircuit::<C1>::empty();
+    let r1cs = get_r1cs_from_cs::<C1::ScalarField>(node_circuit)?;
+    let cf_r1cs = get_r1cs_from_cs::<C2::ScalarField>(cf_circuit)?;
+    Ok((r1cs, cf_r1cs))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use ark_bn254::{Fr, G1Projective as Projective};
+    use ark_ff::Field;
+    use ark_grumpkin::Projective as Projective2;
+    use ark_r1cs_std::{
+        eq::EqGadget,
+        fields::{fp::FpVar, FieldVar},
+    };
+    use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+    use rand::rngs::OsRng;
+
+    use super::*;
+    use crate::commitment::pedersen::Pedersen;
+    use crate::frontend::utils::{cubic_step_native, CubicFCircuit};
+    use crate::transcript::poseidon::poseidon_canonical_config;
+
+    type CS1 = Pedersen<Projective>;
+    type CS2 = Pedersen<Projective2>;
+    type N = NovaPCD<Projective, Projective2, CubicFCircuit<Fr>, CS1, CS2>;
+    type VP = VerifierParams<Projective, Projective2, CS1, CS2>;
+
+    fn setup() -> Result<(N, VP), Error> {
+        let mut rng = OsRng;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+
+        let prep_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        let params = N::preprocess(&mut rng, &prep_param)?;
+        let pcd = N::init(&params, F_circuit)?;
+        Ok((pcd, params.1))
+    }
+
+    // returns the leaves of the computation starting at z_0, ie. the proofs of each of its steps
+    fn prove_leaves(
+        pcd: &N,
+        z_0: Vec<Fr>,
+        n: usize,
+    ) -> Result<Vec<PCDProof<Projective, Projective2>>, Error> {
+        let mut z_i = z_0;
+        let mut leaves = vec![];
+        for i in 0..n {
+            let leaf = pcd.prove_leaf(OsRng, i, z_i, ())?;
+            z_i = leaf.z_end.clone();
+            leaves.push(leaf);
+        }
+        Ok(leaves)
+    }
+
+    #[test]
+    fn test_pcd_balanced_tree() -> Result<(), Error> {
+        let (pcd, vp) = setup()?;
+        let z_0 = vec![Fr::from(3_u32)];
+
+        let leaves = prove_leaves(&pcd, z_0.clone(), 4)?;
+        // a single leaf is already a valid root for one step
+        N::verify(&vp, &z_0, 1, &leaves[0])?;
+
+        let node_01 = pcd.prove_merge(OsRng, &leaves[0], &leaves[1], ())?;
+        let node_23 = pcd.prove_merge(OsRng, &leaves[2], &leaves[3], ())?;
+        let root = pcd.prove_merge(OsRng, &node_01, &node_23, ())?;
+        N::verify(&vp, &z_0, 4, &root)?;
+
+        // the root state is the one of the sequential computation
+        let z_4 = (0..4).fold(z_0.clone(), |z, _| cubic_step_native(z));
+        assert_eq!(root.z_end, z_4);
+
+        // verification fails for a wrong number of steps or a wrong initial state
+        assert!(N::verify(&vp, &z_0, 3, &root).is_err());
+        assert!(N::verify(&vp, &[Fr::from(4_u32)], 4, &root).is_err());
+        // node_23 is valid, but it does not start at step 0
+        assert!(N::verify(&vp, &node_23.z_start, 2, &node_23).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_pcd_unbalanced_tree() -> Result<(), Error> {
+        let (pcd, vp) = setup()?;
+        let z_0 = vec![Fr::from(3_u32)];
+
+        // ((0, 1), 2)
+        let leaves = prove_leaves(&pcd, z_0.clone(), 3)?;
+        let node_01 = pcd.prove_merge(OsRng, &leaves[0], &leaves[1], ())?;
+        let root = pcd.prove_merge(OsRng, &node_01, &leaves[2], ())?;
+        N::verify(&vp, &z_0, 3, &root)?;
+
+        let z_3 = (0..3).fold(z_0.clone(), |z, _| cubic_step_native(z));
+        assert_eq!(root.z_end, z_3);
+
+        // verification fails with a wrong running witness
+        let mut root = root;
+        root.W.W[0] += Fr::one();
+        assert!(N::verify(&vp, &z_0, 3, &root).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_pcd_merge_non_adjacent_nodes() -> Result<(), Error> {
+        let (pcd, _) = setup()?;
+        let z_0 = vec![Fr::from(3_u32)];
+
+        let leaves = prove_leaves(&pcd, z_0, 3)?;
+        assert!(pcd.prove_merge(OsRng, &leaves[0], &leaves[2], ()).is_err());
+        assert!(pcd.prove_merge(OsRng, &leaves[1], &leaves[0], ()).is_err());
+        Ok(())
+    }
+
+    /// `InverseFCircuit` computes `z_{i+1} = z_i + 1`, taking as external input the inverse of
+    /// `z_i`, so that it is not satisfiable with the default external inputs.
+    #[derive(Clone, Copy, Debug)]
+    pub struct InverseFCircuit;
+    impl FCircuit<Fr> for InverseFCircuit {
+        type Params = ();
+        type ExternalInputs = Fr;
+        type ExternalInputsVar = FpVar<Fr>;
+
+        fn new(_params: Self::Params) -> Result<Self, Error> {
+            Ok(Self)
+        }
+        fn state_len(&self) -> usize {
+            1
+        }
+        fn generate_step_constraints(
+            &self,
+            _cs: ConstraintSystemRef<Fr>,
+            _i: usize,
+            z_i: Vec<FpVar<Fr>>,
+            z_i_inv: Self::ExternalInputsVar,
+        ) -> Result<Vec<FpVar<Fr>>, SynthesisError> {
+            (&z_i[0] * &z_i_inv).enforce_equal(&FpVar::one())?;
+            Ok(vec![&z_i[0] + FpVar::one()])
+        }
+    }
+
+    // Tests that the inner nodes evaluate F with the external inputs given by the prover
+    #[test]
+    fn test_pcd_merge_external_inputs() -> Result<(), Error> {
+        type N = NovaPCD<Projective, Projective2, InverseFCircuit, CS1, CS2>;
+        let mut rng = OsRng;
+        let prep_param = PreprocessorParam::new(poseidon_canonical_config::<Fr>(), InverseFCircuit);
+        let params = N::preprocess(&mut rng, &prep_param)?;
+        let pcd = N::init(&params, InverseFCircuit)?;
+        let inverse = |z: &[Fr]| z[0].inverse().ok_or(Error::OutOfBounds);
+
+        let z_0 = vec![Fr::from(3_u32)];
+        let leaf_0 = pcd.prove_leaf(rng, 0, z_0.clone(), inverse(&z_0)?)?;
+        let leaf_1 = pcd.prove_leaf(rng, 1, leaf_0.z_end.clone(), inverse(&leaf_0.z_end)?)?;
+
+        // F is evaluated at the state z_1 where leaf_1 starts
+        let root = pcd.prove_merge(rng, &leaf_0, &leaf_1, inverse(&leaf_1.z_start)?)?;
+        N::verify(&params.1, &z_0, 2, &root)?;
+        assert_eq!(root.z_end, vec![Fr::from(5_u32)]);
+        Ok(())
+    }
+}
@@ -0,0 +1,484 @@
+/// Contains the circuit of the nodes of the Nova-based PCD (see [`super::pcd`]).
+use ark_crypto_primitives::sponge::constraints::AbsorbGadget;
+use ark_ff::PrimeField;
+use ark_r1cs_std::{
+    alloc::AllocVar,
+    boolean::Boolean,
+    eq::EqGadget,
+    fields::{fp::FpVar, FieldVar},
+    prelude::CurveVar,
+    select::CondSelectGadget,
+    R1CSVar,
+};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use ark_std::{marker::PhantomData, One, Zero};
+
+use super::{
+    nifs::{
+        nova_circuits::{CommittedInstanceVar, NIFSGadget},
+        NIFSGadgetTrait, NovaNIFSGadgetTrait,
+    },
+    CommittedInstance,
+};
+use crate::constants::NOVA_N_BITS_RO;
+use crate::folding::circuits::{
+    cyclefold::{
+        CycleFoldChallengeGadget, CycleFoldCircuit, CycleFoldCommittedInstance,
+        CycleFoldCommittedInstanceVar, CycleFoldConfig, NIFSFullGadget,
+    },
+    nonnative::{affine::NonNativeAffineVar, uint::NonNativeUintVar},
+    CF1,
+};
+use crate::folding::traits::Dummy;
+use crate::frontend::FCircuit;
+use crate::transcript::{AbsorbNonNativeGadget, Transcript, TranscriptVar};
+use crate::Curve;
+
+/// Configuration for the CycleFold circuit of the PCD. Unlike in Nova's IVC, when merging two
+/// running instances the `cmE` of the second instance is not zero, so the CycleFold circuit
+/// computes `P_0 + r * P_1 + r^2 * P_2`.
+pub struct PCDCycleFoldConfig<C: Curve> {
+    _c: PhantomData<C>,
+}
+
+impl<C: Curve> CycleFoldConfig for PCDCycleFoldConfig<C> {
+    const RANDOMNESS_BIT_LENGTH: usize = NOVA_N_BITS_RO;
+    const N_INPUT_POINTS: usize = 3;
+    type C = C;
+}
+
+/// CycleFold circuit for computing random linear combinations of group elements in the PCD nodes.
+pub type PCDCycleFoldCircuit<C> = CycleFoldCircuit<PCDCycleFoldConfig<C>>;
+
+/// Number of CycleFold circuits used at each node: one for `cmW` and another one for `cmE` for each
+/// of the three folds (`u_L` into `U_L`, `u_R` into `U_R`, and the resulting running instances
+/// between them).
+pub(super) const N_CYCLEFOLD_CIRCUITS: usize = 6;
+
+/// Public values of a child node, which are the ones used by its parent's circuit.
+#[derive(Debug, Clone)]
+pub struct NodeInstance<C1: Curve, C2: Curve> {
+    pub i_start: C1::ScalarField,
+    pub i_end: C1::ScalarField,
+    pub z_start: Vec<C1::ScalarField>,
+    pub z_end: Vec<C1::ScalarField>,
+    pub U: CommittedInstance<C1>,
+    pub u: CommittedInstance<C1>,
+    pub cf_U: CycleFoldCommittedInstance<C2>,
+}
+
+impl<C1: Curve, C2: Curve> NodeInstance<C1, C2> {
+    fn dummy(state_len: usize) -> Self {
+        Self {
+            i_start: C1::ScalarField::zero(),
+            i_end: C1::ScalarField::zero(),
+            z_start: vec![C1::ScalarField::zero(); state_len],
+            z_end: vec![C1::ScalarField::zero(); state_len],
+            U: CommittedInstance::dummy(2),
+            u: CommittedInstance::dummy(2),
+            cf_U: CycleFoldCommittedInstance::dummy(PCDCycleFoldConfig::<C1>::IO_LEN),
+        }
+    }
+}
+
+/// In-circuit counterpart of [`NodeInstance`], where `u` is constructed from the commitments
+/// provided by the prover and the public inputs computed in-circuit.
+struct NodeInstanceVar<C1: Curve, C2: Curve> {
+    i_start: FpVar<CF1<C1>>,
+    i_end: FpVar<CF1<C1>>,
+    z_start: Vec<FpVar<CF1<C1>>>,
+    z_end: Vec<FpVar<CF1<C1>>>,
+    U: CommittedInstanceVar<C1>,
+    // sponge representations of U and cf_U, so that they are not recomputed
+    U_vec: Vec<FpVar<CF1<C1>>>,
+    u: CommittedInstanceVar<C1>,
+    cf_U: CycleFoldCommittedInstanceVar<C2>,
+    cf_U_vec: Vec<FpVar<CF1<C1>>>,
+}
+
+impl<C1, C2> NodeInstanceVar<C1, C2>
+where
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: Curve,
+{
+    fn new_witness<S: Transcript<CF1<C1>>, T: TranscriptVar<CF1<C1>, S>>(
+        cs: ConstraintSystemRef<CF1<C1>>,
+        sponge: &T,
+        pp_hash: &FpVar<CF1<C1>>,
+        node: &NodeInstance<C1, C2>,
+    ) -> Result<Self, SynthesisError> {
+        let i_start = FpVar::new_witness(cs.clone(), || Ok(node.i_start))?;
+        let i_end = FpVar::new_witness(cs.clone(), || Ok(node.i_end))?;
+        let z_start: Vec<FpVar<CF1<C1>>> =
+            Vec::new_witness(cs.clone(), || Ok(node.z_start.clone()))?;
+        let z_end: Vec<FpVar<CF1<C1>>> = Vec::new_witness(cs.clone(), || Ok(node.z_end.clone()))?;
+        let U = CommittedInstanceVar::new_witness(cs.clone(), || Ok(node.U.clone()))?;
+        let cf_U =
+            CycleFoldCommittedInstanceVar::new_witness(cs.clone(), || Ok(node.cf_U.clone()))?;
+
+        // u.x[0] = H(i_start, i_end, z_start, z_end, U)
+        let (u_x, U_vec) = node_hash_var(sponge, pp_hash, &i_start, &i_end, &z_start, &z_end, &U)?;
+        // u.x[1] = H(cf_U)
+        let (cf_u_x, cf_U_vec) = cf_U.hash(sponge, pp_hash.clone())?;
+        let u = NIFSGadget::<C1, S, T>::new_incoming_var(cs, &node.u, vec![u_x, cf_u_x])?;
+
+        Ok(Self {
+            i_start,
+            i_end,
+            z_start,
+            z_end,
+            U,
+            U_vec,
+            u,
+            cf_U,
+            cf_U_vec,
+        })
+    }
+}
+
+/// In-circuit counterpart of [`super::pcd::node_hash`], which returns `H(i_start, i_end, z_start,
+/// z_end, U)` together with the sponge representation of `U`.
+#[allow(clippy::type_complexity)]
+fn node_hash_var<C: Curve, S: Transcript<CF1<C>>, T: TranscriptVar<CF1<C>, S>>(
+    sponge: &T,
+    pp_hash: &FpVar<CF1<C>>,
+    i_start: &FpVar<CF1<C>>,
+    i_end: &FpVar<CF1<C>>,
+    z_start: &[FpVar<CF1<C>>],
+    z_end: &[FpVar<CF1<C>>],
+    U: &CommittedInstanceVar<C>,
+) -> Result<(FpVar<CF1<C>>, Vec<FpVar<CF1<C>>>), SynthesisError> {
+    let mut sponge = sponge.clone();
+    let U_vec = U.to_sponge_field_elements()?;
+    sponge.absorb(&pp_hash)?;
+    sponge.absorb(&i_start)?;
+    sponge.absorb(&i_end)?;
+    sponge.absorb(&z_start)?;
+    sponge.absorb(&z_end)?;
+    sponge.absorb(&U_vec)?;
+    Ok((
+        // `unwrap` is safe because the sponge is guaranteed to return a single element
+        sponge.squeeze_field_elements(1)?.pop().unwrap(),
+        U_vec,
+    ))
+}
+
+/// `PCDNodeCircuit` is the circuit of a node of the PCD tree, which is used both for the leaves
+/// and for the inner nodes:
+/// - a leaf computes a single step of `F`, ie. it outputs the node `(i, i+1, z_i, F(z_i),
+///   U_{\bot})`.
+/// - an inner node merges its children `L=(a, m, z_a, z_m, U_L)` and `R=(m, b, z_m, z_b, U_R)`,
+///   checking that they are adjacent, folding their incoming instances `u_L`, `u_R` into their
+///   running instances, and folding the two resulting running instances into `U`. It outputs the
+///   node `(a, b, z_a, z_b, U)`.
+///
+/// As in Nova's AugmentedFCircuit, the output node is hashed into the first public input of the
+/// incoming instance of the node, the second one being the hash of the CycleFold running
+/// instance, and the folding of the commitments is delegated to the CycleFold circuit.
+#[derive(Debug, Clone)]
+pub struct PCDNodeCircuit<
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<CF1<C1>>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
+> {
+    pub(super) poseidon_config: S::Config,
+    pub(super) pp_hash: Option<CF1<C1>>,
+    pub(super) is_leaf: Option<bool>,
+    // step computed by the node if it is a leaf, or the step `m` between the children (at which
+    // F is evaluated and its output discarded) if it is an inner node
+    pub(super) i: Option<CF1<C1>>,
+    pub(super) i_usize: Option<usize>,
+    pub(super) z_i: Option<Vec<CF1<C1>>>,
+    pub(super) external_inputs: Option<FC::ExternalInputs>,
+    // children of the node, only used if it is an inner node
+    pub(super) children: Option<[NodeInstance<C1, C2>; 2]>,
+    // proofs (cmT) of the folds of u_L into U_L, u_R into U_R, and of the resulting instances
+    pub(super) cmT: Option<[C1; 3]>,
+    // resulting instances of the three folds, from which only the commitments are used
+    pub(super) U_folded: Option<[CommittedInstance<C1>; 3]>,
+    pub(super) F: FC,
+
+    // cyclefold verifier on C1
+    // proof of merging the children's CycleFold running instances
+    pub(super) cf_merge_cmT: Option<C2>,
+    // `cf_u_cmW` and `cf_cmT` contain a value for each of the CycleFold circuits
+    pub(super) cf_u_cmW: Option<Vec<C2>>,
+    pub(super) cf_cmT: Option<Vec<C2>>,
+    pub(super) _s: PhantomData<S>,
+    pub(super) _t: PhantomData<T>,
+}
+
+impl<C1, C2, FC, S, T> PCDNodeCircuit<C1, C2, FC, S, T>
+where
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<CF1<C1>>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
+{
+    pub fn empty(poseidon_config: &S::Config, F_circuit: FC) -> Self {
+        Self {
+            poseidon_config: poseidon_config.clone(),
+            pp_hash: None,
+            is_leaf: None,
+            i: None,
+            i_usize: None,
+            z_i: None,
+            external_inputs: None,
+            children: None,
+            cmT: None,
+            U_folded: None,
+            F: F_circuit,
+            cf_merge_cmT: None,
+            cf_u_cmW: None,
+            cf_cmT: None,
+            _s: PhantomData,
+            _t: PhantomData,
+        }
+    }
+}
+
+impl<C1, C2, FC, S, T> PCDNodeCircuit<C1, C2, FC, S, T>
+where
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: Curve,
+    FC: FCircuit<CF1<C1>>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
+{
+    /// Generates the constraints of the node, returning the final state `z_end` of the output
+    /// node.
+    pub fn compute_next_state(
+        self,
+        cs: ConstraintSystemRef<CF1<C1>>,
+    ) -> Result<Vec<FpVar<CF1<C1>>>, SynthesisError> {
+        let state_len = self.F.state_len();
+
+        let pp_hash = FpVar::<CF1<C1>>::new_witness(cs.clone(), || {
+            Ok(self.pp_hash.unwrap_or_else(CF1::<C1>::zero))
+        })?;
+        let is_leaf = Boolean::new_witness(cs.clone(), || Ok(self.is_leaf.unwrap_or(true)))?;
+        let is_inner = !&is_leaf;
+        let i = FpVar::<CF1<C1>>::new_witness(cs.clone(), || {
+            Ok(self.i.unwrap_or_else(CF1::<C1>::zero))
+        })?;
+        let z_i = Vec::<FpVar<CF1<C1>>>::new_witness(cs.clone(), || {
+            Ok(self.z_i.unwrap_or(vec![CF1::<C1>::zero(); state_len]))
+        })?;
+        let external_inputs = FC::ExternalInputsVar::new_witness(cs.clone(), || {
+            Ok(self.external_inputs.unwrap_or_default())
+        })?;
+
+        let u_dummy = CommittedInstance::<C1>::dummy(2);
+        let [cmT_L, cmT_R, cmT_M] = self.cmT.unwrap_or([C1::zero(); 3]);
+        let cmT_L = NonNativeAffineVar::new_witness(cs.clone(), || Ok(cmT_L))?;
+        let cmT_R = NonNativeAffineVar::new_witness(cs.clone(), || Ok(cmT_R))?;
+        let cmT_M = NonNativeAffineVar::new_witness(cs.clone(), || Ok(cmT_M))?;
+        let [U_L1_cms, U_R1_cms, U_cms] = self
+            .U_folded
+            .unwrap_or([u_dummy.clone(), u_dummy.clone(), u_dummy.clone()])
+            .map(|U| vec![U.cmW, U.cmE]);
+        let U_L1_cms = Vec::<NonNativeAffineVar<C1>>::new_witness(cs.clone(), || Ok(U_L1_cms))?;
+        let U_R1_cms = Vec::<NonNativeAffineVar<C1>>::new_witness(cs.clone(), || Ok(U_R1_cms))?;
+        let U_cms = Vec::<NonNativeAffineVar<C1>>::new_witness(cs.clone(), || Ok(U_cms))?;
+
+        let cf_u_dummy = CycleFoldCommittedInstance::dummy(PCDCycleFoldConfig::<C1>::IO_LEN);
+        let cf_merge_cmT = C2::Var::new_witness(cs.clone(), || {
+            Ok(self.cf_merge_cmT.unwrap_or_else(C2::zero))
+        })?;
+        let cf_cmT = Vec::<C2::Var>::new_witness(cs.clone(), || {
+            Ok(self
+                .cf_cmT
+                .unwrap_or(vec![C2::zero(); N_CYCLEFOLD_CIRCUITS]))
+        })?;
+
+        // `sponge` is for digest computation.
+        let sponge = T::new(cs.clone(), &self.poseidon_config);
+        // `transcript` is for challenge generation.
+        let mut transcript = sponge.clone();
+
+        // Primary Part
+        // P.1. Allocate the children, computing the public inputs of their incoming instances
+        let [L, R] = self.children.unwrap_or_else(|| {
+            [
+                NodeInstance::dummy(state_len),
+                NodeInstance::dummy(state_len),
+            ]
+        });
+        let L = NodeInstanceVar::new_witness(cs.clone(), &sponge, &pp_hash, &L)?;
+        let R = NodeInstanceVar::new_witness(cs.clone(), &sponge, &pp_hash, &R)?;
+
+        // P.2. Check that the children are adjacent, ie. that R starts where L ends
+        L.i_end.conditional_enforce_equal(&R.i_start, &is_inner)?;
+        L.z_end.conditional_enforce_equal(&R.z_start, &is_inner)?;
+
+        // P.3. nifs.verify, obtains U_L' and U_R' by folding u_L into U_L and u_R into U_R, and
+        // then U by folding U_R' into U_L'.
+        // Notice that NIFSGadget::verify does not fold the commitments, which are set to the
+        // unconstrained witnesses provided by the prover, and checked on the other curve.
+        let (mut U_L1, r_L_bits) = NIFSGadget::<C1, S, T>::verify(
+            &mut transcript,
+            pp_hash.clone(),
+            L.U.clone(),
+            L.U_vec,
+            L.u.clone(),
+            Some(cmT_L.clone()),
+        )?;
+        NIFSGadget::<C1, S, T>::set_commitments(&mut U_L1, U_L1_cms)?;
+        let (mut U_R1, r_R_bits) = NIFSGadget::<C1, S, T>::verify(
+            &mut transcript,
+            pp_hash.clone(),
+            R.U.clone(),
+            R.U_vec,
+            R.u.clone(),
+            Some(cmT_R.clone()),
+        )?;
+        NIFSGadget::<C1, S, T>::set_commitments(&mut U_R1, U_R1_cms)?;
+        let U_L1_vec = U_L1.to_sponge_field_elements()?;
+        let (mut U, r_M_bits) = NIFSGadget::<C1, S, T>::verify(
+            &mut transcript,
+            pp_hash.clone(),
+            U_L1.clone(),
+            U_L1_vec,
+            U_R1.clone(),
+            Some(cmT_M.clone()),
+        )?;
+        NIFSGadget::<C1, S, T>::set_commitments(&mut U, U_cms)?;
+
+        // P.4.a compute and check the first output of the node
+
+        // get z_{i+1} from the F circuit. Notice that in the inner nodes its output is discarded.
+        let i_usize = self.i_usize.unwrap_or(0);
+        let z_i1 =
+            self.F
+                .generate_step_constraints(cs.clone(), i_usize, z_i.clone(), external_inputs)?;
+
+        // Leaf: u.x[0] == H(i, i+1, z_i, z_{i+1}, U_{\bot})
+        // Inner node: u.x[0] == H(L.i_start, R.i_end, L.z_start, R.z_end, U)
+        let i_start = is_leaf.select(&i, &L.i_start)?;
+        let i_end = is_leaf.select(&(i + FpVar::<CF1<C1>>::one()), &R.i_end)?;
+        let z_start = z_i
+            .iter()
+            .zip(&L.z_start)
+            .map(|(a, b)| is_leaf.select(a, b))
+            .collect::<Result<Vec<_>, _>>()?;
+        let z_end = z_i1
+            .iter()
+            .zip(&R.z_end)
+            .map(|(a, b)| is_leaf.select(a, b))
+            .collect::<Result<Vec<_>, _>>()?;
+        let U_out = CommittedInstanceVar::conditionally_select(
+            &is_leaf,
+            &CommittedInstanceVar::new_constant(cs.clone(), u_dummy)?,
+            &U,
+        )?;
+        let (x, _) = node_hash_var(
+            &sponge, &pp_hash, &i_start, &i_end, &z_start, &z_end, &U_out,
+        )?;
+        // This line "converts" `x` from a witness to a public input, see the analogous line in
+        // Nova's AugmentedFCircuit for details.
+        FpVar::new_input(cs.clone(), || x.value())?.enforce_equal(&x)?;
+
+        // CycleFold part
+        let cf_u_cmW = Vec::<C2::Var>::new_witness(cs.clone(), || {
+            Ok(self
+                .cf_u_cmW
+                .unwrap_or(vec![C2::zero(); N_CYCLEFOLD_CIRCUITS]))
+        })?;
+
+        // C.1. Merge the CycleFold running instances of the children into cf_U
+        let cf_r_bits = CycleFoldChallengeGadget::<C2>::get_challenge_gadget(
+            &mut transcript,
+            pp_hash.clone(),
+            L.cf_U_vec,
+            R.cf_U.clone(),
+            cf_merge_cmT.clone(),
+        )?;
+        let mut cf_U = NIFSFullGadget::<C2>::fold_running_committed_instances(
+            cf_r_bits,
+            cf_merge_cmT,
+            L.cf_U,
+            R.cf_U,
+        )?;
+
+        // C.2. Fold the instances of the CycleFold circuits into cf_U, sequentially. Each
+        // CycleFold circuit computes `P_0 + r * P_1 + r^2 * P_2 = P_folded`, and has as public
+        // inputs r and the coordinates of the points.
+        let to_nonnat = |r_bits: Vec<Boolean<CF1<C1>>>| {
+            let mut bits = r_bits;
+            bits.resize(C1::BaseField::MODULUS_BIT_SIZE as usize, Boolean::FALSE);
+            NonNativeUintVar::from(&bits)
+        };
+        let (r_L, r_R, r_M) = (
+            to_nonnat(r_L_bits),
+            to_nonnat(r_R_bits),
+            to_nonnat(r_M_bits),
+        );
+        let zero = NonNativeAffineVar::new_constant(cs.clone(), C1::zero())?;
+        let cf_inputs = [
+            (&r_L, [&L.U.cmW, &L.u.cmW, &zero, &U_L1.cmW]),
+            (&r_L, [&L.U.cmE, &cmT_L, &L.u.cmE, &U_L1.cmE]),
+            (&r_R, [&R.U.cmW, &R.u.cmW, &zero, &U_R1.cmW]),
+            (&r_R, [&R.U.cmE, &cmT_R, &R.u.cmE, &U_R1.cmE]),
+            (&r_M, [&U_L1.cmW, &U_R1.cmW, &zero, &U.cmW]),
+            (&r_M, [&U_L1.cmE, &cmT_M, &U_R1.cmE, &U.cmE]),
+        ];
+        for (((r, points), cf_u_i_cmW), cf_cmT) in cf_inputs.into_iter().zip(cf_u_cmW).zip(cf_cmT) {
+            let cf_x = [
+                vec![r.clone()],
+                points
+                    .into_iter()
+                    .flat_map(|p| [p.x.clone(), p.y.clone()])
+                    .collect(),
+            ]
+            .concat();
+
+            let cf_u_i = CycleFoldCommittedInstanceVar {
+                // cf_u_i.cmE = 0
+                cmE: C2::Var::zero(),
+                // cf_u_i.u = 1
+                u: NonNativeUintVar::new_constant(cs.clone(), C1::BaseField::one())?,
+                // cf_u_i.cmW is provided by the prover as witness
+                cmW: cf_u_i_cmW,
+                x: cf_x,
+            };
+
+            let cf_r_bits = CycleFoldChallengeGadget::<C2>::get_challenge_gadget(
+                &mut transcript,
+                pp_hash.clone(),
+                cf_U.to_native_sponge_field_elements()?,
+                cf_u_i.clone(),
+                cf_cmT.clone(),
+            )?;
+            cf_U = NIFSFullGadget::<C2>::fold_committed_instance(cf_r_bits, cf_cmT, cf_U, cf_u_i)?;
+        }
+
+        // Back to Primary Part
+        // P.4.b compute and check the second output of the node
+        // Leaf: u.x[1] == H(cf_U_{\bot})
+        // Inner node: u.x[1] == H(cf_U)
+        let (cf_u_x, _) = cf_U.hash(&sponge, pp_hash.clone())?;
+        let (cf_u_x_leaf, _) =
+            CycleFoldCommittedInstanceVar::<C2>::new_constant(cs.clone(), cf_u_dummy)?
+                .hash(&sponge, pp_hash)?;
+        let cf_x = is_leaf.select(&cf_u_x_leaf, &cf_u_x)?;
+        FpVar::new_input(cs.clone(), || cf_x.value())?.enforce_equal(&cf_x)?;
+
+        Ok(z_end)
+    }
+}
+
+impl<C1, C2, FC, S, T> ConstraintSynthesizer<CF1<C1>> for PCDNodeCircuit<C1, C2, FC, S, T>
+where
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: Curve,
+    FC: FCircuit<CF1<C1>>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
+{
+    fn generate_constraints(self, cs: ConstraintSystemRef<CF1<C1>>) -> Result<(), SynthesisError> {
+        self.compute_next_state(cs).map(|_| ())
+    }
+}
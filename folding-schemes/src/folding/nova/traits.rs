@@ -1,6 +1,6 @@
 use ark_r1cs_std::fields::fp::FpVar;
 use ark_relations::r1cs::SynthesisError;
-use ark_std::{rand::RngCore, UniformRand};
+use ark_std::{rand::RngCore, UniformRand, Zero};
 
 use super::decider_eth_circuit::WitnessVar;
 use super::nifs::nova_circuits::CommittedInstanceVar;
@@ -62,7 +62,7 @@ impl<C: Curve> ArithRelation<Witness<C>, CommittedInstance<C>> for R1CS<CF1<C>>
 }
 
 impl<C: Curve> ArithSampler<C, Witness<C>, CommittedInstance<C>> for R1CS<CF1<C>> {
-    fn sample_witness_instance<CS: CommitmentScheme<C, true>>(
+    fn sample_witness_instance<CS: CommitmentScheme<C, H>, const H: bool>(
         &self,
         params: &CS::ProverParams,
         mut rng: impl RngCore,
@@ -70,8 +70,14 @@ impl<C: Curve> ArithSampler<C, Witness<C>, CommittedInstance<C>> for R1CS<CF1<C>
         // Implements sampling a (committed) RelaxedR1CS
         // See construction 5 in https://eprint.iacr.org/2023/573.pdf
         let u = C::ScalarField::rand(&mut rng);
-        let rE = C::ScalarField::rand(&mut rng);
-        let rW = C::ScalarField::rand(&mut rng);
+        let (rE, rW) = if H {
+            (
+                C::ScalarField::rand(&mut rng),
+                C::ScalarField::rand(&mut rng),
+            )
+        } else {
+            (C::ScalarField::zero(), C::ScalarField::zero())
+        };
 
         let W = (0..self.n_witnesses())
             .map(|_| C::ScalarField::rand(&mut rng))
@@ -86,7 +92,7 @@ impl<C: Curve> ArithSampler<C, Witness<C>, CommittedInstance<C>> for R1CS<CF1<C>
         let E = self.eval_at_z(&z)?;
 
         let witness = Witness { E, rE, W, rW };
-        let mut cm_witness = witness.commit::<CS, true>(params, x)?;
+        let mut cm_witness = witness.commit::<CS, H>(params, x)?;
 
         // witness.commit() sets u to 1, we set it to the sampled u value
         cm_witness.u = u;
@@ -23,29 +23,44 @@
 ///   involved). In this use-case, the zk is only added at the last IVCProof. Note that this proof
 ///   will be much bigger and expensive to verify than a Decider SNARK proof.
 ///
-/// The current implementation covers the Use-case-3.
-/// Use-case-1 can be achieved directly by a simpler version of the zk IVC scheme skipping steps
-/// and implemented directly at the app level by folding the original instance with a randomized
-/// instance (steps 2,3,4 from section D.4 of the [HyperNova](https://eprint.iacr.org/2023/573.pdf)
-/// paper).
-/// And the Use-case-2 would require a modified version of the Decider circuits.
+/// The current implementation covers the three use-cases.
+///
+/// For the Use-case-1, the user computes a [`BlindedInstance`], using a random satisfying instance
+/// as the initial running instance, and folding the instance of their first step into it (steps
+/// 2,3,4 from section D.4 of the [HyperNova](https://eprint.iacr.org/2023/573.pdf) paper). The
+/// server then continues the IVC with `Nova::prove_step_blinded`, followed by the usual
+/// `prove_step` calls. Both of them use a [`BlindableNova`], whose AugmentedFCircuit binds the
+/// prover-chosen initial running instance (instead of the dummy instance) to the public input of
+/// the first incoming instance, so using a random one does not need any other change in the IVC.
+///
+/// For the Use-case-2, the user computes a [`RandomizedNova`] from its Nova instance, which can be
+/// sent to the server, who uses the modified Decider circuits
+/// ([`super::decider_eth_circuit::RandomizedDeciderEthCircuit`] and
+/// [`super::decider_circuits::RandomizedDeciderCircuit1`]) to generate the Decider proof (see the
+/// `prove_randomized` methods of [`super::decider_eth::Decider`] and [`super::decider::Decider`]).
+/// For the Use-case-3, the user computes a [`RandomizedIVCProof`], which is verified directly.
 ///
 use ark_crypto_primitives::sponge::{
-    poseidon::{PoseidonConfig, PoseidonSponge},
+    poseidon::{constraints::PoseidonSpongeVar, PoseidonConfig, PoseidonSponge},
     CryptographicSponge,
 };
+use ark_ff::{BigInteger, PrimeField};
 use ark_std::{rand::RngCore, One, Zero};
 
 use super::{
+    decider_eth_circuit::RandomizedFoldingProof,
     nifs::{nova::NIFS, NIFSTrait},
-    CommittedInstance, Nova, Witness,
+    CommittedInstance, Nova, ProverParams, VerifierParams, Witness,
 };
 use crate::{
     arith::{r1cs::R1CS, ArithRelation, ArithSampler},
     commitment::CommitmentScheme,
-    folding::traits::CommittedInstanceOps,
+    folding::{
+        circuits::CF1,
+        traits::{CommittedInstanceOps, Dummy},
+    },
     frontend::FCircuit,
-    Curve, Error,
+    Curve, Error, FoldingScheme,
 };
 
 pub struct RandomizedIVCProof<C1: Curve, C2: Curve> {
@@ -89,7 +104,7 @@ impl<C1: Curve, C2: Curve> RandomizedIVCProof<C1, C2> {
         // 2. Sample a satisfying relaxed R1CS instance-witness pair (W_r, U_r)
         let (W_r, U_r) = nova
             .r1cs
-            .sample_witness_instance::<CS1>(&nova.cs_pp, &mut rng)?;
+            .sample_witness_instance::<CS1, true>(&nova.cs_pp, &mut rng)?;
 
         // 3. Fold the instance-witness pair (U_f, W_f) with (U_r, W_r)
         let (W_i_prime, _, cmT_i_prime, _) =
@@ -193,14 +208,264 @@ impl<C1: Curve, C2: Curve> RandomizedIVCProof<C1, C2> {
     }
 }
 
+/// Nova instance whose last folded instance has been randomized, to be sent to an untrusted server
+/// that generates the Decider proof for it (Use-case-2).
+///
+/// The unblinded witnesses `W_i` and `w_i` of `nova` are replaced by dummy values, so the only
+/// witness known by the server is `W_i_prime`, which results from folding `(U_i, W_i)`, `(u_i,
+/// w_i)` and a random satisfying instance-witness pair `(U_r, W_r)`.
+/// The CycleFold instance-witness pair is kept as is, since its witness is derived from the
+/// commitments and folding challenges, which are already public.
+///
+/// Both commitment schemes must be hiding, since otherwise the commitments of the randomized
+/// instance (and of the CycleFold instance), which are made public by the Decider proof, would
+/// reveal information about the witnesses.
+pub struct RandomizedNova<C1, C2, FC, CS1, CS2>
+where
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, true>,
+    CS2: CommitmentScheme<C2, true>,
+{
+    pub nova: Nova<C1, C2, FC, CS1, CS2, true>,
+    pub U_r: CommittedInstance<C1>,
+    pub pi: C1,       // proof = cmT
+    pub pi_prime: C1, // proof' = cmT'
+    pub W_i_prime: Witness<C1>,
+}
+
+impl<C1, C2, FC, CS1, CS2> RandomizedNova<C1, C2, FC, CS1, CS2>
+where
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, true>,
+    CS2: CommitmentScheme<C2, true>,
+{
+    /// Randomizes the last instance of the given Nova, following the same steps as
+    /// [`RandomizedIVCProof::new`], but folding `(U_i, W_i)` with `(u_i, w_i)` in the same order
+    /// as the Decider does.
+    pub fn new(
+        nova: &Nova<C1, C2, FC, CS1, CS2, true>,
+        mut rng: impl RngCore,
+    ) -> Result<Self, Error> {
+        let mut transcript = PoseidonSponge::<C1::ScalarField>::new(&nova.poseidon_config);
+
+        // 1. Fold the instance-witness pairs (U_i, W_i) with (u_i, w_i)
+        let (W_f, U_f, cmT, _) = NIFS::<C1, CS1, PoseidonSponge<C1::ScalarField>, true>::prove(
+            &nova.cs_pp,
+            &nova.r1cs,
+            &mut transcript,
+            nova.pp_hash,
+            &nova.W_i,
+            &nova.U_i,
+            &nova.w_i,
+            &nova.u_i,
+        )?;
+
+        // 2. Sample a satisfying relaxed R1CS instance-witness pair (W_r, U_r)
+        let (W_r, U_r) = nova
+            .r1cs
+            .sample_witness_instance::<CS1, true>(&nova.cs_pp, &mut rng)?;
+
+        // 3. Fold the instance-witness pair (U_f, W_f) with (U_r, W_r)
+        let (W_i_prime, _, cmT_prime, _) =
+            NIFS::<C1, CS1, PoseidonSponge<C1::ScalarField>, true>::prove(
+                &nova.cs_pp,
+                &nova.r1cs,
+                &mut transcript,
+                nova.pp_hash,
+                &W_f,
+                &U_f,
+                &W_r,
+                &U_r,
+            )?;
+
+        // 4. Remove the unblinded witnesses
+        let mut nova = nova.clone();
+        nova.W_i = Witness::dummy(&nova.r1cs);
+        nova.w_i = Witness::dummy(&nova.r1cs);
+
+        Ok(Self {
+            nova,
+            U_r,
+            pi: cmT,
+            pi_prime: cmT_prime,
+            W_i_prime,
+        })
+    }
+
+    /// Computes the randomized instance from the public values, returning it together with the
+    /// folding proof and challenges checked by the Decider, and the transcript after both folds.
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn fold_instances(
+        &self,
+    ) -> Result<
+        (
+            CommittedInstance<C1>,
+            RandomizedFoldingProof<C1>,
+            (C1::ScalarField, C1::ScalarField),
+            PoseidonSponge<C1::ScalarField>,
+        ),
+        Error,
+    > {
+        let mut transcript = PoseidonSponge::<C1::ScalarField>::new(&self.nova.poseidon_config);
+        let (U_f, r_bits) = NIFS::<C1, CS1, PoseidonSponge<C1::ScalarField>, true>::verify(
+            &mut transcript,
+            self.nova.pp_hash,
+            &self.nova.U_i,
+            &self.nova.u_i,
+            &self.pi,
+        )?;
+        let (U_i_prime, r_prime_bits) =
+            NIFS::<C1, CS1, PoseidonSponge<C1::ScalarField>, true>::verify(
+                &mut transcript,
+                self.nova.pp_hash,
+                &U_f,
+                &self.U_r,
+                &self.pi_prime,
+            )?;
+        let r = C1::ScalarField::from_bigint(BigInteger::from_bits_le(&r_bits))
+            .ok_or(Error::OutOfBounds)?;
+        let r_prime = C1::ScalarField::from_bigint(BigInteger::from_bits_le(&r_prime_bits))
+            .ok_or(Error::OutOfBounds)?;
+
+        let proof = RandomizedFoldingProof {
+            cmT: self.pi,
+            cmE: U_f.cmE,
+            cmW: U_f.cmW,
+            U_r: self.U_r.clone(),
+            cmT_prime: self.pi_prime,
+        };
+        Ok((U_i_prime, proof, (r, r_prime), transcript))
+    }
+}
+
+/// Nova+CycleFold's IVC whose initial running instance can be chosen by the prover instead of
+/// being the dummy instance, which allows blinding the first step of the IVC (see
+/// [`BlindedInstance`]). Notice that its circuit differs from the one of [`Nova`], so its
+/// parameters have to be generated for this type.
+pub type BlindableNova<C1, C2, FC, CS1, CS2> =
+    Nova<C1, C2, FC, CS1, CS2, true, PoseidonSponge<CF1<C1>>, PoseidonSpongeVar<CF1<C1>>, true>;
+
+/// Blinded first step of a Nova IVC, computed by the user, to be sent to an untrusted server that
+/// continues the IVC folding (Use-case-1).
+///
+/// The user replaces the initial running instance by a random satisfying instance-witness pair
+/// `(U_r, W_r)`, computes the first IVC step with their secret external inputs (obtaining the
+/// incoming instance-witness pair `(u_1, w_1)`), and folds `(u_1, w_1)` into `(U_r, W_r)`. So the
+/// only witness known by the server is the folded one, `W_2`, which is blinded by `W_r`.
+/// Notice that the state `z_1` after the user's step is sent in the clear, since the server needs
+/// it to compute the next steps.
+pub struct BlindedInstance<C1: Curve> {
+    pub z_1: Vec<C1::ScalarField>,
+    pub U_r: CommittedInstance<C1>, // initial running instance
+    pub u_1: CommittedInstance<C1>, // incoming instance of the user's step
+    pub pi: C1,                     // proof = cmT
+    pub W_2: Witness<C1>,           // witness of the instance obtained by folding u_1 into U_r
+}
+
+impl<C1: Curve> BlindedInstance<C1> {
+    /// Computes the first step of the IVC defined by the given Nova parameters, starting from
+    /// `z_0` with the given `external_inputs`, and blinds it.
+    #[allow(clippy::type_complexity)]
+    pub fn new<C2, FC, CS1, CS2>(
+        params: &(
+            ProverParams<C1, C2, CS1, CS2, true>,
+            VerifierParams<C1, C2, CS1, CS2, true>,
+        ),
+        F: FC,
+        z_0: Vec<C1::ScalarField>,
+        external_inputs: FC::ExternalInputs,
+        mut rng: impl RngCore,
+    ) -> Result<Self, Error>
+    where
+        C2: Curve,
+        FC: FCircuit<C1::ScalarField>,
+        CS1: CommitmentScheme<C1, true>,
+        CS2: CommitmentScheme<C2, true>,
+        C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    {
+        let mut nova = BlindableNova::<C1, C2, FC, CS1, CS2>::init(params, F, z_0)?;
+
+        // 1. Sample a satisfying relaxed R1CS instance-witness pair (W_r, U_r), and use it as the
+        //    initial running instance
+        let (W_r, U_r) = nova
+            .r1cs
+            .sample_witness_instance::<CS1, true>(&nova.cs_pp, &mut rng)?;
+        nova.W_i = W_r;
+        nova.U_i = U_r;
+
+        // 2. Compute the first step, which outputs the incoming instance-witness pair (u_1, w_1)
+        nova.prove_step(&mut rng, external_inputs, None)?;
+
+        // 3. Fold (u_1, w_1) into (U_r, W_r), as the server does at the next step
+        let (W_2, _, cmT, _) = NIFS::<C1, CS1, PoseidonSponge<C1::ScalarField>, true>::prove(
+            &nova.cs_pp,
+            &nova.r1cs,
+            &mut PoseidonSponge::<C1::ScalarField>::new(&nova.poseidon_config),
+            nova.pp_hash,
+            &nova.W_i,
+            &nova.U_i,
+            &nova.w_i,
+            &nova.u_i,
+        )?;
+
+        Ok(Self {
+            z_1: nova.z_i,
+            U_r: nova.U_i,
+            u_1: nova.u_i,
+            pi: cmT,
+            W_2,
+        })
+    }
+}
+
+impl<C1, C2, FC, CS1, CS2> BlindableNova<C1, C2, FC, CS1, CS2>
+where
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, true>,
+    CS2: CommitmentScheme<C2, true>,
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+{
+    /// Server side of the Use-case-1: continues the IVC from the [`BlindedInstance`] computed by
+    /// the user, computing the second step of the IVC without knowing the user's witnesses. It
+    /// has to be called right after [`FoldingScheme::init`] with the same `z_0` used by the user,
+    /// and the following steps are computed with [`FoldingScheme::prove_step`].
+    pub fn prove_step_blinded(
+        &mut self,
+        rng: impl RngCore,
+        external_inputs: FC::ExternalInputs,
+        blinded: BlindedInstance<C1>,
+    ) -> Result<(), Error> {
+        if !self.i.is_zero() {
+            return Err(Error::Other(
+                "the blinded instance can only be folded after the initial state".to_string(),
+            ));
+        }
+
+        // the user's step replaces the first step of the IVC, whose witnesses are unknown, so W_i
+        // and w_i keep their dummy values
+        self.i = C1::ScalarField::one();
+        self.z_i = blinded.z_1;
+        self.U_i = blinded.U_r;
+        self.u_i = blinded.u_1;
+
+        self.prove_step_opt(rng, external_inputs, Some((blinded.W_2, blinded.pi)))
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
-    use crate::commitment::pedersen::Pedersen;
-    use crate::folding::nova::tests::test_ivc_opt;
+    use crate::commitment::{kzg::KZG, pedersen::Pedersen};
+    use crate::folding::nova::{tests::test_ivc_opt, PreprocessorParam};
     use crate::frontend::utils::CubicFCircuit;
     use crate::transcript::poseidon::poseidon_canonical_config;
-    use ark_bn254::{Fr, G1Projective as Projective};
+    use ark_bn254::{Bn254, Fr, G1Projective as Projective};
     use ark_grumpkin::Projective as Projective2;
     use rand::rngs::OsRng;
 
@@ -232,6 +497,33 @@ pub mod tests {
         Ok(())
     }
 
+    // Same as test_zk_nova_ivc, but using hiding KZG commitments on the primary curve
+    #[test]
+    fn test_zk_nova_ivc_with_kzg() -> Result<(), Error> {
+        let mut rng = OsRng;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let (_, nova) = test_ivc_opt::<KZG<Bn254, true>, Pedersen<Projective2, true>, true>(
+            poseidon_config.clone(),
+            F_circuit,
+            3,
+        )?;
+
+        let proof = RandomizedIVCProof::new(&nova, &mut rng)?;
+        let verify = RandomizedIVCProof::verify::<KZG<Bn254, true>, Pedersen<Projective2, true>>(
+            &nova.r1cs,
+            &nova.cf_r1cs,
+            nova.pp_hash,
+            &nova.poseidon_config,
+            nova.i,
+            nova.z_0,
+            nova.z_i,
+            &proof,
+        );
+        assert!(verify.is_ok());
+        Ok(())
+    }
+
     #[test]
     fn test_zk_nova_when_i_is_zero() -> Result<(), Error> {
         let mut rng = OsRng;
@@ -271,7 +563,7 @@ pub mod tests {
         >(poseidon_config.clone(), F_circuit, 3)?;
         let (_, sampled_committed_instance) = nova
             .r1cs
-            .sample_witness_instance::<Pedersen<Projective, true>>(&nova.cs_pp, rng)?;
+            .sample_witness_instance::<Pedersen<Projective, true>, true>(&nova.cs_pp, rng)?;
 
         // proof verification fails with incorrect running instance
         let mut nova_with_incorrect_running_instance = nova.clone();
@@ -305,7 +597,7 @@ pub mod tests {
         >(poseidon_config.clone(), F_circuit, 3)?;
         let (sampled_committed_witness, _) = nova
             .r1cs
-            .sample_witness_instance::<Pedersen<Projective, true>>(&nova.cs_pp, rng)?;
+            .sample_witness_instance::<Pedersen<Projective, true>, true>(&nova.cs_pp, rng)?;
 
         // proof generation fails with incorrect running witness
         let mut nova_with_incorrect_running_witness = nova.clone();
@@ -326,4 +618,142 @@ pub mod tests {
         assert!(verify.is_err());
         Ok(())
     }
+
+    // Tests that the RandomizedNova, which is sent to the Decider prover, does not contain the
+    // unblinded witnesses, and that its randomized witness satisfies the randomized instance
+    #[test]
+    fn test_randomized_nova() -> Result<(), Error> {
+        let mut rng = OsRng;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let (_, nova) = test_ivc_opt::<
+            Pedersen<Projective, true>,
+            Pedersen<Projective2, true>,
+            true,
+        >(poseidon_config, F_circuit, 3)?;
+
+        let randomized_nova = RandomizedNova::new(&nova, &mut rng)?;
+        let dummy_witness = Witness::<Projective>::dummy(&nova.r1cs);
+        assert_eq!(randomized_nova.nova.W_i, dummy_witness);
+        assert_eq!(randomized_nova.nova.w_i, dummy_witness);
+
+        // the randomized witness satisfies the randomized instance
+        let (U_i_prime, _, _, _) = randomized_nova.fold_instances()?;
+        nova.r1cs
+            .check_relation(&randomized_nova.W_i_prime, &U_i_prime)?;
+
+        // while it differs from the witness obtained by folding without the random instance
+        let (W_f, _, _, _) =
+            NIFS::<Projective, Pedersen<Projective, true>, PoseidonSponge<Fr>, true>::prove(
+                &nova.cs_pp,
+                &nova.r1cs,
+                &mut PoseidonSponge::new(&nova.poseidon_config),
+                nova.pp_hash,
+                &nova.W_i,
+                &nova.U_i,
+                &nova.w_i,
+                &nova.u_i,
+            )?;
+        assert_ne!(randomized_nova.W_i_prime.W, W_f.W);
+        assert_ne!(randomized_nova.W_i_prime.E, W_f.E);
+        Ok(())
+    }
+
+    // Tests the Use-case-1: the user blinds the first step, and the server continues the IVC from
+    // it without knowing the user's witnesses
+    #[test]
+    fn test_blinded_first_step() -> Result<(), Error> {
+        type N = BlindableNova<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            Pedersen<Projective, true>,
+            Pedersen<Projective2, true>,
+        >;
+
+        let mut rng = OsRng;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let prep_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        let nova_params = N::preprocess(&mut rng, &prep_param)?;
+        let z_0 = vec![Fr::from(3_u32)];
+
+        // user side
+        let blinded = BlindedInstance::new(&nova_params, F_circuit, z_0.clone(), (), &mut rng)?;
+        let W_2 = blinded.W_2.clone();
+
+        // server side
+        let mut nova = N::init(&nova_params, F_circuit, z_0.clone())?;
+        nova.prove_step_blinded(&mut rng, (), blinded)?;
+        // the only witness known by the server is the blinded one
+        assert_eq!(nova.W_i, W_2);
+        for _ in 0..2 {
+            nova.prove_step(&mut rng, (), None)?;
+        }
+        N::verify(nova_params.1.clone(), nova.ivc_proof())?;
+
+        // the state matches the one of the IVC computed without blinding
+        let mut expected_nova = N::init(&nova_params, F_circuit, z_0)?;
+        for _ in 0..4 {
+            expected_nova.prove_step(&mut rng, (), None)?;
+        }
+        assert_eq!(nova.i, expected_nova.i);
+        assert_eq!(nova.z_i, expected_nova.z_i);
+        Ok(())
+    }
+
+    #[test]
+    fn test_blinded_first_step_fails_with_wrong_witness() -> Result<(), Error> {
+        type N = BlindableNova<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            Pedersen<Projective, true>,
+            Pedersen<Projective2, true>,
+        >;
+
+        let mut rng = OsRng;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let prep_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        let nova_params = N::preprocess(&mut rng, &prep_param)?;
+        let z_0 = vec![Fr::from(3_u32)];
+
+        let mut blinded = BlindedInstance::new(&nova_params, F_circuit, z_0.clone(), (), &mut rng)?;
+        blinded.W_2.W[0] += Fr::one();
+
+        let mut nova = N::init(&nova_params, F_circuit, z_0)?;
+        assert!(nova.prove_step_blinded(&mut rng, (), blinded).is_err());
+        Ok(())
+    }
+
+    // Checks that in the default Nova, whose AugmentedFCircuit hashes the dummy instance in the
+    // base case, the initial running instance can not be replaced by a random one
+    #[test]
+    fn test_random_initial_running_instance_without_blinded_base_case() -> Result<(), Error> {
+        type N = Nova<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            Pedersen<Projective, true>,
+            Pedersen<Projective2, true>,
+            true,
+        >;
+
+        let mut rng = OsRng;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let prep_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        let nova_params = N::preprocess(&mut rng, &prep_param)?;
+        let mut nova = N::init(&nova_params, F_circuit, vec![Fr::from(3_u32)])?;
+
+        let (W_r, U_r) = nova
+            .r1cs
+            .sample_witness_instance::<Pedersen<Projective, true>, true>(&nova.cs_pp, &mut rng)?;
+        nova.W_i = W_r;
+        nova.U_i = U_r;
+        nova.prove_step(&mut rng, (), None)?;
+        assert!(N::verify(nova_params.1, nova.ivc_proof()).is_err());
+        Ok(())
+    }
 }
@@ -0,0 +1,419 @@
+/// This file implements the offchain decider circuits for Ova. For more details about the
+/// offchain decider circuits, see the Nova's documentation page:
+/// https://privacy-scaling-explorations.github.io/sonobe-docs/design/nova-decider-offchain.html
+use ark_crypto_primitives::sponge::{
+    poseidon::{constraints::PoseidonSpongeVar, PoseidonSponge},
+    CryptographicSponge,
+};
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::{
+    alloc::{AllocVar, AllocationMode},
+    fields::fp::FpVar,
+};
+use ark_relations::r1cs::{Namespace, SynthesisError};
+use ark_std::{borrow::Borrow, marker::PhantomData, Zero};
+
+use super::Ova;
+use crate::arith::{
+    r1cs::{circuits::R1CSMatricesVar, R1CS},
+    Arith, ArithRelation, ArithRelationGadget,
+};
+use crate::commitment::CommitmentScheme;
+use crate::folding::{
+    circuits::{
+        decider::{
+            off_chain::{GenericOffchainDeciderCircuit1, GenericOffchainDeciderCircuit2},
+            DeciderEnabledNIFS, EvalGadget, KZGChallengesGadget,
+        },
+        CF1,
+    },
+    nova::nifs::{
+        ova::{compute_E, compute_T, CommittedInstance, Witness, NIFS},
+        ova_circuits::{CommittedInstanceVar, NIFSGadget},
+        NIFSGadgetTrait, NIFSTrait,
+    },
+    traits::{Dummy, WitnessOps, WitnessVarOps},
+};
+use crate::frontend::FCircuit;
+use crate::transcript::poseidon::poseidon_canonical_config;
+use crate::utils::gadgets::{EquivalenceGadget, VectorGadget};
+use crate::{Curve, Error};
+
+/// Witness used by the Ova deciders. Unlike [`Witness`], which only contains the R1CS witness
+/// `w`, it contains the vector `w || e` (or `w || t` for incoming instances) that is committed in
+/// `cmWE`, so that the deciders can check the commitment openings.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DeciderWitness<C: Curve> {
+    pub WE: Vec<C::ScalarField>,
+    pub rW: C::ScalarField,
+}
+
+impl<C: Curve> DeciderWitness<C> {
+    /// Builds the [`DeciderWitness`] of the running instance `(W, U)`, computing its error term.
+    pub fn from_running(
+        r1cs: &R1CS<CF1<C>>,
+        W: &Witness<C>,
+        U: &CommittedInstance<C>,
+    ) -> Result<Self, Error> {
+        let z: Vec<C::ScalarField> = [&[U.u][..], &U.x, &W.w].concat();
+        let e = compute_E::<C>(r1cs, &z, U.u)?;
+        Ok(Self {
+            WE: [W.w.clone(), e].concat(),
+            rW: W.rW,
+        })
+    }
+}
+
+impl<C: Curve> Dummy<&R1CS<CF1<C>>> for DeciderWitness<C> {
+    fn dummy(r1cs: &R1CS<CF1<C>>) -> Self {
+        Self {
+            WE: vec![C::ScalarField::zero(); r1cs.n_witnesses() + r1cs.n_constraints()],
+            rW: C::ScalarField::zero(),
+        }
+    }
+}
+
+impl<C: Curve> WitnessOps<C::ScalarField> for DeciderWitness<C> {
+    type Var = DeciderWitnessVar<C>;
+
+    fn get_openings(&self) -> Vec<(&[C::ScalarField], C::ScalarField)> {
+        vec![(&self.WE, self.rW)]
+    }
+}
+
+/// In-circuit representation of the [`DeciderWitness`].
+#[derive(Debug, Clone)]
+pub struct DeciderWitnessVar<C: Curve> {
+    pub WE: Vec<FpVar<C::ScalarField>>,
+    pub rW: FpVar<C::ScalarField>,
+}
+
+impl<C: Curve> AllocVar<DeciderWitness<C>, CF1<C>> for DeciderWitnessVar<C> {
+    fn new_variable<T: Borrow<DeciderWitness<C>>>(
+        cs: impl Into<Namespace<CF1<C>>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        f().and_then(|val| {
+            let cs = cs.into();
+
+            let WE: Vec<FpVar<C::ScalarField>> =
+                Vec::new_variable(cs.clone(), || Ok(val.borrow().WE.clone()), mode)?;
+            let rW =
+                FpVar::<C::ScalarField>::new_variable(cs.clone(), || Ok(val.borrow().rW), mode)?;
+
+            Ok(Self { WE, rW })
+        })
+    }
+}
+
+impl<C: Curve> WitnessVarOps<C::ScalarField> for DeciderWitnessVar<C> {
+    fn get_openings(&self) -> Vec<(&[FpVar<C::ScalarField>], FpVar<C::ScalarField>)> {
+        vec![(&self.WE, self.rW.clone())]
+    }
+}
+
+/// Implements [`ArithRelation`] for R1CS, where the witness is of type [`DeciderWitness`], and
+/// the committed instance is of type [`CommittedInstance`]. As in Nova, R1CS here is considered as
+/// a relaxed R1CS, where the error term is the tail of `DeciderWitness.WE`.
+impl<C: Curve> ArithRelation<DeciderWitness<C>, CommittedInstance<C>> for R1CS<CF1<C>> {
+    type Evaluation = Vec<CF1<C>>;
+
+    fn eval_relation(
+        &self,
+        w: &DeciderWitness<C>,
+        u: &CommittedInstance<C>,
+    ) -> Result<Self::Evaluation, Error> {
+        if w.WE.len() < self.n_witnesses() {
+            return Err(Error::NotSameLength(
+                "w.WE.len()".to_string(),
+                w.WE.len(),
+                "number of witnesses in R1CS".to_string(),
+                self.n_witnesses(),
+            ));
+        }
+        self.eval_at_z(&[&[u.u][..], &u.x, &w.WE[..self.n_witnesses()]].concat())
+    }
+
+    fn check_evaluation(
+        w: &DeciderWitness<C>,
+        _u: &CommittedInstance<C>,
+        e: Self::Evaluation,
+    ) -> Result<(), Error> {
+        w.WE.ends_with(&e).then_some(()).ok_or(Error::NotSatisfied)
+    }
+}
+
+impl<C: Curve> ArithRelationGadget<DeciderWitnessVar<C>, CommittedInstanceVar<C>>
+    for R1CSMatricesVar<C::ScalarField, FpVar<C::ScalarField>>
+{
+    type Evaluation = (Vec<FpVar<C::ScalarField>>, Vec<FpVar<C::ScalarField>>);
+
+    fn eval_relation(
+        &self,
+        w: &DeciderWitnessVar<C>,
+        u: &CommittedInstanceVar<C>,
+    ) -> Result<Self::Evaluation, SynthesisError> {
+        // the columns of the R1CS matrices only cover `[u, x, w]`, so the error term at the end of
+        // `WE` does not take part in the evaluation
+        self.eval_at_z(&[&[u.u.clone()][..], &u.x, &w.WE].concat())
+    }
+
+    fn enforce_evaluation(
+        w: &DeciderWitnessVar<C>,
+        _u: &CommittedInstanceVar<C>,
+        (AzBz, uCz): Self::Evaluation,
+    ) -> Result<(), SynthesisError> {
+        if w.WE.len() < AzBz.len() {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let E = &w.WE[w.WE.len() - AzBz.len()..];
+        EquivalenceGadget::<C::ScalarField>::enforce_equivalent(&AzBz[..], &uCz.add(E)?[..])
+    }
+}
+
+pub struct DeciderOvaGadget;
+
+impl<C: Curve>
+    DeciderEnabledNIFS<
+        C,
+        CommittedInstance<C>,
+        CommittedInstance<C>,
+        DeciderWitness<C>,
+        R1CS<CF1<C>>,
+    > for DeciderOvaGadget
+{
+    type ProofDummyCfg = ();
+    type Proof = ();
+    type RandomnessDummyCfg = ();
+    type Randomness = CF1<C>;
+
+    fn fold_field_elements_gadget(
+        _arith: &R1CS<CF1<C>>,
+        transcript: &mut PoseidonSpongeVar<CF1<C>>,
+        pp_hash: FpVar<CF1<C>>,
+        U: CommittedInstanceVar<C>,
+        U_vec: Vec<FpVar<CF1<C>>>,
+        u: CommittedInstanceVar<C>,
+        _proof: (),
+        _randomness: CF1<C>,
+    ) -> Result<CommittedInstanceVar<C>, SynthesisError> {
+        let (new_U, _) = NIFSGadget::verify(transcript, pp_hash, U, U_vec, u, None)?;
+        Ok(new_U)
+    }
+
+    fn fold_group_elements_native(
+        U_commitments: &[C],
+        u_commitments: &[C],
+        _proof: Option<Self::Proof>,
+        r: Self::Randomness,
+    ) -> Result<Vec<C>, Error> {
+        let U_cmWE = U_commitments[0];
+        let u_cmWE = u_commitments[0];
+        Ok(vec![U_cmWE + u_cmWE.mul(r)])
+    }
+}
+
+/// Circuit that implements part of the in-circuit checks needed for the offchain verification over
+/// the Curve2's BaseField (=Curve1's ScalarField).
+pub type DeciderCircuit1<C1, C2> = GenericOffchainDeciderCircuit1<
+    C1,
+    C2,
+    CommittedInstance<C1>,
+    CommittedInstance<C1>,
+    DeciderWitness<C1>,
+    R1CS<CF1<C1>>,
+    R1CSMatricesVar<CF1<C1>, FpVar<CF1<C1>>>,
+    DeciderOvaGadget,
+>;
+
+impl<
+        C1: Curve,
+        C2: Curve,
+        FC: FCircuit<C1::ScalarField>,
+        CS1: CommitmentScheme<C1, H>,
+        CS2: CommitmentScheme<C2, H>,
+        const H: bool,
+    > TryFrom<Ova<C1, C2, FC, CS1, CS2, H>> for DeciderCircuit1<C1, C2>
+{
+    type Error = Error;
+
+    fn try_from(ova: Ova<C1, C2, FC, CS1, CS2, H>) -> Result<Self, Error> {
+        let mut transcript = PoseidonSponge::<C1::ScalarField>::new(&ova.poseidon_config);
+        // pp_hash is absorbed to transcript at the NIFS::prove call
+
+        // compute the U_{i+1}, W_{i+1}
+        let (W_i1, U_i1, _, r_bits) = NIFS::<C1, CS1, PoseidonSponge<C1::ScalarField>, H>::prove(
+            &ova.cs_pp,
+            &ova.r1cs.clone(),
+            &mut transcript,
+            ova.pp_hash,
+            &ova.W_i,
+            &ova.U_i,
+            &ova.w_i,
+            &ova.u_i,
+        )?;
+        let r_Fr = C1::ScalarField::from_bigint(BigInteger::from_bits_le(&r_bits))
+            .ok_or(Error::OutOfBounds)?;
+
+        let W_i = DeciderWitness::from_running(&ova.r1cs, &ova.W_i, &ova.U_i)?;
+        let w_i = DeciderWitness {
+            WE: [
+                ova.w_i.w.clone(),
+                compute_T(&ova.r1cs, &ova.W_i, &ova.U_i, &ova.w_i, &ova.u_i.x)?,
+            ]
+            .concat(),
+            rW: ova.w_i.rW,
+        };
+        let W_i1 = DeciderWitness::from_running(&ova.r1cs, &W_i1, &U_i1)?;
+
+        // compute the KZG challenges used as inputs in the circuit
+        let kzg_challenges = KZGChallengesGadget::get_challenges_native(&mut transcript, &U_i1);
+
+        // get KZG evals
+        let kzg_evaluations = W_i1
+            .get_openings()
+            .iter()
+            .zip(&kzg_challenges)
+            .map(|((v, _), &c)| EvalGadget::evaluate_native(v, c))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            _avar: PhantomData,
+            arith: ova.r1cs,
+            poseidon_config: ova.poseidon_config,
+            pp_hash: ova.pp_hash,
+            i: ova.i,
+            z_0: ova.z_0,
+            z_i: ova.z_i,
+            U_i: ova.U_i,
+            W_i,
+            u_i: ova.u_i,
+            w_i,
+            U_i1,
+            W_i1,
+            proof: (),
+            randomness: r_Fr,
+            cf_U_i: ova.cf_U_i,
+            kzg_challenges,
+            kzg_evaluations,
+        })
+    }
+}
+
+/// Circuit that implements part of the in-circuit checks needed for the offchain verification over
+/// the Curve1's BaseField (=Curve2's ScalarField).
+pub type DeciderCircuit2<C2> = GenericOffchainDeciderCircuit2<C2>;
+
+impl<
+        C1: Curve,
+        C2: Curve,
+        FC: FCircuit<C1::ScalarField>,
+        CS1: CommitmentScheme<C1, H>,
+        CS2: CommitmentScheme<C2, H>,
+        const H: bool,
+    > TryFrom<Ova<C1, C2, FC, CS1, CS2, H>> for DeciderCircuit2<C2>
+{
+    type Error = Error;
+
+    fn try_from(ova: Ova<C1, C2, FC, CS1, CS2, H>) -> Result<Self, Error> {
+        // compute the Commitment Scheme challenges of the CycleFold instance commitments, used as
+        // inputs in the circuit
+        let poseidon_config = poseidon_canonical_config::<C2::ScalarField>();
+        let mut transcript = PoseidonSponge::<C2::ScalarField>::new(&poseidon_config);
+        let pp_hash_Fq =
+            C2::ScalarField::from_le_bytes_mod_order(&ova.pp_hash.into_bigint().to_bytes_le());
+        transcript.absorb(&pp_hash_Fq);
+
+        // compute the KZG challenges used as inputs in the circuit
+        let kzg_challenges =
+            KZGChallengesGadget::get_challenges_native(&mut transcript, &ova.cf_U_i);
+
+        // get KZG evals
+        let kzg_evaluations = ova
+            .cf_W_i
+            .get_openings()
+            .iter()
+            .zip(&kzg_challenges)
+            .map(|((v, _), &c)| EvalGadget::evaluate_native(v, c))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            cf_arith: ova.cf_r1cs,
+            poseidon_config,
+            pp_hash: pp_hash_Fq,
+            cf_U_i: ova.cf_U_i,
+            cf_W_i: ova.cf_W_i,
+            kzg_challenges,
+            kzg_evaluations,
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use ark_pallas::{Fq, Fr, Projective};
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+    use ark_vesta::Projective as Projective2;
+
+    use super::*;
+    use crate::commitment::pedersen::Pedersen;
+    use crate::folding::nova::PreprocessorParam;
+    use crate::frontend::utils::CubicFCircuit;
+    use crate::transcript::poseidon::poseidon_canonical_config;
+    use crate::FoldingScheme;
+
+    #[test]
+    fn test_decider_circuits() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let z_0 = vec![Fr::from(3_u32)];
+
+        type O = Ova<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >;
+
+        let prep_param = PreprocessorParam::<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >::new(poseidon_config, F_circuit);
+        let ova_params = O::preprocess(&mut rng, &prep_param)?;
+
+        // generate an Ova instance and do two steps of it, so that the running instance is not
+        // the dummy one
+        let mut ova = O::init(&ova_params, F_circuit, z_0.clone())?;
+        ova.prove_step(&mut rng, (), None)?;
+        ova.prove_step(&mut rng, (), None)?;
+        // verify the IVC
+        let ivc_proof = ova.ivc_proof();
+        O::verify(ova_params.1, ivc_proof)?;
+
+        // load the DeciderCircuit 1 & 2 from the Ova instance
+        let decider_circuit1 = DeciderCircuit1::<Projective, Projective2>::try_from(ova.clone())?;
+        // the running instance obtained in the decider satisfies the relaxed R1CS
+        decider_circuit1
+            .arith
+            .check_relation(&decider_circuit1.W_i1, &decider_circuit1.U_i1)?;
+        let decider_circuit2 = DeciderCircuit2::<Projective2>::try_from(ova)?;
+
+        // generate the constraints of both circuits and check that are satisfied by the inputs
+        let cs1 = ConstraintSystem::<Fr>::new_ref();
+        decider_circuit1.generate_constraints(cs1.clone())?;
+        assert!(cs1.is_satisfied()?);
+        let cs2 = ConstraintSystem::<Fq>::new_ref();
+        decider_circuit2.generate_constraints(cs2.clone())?;
+        assert!(cs2.is_satisfied()?);
+        Ok(())
+    }
+}
@@ -0,0 +1,146 @@
+/// Implements the IVC scheme obtained by using the [Ova](https://hackmd.io/V4838nnlRKal9ZiTHiGYzw)
+/// NIFS inside the Nova's IVC construction, together with
+/// [CycleFold](https://eprint.iacr.org/2023/1192.pdf).
+///
+/// Unlike Nova, Ova does not commit to the cross term `T` separately, but instead the incoming
+/// instance commits to the witness `w` concatenated with the cross term `t` (computed against the
+/// running instance it is going to be folded with), and the running instance commits to `w`
+/// concatenated with the error term `e`. Hence at each step only one commitment needs to be
+/// computed, and only one scalar multiplication is delegated to the CycleFold circuit.
+///
+/// The IVC and its AugmentedFCircuit are shared with Nova (see
+/// [`crate::folding::nova::GenericNova`]), and the structure of the Ova code is the following:
+/// - NIFS implementation for Ova and its gadget (nova/nifs/ova.rs, nova/nifs/ova_circuits.rs)
+/// - offchain Decider (decider.rs) and its circuits (decider_circuits.rs)
+use ark_crypto_primitives::sponge::poseidon::PoseidonSponge;
+
+use crate::folding::{
+    circuits::CF1,
+    nova::{
+        nifs::ova::{CommittedInstance, Witness, NIFS},
+        GenericNova,
+    },
+};
+
+// offchain decider
+pub mod decider;
+pub mod decider_circuits;
+
+/// IVCProof of Ova+CycleFold, which has the same structure as Nova's one, but containing Ova's
+/// witnesses and committed instances.
+pub type IVCProof<C1, C2> =
+    crate::folding::nova::IVCProof<C1, C2, Witness<C1>, CommittedInstance<C1>>;
+
+/// Implements Ova+CycleFold's IVC, following the FoldingScheme trait.
+/// The `H` const generic specifies whether the homorphic commitment scheme is blinding
+pub type Ova<C1, C2, FC, CS1, CS2, const H: bool = false> =
+    GenericNova<C1, C2, FC, CS1, CS2, NIFS<C1, CS1, PoseidonSponge<CF1<C1>>, H>, H>;
+
+#[cfg(test)]
+pub mod tests {
+    use crate::commitment::kzg::KZG;
+    use ark_bn254::{Bn254, Fr, G1Projective as Projective};
+    use ark_grumpkin::Projective as Projective2;
+
+    use ark_crypto_primitives::sponge::poseidon::PoseidonConfig;
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+    use super::*;
+    use crate::commitment::{pedersen::Pedersen, CommitmentScheme};
+    use crate::folding::nova::{nifs::ova::compute_E, PreprocessorParam};
+    use crate::frontend::{utils::CubicFCircuit, FCircuit};
+    use crate::transcript::poseidon::poseidon_canonical_config;
+    use crate::{Error, FoldingScheme};
+
+    /// This test tests the Ova+CycleFold IVC, and by consequence it is also testing the
+    /// AugmentedFCircuit
+    #[test]
+    fn test_ivc() -> Result<(), Error> {
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+
+        // run the test using Pedersen commitments on both sides of the curve cycle
+        test_ivc_opt::<Pedersen<Projective>, Pedersen<Projective2>, false>(
+            poseidon_config.clone(),
+            F_circuit,
+            3,
+        )?;
+
+        test_ivc_opt::<Pedersen<Projective, true>, Pedersen<Projective2, true>, true>(
+            poseidon_config.clone(),
+            F_circuit,
+            3,
+        )?;
+
+        // run the test using KZG for the commitments on the main curve, and Pedersen for the
+        // commitments on the secondary curve
+        test_ivc_opt::<KZG<Bn254>, Pedersen<Projective2>, false>(poseidon_config, F_circuit, 3)?;
+        Ok(())
+    }
+
+    // test_ivc allowing to choose the CommitmentSchemes
+    fn test_ivc_opt<
+        CS1: CommitmentScheme<Projective, H>,
+        CS2: CommitmentScheme<Projective2, H>,
+        const H: bool,
+    >(
+        poseidon_config: PoseidonConfig<Fr>,
+        F_circuit: CubicFCircuit<Fr>,
+        num_steps: usize,
+    ) -> Result<(), Error> {
+        type O<CS1, CS2, const H: bool> =
+            Ova<Projective, Projective2, CubicFCircuit<Fr>, CS1, CS2, H>;
+
+        let mut rng = ark_std::test_rng();
+
+        let prep_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        let ova_params = O::<CS1, CS2, H>::preprocess(&mut rng, &prep_param)?;
+
+        let z_0 = vec![Fr::from(3_u32)];
+        let mut ova = O::<CS1, CS2, H>::init(&ova_params, F_circuit, z_0.clone())?;
+
+        for _ in 0..num_steps {
+            ova.prove_step(&mut rng, (), None)?;
+        }
+        assert_eq!(Fr::from(num_steps as u32), ova.i);
+
+        // check that the running instance commits to the witness concatenated with its error term
+        let z: Vec<Fr> = [&[ova.U_i.u][..], &ova.U_i.x, &ova.W_i.w].concat();
+        let e = compute_E::<Projective>(&ova.r1cs, &z, ova.U_i.u)?;
+        assert_eq!(
+            CS1::commit(&ova.cs_pp, &[ova.W_i.w.clone(), e].concat(), &ova.W_i.rW)?,
+            ova.U_i.cmWE
+        );
+
+        // serialize the Ova Verifier params
+        let mut ova_vp_serialized = vec![];
+        ova_params.1.serialize_compressed(&mut ova_vp_serialized)?;
+        // deserialize the Ova Verifier params
+        let ova_vp_deserialized = O::<CS1, CS2, H>::vp_deserialize_with_mode(
+            &mut ova_vp_serialized.as_slice(),
+            ark_serialize::Compress::Yes,
+            ark_serialize::Validate::Yes,
+            (), // fcircuit_params
+        )?;
+
+        let ivc_proof = ova.ivc_proof();
+
+        // serialize IVCProof
+        let mut ivc_proof_serialized = vec![];
+        ivc_proof.serialize_compressed(&mut ivc_proof_serialized)?;
+        // deserialize IVCProof
+        let ivc_proof_deserialized = IVCProof::<Projective, Projective2>::deserialize_compressed(
+            ivc_proof_serialized.as_slice(),
+        )?;
+
+        // verify the deserialized IVCProof with the deserialized VerifierParams
+        O::<CS1, CS2, H>::verify(ova_vp_deserialized, ivc_proof_deserialized.clone())?;
+
+        // restore the IVC from the IVCProof and continue folding
+        let mut ova = O::<CS1, CS2, H>::from_ivc_proof(ivc_proof_deserialized, (), ova_params)?;
+        ova.prove_step(&mut rng, (), None)?;
+        assert_eq!(Fr::from(num_steps as u32 + 1), ova.i);
+        Ok(())
+    }
+}
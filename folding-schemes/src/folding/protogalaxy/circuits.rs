@@ -1,8 +1,4 @@
-use ark_crypto_primitives::sponge::{
-    constraints::CryptographicSpongeVar,
-    poseidon::{constraints::PoseidonSpongeVar, PoseidonConfig, PoseidonSponge},
-    CryptographicSponge,
-};
+use ark_crypto_primitives::sponge::CryptographicSponge;
 use ark_ff::PrimeField;
 use ark_poly::{univariate::DensePolynomial, EvaluationDomain, GeneralEvaluationDomain};
 use ark_r1cs_std::{
@@ -16,7 +12,7 @@ use ark_r1cs_std::{
     R1CSVar,
 };
 use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
-use ark_std::{fmt::Debug, One, Zero};
+use ark_std::{fmt::Debug, marker::PhantomData, One, Zero};
 
 use super::{
     folding::lagrange_polys,
@@ -36,7 +32,7 @@ use crate::{
         traits::{CommittedInstanceVarOps, Dummy},
     },
     frontend::FCircuit,
-    transcript::{AbsorbNonNativeGadget, TranscriptVar},
+    transcript::{AbsorbNonNativeGadget, Transcript, TranscriptVar},
     utils::gadgets::VectorGadget,
     Curve,
 };
@@ -175,7 +171,7 @@ impl AugmentationGadget {
         C2: Curve,
         S: CryptographicSponge,
     >(
-        transcript: &mut PoseidonSpongeVar<CF1<C1>>,
+        transcript: &mut impl TranscriptVar<CF1<C1>, S>,
         pp_hash: FpVar<CF1<C1>>,
         mut cf_U: CycleFoldCommittedInstanceVar<C2>,
         cf_u_cmWs: Vec<C2::Var>,
@@ -230,9 +226,18 @@ impl AugmentationGadget {
 /// Furthermore, to reduce circuit size over `C2`, we implement the constraints
 /// defined in [CycleFold](https://eprint.iacr.org/2023/1192.pdf). These extra
 /// constraints verify the correct folding of CycleFold instances.
+///
+/// The sponge `S` (and its in-circuit counterpart `T`) is used both for the
+/// hashes of the instances and for the challenges of the folding verifiers.
 #[derive(Debug, Clone)]
-pub struct AugmentedFCircuit<C1: Curve, C2: Curve, FC: FCircuit<CF1<C1>>> {
-    pub(super) poseidon_config: PoseidonConfig<CF1<C1>>,
+pub struct AugmentedFCircuit<
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<CF1<C1>>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
+> {
+    pub(super) poseidon_config: S::Config,
     pub(super) pp_hash: CF1<C1>,
     pub(super) i: CF1<C1>,
     pub(super) i_usize: usize,
@@ -241,6 +246,8 @@ pub struct AugmentedFCircuit<C1: Curve, C2: Curve, FC: FCircuit<CF1<C1>>> {
     pub(super) external_inputs: FC::ExternalInputs,
     pub(super) F: FC, // F circuit
     pub(super) u_i_phi: C1,
+    // other incoming instances to be folded together with `u_i`, used when `k > 1`
+    pub(super) us: Vec<CommittedInstance<C1, false>>,
     pub(super) U_i: CommittedInstance<C1, true>,
     pub(super) U_i1_phi: C1,
     pub(super) F_coeffs: Vec<CF1<C1>>,
@@ -248,21 +255,23 @@ pub struct AugmentedFCircuit<C1: Curve, C2: Curve, FC: FCircuit<CF1<C1>>> {
 
     pub(super) phi_stars: Vec<C1>,
 
-    pub(super) cf1_u_i_cmW: C2,                        // input
-    pub(super) cf2_u_i_cmW: C2,                        // input
+    // one CycleFold instance for each of the `k + 1` scalar multiplications
+    pub(super) cf_u_i_cmWs: Vec<C2>,                   // input
     pub(super) cf_U_i: CycleFoldCommittedInstance<C2>, // input
-    pub(super) cf1_cmT: C2,
-    pub(super) cf2_cmT: C2,
+    pub(super) cf_cmTs: Vec<C2>,
+    pub(super) _s: PhantomData<S>,
+    pub(super) _t: PhantomData<T>,
 }
 
-impl<C1: Curve, C2: Curve, FC: FCircuit<CF1<C1>>> AugmentedFCircuit<C1, C2, FC> {
-    pub fn empty(
-        poseidon_config: &PoseidonConfig<CF1<C1>>,
-        F_circuit: FC,
-        t: usize,
-        d: usize,
-        k: usize,
-    ) -> Self {
+impl<C1, C2, FC, S, T> AugmentedFCircuit<C1, C2, FC, S, T>
+where
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<CF1<C1>>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
+{
+    pub fn empty(poseidon_config: &S::Config, F_circuit: FC, t: usize, d: usize, k: usize) -> Self {
         let u_dummy = CommittedInstance::dummy((2, t));
         let cf_u_dummy =
             CycleFoldCommittedInstance::dummy(ProtoGalaxyCycleFoldConfig::<C1>::IO_LEN);
@@ -276,6 +285,7 @@ impl<C1: Curve, C2: Curve, FC: FCircuit<CF1<C1>>> AugmentedFCircuit<C1, C2, FC>
             z_i: vec![CF1::<C1>::zero(); F_circuit.state_len()],
             external_inputs: FC::ExternalInputs::default(),
             u_i_phi: C1::zero(),
+            us: vec![CommittedInstance::dummy((2, 0)); k - 1],
             U_i: u_dummy,
             U_i1_phi: C1::zero(),
             F_coeffs: vec![CF1::<C1>::zero(); t],
@@ -283,20 +293,22 @@ impl<C1: Curve, C2: Curve, FC: FCircuit<CF1<C1>>> AugmentedFCircuit<C1, C2, FC>
             phi_stars: vec![C1::zero(); k],
             F: F_circuit,
             // cyclefold values
-            cf1_u_i_cmW: C2::zero(),
-            cf2_u_i_cmW: C2::zero(),
+            cf_u_i_cmWs: vec![C2::zero(); k + 1],
             cf_U_i: cf_u_dummy,
-            cf1_cmT: C2::zero(),
-            cf2_cmT: C2::zero(),
+            cf_cmTs: vec![C2::zero(); k + 1],
+            _s: PhantomData,
+            _t: PhantomData,
         }
     }
 }
 
-impl<C1, C2, FC> AugmentedFCircuit<C1, C2, FC>
+impl<C1, C2, FC, S, T> AugmentedFCircuit<C1, C2, FC, S, T>
 where
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
     C2: Curve,
     FC: FCircuit<CF1<C1>>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
 {
     pub fn compute_next_state(
         self,
@@ -312,6 +324,7 @@ where
         let u_dummy = CommittedInstance::<C1, true>::dummy((2, self.U_i.betas.len()));
         let U_i = CommittedInstanceVar::<C1, true>::new_witness(cs.clone(), || Ok(self.U_i))?;
         let u_i_phi = NonNativeAffineVar::new_witness(cs.clone(), || Ok(self.u_i_phi))?;
+        let us = Vec::<CommittedInstanceVar<C1, false>>::new_witness(cs.clone(), || Ok(self.us))?;
         let U_i1_phi = NonNativeAffineVar::new_witness(cs.clone(), || Ok(self.U_i1_phi))?;
         let phi_stars =
             Vec::<NonNativeAffineVar<C1>>::new_witness(cs.clone(), || Ok(self.phi_stars))?;
@@ -320,14 +333,14 @@ where
             CycleFoldCommittedInstance::dummy(ProtoGalaxyCycleFoldConfig::<C1>::IO_LEN);
         let cf_U_i =
             CycleFoldCommittedInstanceVar::<C2>::new_witness(cs.clone(), || Ok(self.cf_U_i))?;
-        let cf1_cmT = C2::Var::new_witness(cs.clone(), || Ok(self.cf1_cmT))?;
-        let cf2_cmT = C2::Var::new_witness(cs.clone(), || Ok(self.cf2_cmT))?;
+        let cf_u_i_cmWs = Vec::<C2::Var>::new_witness(cs.clone(), || Ok(self.cf_u_i_cmWs))?;
+        let cf_cmTs = Vec::<C2::Var>::new_witness(cs.clone(), || Ok(self.cf_cmTs))?;
 
         let F_coeffs = Vec::new_witness(cs.clone(), || Ok(self.F_coeffs))?;
         let K_coeffs = Vec::new_witness(cs.clone(), || Ok(self.K_coeffs))?;
 
         // `sponge` is for digest computation.
-        let sponge = PoseidonSpongeVar::<C1::ScalarField>::new(cs.clone(), &self.poseidon_config);
+        let sponge = T::new(cs.clone(), &self.poseidon_config);
         // `transcript` is for challenge generation.
         let mut transcript = sponge.clone();
 
@@ -342,11 +355,15 @@ where
 
         // P.2. Prepare incoming primary instances
         // P.3. Fold incoming primary instances into the running instance
+        let (u_phis, u_xs): (Vec<_>, Vec<_>) = [(u_i_phi, vec![u_i_x, cf_u_i_x])]
+            .into_iter()
+            .chain(us.into_iter().map(|u| (u.phi, u.x)))
+            .unzip();
         let (U_i1, r) = AugmentationGadget::prepare_and_fold_primary(
             &mut transcript,
             U_i.clone(),
-            vec![u_i_phi.clone()],
-            vec![vec![u_i_x, cf_u_i_x]],
+            u_phis.clone(),
+            u_xs,
             U_i1_phi,
             F_coeffs,
             K_coeffs,
@@ -388,64 +405,50 @@ where
         FpVar::new_input(cs.clone(), || x.value())?.enforce_equal(&x)?;
 
         // CycleFold part
-        // C.1. Compute cf1_u_i.x and cf2_u_i.x
-        let mut r0_bits = r[0].to_bits_le()?;
-        let mut r1_bits = r[1].to_bits_le()?;
-        r0_bits.resize(C1::ScalarField::MODULUS_BIT_SIZE as usize, Boolean::FALSE);
-        r1_bits.resize(C1::ScalarField::MODULUS_BIT_SIZE as usize, Boolean::FALSE);
-        let cf1_x = [
-            r0_bits
-                .chunks(C1::BaseField::MODULUS_BIT_SIZE as usize - 1)
-                .map(|bits| {
-                    let mut bits = bits.to_vec();
-                    bits.resize(C1::BaseField::MODULUS_BIT_SIZE as usize, Boolean::FALSE);
-                    NonNativeUintVar::from(&bits)
-                })
-                .collect::<Vec<_>>(),
-            vec![
-                NonNativeUintVar::new_constant(cs.clone(), C1::BaseField::zero())?,
-                NonNativeUintVar::new_constant(cs.clone(), C1::BaseField::zero())?,
-                U_i.phi.x.clone(),
-                U_i.phi.y.clone(),
-                phi_stars[0].x.clone(),
-                phi_stars[0].y.clone(),
-            ],
-        ]
-        .concat();
-        let cf2_x = [
-            r1_bits
-                .chunks(C1::BaseField::MODULUS_BIT_SIZE as usize - 1)
-                .map(|bits| {
-                    let mut bits = bits.to_vec();
-                    bits.resize(C1::BaseField::MODULUS_BIT_SIZE as usize, Boolean::FALSE);
-                    NonNativeUintVar::from(&bits)
-                })
-                .collect::<Vec<_>>(),
-            vec![
-                phi_stars[0].x.clone(),
-                phi_stars[0].y.clone(),
-                u_i_phi.x.clone(),
-                u_i_phi.y.clone(),
-                U_i1.phi.x.clone(),
-                U_i1.phi.y.clone(),
-            ],
-        ]
-        .concat();
+        // C.1. Compute cf_u_i.x for each of the `k + 1` CycleFold instances,
+        // where the j-th instance checks that
+        // `phi_stars[j - 1] + u_phis[j - 1] * r[j] == phi_stars[j]`, with
+        // `phi_stars[-1] = 0`, `u_phis[-1] = U_i.phi` and
+        // `phi_stars[k] = U_i1.phi`.
+        let k = u_phis.len();
+        let cf_xs = (0..=k)
+            .map(|j| {
+                let mut r_bits = r[j].to_bits_le()?;
+                r_bits.resize(C1::ScalarField::MODULUS_BIT_SIZE as usize, Boolean::FALSE);
+                let (p0, p1) = if j == 0 {
+                    (
+                        NonNativeAffineVar::new_constant(cs.clone(), C1::zero())?,
+                        U_i.phi.clone(),
+                    )
+                } else {
+                    (phi_stars[j - 1].clone(), u_phis[j - 1].clone())
+                };
+                let p_out = if j < k { &phi_stars[j] } else { &U_i1.phi };
+                Ok([
+                    r_bits
+                        .chunks(C1::BaseField::MODULUS_BIT_SIZE as usize - 1)
+                        .map(|bits| {
+                            let mut bits = bits.to_vec();
+                            bits.resize(C1::BaseField::MODULUS_BIT_SIZE as usize, Boolean::FALSE);
+                            NonNativeUintVar::from(&bits)
+                        })
+                        .collect::<Vec<_>>(),
+                    vec![p0.x, p0.y, p1.x, p1.y, p_out.x.clone(), p_out.y.clone()],
+                ]
+                .concat())
+            })
+            .collect::<Result<Vec<_>, SynthesisError>>()?;
 
         // C.2. Prepare incoming CycleFold instances
         // C.3. Fold incoming CycleFold instances into the running instance
-        let cf_U_i1 =
-            AugmentationGadget::prepare_and_fold_cyclefold::<C1, C2, PoseidonSponge<CF1<C1>>>(
-                &mut transcript,
-                pp_hash.clone(),
-                cf_U_i,
-                vec![
-                    C2::Var::new_witness(cs.clone(), || Ok(self.cf1_u_i_cmW))?,
-                    C2::Var::new_witness(cs.clone(), || Ok(self.cf2_u_i_cmW))?,
-                ],
-                vec![cf1_x, cf2_x],
-                vec![cf1_cmT, cf2_cmT],
-            )?;
+        let cf_U_i1 = AugmentationGadget::prepare_and_fold_cyclefold::<C1, C2, S>(
+            &mut transcript,
+            pp_hash.clone(),
+            cf_U_i,
+            cf_u_i_cmWs,
+            cf_xs,
+            cf_cmTs,
+        )?;
 
         // Back to Primary Part
         // P.4.b compute and check the second output of F'
@@ -471,11 +474,13 @@ where
     }
 }
 
-impl<C1, C2, FC> ConstraintSynthesizer<CF1<C1>> for AugmentedFCircuit<C1, C2, FC>
+impl<C1, C2, FC, S, T> ConstraintSynthesizer<CF1<C1>> for AugmentedFCircuit<C1, C2, FC, S, T>
 where
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
     C2: Curve,
     FC: FCircuit<CF1<C1>>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
 {
     fn generate_constraints(self, cs: ConstraintSystemRef<CF1<C1>>) -> Result<(), SynthesisError> {
         self.compute_next_state(cs).map(|_| ())
@@ -492,6 +497,10 @@ mod tests {
         transcript::poseidon::poseidon_canonical_config,
         Error,
     };
+    use ark_crypto_primitives::sponge::{
+        constraints::CryptographicSpongeVar,
+        poseidon::{constraints::PoseidonSpongeVar, PoseidonSponge},
+    };
 
     use ark_bn254::{Fr, G1Projective as Projective};
     use ark_relations::r1cs::ConstraintSystem;
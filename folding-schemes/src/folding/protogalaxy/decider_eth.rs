@@ -2,6 +2,9 @@
 /// the Decider from decider.rs file will be more efficient.
 /// More details can be found at the documentation page:
 /// https://privacy-scaling-explorations.github.io/sonobe-docs/design/nova-decider-onchain.html
+use ark_bn254::Bn254;
+use ark_crypto_primitives::sponge::CryptographicSponge;
+use ark_groth16::Groth16;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_snark::SNARK;
 use ark_std::{
@@ -11,16 +14,23 @@ use ark_std::{
     One, Zero,
 };
 
+use super::constants::{INCOMING, RUNNING};
 pub use super::decider_eth_circuit::DeciderEthCircuit;
 use super::decider_eth_circuit::DeciderProtoGalaxyGadget;
-use super::ProtoGalaxy;
+use super::{CommittedInstance, ProtoGalaxy};
 use crate::arith::Arith;
 use crate::folding::traits::{InputizeNonNative, WitnessOps};
 use crate::folding::{circuits::decider::DeciderEnabledNIFS, traits::Dummy};
 use crate::frontend::FCircuit;
+use crate::transcript::keccak::KeccakTranscript;
+use crate::utils::eth::ToEth;
 use crate::Error;
 use crate::{
-    commitment::{kzg::Proof as KZGProof, pedersen::Params as PedersenParams, CommitmentScheme},
+    commitment::{
+        kzg::{Proof as KZGProof, KZG},
+        pedersen::Params as PedersenParams,
+        CommitmentScheme,
+    },
     Curve,
 };
 use crate::{Decider as DeciderTrait, FoldingScheme};
@@ -53,8 +63,11 @@ where
 }
 
 /// Onchain Decider, for ethereum use cases
+///
+/// `K` is the number of incoming instances that the ProtoGalaxy IVC folds at
+/// each step.
 #[derive(Clone, Debug)]
-pub struct Decider<C1, C2, FC, CS1, CS2, S, FS> {
+pub struct Decider<C1, C2, FC, CS1, CS2, S, FS, const K: usize = 1> {
     _c1: PhantomData<C1>,
     _c2: PhantomData<C2>,
     _fc: PhantomData<FC>,
@@ -64,8 +77,8 @@ pub struct Decider<C1, C2, FC, CS1, CS2, S, FS> {
     _fs: PhantomData<FS>,
 }
 
-impl<C1, C2, FC, CS1, CS2, S, FS> DeciderTrait<C1, C2, FC, FS>
-    for Decider<C1, C2, FC, CS1, CS2, S, FS>
+impl<C1, C2, FC, CS1, CS2, S, FS, const K: usize> DeciderTrait<C1, C2, FC, FS>
+    for Decider<C1, C2, FC, CS1, CS2, S, FS, K>
 where
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
     C2: Curve,
@@ -82,7 +95,7 @@ where
     S: SNARK<C1::ScalarField>,
     FS: FoldingScheme<C1, C2, FC>,
     // constrain FS into ProtoGalaxy, since this is a Decider specifically for ProtoGalaxy
-    ProtoGalaxy<C1, C2, FC, CS1, CS2>: From<FS>,
+    ProtoGalaxy<C1, C2, FC, CS1, CS2, K>: From<FS>,
     crate::folding::protogalaxy::ProverParams<C1, C2, CS1, CS2>:
         From<<FS as FoldingScheme<C1, C2, FC>>::ProverParam>,
     crate::folding::protogalaxy::VerifierParams<C1, C2, CS1, CS2>:
@@ -100,23 +113,20 @@ where
         ((pp, vp), state_len): Self::PreprocessorParam,
     ) -> Result<(Self::ProverParam, Self::VerifierParam), Error> {
         // get the FoldingScheme prover & verifier params from ProtoGalaxy
-        let protogalaxy_pp: <ProtoGalaxy<C1, C2, FC, CS1, CS2> as FoldingScheme<
+        let protogalaxy_pp: <ProtoGalaxy<C1, C2, FC, CS1, CS2, K> as FoldingScheme<
             C1,
             C2,
             FC,
         >>::ProverParam = pp.into();
-        let protogalaxy_vp: <ProtoGalaxy<C1, C2, FC, CS1, CS2> as FoldingScheme<
+        let protogalaxy_vp: <ProtoGalaxy<C1, C2, FC, CS1, CS2, K> as FoldingScheme<
             C1,
             C2,
             FC,
         >>::VerifierParam = vp.into();
         let pp_hash = protogalaxy_vp.pp_hash()?;
 
-        // We fix `k`, the number of incoming instances, to 1, because
-        // multi-instances folding is not supported yet.
-        // TODO: Support multi-instances folding and make `k` a constant generic parameter (as in
-        // HyperNova). Tracking issue:
-        // https://github.com/privacy-scaling-explorations/sonobe/issues/82
+        // Regardless of `K`, the decider only folds the last incoming instance
+        // `u_i` into the running instance `U_i`, hence `k` is 1 here.
         let k = 1;
         let d = protogalaxy_vp.r1cs.degree();
         let t = log2(protogalaxy_vp.r1cs.n_constraints()) as usize;
@@ -152,7 +162,9 @@ where
     ) -> Result<Self::Proof, Error> {
         let (snark_pk, cs_pk): (S::ProvingKey, CS1::ProverParams) = pp;
 
-        let circuit = DeciderEthCircuit::<C1, C2>::try_from(ProtoGalaxy::from(folding_scheme))?;
+        let circuit = DeciderEthCircuit::<C1, C2>::try_from(
+            ProtoGalaxy::<C1, C2, FC, CS1, CS2, K>::from(folding_scheme),
+        )?;
 
         let L_X_evals = circuit.randomness.clone();
 
@@ -239,30 +251,55 @@ where
             return Err(Error::SNARKVerificationFail);
         }
 
-        // 7.3. Verify the KZG proofs
-        for ((cm, &c), pi) in U_final_commitments
-            .iter()
-            .zip(&proof.kzg_challenges)
-            .zip(&proof.kzg_proofs)
-        {
-            // we're at the Ethereum EVM case, so the CS1 is KZG commitments
-            CS1::verify_with_challenge(&cs_vp, c, cm, pi)?;
-        }
+        // 7.3. Verify the KZG proofs, batched into a single pairing check as in the other onchain
+        // deciders
+        // we're at the Ethereum EVM case, so the CS1 is KZG commitments
+        CS1::batch_verify_with_challenges(
+            &cs_vp,
+            &mut KeccakTranscript::new(&()),
+            &proof.kzg_challenges,
+            &U_final_commitments,
+            &proof.kzg_proofs,
+        )?;
 
         Ok(true)
     }
 }
 
+/// Prepares solidity calldata for calling the ProtoGalaxyDecider contract
+#[allow(clippy::too_many_arguments)]
+pub fn prepare_calldata(
+    function_signature_check: [u8; 4],
+    i: ark_bn254::Fr,
+    z_0: Vec<ark_bn254::Fr>,
+    z_i: Vec<ark_bn254::Fr>,
+    running_instance: &CommittedInstance<ark_bn254::G1Projective, RUNNING>,
+    incoming_instance: &CommittedInstance<ark_bn254::G1Projective, INCOMING>,
+    proof: Proof<ark_bn254::G1Projective, KZG<'static, Bn254>, Groth16<Bn254>>,
+) -> Result<Vec<u8>, Error> {
+    Ok([
+        function_signature_check.to_eth(),
+        i.to_eth(),   // i
+        z_0.to_eth(), // z_0
+        z_i.to_eth(), // z_i
+        running_instance.phi.to_eth(),
+        incoming_instance.phi.to_eth(),
+        proof.L_X_evals.to_eth(),           // L_X_evals
+        proof.snark_proof.to_eth(),         // pA, pB, pC
+        proof.kzg_challenges.to_eth(),      // challenge
+        proof.kzg_proofs[0].eval.to_eth(),  // eval
+        proof.kzg_proofs[0].proof.to_eth(), // kzg_proof
+    ]
+    .concat())
+}
+
 #[cfg(test)]
 pub mod tests {
-    use ark_bn254::Bn254;
     use ark_bn254::{Fr, G1Projective as Projective};
-    use ark_groth16::Groth16;
     use ark_grumpkin::Projective as Projective2;
     use std::time::Instant;
 
     use super::*;
-    use crate::commitment::kzg::KZG;
     use crate::commitment::pedersen::Pedersen;
     use crate::folding::protogalaxy::ProverParams;
     use crate::folding::traits::CommittedInstanceOps;
@@ -87,11 +87,12 @@ impl<
         CS1: CommitmentScheme<C1, false>,
         // enforce that the CS2 is Pedersen commitment scheme, since we're at Ethereum's EVM decider
         CS2: CommitmentScheme<C2, false, ProverParams = PedersenParams<C2>>,
-    > TryFrom<ProtoGalaxy<C1, C2, FC, CS1, CS2>> for DeciderEthCircuit<C1, C2>
+        const K: usize,
+    > TryFrom<ProtoGalaxy<C1, C2, FC, CS1, CS2, K>> for DeciderEthCircuit<C1, C2>
 {
     type Error = Error;
 
-    fn try_from(protogalaxy: ProtoGalaxy<C1, C2, FC, CS1, CS2>) -> Result<Self, Error> {
+    fn try_from(protogalaxy: ProtoGalaxy<C1, C2, FC, CS1, CS2, K>) -> Result<Self, Error> {
         let mut transcript = PoseidonSponge::<C1::ScalarField>::new(&protogalaxy.poseidon_config);
 
         let (U_i1, W_i1, proof, aux) = Folding::prove(
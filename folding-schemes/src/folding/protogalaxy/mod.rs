@@ -1,7 +1,6 @@
 /// Implements the scheme described in [ProtoGalaxy](https://eprint.iacr.org/2023/1106.pdf)
-use ark_crypto_primitives::sponge::{
-    poseidon::{PoseidonConfig, PoseidonSponge},
-    CryptographicSponge,
+use ark_crypto_primitives::sponge::poseidon::{
+    constraints::PoseidonSpongeVar, PoseidonConfig, PoseidonSponge,
 };
 use ark_ff::{BigInteger, PrimeField};
 use ark_r1cs_std::{
@@ -35,18 +34,23 @@ use crate::{
         CF1,
     },
     frontend::{utils::DummyCircuit, FCircuit},
-    transcript::poseidon::poseidon_canonical_config,
+    transcript::{CanonicalConfig, Transcript, TranscriptVar},
     utils::pp_hash,
-    Curve, Error, FoldingScheme,
+    Curve, Error, FoldingScheme, MultiFolding,
 };
 
 pub mod circuits;
 pub mod constants;
+// offchain decider
+pub mod decider;
+pub mod decider_circuits;
+// onchain decider
 pub mod decider_eth;
 pub mod decider_eth_circuit;
 pub mod folding;
 pub mod traits;
 pub(crate) mod utils;
+pub mod zk;
 
 use circuits::AugmentedFCircuit;
 use folding::Folding;
@@ -55,7 +59,14 @@ use super::traits::{
     CommittedInstanceOps, CommittedInstanceVarOps, Dummy, Inputize, WitnessOps, WitnessVarOps,
 };
 
-/// Configuration for ProtoGalaxy's CycleFold circuit
+/// Configuration for ProtoGalaxy's CycleFold circuit.
+///
+/// Note that `N_INPUT_POINTS` does not depend on the number of instances `k`
+/// that are folded at each step. The folded `phi` is a linear combination of
+/// the input `phi`s with the evaluations of the Lagrange polynomials as
+/// coefficients, which are not powers of a single randomness, so we compute it
+/// with `k + 1` CycleFold circuits that are chained through `phi_stars`, each
+/// of them computing `P_0 + r * P_1` (see `ProtoGalaxy::prove_step`).
 pub struct ProtoGalaxyCycleFoldConfig<C: Curve> {
     _c: PhantomData<C>,
 }
@@ -115,6 +126,10 @@ impl<C: Curve, const TYPE: bool> CommittedInstanceOps<C> for CommittedInstance<C
         vec![self.phi]
     }
 
+    fn get_public_inputs(&self) -> &[CF1<C>] {
+        &self.x
+    }
+
     fn is_incoming(&self) -> bool {
         TYPE == INCOMING
     }
@@ -229,14 +244,15 @@ pub struct Witness<F: PrimeField> {
 }
 
 impl<F: PrimeField> Witness<F> {
-    pub fn new(w: Vec<F>) -> Self {
-        // note: at the current version, we don't use the blinding factors and we set them to 0
-        // always.
-        // Tracking issue: https://github.com/privacy-scaling-explorations/sonobe/issues/82
-        Self { w, r_w: F::zero() }
+    /// Creates a new witness, where the blinding factor `r_w` is sampled at
+    /// random if `H` is set (i.e., if the commitment scheme is hiding), and is
+    /// zero otherwise.
+    pub fn new<const H: bool>(w: Vec<F>, mut rng: impl RngCore) -> Self {
+        let r_w = if H { F::rand(&mut rng) } else { F::zero() };
+        Self { w, r_w }
     }
 
-    pub fn commit<CS: CommitmentScheme<C>, C: Curve<ScalarField = F>>(
+    pub fn commit<CS: CommitmentScheme<C, H>, C: Curve<ScalarField = F>, const H: bool>(
         &self,
         params: &CS::ProverParams,
         x: Vec<F>,
@@ -314,26 +330,27 @@ pub enum ProtoGalaxyError {
 
 /// Proving parameters for ProtoGalaxy-based IVC
 #[derive(Debug, Clone)]
-pub struct ProverParams<C1, C2, CS1, CS2>
+pub struct ProverParams<C1, C2, CS1, CS2, const H: bool = false, SC = PoseidonConfig<CF1<C1>>>
 where
     C1: Curve,
     C2: Curve,
-    CS1: CommitmentScheme<C1>,
-    CS2: CommitmentScheme<C2>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
 {
-    /// Poseidon sponge configuration
-    pub poseidon_config: PoseidonConfig<C1::ScalarField>,
+    /// Sponge configuration
+    pub poseidon_config: SC,
     /// Proving parameters of the underlying commitment scheme over C1
     pub cs_params: CS1::ProverParams,
     /// Proving parameters of the underlying commitment scheme over C2
     pub cf_cs_params: CS2::ProverParams,
 }
-impl<C1, C2, CS1, CS2> CanonicalSerialize for ProverParams<C1, C2, CS1, CS2>
+impl<C1, C2, CS1, CS2, const H: bool, SC> CanonicalSerialize
+    for ProverParams<C1, C2, CS1, CS2, H, SC>
 where
     C1: Curve,
     C2: Curve,
-    CS1: CommitmentScheme<C1, false>,
-    CS2: CommitmentScheme<C2, false>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
 {
     fn serialize_with_mode<W: std::io::prelude::Write>(
         &self,
@@ -348,32 +365,29 @@ where
         self.cs_params.serialized_size(compress) + self.cf_cs_params.serialized_size(compress)
     }
 }
-impl<C1, C2, CS1, CS2> Valid for ProverParams<C1, C2, CS1, CS2>
+impl<C1, C2, CS1, CS2, const H: bool, SC> Valid for ProverParams<C1, C2, CS1, CS2, H, SC>
 where
     C1: Curve,
     C2: Curve,
-    CS1: CommitmentScheme<C1>,
-    CS2: CommitmentScheme<C2>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    SC: Valid,
 {
     fn check(&self) -> Result<(), ark_serialize::SerializationError> {
-        self.poseidon_config.full_rounds.check()?;
-        self.poseidon_config.partial_rounds.check()?;
-        self.poseidon_config.alpha.check()?;
-        self.poseidon_config.ark.check()?;
-        self.poseidon_config.mds.check()?;
-        self.poseidon_config.rate.check()?;
-        self.poseidon_config.capacity.check()?;
+        self.poseidon_config.check()?;
         self.cs_params.check()?;
         self.cf_cs_params.check()?;
         Ok(())
     }
 }
-impl<C1, C2, CS1, CS2> CanonicalDeserialize for ProverParams<C1, C2, CS1, CS2>
+impl<C1, C2, CS1, CS2, const H: bool, SC> CanonicalDeserialize
+    for ProverParams<C1, C2, CS1, CS2, H, SC>
 where
     C1: Curve,
     C2: Curve,
-    CS1: CommitmentScheme<C1, false>,
-    CS2: CommitmentScheme<C2, false>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    SC: CanonicalConfig + Valid + Sync,
 {
     fn deserialize_with_mode<R: std::io::prelude::Read>(
         mut reader: R,
@@ -384,7 +398,7 @@ where
         let cf_cs_params =
             CS2::ProverParams::deserialize_with_mode(&mut reader, compress, validate)?;
         Ok(ProverParams {
-            poseidon_config: poseidon_canonical_config::<C1::ScalarField>(),
+            poseidon_config: SC::canonical_config(),
             cs_params,
             cf_cs_params,
         })
@@ -393,15 +407,15 @@ where
 
 /// Verification parameters for ProtoGalaxy-based IVC
 #[derive(Debug, Clone)]
-pub struct VerifierParams<C1, C2, CS1, CS2>
+pub struct VerifierParams<C1, C2, CS1, CS2, const H: bool = false, SC = PoseidonConfig<CF1<C1>>>
 where
     C1: Curve,
     C2: Curve,
-    CS1: CommitmentScheme<C1>,
-    CS2: CommitmentScheme<C2>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
 {
-    /// Poseidon sponge configuration
-    pub poseidon_config: PoseidonConfig<C1::ScalarField>,
+    /// Sponge configuration
+    pub poseidon_config: SC,
     /// R1CS of the Augmented step circuit
     pub r1cs: R1CS<C1::ScalarField>,
     /// R1CS of the CycleFold circuit
@@ -412,25 +426,28 @@ where
     pub cf_cs_vp: CS2::VerifierParams,
 }
 
-impl<C1, C2, CS1, CS2> Valid for VerifierParams<C1, C2, CS1, CS2>
+impl<C1, C2, CS1, CS2, const H: bool, SC> Valid for VerifierParams<C1, C2, CS1, CS2, H, SC>
 where
     C1: Curve,
     C2: Curve,
-    CS1: CommitmentScheme<C1>,
-    CS2: CommitmentScheme<C2>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    SC: Valid,
 {
     fn check(&self) -> Result<(), ark_serialize::SerializationError> {
+        self.poseidon_config.check()?;
         self.cs_vp.check()?;
         self.cf_cs_vp.check()?;
         Ok(())
     }
 }
-impl<C1, C2, CS1, CS2> CanonicalSerialize for VerifierParams<C1, C2, CS1, CS2>
+impl<C1, C2, CS1, CS2, const H: bool, SC> CanonicalSerialize
+    for VerifierParams<C1, C2, CS1, CS2, H, SC>
 where
     C1: Curve,
     C2: Curve,
-    CS1: CommitmentScheme<C1>,
-    CS2: CommitmentScheme<C2>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
 {
     fn serialize_with_mode<W: std::io::prelude::Write>(
         &self,
@@ -446,18 +463,17 @@ where
     }
 }
 
-impl<C1, C2, CS1, CS2> VerifierParams<C1, C2, CS1, CS2>
+impl<C1, C2, CS1, CS2, const H: bool, SC> VerifierParams<C1, C2, CS1, CS2, H, SC>
 where
     C1: Curve,
     C2: Curve,
-    CS1: CommitmentScheme<C1>,
-    CS2: CommitmentScheme<C2>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    SC: CanonicalSerialize,
 {
     /// returns the hash of the public parameters of ProtoGalaxy
     pub fn pp_hash(&self) -> Result<C1::ScalarField, Error> {
-        // TODO: support hiding commitments in ProtoGalaxy. For now, `H` is set to false. Tracking
-        // issue: https://github.com/privacy-scaling-explorations/sonobe/issues/82
-        pp_hash::<C1, C2, CS1, CS2, false>(
+        pp_hash::<C1, C2, CS1, CS2, H>(
             &self.r1cs,
             &self.cf_r1cs,
             &self.cs_vp,
@@ -483,22 +499,43 @@ pub struct IVCProof<C1: Curve, C2: Curve> {
 /// Implements ProtoGalaxy+CycleFold's IVC, described in [ProtoGalaxy] and
 /// [CycleFold], following the FoldingScheme trait
 ///
+/// * `K` - the number of incoming instances folded at each step, including the
+///   IVC's own incoming instance `u_i`. `K + 1` needs to be a power of two.
+/// * `H` - whether the commitment schemes are hiding. When set, the witnesses
+///   carry random blinding factors, and [`zk::RandomizedIVCProof`] can be used
+///   to obtain a zero-knowledge IVC proof.
+/// * `S` - the sponge (Poseidon by default) used for the transcripts and the
+///   hashes of the IVC, and `T` is its in-circuit counterpart, used in the
+///   augmented circuit.
+///
 /// [ProtoGalaxy]: https://eprint.iacr.org/2023/1106.pdf
 /// [CycleFold]: https://eprint.iacr.org/2023/1192.pdf
-#[derive(Clone, Debug)]
-pub struct ProtoGalaxy<C1, C2, FC, CS1, CS2>
-where
+#[derive(Clone)]
+pub struct ProtoGalaxy<
+    C1,
+    C2,
+    FC,
+    CS1,
+    CS2,
+    const K: usize = 1,
+    const H: bool = false,
+    S = PoseidonSponge<CF1<C1>>,
+    T = PoseidonSpongeVar<CF1<C1>>,
+> where
     C1: Curve,
     C2: Curve,
     FC: FCircuit<C1::ScalarField>,
-    CS1: CommitmentScheme<C1>,
-    CS2: CommitmentScheme<C2>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
 {
     /// R1CS of the Augmented Function circuit
     pub r1cs: R1CS<C1::ScalarField>,
     /// R1CS of the CycleFold circuit
     pub cf_r1cs: R1CS<C2::ScalarField>,
-    pub poseidon_config: PoseidonConfig<C1::ScalarField>,
+    /// Sponge configuration
+    pub poseidon_config: S::Config,
     /// CommitmentScheme::ProverParams over C1
     pub cs_params: CS1::ProverParams,
     /// CycleFold CommitmentScheme::ProverParams, over C2
@@ -521,24 +558,58 @@ where
     /// CycleFold running instance
     pub cf_W_i: CycleFoldWitness<C2>,
     pub cf_U_i: CycleFoldCommittedInstance<C2>,
+
+    _s: PhantomData<S>,
+    _t: PhantomData<T>,
+}
+
+impl<C1, C2, FC, CS1, CS2, const K: usize, const H: bool, S, T> Debug
+    for ProtoGalaxy<C1, C2, FC, CS1, CS2, K, H, S, T>
+where
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
+{
+    fn fmt(&self, f: &mut ark_std::fmt::Formatter<'_>) -> ark_std::fmt::Result {
+        f.debug_struct("ProtoGalaxy")
+            .field("r1cs", &self.r1cs)
+            .field("cf_r1cs", &self.cf_r1cs)
+            .field("poseidon_config", &self.poseidon_config)
+            .field("cs_params", &self.cs_params)
+            .field("cf_cs_params", &self.cf_cs_params)
+            .field("F", &self.F)
+            .field("pp_hash", &self.pp_hash)
+            .field("i", &self.i)
+            .field("z_0", &self.z_0)
+            .field("z_i", &self.z_i)
+            .field("w_i", &self.w_i)
+            .field("u_i", &self.u_i)
+            .field("W_i", &self.W_i)
+            .field("U_i", &self.U_i)
+            .field("cf_W_i", &self.cf_W_i)
+            .field("cf_U_i", &self.cf_U_i)
+            .finish()
+    }
 }
 
-impl<C1, C2, FC, CS1, CS2> ProtoGalaxy<C1, C2, FC, CS1, CS2>
+impl<C1, C2, FC, CS1, CS2, const K: usize, const H: bool, S, T>
+    ProtoGalaxy<C1, C2, FC, CS1, CS2, K, H, S, T>
 where
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
     C2: Curve,
     FC: FCircuit<C1::ScalarField>,
-    CS1: CommitmentScheme<C1>,
-    CS2: CommitmentScheme<C2>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
 {
     /// This method computes the parameter `t` in ProtoGalaxy for folding `F'`,
     /// the augmented circuit of `F`
-    fn compute_t(
-        poseidon_config: &PoseidonConfig<CF1<C1>>,
-        F: &FC,
-        d: usize,
-        k: usize,
-    ) -> Result<usize, Error> {
+    fn compute_t(poseidon_config: &S::Config, F: &FC, d: usize, k: usize) -> Result<usize, Error> {
         // In ProtoGalaxy, prover and verifier are parameterized by `t = log(n)`
         // where `n` is the number of constraints in the circuit (known as the
         // mapping `f` in the paper).
@@ -577,7 +648,7 @@ where
 
         // Compute `augmentation_constraints`, the size of `F'` without `F`.
         let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
-        AugmentedFCircuit::<C1, C2, DummyCircuit>::empty(
+        AugmentedFCircuit::<C1, C2, DummyCircuit, S, T>::empty(
             poseidon_config,
             dummy_circuit.clone(),
             1,
@@ -600,7 +671,7 @@ where
 
         for t in t_lower_bound..=t_upper_bound {
             let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
-            AugmentedFCircuit::<C1, C2, DummyCircuit>::empty(
+            AugmentedFCircuit::<C1, C2, DummyCircuit, S, T>::empty(
                 poseidon_config,
                 dummy_circuit.clone(),
                 t,
@@ -617,21 +688,95 @@ where
     }
 }
 
-impl<C1, C2, FC, CS1, CS2> FoldingScheme<C1, C2, FC> for ProtoGalaxy<C1, C2, FC, CS1, CS2>
+impl<C1, C2, FC, CS1, CS2, const K: usize, const H: bool, S, T> MultiFolding<C1, C2, FC>
+    for ProtoGalaxy<C1, C2, FC, CS1, CS2, K, H, S, T>
+where
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
+{
+    type RunningInstance = (CommittedInstance<C1, true>, Witness<C1::ScalarField>);
+    type IncomingInstance = (CommittedInstance<C1, false>, Witness<C1::ScalarField>);
+    type MultiInstance = Vec<Self::IncomingInstance>;
+
+    /// ProtoGalaxy folds `K` incoming instances into a single running instance
+    /// at each step, so there is no support for folding extra running
+    /// instances.
+    fn new_running_instance(
+        &self,
+        _rng: impl RngCore,
+        _state: Vec<C1::ScalarField>,
+        _external_inputs: FC::ExternalInputs,
+    ) -> Result<Self::RunningInstance, Error> {
+        Err(Error::NotSupported(
+            "ProtoGalaxy only folds a single running instance at each step".to_string(),
+        ))
+    }
+
+    /// Creates a new incoming instance for the given state, which can be folded
+    /// together with `u_i` by passing it to `prove_step` as part of the
+    /// `other_instances`.
+    fn new_incoming_instance(
+        &self,
+        mut rng: impl RngCore,
+        state: Vec<C1::ScalarField>,
+        external_inputs: FC::ExternalInputs,
+    ) -> Result<Self::IncomingInstance, Error> {
+        // run the augmented circuit at the base case for the given state, so
+        // that the resulting instance satisfies the R1CS of `F'`
+        let mut augmented_F_circuit = AugmentedFCircuit::<C1, C2, FC, S, T>::empty(
+            &self.poseidon_config,
+            self.F.clone(),
+            self.U_i.betas.len(),
+            self.r1cs.degree(),
+            K,
+        );
+        augmented_F_circuit.pp_hash = self.pp_hash;
+        augmented_F_circuit.z_0.clone_from(&self.z_0);
+        augmented_F_circuit.z_i = state;
+        augmented_F_circuit.external_inputs = external_inputs;
+
+        let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
+        augmented_F_circuit.generate_constraints(cs.clone())?;
+
+        #[cfg(test)]
+        assert!(cs.is_satisfied()?);
+
+        let cs = cs.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
+        let (w, x) = extract_w_x::<C1::ScalarField>(&cs);
+
+        let w = Witness::new::<H>(w, &mut rng);
+        let u = w.commit::<CS1, C1, H>(&self.cs_params, x)?;
+
+        #[cfg(test)]
+        self.r1cs.check_relation(&w, &u)?;
+
+        Ok((u, w))
+    }
+}
+
+impl<C1, C2, FC, CS1, CS2, const K: usize, const H: bool, S, T> FoldingScheme<C1, C2, FC>
+    for ProtoGalaxy<C1, C2, FC, CS1, CS2, K, H, S, T>
 where
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
     C2: Curve,
     FC: FCircuit<C1::ScalarField>,
-    CS1: CommitmentScheme<C1>,
-    CS2: CommitmentScheme<C2>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
 {
-    type PreprocessorParam = (PoseidonConfig<CF1<C1>>, FC);
-    type ProverParam = ProverParams<C1, C2, CS1, CS2>;
-    type VerifierParam = VerifierParams<C1, C2, CS1, CS2>;
+    type PreprocessorParam = (S::Config, FC);
+    type ProverParam = ProverParams<C1, C2, CS1, CS2, H, S::Config>;
+    type VerifierParam = VerifierParams<C1, C2, CS1, CS2, H, S::Config>;
     type RunningInstance = (CommittedInstance<C1, true>, Witness<C1::ScalarField>);
     type IncomingInstance = (CommittedInstance<C1, false>, Witness<C1::ScalarField>);
     type MultiCommittedInstanceWithWitness =
-        (CommittedInstance<C1, false>, Witness<C1::ScalarField>);
+        Vec<(CommittedInstance<C1, false>, Witness<C1::ScalarField>)>;
     type CFInstance = (CycleFoldCommittedInstance<C2>, CycleFoldWitness<C2>);
     type IVCProof = IVCProof<C1, C2>;
 
@@ -652,20 +797,24 @@ where
         validate: ark_serialize::Validate,
         fc_params: FC::Params,
     ) -> Result<Self::VerifierParam, Error> {
-        let poseidon_config = poseidon_canonical_config::<C1::ScalarField>();
+        let poseidon_config = S::Config::canonical_config();
 
         // generate the r1cs & cf_r1cs needed for the VerifierParams. In this way we avoid needing
         // to serialize them, saving significant space in the VerifierParams serialized size.
 
         let f_circuit = FC::new(fc_params)?;
-        let k = 1;
         let d = R1CS::<CF1<C1>>::empty().degree();
-        let t = Self::compute_t(&poseidon_config, &f_circuit, d, k)?;
+        let t = Self::compute_t(&poseidon_config, &f_circuit, d, K)?;
 
         // main circuit R1CS:
         let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
-        let augmented_F_circuit =
-            AugmentedFCircuit::<C1, C2, FC>::empty(&poseidon_config, f_circuit.clone(), t, d, k);
+        let augmented_F_circuit = AugmentedFCircuit::<C1, C2, FC, S, T>::empty(
+            &poseidon_config,
+            f_circuit.clone(),
+            t,
+            d,
+            K,
+        );
         augmented_F_circuit.generate_constraints(cs.clone())?;
         cs.finalize();
         let cs = cs.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
@@ -695,21 +844,21 @@ where
         mut rng: impl RngCore,
         (poseidon_config, F): &Self::PreprocessorParam,
     ) -> Result<(Self::ProverParam, Self::VerifierParam), Error> {
-        // We fix `k`, the number of incoming instances, to 1, because
-        // multi-instances folding is not supported yet.
-        // TODO: Support multi-instances folding and make `k` a constant generic parameter (as in
-        // HyperNova). Tracking issue:
-        // https://github.com/privacy-scaling-explorations/sonobe/issues/82
-        let k = 1;
+        if K < 1 {
+            return Err(Error::CantBeZero("k".to_string()));
+        }
+        if !(K + 1).is_power_of_two() {
+            return Err(Error::ProtoGalaxy(ProtoGalaxyError::WrongNumInstances(K)));
+        }
         let d = R1CS::<CF1<C1>>::empty().degree();
-        let t = Self::compute_t(poseidon_config, F, d, k)?;
+        let t = Self::compute_t(poseidon_config, F, d, K)?;
 
         // prepare the circuit to obtain its R1CS
         let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
         let cs2 = ConstraintSystem::<C1::BaseField>::new_ref();
 
         let augmented_F_circuit =
-            AugmentedFCircuit::<C1, C2, FC>::empty(poseidon_config, F.clone(), t, d, k);
+            AugmentedFCircuit::<C1, C2, FC, S, T>::empty(poseidon_config, F.clone(), t, d, K);
         let cf_circuit = ProtoGalaxyCycleFoldCircuit::<C1>::empty();
 
         augmented_F_circuit.generate_constraints(cs.clone())?;
@@ -784,6 +933,8 @@ where
             // cyclefold running instance
             cf_W_i: cf_W_dummy,
             cf_U_i: cf_U_dummy,
+            _s: PhantomData,
+            _t: PhantomData,
         })
     }
 
@@ -792,26 +943,32 @@ where
         &mut self,
         mut rng: impl RngCore,
         external_inputs: FC::ExternalInputs,
-        _other_instances: Option<Self::MultiCommittedInstanceWithWitness>,
+        other_instances: Option<Self::MultiCommittedInstanceWithWitness>,
     ) -> Result<(), Error> {
-        // Multi-instances folding is not supported yet.
-        if _other_instances.is_some() {
-            return Err(Error::NoMultiInstances);
-        }
-        // We fix `k`, the number of incoming instances, to 1, because
-        // multi-instances folding is not supported yet.
-        // TODO: Support multi-instances folding and make `k` a constant generic parameter (as in
-        // HyperNova). Tracking issue:
-        // https://github.com/privacy-scaling-explorations/sonobe/issues/82
-        let k = 1;
+        // The other incoming instances to be folded together with `u_i`. There
+        // are `K - 1` of them, since `u_i` is not part of `other_instances`.
+        let (us, ws): (Vec<_>, Vec<_>) = if K > 1 {
+            let other_instances = other_instances.ok_or(Error::MissingOtherInstances(1, K))?;
+            if other_instances.len() + 1 != K {
+                return Err(Error::NotSameLength(
+                    "other_instances.len()".to_string(),
+                    other_instances.len(),
+                    "number of other incoming instances (K - 1)".to_string(),
+                    K - 1,
+                ));
+            }
+            other_instances.into_iter().unzip()
+        } else {
+            (vec![], vec![])
+        };
         let d = self.r1cs.degree();
 
         // `sponge` is for digest computation.
-        let sponge = PoseidonSponge::<C1::ScalarField>::new(&self.poseidon_config);
+        let sponge = S::new(&self.poseidon_config);
         // `transcript` is for challenge generation.
         let mut transcript_prover = sponge.clone();
 
-        let mut augmented_F_circuit: AugmentedFCircuit<C1, C2, FC>;
+        let mut augmented_F_circuit: AugmentedFCircuit<C1, C2, FC, S, T>;
 
         if self.z_i.len() != self.F.state_len() {
             return Err(Error::NotSameLength(
@@ -831,7 +988,7 @@ where
                 self.F.clone(),
                 self.U_i.betas.len(),
                 d,
-                k,
+                K,
             );
             augmented_F_circuit.pp_hash = self.pp_hash;
             augmented_F_circuit.z_0.clone_from(&self.z_0);
@@ -843,53 +1000,56 @@ where
         // There is no need to update `self.U_i` etc. as they are unchanged.
         } else {
             // Primary part:
-            // Compute `U_{i+1}` by folding `u_i` into `U_i`.
+            // Compute `U_{i+1}` by folding `u_i` and the other incoming
+            // instances `us` into `U_i`.
+            let all_us = [vec![self.u_i.clone()], us.clone()].concat();
+            let all_ws = [vec![self.w_i.clone()], ws].concat();
             let (U_i1, W_i1, proof, aux) = Folding::prove(
                 &mut transcript_prover,
                 &self.r1cs,
                 &self.U_i,
                 &self.W_i,
-                &[self.u_i.clone()],
-                &[self.w_i.clone()],
+                &all_us,
+                &all_ws,
             )?;
 
             // CycleFold part:
-            let mut r0_bits = aux.L_X_evals[0].into_bigint().to_bits_le();
-            let mut r1_bits = aux.L_X_evals[1].into_bigint().to_bits_le();
-            r0_bits.resize(C1::ScalarField::MODULUS_BIT_SIZE as usize, false);
-            r1_bits.resize(C1::ScalarField::MODULUS_BIT_SIZE as usize, false);
-
-            // cyclefold circuit for enforcing:
-            // 0 + U_i.phi * L_evals[0] == phi_stars[0]
-            let cf1_circuit = ProtoGalaxyCycleFoldCircuit::<C1> {
-                r_bits: Some(r0_bits),
-                points: Some(vec![C1::zero(), self.U_i.phi]),
-            };
-
-            // cyclefold circuit for enforcing:
-            // phi_stars[0] + u_i.phi * L_evals[1] == U_i1.phi
-            // i.e., U_i.phi * L_evals[0] + u_i.phi * L_evals[1] == U_i1.phi
-            let cf2_circuit = ProtoGalaxyCycleFoldCircuit::<C1> {
-                r_bits: Some(r1_bits),
-                points: Some(vec![aux.phi_stars[0], self.u_i.phi]),
-            };
-
-            // fold self.cf_U_i + cf1_U -> folded running with cf1
-            let (cf1_u_i, cf1_W_i1, cf1_U_i1, cf1_cmT) = self.fold_cyclefold_circuit(
-                &mut transcript_prover,
-                self.cf_W_i.clone(), // CycleFold running instance witness
-                self.cf_U_i.clone(), // CycleFold running instance
-                cf1_circuit,
-                &mut rng,
-            )?;
-            // fold [the output from folding self.cf_U_i + cf1_U] + cf2_U = folded_running_with_cf1 + cf2
-            let (cf2_u_i, cf_W_i1, cf_U_i1, cf2_cmT) = self.fold_cyclefold_circuit(
-                &mut transcript_prover,
-                cf1_W_i1,
-                cf1_U_i1.clone(),
-                cf2_circuit,
-                &mut rng,
-            )?;
+            // For j in 0..=K, the j-th cyclefold circuit enforces:
+            // phi_stars[j - 1] + all_us[j - 1].phi * L_evals[j] == phi_stars[j]
+            // where we set `phi_stars[-1] = 0`, `all_us[-1] = U_i` and
+            // `phi_stars[K] = U_i1.phi`, i.e., altogether they enforce
+            // U_i.phi * L_evals[0] + \sum_j all_us[j].phi * L_evals[j + 1] == U_i1.phi
+            let mut cf_u_i_cmWs = Vec::with_capacity(K + 1);
+            let mut cf_cmTs = Vec::with_capacity(K + 1);
+            let mut cf_W_i1 = self.cf_W_i.clone();
+            let mut cf_U_i1 = self.cf_U_i.clone();
+            for j in 0..=K {
+                let mut r_bits = aux.L_X_evals[j].into_bigint().to_bits_le();
+                r_bits.resize(C1::ScalarField::MODULUS_BIT_SIZE as usize, false);
+                let points = if j == 0 {
+                    vec![C1::zero(), self.U_i.phi]
+                } else {
+                    vec![aux.phi_stars[j - 1], all_us[j - 1].phi]
+                };
+                let cf_circuit = ProtoGalaxyCycleFoldCircuit::<C1> {
+                    r_bits: Some(r_bits),
+                    points: Some(points),
+                };
+
+                // fold the output from the previous iteration with the current
+                // cyclefold instance
+                let (cf_u_i, cf_W, cf_U, cf_cmT) = self.fold_cyclefold_circuit(
+                    &mut transcript_prover,
+                    cf_W_i1,
+                    cf_U_i1,
+                    cf_circuit,
+                    &mut rng,
+                )?;
+                cf_W_i1 = cf_W;
+                cf_U_i1 = cf_U;
+                cf_u_i_cmWs.push(cf_u_i.cmW);
+                cf_cmTs.push(cf_cmT);
+            }
 
             augmented_F_circuit = AugmentedFCircuit {
                 poseidon_config: self.poseidon_config.clone(),
@@ -900,6 +1060,7 @@ where
                 z_i: self.z_i.clone(),
                 external_inputs: external_inputs.clone(),
                 u_i_phi: self.u_i.phi,
+                us,
                 U_i: self.U_i.clone(),
                 U_i1_phi: U_i1.phi,
                 F_coeffs: proof.F_coeffs.clone(),
@@ -907,23 +1068,18 @@ where
                 phi_stars: aux.phi_stars,
                 F: self.F.clone(),
                 // cyclefold values
-                cf1_u_i_cmW: cf1_u_i.cmW,
-                cf2_u_i_cmW: cf2_u_i.cmW,
+                cf_u_i_cmWs,
                 cf_U_i: self.cf_U_i.clone(),
-                cf1_cmT,
-                cf2_cmT,
+                cf_cmTs,
+                _s: PhantomData,
+                _t: PhantomData,
             };
 
             #[cfg(test)]
             {
                 let mut transcript_verifier = sponge.clone();
                 assert_eq!(
-                    Folding::verify(
-                        &mut transcript_verifier,
-                        &self.U_i,
-                        &[self.u_i.clone()],
-                        proof
-                    )?,
+                    Folding::verify(&mut transcript_verifier, &self.U_i, &all_us, proof)?,
                     U_i1
                 );
             }
@@ -954,8 +1110,8 @@ where
         // set values for next iteration
         self.i += C1::ScalarField::one();
         self.z_i = z_i1;
-        self.w_i = Witness::new(w_i1);
-        self.u_i = self.w_i.commit::<CS1, C1>(&self.cs_params, x_i1)?;
+        self.w_i = Witness::new::<H>(w_i1, &mut rng);
+        self.u_i = self.w_i.commit::<CS1, C1, H>(&self.cs_params, x_i1)?;
 
         #[cfg(test)]
         {
@@ -1022,6 +1178,8 @@ where
             U_i,
             cf_W_i,
             cf_U_i,
+            _s: PhantomData,
+            _t: PhantomData,
         })
     }
 
@@ -1039,7 +1197,7 @@ where
             cf_U_i,
         } = ivc_proof;
 
-        let sponge = PoseidonSponge::<C1::ScalarField>::new(&vp.poseidon_config);
+        let sponge = S::new(&vp.poseidon_config);
 
         if u_i.x.len() != 2 || U_i.x.len() != 2 {
             return Err(Error::IVCVerificationFail);
@@ -1073,19 +1231,22 @@ where
     }
 }
 
-impl<C1, C2, FC, CS1, CS2> ProtoGalaxy<C1, C2, FC, CS1, CS2>
+impl<C1, C2, FC, CS1, CS2, const K: usize, const H: bool, S, T>
+    ProtoGalaxy<C1, C2, FC, CS1, CS2, K, H, S, T>
 where
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
     C2: Curve,
     FC: FCircuit<C1::ScalarField>,
-    CS1: CommitmentScheme<C1>,
-    CS2: CommitmentScheme<C2>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
 {
     // folds the given cyclefold circuit and its instances
     #[allow(clippy::type_complexity)]
     fn fold_cyclefold_circuit(
         &self,
-        transcript: &mut PoseidonSponge<C1::ScalarField>,
+        transcript: &mut S,
         cf_W_i: CycleFoldWitness<C2>, // witness of the running instance
         cf_U_i: CycleFoldCommittedInstance<C2>, // running instance
         cf_circuit: ProtoGalaxyCycleFoldCircuit<C1>,
@@ -1099,7 +1260,7 @@ where
         ),
         Error,
     > {
-        fold_cyclefold_circuit::<ProtoGalaxyCycleFoldConfig<C1>, C2, CS2, false>(
+        fold_cyclefold_circuit::<ProtoGalaxyCycleFoldConfig<C1>, C2, CS2, H>(
             transcript,
             self.cf_r1cs.clone(),
             self.cf_cs_params.clone(),
@@ -1113,7 +1274,7 @@ where
 }
 
 #[cfg(test)]
-mod tests {
+pub mod tests {
     use super::*;
 
     use ark_bn254::{Bn254, Fr, G1Projective as Projective};
@@ -1136,36 +1297,100 @@ mod tests {
         let F_circuit = CubicFCircuit::<Fr>::new(())?;
 
         // run the test using Pedersen commitments on both sides of the curve cycle
-        let _ = test_ivc_opt::<Pedersen<Projective>, Pedersen<Projective2>>(
+        let _ = test_ivc_opt::<Pedersen<Projective>, Pedersen<Projective2>, false>(
+            poseidon_config.clone(),
+            F_circuit,
+            3,
+        )?;
+        // run the test using hiding Pedersen commitments
+        let _ = test_ivc_opt::<Pedersen<Projective, true>, Pedersen<Projective2, true>, true>(
             poseidon_config.clone(),
             F_circuit,
+            3,
         )?;
         // run the test using KZG for the commitments on the main curve, and Pedersen for the
         // commitments on the secondary curve
-        let _ = test_ivc_opt::<KZG<Bn254>, Pedersen<Projective2>>(poseidon_config, F_circuit)?;
+        let _ = test_ivc_opt::<KZG<Bn254>, Pedersen<Projective2>, false>(
+            poseidon_config,
+            F_circuit,
+            3,
+        )?;
         Ok(())
     }
 
     // test_ivc allowing to choose the CommitmentSchemes
-    fn test_ivc_opt<CS1: CommitmentScheme<Projective>, CS2: CommitmentScheme<Projective2>>(
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn test_ivc_opt<
+        CS1: CommitmentScheme<Projective, H>,
+        CS2: CommitmentScheme<Projective2, H>,
+        const H: bool,
+    >(
         poseidon_config: PoseidonConfig<Fr>,
         F_circuit: CubicFCircuit<Fr>,
-    ) -> Result<(), Error> {
-        type PG<CS1, CS2> = ProtoGalaxy<Projective, Projective2, CubicFCircuit<Fr>, CS1, CS2>;
+        num_steps: usize,
+    ) -> Result<ProtoGalaxy<Projective, Projective2, CubicFCircuit<Fr>, CS1, CS2, 1, H>, Error>
+    {
+        type PG<CS1, CS2, const H: bool> =
+            ProtoGalaxy<Projective, Projective2, CubicFCircuit<Fr>, CS1, CS2, 1, H>;
 
-        let params = PG::<CS1, CS2>::preprocess(&mut test_rng(), &(poseidon_config, F_circuit))?;
+        let params = PG::<CS1, CS2, H>::preprocess(&mut test_rng(), &(poseidon_config, F_circuit))?;
 
         let z_0 = vec![Fr::from(3_u32)];
         let mut protogalaxy = PG::init(&params, F_circuit, z_0.clone())?;
 
-        let num_steps: usize = 3;
         for _ in 0..num_steps {
             protogalaxy.prove_step(&mut test_rng(), (), None)?;
         }
         assert_eq!(Fr::from(num_steps as u32), protogalaxy.i);
 
         let ivc_proof = protogalaxy.ivc_proof();
-        PG::<CS1, CS2>::verify(params.1, ivc_proof)?;
+        PG::<CS1, CS2, H>::verify(params.1, ivc_proof)?;
+        Ok(protogalaxy)
+    }
+
+    /// This test tests the ProtoGalaxy+CycleFold IVC when folding multiple
+    /// incoming instances at each step
+    #[test]
+    fn test_ivc_multi_instances() -> Result<(), Error> {
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+
+        const K: usize = 3;
+        type PG = ProtoGalaxy<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            K,
+        >;
+
+        let params = PG::preprocess(&mut test_rng(), &(poseidon_config, F_circuit))?;
+
+        let z_0 = vec![Fr::from(3_u32)];
+        let mut protogalaxy = PG::init(&params, F_circuit, z_0)?;
+
+        // `other_instances` are required when K > 1
+        assert!(protogalaxy.prove_step(&mut test_rng(), (), None).is_err());
+
+        let num_steps: usize = 3;
+        for _ in 0..num_steps {
+            // prepare some new instances to fold in the multi-instance folding step
+            let mut other_instances = vec![];
+            for j in 0..K - 1 {
+                let instance_state = vec![Fr::from(j as u32 + 85_u32)];
+                other_instances.push(protogalaxy.new_incoming_instance(
+                    &mut test_rng(),
+                    instance_state,
+                    (),
+                )?);
+            }
+            protogalaxy.prove_step(&mut test_rng(), (), Some(other_instances))?;
+        }
+        assert_eq!(Fr::from(num_steps as u32), protogalaxy.i);
+
+        let ivc_proof = protogalaxy.ivc_proof();
+        PG::verify(params.1, ivc_proof)?;
         Ok(())
     }
 
@@ -1183,13 +1408,13 @@ mod tests {
                 .into_par_iter()
                 .map(|t| {
                     let cs = ConstraintSystem::<Fr>::new_ref();
-                    AugmentedFCircuit::<Projective, Projective2, DummyCircuit>::empty(
-                        &poseidon_config,
-                        dummy_circuit.clone(),
-                        t,
-                        d,
-                        k,
-                    )
+                    AugmentedFCircuit::<
+                        Projective,
+                        Projective2,
+                        DummyCircuit,
+                        PoseidonSponge<Fr>,
+                        PoseidonSpongeVar<Fr>,
+                    >::empty(&poseidon_config, dummy_circuit.clone(), t, d, k)
                     .generate_constraints(cs.clone())?;
                     Ok(cs.num_constraints())
                 })
@@ -0,0 +1,416 @@
+/// Implements the zero-knowledge layer for ProtoGalaxy's IVC, following the same approach as the
+/// Nova's one (see [`crate::folding::nova::zk`]), which is described in appendix D.4 of
+/// [HyperNova](https://eprint.iacr.org/2023/573.pdf).
+///
+/// As in Nova, this covers the Use-case-3 described in [`crate::folding::nova::zk`]: the user
+/// wants to generate a zk-proof of the IVC state to an IVC verifier, without any SNARK proof
+/// involved.
+///
+/// The main difference with Nova's approach is in the randomization of the folded instance. In
+/// Nova, the folded instance is folded with a random relaxed R1CS instance. In ProtoGalaxy instead,
+/// the folding scheme only folds incoming instances (which satisfy the non-relaxed relation, and
+/// thus can not be sampled at random) into a running one. So we sample a random running instance
+/// `U_r` with the same `betas` as the folded instance `U_f`, and fold both running instances as
+/// follows: defining `G(X) = \sum_i pow_i(betas) * f_i(L_0(X) * z_f + L_1(X) * z_r)`, it holds
+/// that `G(X) = e_f * L_0(X) + e_r * L_1(X) + Z(X) * K(X)`, so the prover sends `K(X)`, and for a
+/// random challenge `gamma`, the folded instance has
+/// `e = e_f * L_0(gamma) + e_r * L_1(gamma) + Z(gamma) * K(gamma)`, while the rest of its values
+/// are the linear combination of the ones of `U_f` and `U_r` with `L_0(gamma)` and `L_1(gamma)`.
+///
+/// Notice that, unlike the cross term commitments of Nova, the coefficients of the polynomials in
+/// ProtoGalaxy's folding proofs are sent in the clear.
+use ark_crypto_primitives::sponge::{
+    poseidon::{PoseidonConfig, PoseidonSponge},
+    CryptographicSponge,
+};
+use ark_poly::{
+    univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, Evaluations,
+    GeneralEvaluationDomain, Polynomial,
+};
+use ark_std::{rand::RngCore, One, UniformRand, Zero};
+
+use super::{
+    folding::{lagrange_polys, Folding, ProtoGalaxyProof},
+    utils::pow_i,
+    CommittedInstance, ProtoGalaxy, ProtoGalaxyError, Witness,
+};
+use crate::{
+    arith::{r1cs::R1CS, Arith, ArithRelation},
+    commitment::CommitmentScheme,
+    folding::{
+        circuits::cyclefold::{CycleFoldCommittedInstance, CycleFoldWitness},
+        traits::CommittedInstanceOps,
+    },
+    frontend::FCircuit,
+    transcript::Transcript,
+    utils::vec::{vec_add, vec_scalar_mul},
+    Curve, Error,
+};
+
+pub struct RandomizedIVCProof<C1: Curve, C2: Curve> {
+    pub U_i: CommittedInstance<C1, true>,
+    pub u_i: CommittedInstance<C1, false>,
+    pub U_r: CommittedInstance<C1, true>,
+    pub pi: ProtoGalaxyProof<C1::ScalarField>, // proof of folding u_i into U_i
+    pub pi_prime: Vec<C1::ScalarField>,        // coefficients of K(X) when folding U_r into U_f
+    pub W_i_prime: Witness<C1::ScalarField>,
+    pub cf_U_i: CycleFoldCommittedInstance<C2>,
+    pub cf_W_i: CycleFoldWitness<C2>,
+}
+
+impl<C1: Curve, C2: Curve> RandomizedIVCProof<C1, C2> {
+    /// Compute a zero-knowledge proof of a ProtoGalaxy IVC proof
+    pub fn new<
+        FC: FCircuit<C1::ScalarField>,
+        CS1: CommitmentScheme<C1, true>,
+        CS2: CommitmentScheme<C2, true>,
+        const K: usize,
+    >(
+        protogalaxy: &ProtoGalaxy<C1, C2, FC, CS1, CS2, K, true>,
+        mut rng: impl RngCore,
+    ) -> Result<RandomizedIVCProof<C1, C2>, Error>
+    where
+        C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    {
+        let mut transcript = PoseidonSponge::<C1::ScalarField>::new(&protogalaxy.poseidon_config);
+        transcript.absorb(&protogalaxy.pp_hash);
+
+        // I. Compute proof for 'regular' instances
+        // 1. Fold the instance-witness pair (u_i, w_i) into (U_i, W_i)
+        let (U_f, W_f, pi, _) = Folding::prove(
+            &mut transcript,
+            &protogalaxy.r1cs,
+            &protogalaxy.U_i,
+            &protogalaxy.W_i,
+            &[protogalaxy.u_i.clone()],
+            &[protogalaxy.w_i.clone()],
+        )?;
+
+        // 2. Sample a satisfying running instance-witness pair (U_r, W_r) with the same betas as
+        //    U_f
+        let (U_r, W_r) = sample_running_instance::<C1, CS1>(
+            &protogalaxy.r1cs,
+            &protogalaxy.cs_params,
+            &U_f.betas,
+            &mut rng,
+        )?;
+
+        // 3. Fold the instance-witness pair (U_r, W_r) into (U_f, W_f)
+        let (_, W_i_prime, pi_prime) =
+            prove_fold_running(&mut transcript, &protogalaxy.r1cs, &U_f, &W_f, &U_r, &W_r)?;
+
+        Ok(RandomizedIVCProof {
+            U_i: protogalaxy.U_i.clone(),
+            u_i: protogalaxy.u_i.clone(),
+            U_r,
+            pi,
+            pi_prime,
+            W_i_prime,
+            cf_U_i: protogalaxy.cf_U_i.clone(),
+            cf_W_i: protogalaxy.cf_W_i.clone(),
+        })
+    }
+
+    /// Verify a zero-knowledge proof of a ProtoGalaxy IVC proof
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify(
+        r1cs: &R1CS<C1::ScalarField>,
+        cf_r1cs: &R1CS<C2::ScalarField>,
+        pp_hash: C1::ScalarField,
+        poseidon_config: &PoseidonConfig<C1::ScalarField>,
+        i: C1::ScalarField,
+        z_0: Vec<C1::ScalarField>,
+        z_i: Vec<C1::ScalarField>,
+        proof: &RandomizedIVCProof<C1, C2>,
+    ) -> Result<(), Error>
+    where
+        C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    {
+        // Handles case where i=0
+        if i == C1::ScalarField::zero() {
+            if z_0 == z_i {
+                return Ok(());
+            } else {
+                return Err(Error::zkIVCVerificationFail);
+            }
+        }
+
+        // 1. Check that u_i.x is correct - including the cyclefold running instance
+        // a. Check length
+        if proof.u_i.x.len() != 2 || proof.U_i.x.len() != 2 {
+            return Err(Error::IVCVerificationFail);
+        }
+
+        // b. Check computed hashes are correct
+        let sponge = PoseidonSponge::<C1::ScalarField>::new(poseidon_config);
+        let expected_u_i_x = proof.U_i.hash(&sponge, pp_hash, i, &z_0, &z_i);
+        if expected_u_i_x != proof.u_i.x[0] {
+            return Err(Error::zkIVCVerificationFail);
+        }
+
+        let expected_cf_u_i_x = proof.cf_U_i.hash_cyclefold(&sponge, pp_hash);
+        if expected_cf_u_i_x != proof.u_i.x[1] {
+            return Err(Error::IVCVerificationFail);
+        }
+
+        let mut transcript = PoseidonSponge::<C1::ScalarField>::new(poseidon_config);
+        transcript.absorb(&pp_hash);
+        // 2. Obtain the U_f folded instance
+        let U_f = Folding::verify(
+            &mut transcript,
+            &proof.U_i,
+            &[proof.u_i.clone()],
+            proof.pi.clone(),
+        )?;
+
+        // 3. Obtain the U^{\prime}_i folded instance, checking that U_r uses the same betas as U_f
+        if proof.U_r.betas != U_f.betas {
+            return Err(Error::zkIVCVerificationFail);
+        }
+        let U_i_prime = verify_fold_running(&mut transcript, &U_f, &proof.U_r, &proof.pi_prime)?;
+
+        // 4. Check that W^{\prime}_i is a satisfying witness
+        r1cs.check_relation(&proof.W_i_prime, &U_i_prime)?;
+
+        // 5. Check that the cyclefold instance-witness pair satisfies the cyclefold relaxed r1cs
+        cf_r1cs.check_relation(&proof.cf_W_i, &proof.cf_U_i)?;
+
+        Ok(())
+    }
+}
+
+/// Samples a random running instance-witness pair for the given `betas`, where the witness is
+/// blinded and the error term `e` is computed so that the pair satisfies the relation.
+fn sample_running_instance<C: Curve, CS: CommitmentScheme<C, true>>(
+    r1cs: &R1CS<C::ScalarField>,
+    params: &CS::ProverParams,
+    betas: &[C::ScalarField],
+    mut rng: impl RngCore,
+) -> Result<(CommittedInstance<C, true>, Witness<C::ScalarField>), Error> {
+    let w = (0..r1cs.n_witnesses())
+        .map(|_| C::ScalarField::rand(&mut rng))
+        .collect::<Vec<_>>();
+    let x = (0..r1cs.n_public_inputs())
+        .map(|_| C::ScalarField::rand(&mut rng))
+        .collect::<Vec<_>>();
+    let r_w = C::ScalarField::rand(&mut rng);
+    let phi = CS::commit(params, &w, &r_w)?;
+
+    let e = r1cs
+        .eval_at_z(&[&[C::ScalarField::one()][..], &x, &w].concat())?
+        .into_iter()
+        .enumerate()
+        .map(|(i, f_i)| pow_i(i, betas) * f_i)
+        .sum();
+
+    Ok((
+        CommittedInstance {
+            phi,
+            betas: betas.to_vec(),
+            e,
+            x,
+        },
+        Witness { w, r_w },
+    ))
+}
+
+/// Folds the running instance-witness pair `(U_r, W_r)` into `(U, W)`, where both instances share
+/// the same `betas`. Returns the folded instance and witness, together with the coefficients of
+/// `K(X)`.
+#[allow(clippy::type_complexity)]
+fn prove_fold_running<C: Curve>(
+    transcript: &mut impl Transcript<C::ScalarField>,
+    r1cs: &R1CS<C::ScalarField>,
+    U: &CommittedInstance<C, true>,
+    W: &Witness<C::ScalarField>,
+    U_r: &CommittedInstance<C, true>,
+    W_r: &Witness<C::ScalarField>,
+) -> Result<
+    (
+        CommittedInstance<C, true>,
+        Witness<C::ScalarField>,
+        Vec<C::ScalarField>,
+    ),
+    Error,
+> {
+    let d = r1cs.degree();
+
+    transcript.absorb(U);
+    transcript.absorb(U_r);
+
+    let z = [&[C::ScalarField::one()][..], &U.x, &W.w].concat();
+    let z_r = [&[C::ScalarField::one()][..], &U_r.x, &W_r.w].concat();
+
+    let H = GeneralEvaluationDomain::<C::ScalarField>::new(2).ok_or(Error::NewDomainFail)?;
+    let G_domain =
+        GeneralEvaluationDomain::<C::ScalarField>::new(d + 1).ok_or(Error::NewDomainFail)?;
+    let L_X: Vec<DensePolynomial<C::ScalarField>> = lagrange_polys(H);
+
+    // G(X) = \sum_i pow_i(betas) * f_i(L_0(X) * z + L_1(X) * z_r)
+    let G_evals = G_domain
+        .elements()
+        .map(|h| {
+            let z_h = vec_add(
+                &vec_scalar_mul(&z, &L_X[0].evaluate(&h)),
+                &vec_scalar_mul(&z_r, &L_X[1].evaluate(&h)),
+            )?;
+            Ok(r1cs
+                .eval_at_z(&z_h)?
+                .into_iter()
+                .enumerate()
+                .map(|(i, f_i)| pow_i(i, &U.betas) * f_i)
+                .sum())
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    let G_X: DensePolynomial<C::ScalarField> =
+        Evaluations::<C::ScalarField>::from_vec_and_domain(G_evals, G_domain).interpolate();
+
+    // K(X) = (G(X) - e * L_0(X) - e_r * L_1(X)) / Z(X)
+    let G_L_e = &(&G_X - &(&L_X[0] * U.e)) - &(&L_X[1] * U_r.e);
+    let (K_X, remainder) = G_L_e.divide_by_vanishing_poly(H);
+    if !remainder.is_zero() {
+        return Err(Error::ProtoGalaxy(ProtoGalaxyError::RemainderNotZero));
+    }
+
+    let mut K_coeffs = K_X.coeffs;
+    K_coeffs.resize(d + 1, C::ScalarField::zero());
+    transcript.absorb(&K_coeffs);
+
+    let gamma = transcript.get_challenge();
+    let L_X_evals = [L_X[0].evaluate(&gamma), L_X[1].evaluate(&gamma)];
+
+    let U_folded = fold_running_instances(&H, &L_X_evals, &gamma, U, U_r, &K_coeffs)?;
+    let W_folded = Witness {
+        w: vec_add(
+            &vec_scalar_mul(&W.w, &L_X_evals[0]),
+            &vec_scalar_mul(&W_r.w, &L_X_evals[1]),
+        )?,
+        r_w: W.r_w * L_X_evals[0] + W_r.r_w * L_X_evals[1],
+    };
+
+    Ok((U_folded, W_folded, K_coeffs))
+}
+
+/// Verifier counterpart of `prove_fold_running`, returns the folded instance.
+fn verify_fold_running<C: Curve>(
+    transcript: &mut impl Transcript<C::ScalarField>,
+    U: &CommittedInstance<C, true>,
+    U_r: &CommittedInstance<C, true>,
+    K_coeffs: &[C::ScalarField],
+) -> Result<CommittedInstance<C, true>, Error> {
+    transcript.absorb(U);
+    transcript.absorb(U_r);
+    transcript.absorb(&K_coeffs);
+
+    let H = GeneralEvaluationDomain::<C::ScalarField>::new(2).ok_or(Error::NewDomainFail)?;
+    let L_X: Vec<DensePolynomial<C::ScalarField>> = lagrange_polys(H);
+
+    let gamma = transcript.get_challenge();
+    let L_X_evals = [L_X[0].evaluate(&gamma), L_X[1].evaluate(&gamma)];
+
+    fold_running_instances(&H, &L_X_evals, &gamma, U, U_r, K_coeffs)
+}
+
+fn fold_running_instances<C: Curve>(
+    H: &GeneralEvaluationDomain<C::ScalarField>,
+    L_X_evals: &[C::ScalarField; 2],
+    gamma: &C::ScalarField,
+    U: &CommittedInstance<C, true>,
+    U_r: &CommittedInstance<C, true>,
+    K_coeffs: &[C::ScalarField],
+) -> Result<CommittedInstance<C, true>, Error> {
+    let K_X = DensePolynomial::from_coefficients_slice(K_coeffs);
+    Ok(CommittedInstance {
+        phi: U.phi * L_X_evals[0] + U_r.phi * L_X_evals[1],
+        betas: U.betas.clone(),
+        e: U.e * L_X_evals[0]
+            + U_r.e * L_X_evals[1]
+            + H.evaluate_vanishing_polynomial(*gamma) * K_X.evaluate(gamma),
+        x: vec_add(
+            &vec_scalar_mul(&U.x, &L_X_evals[0]),
+            &vec_scalar_mul(&U_r.x, &L_X_evals[1]),
+        )?,
+    })
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::commitment::pedersen::Pedersen;
+    use crate::folding::protogalaxy::tests::test_ivc_opt;
+    use crate::frontend::utils::CubicFCircuit;
+    use crate::transcript::poseidon::poseidon_canonical_config;
+    use ark_bn254::{Fr, G1Projective as Projective};
+    use ark_grumpkin::Projective as Projective2;
+    use rand::rngs::OsRng;
+
+    // Tests zk proof generation and verification for a valid ProtoGalaxy IVC proof
+    #[test]
+    fn test_zk_protogalaxy_ivc() -> Result<(), Error> {
+        let mut rng = OsRng;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let protogalaxy = test_ivc_opt::<
+            Pedersen<Projective, true>,
+            Pedersen<Projective2, true>,
+            true,
+        >(poseidon_config, F_circuit, 3)?;
+
+        let proof = RandomizedIVCProof::new(&protogalaxy, &mut rng)?;
+        RandomizedIVCProof::verify(
+            &protogalaxy.r1cs,
+            &protogalaxy.cf_r1cs,
+            protogalaxy.pp_hash,
+            &protogalaxy.poseidon_config,
+            protogalaxy.i,
+            protogalaxy.z_0.clone(),
+            protogalaxy.z_i.clone(),
+            &proof,
+        )?;
+
+        // the randomized witness should not match the original running witness
+        assert_ne!(proof.W_i_prime.w, protogalaxy.W_i.w);
+
+        // verification fails with a wrong state
+        let verify = RandomizedIVCProof::verify(
+            &protogalaxy.r1cs,
+            &protogalaxy.cf_r1cs,
+            protogalaxy.pp_hash,
+            &protogalaxy.poseidon_config,
+            protogalaxy.i,
+            protogalaxy.z_0.clone(),
+            protogalaxy.z_0,
+            &proof,
+        );
+        assert!(verify.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_zk_protogalaxy_verification_fails_with_wrong_witness() -> Result<(), Error> {
+        let mut rng = OsRng;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let protogalaxy = test_ivc_opt::<
+            Pedersen<Projective, true>,
+            Pedersen<Projective2, true>,
+            true,
+        >(poseidon_config, F_circuit, 3)?;
+
+        // proof verification fails with an incorrect randomized witness
+        let mut incorrect_proof = RandomizedIVCProof::new(&protogalaxy, &mut rng)?;
+        incorrect_proof.W_i_prime.w[0] += Fr::one();
+        let verify = RandomizedIVCProof::verify(
+            &protogalaxy.r1cs,
+            &protogalaxy.cf_r1cs,
+            protogalaxy.pp_hash,
+            &protogalaxy.poseidon_config,
+            protogalaxy.i,
+            protogalaxy.z_0,
+            protogalaxy.z_i,
+            &incorrect_proof,
+        );
+        assert!(verify.is_err());
+        Ok(())
+    }
+}
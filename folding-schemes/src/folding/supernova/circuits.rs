@@ -0,0 +1,494 @@
+/// contains [SuperNova](https://eprint.iacr.org/2022/1758.pdf) related circuits
+use ark_crypto_primitives::sponge::{constraints::AbsorbGadget, CryptographicSponge};
+use ark_ff::PrimeField;
+use ark_r1cs_std::{
+    alloc::AllocVar,
+    boolean::Boolean,
+    eq::EqGadget,
+    fields::{fp::FpVar, FieldVar},
+    prelude::CurveVar,
+    R1CSVar,
+};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use ark_std::{fmt::Debug, marker::PhantomData, One, Zero};
+
+use crate::folding::circuits::{
+    cyclefold::{
+        CycleFoldChallengeGadget, CycleFoldCommittedInstance, CycleFoldCommittedInstanceVar,
+        CycleFoldConfig, NIFSFullGadget,
+    },
+    nonnative::{affine::NonNativeAffineVar, uint::NonNativeUintVar},
+    CF1,
+};
+use crate::folding::nova::{
+    nifs::{
+        nova_circuits::{CommittedInstanceVar, NIFSGadget},
+        NIFSGadgetTrait,
+    },
+    CommittedInstance, NovaCycleFoldConfig,
+};
+use crate::folding::traits::Dummy;
+use crate::frontend::FCircuit;
+use crate::transcript::{AbsorbNonNativeGadget, Transcript, TranscriptVar};
+use crate::Curve;
+
+/// In-circuit counterpart of [`super::hash_running_instances`].
+/// Returns `H(pp_hash, i, pc, z_0, z_i, U_i[0], ..., U_i[l-1])`, together with
+/// the sponge representation of each of the running instances, so that they
+/// can be reused in other gadgets without reconstraining them.
+#[allow(clippy::type_complexity)]
+pub fn hash_running_instances_gadget<
+    C: Curve,
+    S: CryptographicSponge,
+    T: TranscriptVar<CF1<C>, S>,
+>(
+    sponge: &T,
+    pp_hash: &FpVar<CF1<C>>,
+    i: &FpVar<CF1<C>>,
+    pc: &FpVar<CF1<C>>,
+    z_0: &[FpVar<CF1<C>>],
+    z_i: &[FpVar<CF1<C>>],
+    U_i: &[CommittedInstanceVar<C>],
+) -> Result<(FpVar<CF1<C>>, Vec<Vec<FpVar<CF1<C>>>>), SynthesisError> {
+    let mut sponge = sponge.clone();
+    let U_vecs = U_i
+        .iter()
+        .map(|U| U.to_sponge_field_elements())
+        .collect::<Result<Vec<_>, _>>()?;
+    sponge.absorb(&pp_hash)?;
+    sponge.absorb(&i)?;
+    sponge.absorb(&pc)?;
+    sponge.absorb(&z_0)?;
+    sponge.absorb(&z_i)?;
+    for U_vec in U_vecs.iter() {
+        sponge.absorb(U_vec)?;
+    }
+    Ok((
+        // `unwrap` is safe because the sponge is guaranteed to return a single element
+        sponge.squeeze_field_elements(1)?.pop().unwrap(),
+        U_vecs,
+    ))
+}
+
+/// `AugmentedFCircuit` is the non-uniform version of Nova's
+/// [`crate::folding::nova::circuits::AugmentedFCircuit`]. There is one
+/// `AugmentedFCircuit` for each of the step circuits `F_0, ..., F_{l-1}`, and
+/// the `pc`-th one (`F'_{pc}`):
+/// - enforces that the program counter in the state, `z_i[0]`, is equal to
+///   `pc`, so that the prover can not run a circuit different from the one
+///   selected by the program;
+/// - folds the incoming instance `u_i` into the running instance `U_i[prev_pc]`
+///   that corresponds to the circuit `F'_{prev_pc}` that generated `u_i`,
+///   leaving the rest of running instances untouched;
+/// - verifies the CycleFold folding of the commitments exactly as Nova does.
+#[derive(Debug, Clone)]
+pub struct AugmentedFCircuit<
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<CF1<C1>>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
+> {
+    pub(super) poseidon_config: S::Config,
+    pub(super) pp_hash: Option<CF1<C1>>,
+    pub(super) i: Option<CF1<C1>>,
+    pub(super) i_usize: Option<usize>,
+    pub(super) prev_pc: Option<usize>,
+    pub(super) z_0: Option<Vec<C1::ScalarField>>,
+    pub(super) z_i: Option<Vec<C1::ScalarField>>,
+    pub(super) external_inputs: Option<FC::ExternalInputs>,
+    pub(super) u_i_cmW: Option<C1>,
+    pub(super) U_i: Option<Vec<CommittedInstance<C1>>>,
+    pub(super) U_i1_cmE: Option<C1>,
+    pub(super) U_i1_cmW: Option<C1>,
+    pub(super) cmT: Option<C1>,
+    pub(super) F: FC,             // F circuit
+    pub(super) pc: usize,         // index of F in the set of step circuits
+    pub(super) n_circuits: usize, // number of step circuits
+
+    // cyclefold verifier on C1
+    // Here 'cf1, cf2' are for each of the CycleFold circuits, corresponding to the fold of cmW and
+    // cmE respectively
+    pub(super) cf1_u_i_cmW: Option<C2>, // input
+    pub(super) cf2_u_i_cmW: Option<C2>, // input
+    pub(super) cf_U_i: Option<CycleFoldCommittedInstance<C2>>, // input
+    pub(super) cf1_cmT: Option<C2>,
+    pub(super) cf2_cmT: Option<C2>,
+    pub(super) _s: PhantomData<S>,
+    pub(super) _t: PhantomData<T>,
+}
+
+impl<C1, C2, FC, S, T> AugmentedFCircuit<C1, C2, FC, S, T>
+where
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<CF1<C1>>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
+{
+    pub fn empty(poseidon_config: &S::Config, F_circuit: FC, pc: usize, n_circuits: usize) -> Self {
+        Self {
+            poseidon_config: poseidon_config.clone(),
+            pp_hash: None,
+            i: None,
+            i_usize: None,
+            prev_pc: None,
+            z_0: None,
+            z_i: None,
+            external_inputs: None,
+            u_i_cmW: None,
+            U_i: None,
+            U_i1_cmE: None,
+            U_i1_cmW: None,
+            cmT: None,
+            F: F_circuit,
+            pc,
+            n_circuits,
+            // cyclefold values
+            cf1_u_i_cmW: None,
+            cf2_u_i_cmW: None,
+            cf_U_i: None,
+            cf1_cmT: None,
+            cf2_cmT: None,
+            _s: PhantomData,
+            _t: PhantomData,
+        }
+    }
+}
+
+impl<C1, C2, FC, S, T> AugmentedFCircuit<C1, C2, FC, S, T>
+where
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: Curve,
+    FC: FCircuit<CF1<C1>>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
+{
+    pub fn compute_next_state(
+        self,
+        cs: ConstraintSystemRef<CF1<C1>>,
+    ) -> Result<Vec<FpVar<CF1<C1>>>, SynthesisError> {
+        let pp_hash = FpVar::<CF1<C1>>::new_witness(cs.clone(), || {
+            Ok(self.pp_hash.unwrap_or_else(CF1::<C1>::zero))
+        })?;
+        let i = FpVar::<CF1<C1>>::new_witness(cs.clone(), || {
+            Ok(self.i.unwrap_or_else(CF1::<C1>::zero))
+        })?;
+        let prev_pc = FpVar::<CF1<C1>>::new_witness(cs.clone(), || {
+            Ok(CF1::<C1>::from(self.prev_pc.unwrap_or(0) as u64))
+        })?;
+        let z_0 = Vec::<FpVar<CF1<C1>>>::new_witness(cs.clone(), || {
+            Ok(self
+                .z_0
+                .unwrap_or(vec![CF1::<C1>::zero(); self.F.state_len()]))
+        })?;
+        let z_i = Vec::<FpVar<CF1<C1>>>::new_witness(cs.clone(), || {
+            Ok(self
+                .z_i
+                .unwrap_or(vec![CF1::<C1>::zero(); self.F.state_len()]))
+        })?;
+        let external_inputs = FC::ExternalInputsVar::new_witness(cs.clone(), || {
+            Ok(self.external_inputs.unwrap_or_default())
+        })?;
+
+        let u_dummy = CommittedInstance::dummy(2);
+        let U_i = Vec::<CommittedInstanceVar<C1>>::new_witness(cs.clone(), || {
+            Ok(self.U_i.unwrap_or(vec![u_dummy.clone(); self.n_circuits]))
+        })?;
+        let U_i1_cmE = NonNativeAffineVar::new_witness(cs.clone(), || {
+            Ok(self.U_i1_cmE.unwrap_or_else(C1::zero))
+        })?;
+        let U_i1_cmW = NonNativeAffineVar::new_witness(cs.clone(), || {
+            Ok(self.U_i1_cmW.unwrap_or_else(C1::zero))
+        })?;
+
+        let cmT =
+            NonNativeAffineVar::new_witness(cs.clone(), || Ok(self.cmT.unwrap_or_else(C1::zero)))?;
+
+        let cf_u_dummy = CycleFoldCommittedInstance::dummy(NovaCycleFoldConfig::<C1>::IO_LEN);
+        let cf_U_i = CycleFoldCommittedInstanceVar::<C2>::new_witness(cs.clone(), || {
+            Ok(self.cf_U_i.unwrap_or(cf_u_dummy.clone()))
+        })?;
+        let cf1_cmT =
+            C2::Var::new_witness(cs.clone(), || Ok(self.cf1_cmT.unwrap_or_else(C2::zero)))?;
+        let cf2_cmT =
+            C2::Var::new_witness(cs.clone(), || Ok(self.cf2_cmT.unwrap_or_else(C2::zero)))?;
+
+        // `sponge` is for digest computation.
+        let sponge = T::new(cs.clone(), &self.poseidon_config);
+        // `transcript` is for challenge generation.
+        let mut transcript = sponge.clone();
+
+        let is_basecase = i.is_zero()?;
+
+        // P.0. Check that the program counter z_i[0] selects this circuit
+        let pc = FpVar::<CF1<C1>>::constant(CF1::<C1>::from(self.pc as u64));
+        z_i.first()
+            .ok_or(SynthesisError::Unsatisfiable)?
+            .enforce_equal(&pc)?;
+
+        // Primary Part
+        // P.1. Compute u_i.x
+        // u_i.x[0] = H(i, prev_pc, z_0, z_i, U_i)
+        let (u_i_x, U_i_vecs) =
+            hash_running_instances_gadget(&sponge, &pp_hash, &i, &prev_pc, &z_0, &z_i, &U_i)?;
+        // u_i.x[1] = H(cf_U_i)
+        let (cf_u_i_x, cf_U_i_vec) = cf_U_i.clone().hash(&sponge, pp_hash.clone())?;
+
+        // P.2. Construct u_i
+        let u_i = CommittedInstanceVar {
+            // u_i.cmE = cm(0)
+            cmE: NonNativeAffineVar::new_constant(cs.clone(), C1::zero())?,
+            // u_i.u = 1
+            u: FpVar::one(),
+            // u_i.cmW is provided by the prover as witness
+            cmW: NonNativeAffineVar::new_witness(cs.clone(), || {
+                Ok(self.u_i_cmW.unwrap_or(C1::zero()))
+            })?,
+            // u_i.x is computed in step 1
+            x: vec![u_i_x, cf_u_i_x],
+        };
+
+        // P.3. Select the running instance U_i[prev_pc] corresponding to the circuit that
+        // generated u_i. Exactly one of the selectors must be true, which also ensures that
+        // prev_pc < n_circuits.
+        let selectors = (0..self.n_circuits)
+            .map(|k| prev_pc.is_eq(&FpVar::constant(CF1::<C1>::from(k as u64))))
+            .collect::<Result<Vec<_>, _>>()?;
+        selectors
+            .iter()
+            .fold(FpVar::<CF1<C1>>::zero(), |acc, s| {
+                acc + FpVar::from(s.clone())
+            })
+            .enforce_equal(&FpVar::one())?;
+        let mut U_sel = U_i[0].clone();
+        let mut U_sel_vec = U_i_vecs[0].clone();
+        for k in 1..self.n_circuits {
+            U_sel = selectors[k].select(&U_i[k], &U_sel)?;
+            U_sel_vec = U_sel_vec
+                .iter()
+                .zip(&U_i_vecs[k])
+                .map(|(sel, v)| selectors[k].select(v, sel))
+                .collect::<Result<Vec<_>, _>>()?;
+        }
+
+        // P.4. nifs.verify, obtains U_{i+1}[prev_pc] by folding u_i & U_i[prev_pc].
+        // Notice that NIFSGadget::verify does not fold cmE & cmW.
+        // We set `U_i1.cmE` and `U_i1.cmW` to unconstrained witnesses `U_i1_cmE` and `U_i1_cmW`
+        // respectively.
+        // The correctness of them will be checked on the other curve.
+        let (mut U_i1, r_bits) = NIFSGadget::<C1, S, T>::verify(
+            &mut transcript,
+            pp_hash.clone(),
+            U_sel.clone(),
+            U_sel_vec,
+            u_i.clone(),
+            Some(cmT.clone()),
+        )?;
+        U_i1.cmE = U_i1_cmE;
+        U_i1.cmW = U_i1_cmW;
+
+        // the rest of running instances are kept as they are
+        let U_i1_vec = U_i
+            .iter()
+            .zip(&selectors)
+            .map(|(U, s)| s.select(&U_i1, U))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // convert r_bits to a `NonNativeFieldVar`
+        let r_nonnat = {
+            let mut bits = r_bits;
+            bits.resize(C1::BaseField::MODULUS_BIT_SIZE as usize, Boolean::FALSE);
+            NonNativeUintVar::from(&bits)
+        };
+
+        // P.5.a compute and check the first output of F'
+
+        // get z_{i+1} from the F circuit
+        let i_usize = self.i_usize.unwrap_or(0);
+        let z_i1 = self
+            .F
+            .generate_step_constraints(cs.clone(), i_usize, z_i, external_inputs)?;
+
+        // Base case: u_{i+1}.x[0] == H((i+1, pc, z_0, z_{i+1}, [U_{\bot}; l])
+        // Non-base case: u_{i+1}.x[0] == H((i+1, pc, z_0, z_{i+1}, U_{i+1})
+        let (u_i1_x, _) = hash_running_instances_gadget(
+            &sponge,
+            &pp_hash,
+            &(i + FpVar::<CF1<C1>>::one()),
+            &pc,
+            &z_0,
+            &z_i1,
+            &U_i1_vec,
+        )?;
+        let (u_i1_x_base, _) = hash_running_instances_gadget(
+            &sponge,
+            &pp_hash,
+            &FpVar::<CF1<C1>>::one(),
+            &pc,
+            &z_0,
+            &z_i1,
+            &Vec::<CommittedInstanceVar<C1>>::new_constant(
+                cs.clone(),
+                vec![u_dummy; self.n_circuits],
+            )?,
+        )?;
+        let x = is_basecase.select(&u_i1_x_base, &u_i1_x)?;
+        // This line "converts" `x` from a witness to a public input, see the
+        // analogous line in Nova's `AugmentedFCircuit` for the rationale.
+        FpVar::new_input(cs.clone(), || x.value())?.enforce_equal(&x)?;
+
+        // CycleFold part
+        // C.1. Compute cf1_u_i.x and cf2_u_i.x
+        let cfW_x = vec![
+            r_nonnat.clone(),
+            U_sel.cmW.x,
+            U_sel.cmW.y,
+            u_i.cmW.x,
+            u_i.cmW.y,
+            U_i1.cmW.x,
+            U_i1.cmW.y,
+        ];
+        let cfE_x = vec![
+            r_nonnat,
+            U_sel.cmE.x,
+            U_sel.cmE.y,
+            cmT.x,
+            cmT.y,
+            U_i1.cmE.x,
+            U_i1.cmE.y,
+        ];
+
+        // C.2. Construct `cf1_u_i` and `cf2_u_i`
+        let cf1_u_i = CycleFoldCommittedInstanceVar {
+            // cf1_u_i.cmE = 0
+            cmE: C2::Var::zero(),
+            // cf1_u_i.u = 1
+            u: NonNativeUintVar::new_constant(cs.clone(), C1::BaseField::one())?,
+            // cf1_u_i.cmW is provided by the prover as witness
+            cmW: C2::Var::new_witness(cs.clone(), || Ok(self.cf1_u_i_cmW.unwrap_or(C2::zero())))?,
+            // cf1_u_i.x is computed in step 1
+            x: cfW_x,
+        };
+        let cf2_u_i = CycleFoldCommittedInstanceVar {
+            // cf2_u_i.cmE = 0
+            cmE: C2::Var::zero(),
+            // cf2_u_i.u = 1
+            u: NonNativeUintVar::new_constant(cs.clone(), C1::BaseField::one())?,
+            // cf2_u_i.cmW is provided by the prover as witness
+            cmW: C2::Var::new_witness(cs.clone(), || Ok(self.cf2_u_i_cmW.unwrap_or(C2::zero())))?,
+            // cf2_u_i.x is computed in step 1
+            x: cfE_x,
+        };
+
+        // C.3. nifs.verify, obtains cf1_U_{i+1} by folding cf1_u_i & cf_U_i, and then cf_U_{i+1}
+        // by folding cf2_u_i & cf1_U_{i+1}.
+        let cf1_r_bits = CycleFoldChallengeGadget::<C2>::get_challenge_gadget(
+            &mut transcript,
+            pp_hash.clone(),
+            cf_U_i_vec,
+            cf1_u_i.clone(),
+            cf1_cmT.clone(),
+        )?;
+        let cf1_U_i1 =
+            NIFSFullGadget::<C2>::fold_committed_instance(cf1_r_bits, cf1_cmT, cf_U_i, cf1_u_i)?;
+
+        let cf2_r_bits = CycleFoldChallengeGadget::<C2>::get_challenge_gadget(
+            &mut transcript,
+            pp_hash.clone(),
+            cf1_U_i1.to_native_sponge_field_elements()?,
+            cf2_u_i.clone(),
+            cf2_cmT.clone(),
+        )?;
+        let cf_U_i1 =
+            NIFSFullGadget::<C2>::fold_committed_instance(cf2_r_bits, cf2_cmT, cf1_U_i1, cf2_u_i)?;
+
+        // Back to Primary Part
+        // P.5.b compute and check the second output of F'
+        // Base case: u_{i+1}.x[1] == H(cf_U_{\bot})
+        // Non-base case: u_{i+1}.x[1] == H(cf_U_{i+1})
+        let (cf_u_i1_x, _) = cf_U_i1.clone().hash(&sponge, pp_hash.clone())?;
+        let (cf_u_i1_x_base, _) =
+            CycleFoldCommittedInstanceVar::<C2>::new_constant(cs.clone(), cf_u_dummy)?
+                .hash(&sponge, pp_hash)?;
+        let cf_x = is_basecase.select(&cf_u_i1_x_base, &cf_u_i1_x)?;
+        FpVar::new_input(cs.clone(), || cf_x.value())?.enforce_equal(&cf_x)?;
+
+        Ok(z_i1)
+    }
+}
+
+impl<C1, C2, FC, S, T> ConstraintSynthesizer<CF1<C1>> for AugmentedFCircuit<C1, C2, FC, S, T>
+where
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: Curve,
+    FC: FCircuit<CF1<C1>>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
+{
+    fn generate_constraints(self, cs: ConstraintSystemRef<CF1<C1>>) -> Result<(), SynthesisError> {
+        self.compute_next_state(cs).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_bn254::{Fr, G1Projective as Projective};
+    use ark_crypto_primitives::sponge::{
+        constraints::CryptographicSpongeVar,
+        poseidon::{constraints::PoseidonSpongeVar, PoseidonSponge},
+    };
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::UniformRand;
+
+    use crate::folding::supernova::hash_running_instances;
+    use crate::transcript::poseidon::poseidon_canonical_config;
+    use crate::Error;
+
+    // checks that the gadget and native implementations of the running instances hash match
+    #[test]
+    fn test_hash_running_instances() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let sponge = PoseidonSponge::<Fr>::new(&poseidon_config);
+
+        let U_i = (0..3)
+            .map(|_| CommittedInstance::<Projective> {
+                cmE: Projective::rand(&mut rng),
+                u: Fr::rand(&mut rng),
+                cmW: Projective::rand(&mut rng),
+                x: vec![Fr::rand(&mut rng); 2],
+            })
+            .collect::<Vec<_>>();
+        let pp_hash = Fr::from(42u32); // only for testing
+        let i = Fr::from(3u32);
+        let pc = 2;
+        let z_0 = vec![Fr::from(1u32), Fr::from(2u32)];
+        let z_i = vec![Fr::from(2u32), Fr::from(5u32)];
+
+        let h = hash_running_instances(&sponge, pp_hash, i, pc, &z_0, &z_i, &U_i);
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let spongeVar = PoseidonSpongeVar::<Fr>::new(cs.clone(), &poseidon_config);
+        let pp_hashVar = FpVar::<Fr>::new_witness(cs.clone(), || Ok(pp_hash))?;
+        let iVar = FpVar::<Fr>::new_witness(cs.clone(), || Ok(i))?;
+        let pcVar = FpVar::<Fr>::new_witness(cs.clone(), || Ok(Fr::from(pc as u64)))?;
+        let z_0Var = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(z_0.clone()))?;
+        let z_iVar = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(z_i.clone()))?;
+        let U_iVar =
+            Vec::<CommittedInstanceVar<Projective>>::new_witness(cs.clone(), || Ok(U_i.clone()))?;
+        let (hVar, _) = hash_running_instances_gadget(
+            &spongeVar,
+            &pp_hashVar,
+            &iVar,
+            &pcVar,
+            &z_0Var,
+            &z_iVar,
+            &U_iVar,
+        )?;
+        assert!(cs.is_satisfied()?);
+        assert_eq!(hVar.value()?, h);
+        Ok(())
+    }
+}
@@ -0,0 +1,1048 @@
+/// Implements a non-uniform IVC following the approach of
+/// [SuperNova](https://eprint.iacr.org/2022/1758.pdf), on top of the Nova NIFS and
+/// [CycleFold](https://eprint.iacr.org/2023/1192.pdf).
+///
+/// The prover registers a set of step circuits `F_0, ..., F_{l-1}`, and at each step the program
+/// counter `pc = z_i[0]` (the first element of the IVC state) selects which of them is executed.
+/// The circuit `F_{pc}` is in charge of setting the program counter for the next step in
+/// `z_{i+1}[0]`. A running instance is kept for each of the step circuits, so that at each step
+/// only the constraints of the executed circuit are paid for.
+use ark_crypto_primitives::sponge::poseidon::{
+    constraints::PoseidonSpongeVar, PoseidonConfig, PoseidonSponge,
+};
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::R1CSVar;
+use ark_relations::r1cs::ConstraintSystem;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Valid};
+use ark_std::{cmp::max, fmt::Debug, marker::PhantomData, rand::RngCore, One, Zero};
+
+use crate::arith::{
+    r1cs::{extract_w_x, R1CS},
+    Arith, ArithRelation,
+};
+use crate::commitment::CommitmentScheme;
+use crate::folding::{
+    circuits::{
+        cyclefold::{fold_cyclefold_circuit, CycleFoldCommittedInstance, CycleFoldWitness},
+        CF1,
+    },
+    nova::{
+        get_r1cs_from_cs,
+        nifs::{nova::NIFS, NIFSTrait},
+        CommittedInstance, NovaCycleFoldCircuit, NovaCycleFoldConfig, ProverParams, Witness,
+    },
+    traits::CommittedInstanceOps,
+};
+use crate::frontend::FCircuit;
+use crate::transcript::{Transcript, TranscriptVar};
+use crate::utils::pp_hash;
+use crate::{Curve, Error};
+
+pub mod circuits;
+
+use circuits::AugmentedFCircuit;
+
+/// Returns `H(pp_hash, i, pc, z_0, z_i, U_i[0], ..., U_i[l-1])`, where `pc` is the index of the
+/// step circuit that generated the instance that carries this hash, and `U_i` contains the
+/// running instances of all the step circuits.
+///
+/// It is compatible with the in-circuit implementation
+/// [`circuits::hash_running_instances_gadget`].
+pub fn hash_running_instances<C: Curve, T: Transcript<CF1<C>>>(
+    sponge: &T,
+    pp_hash: CF1<C>, // public params hash
+    i: CF1<C>,
+    pc: usize,
+    z_0: &[CF1<C>],
+    z_i: &[CF1<C>],
+    U_i: &[CommittedInstance<C>],
+) -> CF1<C> {
+    let mut sponge = sponge.clone();
+    sponge.absorb(&pp_hash);
+    sponge.absorb(&i);
+    sponge.absorb(&CF1::<C>::from(pc as u64));
+    sponge.absorb(&z_0);
+    sponge.absorb(&z_i);
+    for U in U_i.iter() {
+        sponge.absorb(U);
+    }
+    sponge.squeeze_field_elements(1)[0]
+}
+
+/// Preprocessing parameters for SuperNova, where `SC` is the configuration of the sponge used by
+/// the IVC (Poseidon by default).
+#[derive(Debug, Clone)]
+pub struct PreprocessorParam<
+    C1,
+    C2,
+    FC,
+    CS1,
+    CS2,
+    const H: bool = false,
+    SC = PoseidonConfig<CF1<C1>>,
+> where
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    pub poseidon_config: SC,
+    /// step circuits, where the i-th one is selected when the program counter is i
+    pub F: Vec<FC>,
+    // cs params if not provided, will be generated at the preprocess method
+    pub cs_pp: Option<CS1::ProverParams>,
+    pub cs_vp: Option<CS1::VerifierParams>,
+    pub cf_cs_pp: Option<CS2::ProverParams>,
+    pub cf_cs_vp: Option<CS2::VerifierParams>,
+}
+
+impl<C1, C2, FC, CS1, CS2, const H: bool, SC> PreprocessorParam<C1, C2, FC, CS1, CS2, H, SC>
+where
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    pub fn new(poseidon_config: SC, F: Vec<FC>) -> Self {
+        Self {
+            poseidon_config,
+            F,
+            cs_pp: None,
+            cs_vp: None,
+            cf_cs_pp: None,
+            cf_cs_vp: None,
+        }
+    }
+}
+
+/// Verification parameters for SuperNova-based non-uniform IVC
+#[derive(Debug, Clone)]
+pub struct VerifierParams<C1, C2, CS1, CS2, const H: bool = false, SC = PoseidonConfig<CF1<C1>>>
+where
+    C1: Curve,
+    C2: Curve,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    /// Sponge configuration
+    pub poseidon_config: SC,
+    /// R1CS of the Augmented step circuits, one for each of the step circuits
+    pub r1cs: Vec<R1CS<C1::ScalarField>>,
+    /// R1CS of the CycleFold circuit
+    pub cf_r1cs: R1CS<C2::ScalarField>,
+    /// Verification parameters of the underlying commitment scheme over C1
+    pub cs_vp: CS1::VerifierParams,
+    /// Verification parameters of the underlying commitment scheme over C2
+    pub cf_cs_vp: CS2::VerifierParams,
+}
+
+impl<C1, C2, CS1, CS2, const H: bool, SC> Valid for VerifierParams<C1, C2, CS1, CS2, H, SC>
+where
+    C1: Curve,
+    C2: Curve,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    SC: Valid,
+{
+    fn check(&self) -> Result<(), ark_serialize::SerializationError> {
+        self.cs_vp.check()?;
+        self.cf_cs_vp.check()?;
+        Ok(())
+    }
+}
+impl<C1, C2, CS1, CS2, const H: bool, SC> CanonicalSerialize
+    for VerifierParams<C1, C2, CS1, CS2, H, SC>
+where
+    C1: Curve,
+    C2: Curve,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    fn serialize_with_mode<W: std::io::prelude::Write>(
+        &self,
+        mut writer: W,
+        compress: ark_serialize::Compress,
+    ) -> Result<(), ark_serialize::SerializationError> {
+        self.cs_vp.serialize_with_mode(&mut writer, compress)?;
+        self.cf_cs_vp.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: ark_serialize::Compress) -> usize {
+        self.cs_vp.serialized_size(compress) + self.cf_cs_vp.serialized_size(compress)
+    }
+}
+
+impl<C1, C2, CS1, CS2, const H: bool, SC> VerifierParams<C1, C2, CS1, CS2, H, SC>
+where
+    C1: Curve,
+    C2: Curve,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    SC: CanonicalSerialize,
+{
+    /// returns the hash of the public parameters of SuperNova
+    pub fn pp_hash(&self) -> Result<C1::ScalarField, Error> {
+        pp_hash::<C1, C2, CS1, CS2, H>(
+            &self.r1cs,
+            &self.cf_r1cs,
+            &self.cs_vp,
+            &self.cf_cs_vp,
+            &self.poseidon_config,
+        )
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct IVCProof<C1, C2>
+where
+    C1: Curve,
+    C2: Curve,
+{
+    // current step of the IVC
+    pub i: C1::ScalarField,
+    // index of the step circuit that generated the incoming instance
+    pub prev_pc: usize,
+    // initial state
+    pub z_0: Vec<C1::ScalarField>,
+    // current state
+    pub z_i: Vec<C1::ScalarField>,
+    // running instances, one for each step circuit
+    pub W_i: Vec<Witness<C1>>,
+    pub U_i: Vec<CommittedInstance<C1>>,
+    // incoming instance
+    pub w_i: Witness<C1>,
+    pub u_i: CommittedInstance<C1>,
+    // CycleFold instances
+    pub cf_W_i: CycleFoldWitness<C2>,
+    pub cf_U_i: CycleFoldCommittedInstance<C2>,
+}
+
+/// Implements SuperNova+CycleFold's non-uniform IVC, where the step circuit executed at each step
+/// is selected by the program counter `z_i[0]`.
+/// The `H` const generic specifies whether the homorphic commitment scheme is blinding
+/// The sponge `S` (Poseidon by default) is used for the transcripts and the hashes of the IVC,
+/// and `T` is its in-circuit counterpart, used in the augmented circuits.
+pub struct SuperNova<
+    C1,
+    C2,
+    FC,
+    CS1,
+    CS2,
+    const H: bool = false,
+    S = PoseidonSponge<CF1<C1>>,
+    T = PoseidonSpongeVar<CF1<C1>>,
+> where
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
+{
+    /// R1CS of the Augmented Function circuits, one for each step circuit
+    pub r1cs: Vec<R1CS<C1::ScalarField>>,
+    /// R1CS of the CycleFold circuit
+    pub cf_r1cs: R1CS<C2::ScalarField>,
+    pub poseidon_config: S::Config,
+    /// CommitmentScheme::ProverParams over C1
+    pub cs_pp: CS1::ProverParams,
+    /// CycleFold CommitmentScheme::ProverParams, over C2
+    pub cf_cs_pp: CS2::ProverParams,
+    /// F circuits, the step circuits that are being folded
+    pub F: Vec<FC>,
+    /// public params hash
+    pub pp_hash: C1::ScalarField,
+    pub i: C1::ScalarField,
+    /// index of the step circuit that generated the incoming instance `u_i`
+    pub prev_pc: usize,
+    /// initial state
+    pub z_0: Vec<C1::ScalarField>,
+    /// current i-th state
+    pub z_i: Vec<C1::ScalarField>,
+    /// incoming instance
+    pub w_i: Witness<C1>,
+    pub u_i: CommittedInstance<C1>,
+    /// running instances, one for each step circuit
+    pub W_i: Vec<Witness<C1>>,
+    pub U_i: Vec<CommittedInstance<C1>>,
+
+    /// CycleFold running instance
+    pub cf_W_i: CycleFoldWitness<C2>,
+    pub cf_U_i: CycleFoldCommittedInstance<C2>,
+
+    _s: PhantomData<S>,
+    _t: PhantomData<T>,
+}
+
+// `Clone` and `Debug` are implemented manually instead of derived, since deriving them would
+// require the sponges `S` and `T` to implement them too.
+impl<C1, C2, FC, CS1, CS2, const H: bool, S, T> Clone for SuperNova<C1, C2, FC, CS1, CS2, H, S, T>
+where
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            r1cs: self.r1cs.clone(),
+            cf_r1cs: self.cf_r1cs.clone(),
+            poseidon_config: self.poseidon_config.clone(),
+            cs_pp: self.cs_pp.clone(),
+            cf_cs_pp: self.cf_cs_pp.clone(),
+            F: self.F.clone(),
+            pp_hash: self.pp_hash,
+            i: self.i,
+            prev_pc: self.prev_pc,
+            z_0: self.z_0.clone(),
+            z_i: self.z_i.clone(),
+            w_i: self.w_i.clone(),
+            u_i: self.u_i.clone(),
+            W_i: self.W_i.clone(),
+            U_i: self.U_i.clone(),
+            cf_W_i: self.cf_W_i.clone(),
+            cf_U_i: self.cf_U_i.clone(),
+            _s: PhantomData,
+            _t: PhantomData,
+        }
+    }
+}
+
+impl<C1, C2, FC, CS1, CS2, const H: bool, S, T> Debug for SuperNova<C1, C2, FC, CS1, CS2, H, S, T>
+where
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
+{
+    fn fmt(&self, f: &mut ark_std::fmt::Formatter<'_>) -> ark_std::fmt::Result {
+        f.debug_struct("SuperNova")
+            .field("r1cs", &self.r1cs)
+            .field("cf_r1cs", &self.cf_r1cs)
+            .field("poseidon_config", &self.poseidon_config)
+            .field("cs_pp", &self.cs_pp)
+            .field("cf_cs_pp", &self.cf_cs_pp)
+            .field("F", &self.F)
+            .field("pp_hash", &self.pp_hash)
+            .field("i", &self.i)
+            .field("prev_pc", &self.prev_pc)
+            .field("z_0", &self.z_0)
+            .field("z_i", &self.z_i)
+            .field("w_i", &self.w_i)
+            .field("u_i", &self.u_i)
+            .field("W_i", &self.W_i)
+            .field("U_i", &self.U_i)
+            .field("cf_W_i", &self.cf_W_i)
+            .field("cf_U_i", &self.cf_U_i)
+            .finish()
+    }
+}
+
+impl<C1, C2, FC, CS1, CS2, const H: bool, S, T> SuperNova<C1, C2, FC, CS1, CS2, H, S, T>
+where
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+{
+    /// deserialize the VerifierParams, recomputing the R1CS of each of the step circuits from the
+    /// given FCircuit params, in the same order in which the circuits were registered, and the
+    /// given sponge config, which has to be the one used at preprocessing.
+    pub fn vp_deserialize_with_mode<R: std::io::prelude::Read>(
+        mut reader: R,
+        compress: ark_serialize::Compress,
+        validate: ark_serialize::Validate,
+        poseidon_config: S::Config,
+        fc_params: Vec<FC::Params>,
+    ) -> Result<VerifierParams<C1, C2, CS1, CS2, H, S::Config>, Error> {
+        let F = fc_params
+            .into_iter()
+            .map(FC::new)
+            .collect::<Result<Vec<_>, _>>()?;
+        let (r1cs, cf_r1cs) = get_r1cs::<C1, C2, FC, S, T>(&poseidon_config, &F)?;
+
+        let cs_vp = CS1::VerifierParams::deserialize_with_mode(&mut reader, compress, validate)?;
+        let cf_cs_vp = CS2::VerifierParams::deserialize_with_mode(&mut reader, compress, validate)?;
+
+        Ok(VerifierParams {
+            poseidon_config,
+            r1cs,
+            cf_r1cs,
+            cs_vp,
+            cf_cs_vp,
+        })
+    }
+
+    #[allow(clippy::type_complexity)]
+    pub fn preprocess(
+        mut rng: impl RngCore,
+        prep_param: &PreprocessorParam<C1, C2, FC, CS1, CS2, H, S::Config>,
+    ) -> Result<
+        (
+            ProverParams<C1, C2, CS1, CS2, H, S::Config>,
+            VerifierParams<C1, C2, CS1, CS2, H, S::Config>,
+        ),
+        Error,
+    > {
+        let (r1cs, cf_r1cs) =
+            get_r1cs::<C1, C2, FC, S, T>(&prep_param.poseidon_config, &prep_param.F)?;
+
+        // if cs params exist, use them, if not, generate new ones
+        let (cs_pp, cs_vp) = match (&prep_param.cs_pp, &prep_param.cs_vp) {
+            (Some(cs_pp), Some(cs_vp)) => (cs_pp.clone(), cs_vp.clone()),
+            _ => CS1::setup(
+                &mut rng,
+                // `CS1` is used for the witnesses and error terms of all the step circuits, so
+                // we set `len` to the biggest of them.
+                r1cs.iter()
+                    .map(|r1cs| max(r1cs.n_constraints(), r1cs.n_witnesses()))
+                    .max()
+                    .unwrap_or_default(),
+            )?,
+        };
+        let (cf_cs_pp, cf_cs_vp) = match (&prep_param.cf_cs_pp, &prep_param.cf_cs_vp) {
+            (Some(cf_cs_pp), Some(cf_cs_vp)) => (cf_cs_pp.clone(), cf_cs_vp.clone()),
+            _ => CS2::setup(
+                &mut rng,
+                max(cf_r1cs.n_constraints(), cf_r1cs.n_witnesses()),
+            )?,
+        };
+
+        let prover_params = ProverParams::<C1, C2, CS1, CS2, H, S::Config> {
+            poseidon_config: prep_param.poseidon_config.clone(),
+            cs_pp,
+            cf_cs_pp,
+        };
+        let verifier_params = VerifierParams::<C1, C2, CS1, CS2, H, S::Config> {
+            poseidon_config: prep_param.poseidon_config.clone(),
+            r1cs,
+            cf_r1cs,
+            cs_vp,
+            cf_cs_vp,
+        };
+
+        Ok((prover_params, verifier_params))
+    }
+
+    /// Initializes the SuperNova+CycleFold's IVC for the given parameters, step circuits and
+    /// initial state `z_0`, where `z_0[0]` is the index of the first step circuit to be executed.
+    #[allow(clippy::type_complexity)]
+    pub fn init(
+        params: &(
+            ProverParams<C1, C2, CS1, CS2, H, S::Config>,
+            VerifierParams<C1, C2, CS1, CS2, H, S::Config>,
+        ),
+        F: Vec<FC>,
+        z_0: Vec<C1::ScalarField>,
+    ) -> Result<Self, Error> {
+        let (pp, vp) = params;
+
+        if F.is_empty() {
+            return Err(Error::Empty);
+        }
+        if F.len() != vp.r1cs.len() {
+            return Err(Error::NotSameLength(
+                "F.len()".to_string(),
+                F.len(),
+                "vp.r1cs.len()".to_string(),
+                vp.r1cs.len(),
+            ));
+        }
+        let (r1cs, cf_r1cs) = get_r1cs::<C1, C2, FC, S, T>(&pp.poseidon_config, &F)?;
+        // the step circuits have to be the ones (and in the same order) used at preprocessing
+        if let Some(j) = (0..r1cs.len()).find(|&j| r1cs[j] != vp.r1cs[j]) {
+            return Err(Error::Other(format!(
+                "the R1CS of step circuit {} does not match the one in the VerifierParams",
+                j
+            )));
+        }
+
+        // compute the public params hash
+        let pp_hash = vp.pp_hash()?;
+
+        // setup the dummy instances, one running instance for each step circuit
+        let (W_dummy, U_dummy): (Vec<Witness<C1>>, Vec<CommittedInstance<C1>>) = r1cs
+            .iter()
+            .map(|r1cs| r1cs.dummy_witness_instance())
+            .unzip();
+        // the dummy incoming instance is considered to be generated by the first circuit
+        let (w_dummy, u_dummy) = r1cs[0].dummy_witness_instance();
+        let (cf_W_dummy, cf_U_dummy) = cf_r1cs.dummy_witness_instance();
+
+        let supernova = Self {
+            r1cs,
+            cf_r1cs,
+            poseidon_config: pp.poseidon_config.clone(),
+            cs_pp: pp.cs_pp.clone(),
+            cf_cs_pp: pp.cf_cs_pp.clone(),
+            F,
+            pp_hash,
+            i: C1::ScalarField::zero(),
+            prev_pc: 0,
+            z_0: z_0.clone(),
+            z_i: z_0,
+            w_i: w_dummy,
+            u_i: u_dummy,
+            W_i: W_dummy,
+            U_i: U_dummy,
+            // cyclefold running instance
+            cf_W_i: cf_W_dummy,
+            cf_U_i: cf_U_dummy,
+            _s: PhantomData,
+            _t: PhantomData,
+        };
+        // check that z_0 contains a valid program counter
+        supernova.pc()?;
+        Ok(supernova)
+    }
+
+    /// Returns the program counter of the current state, that is, the index of the step circuit
+    /// that will be executed at the next call to `prove_step`.
+    pub fn pc(&self) -> Result<usize, Error> {
+        let pc = self.z_i.first().ok_or(Error::Empty)?;
+        (0..self.F.len())
+            .find(|k| C1::ScalarField::from(*k as u64) == *pc)
+            .ok_or(Error::OutOfBounds)
+    }
+
+    /// Implements IVC.P of SuperNova+CycleFold, running the step circuit selected by the program
+    /// counter `z_i[0]`.
+    pub fn prove_step(
+        &mut self,
+        mut rng: impl RngCore,
+        external_inputs: FC::ExternalInputs,
+    ) -> Result<(), Error> {
+        // ensure that commitments are blinding if user has specified so. Notice that only the
+        // incoming instance is checked, since the running instances of the step circuits that
+        // have not been executed yet are still the dummy ones, and that its E is zero, so it is
+        // not blinded.
+        if H && self.i >= C1::ScalarField::one() {
+            let blinding_commitments = vec![self.w_i.rW];
+            if blinding_commitments.contains(&C1::ScalarField::zero()) {
+                return Err(Error::IncorrectBlinding(
+                    H,
+                    format!("{:?}", blinding_commitments),
+                ));
+            }
+        }
+        // `sponge` is for digest computation.
+        let sponge = S::new(&self.poseidon_config);
+        // `transcript` is for challenge generation.
+        let mut transcript = sponge.clone();
+
+        let pc = self.pc()?;
+        let prev_pc = self.prev_pc;
+
+        if self.z_i.len() != self.F[pc].state_len() {
+            return Err(Error::NotSameLength(
+                "z_i.len()".to_string(),
+                self.z_i.len(),
+                "F.state_len()".to_string(),
+                self.F[pc].state_len(),
+            ));
+        }
+
+        if self.i > C1::ScalarField::from_le_bytes_mod_order(&usize::MAX.to_le_bytes()) {
+            return Err(Error::MaxStep);
+        }
+
+        let i_usize;
+
+        #[cfg(target_pointer_width = "64")]
+        {
+            let mut i_bytes: [u8; 8] = [0; 8];
+            i_bytes.copy_from_slice(&self.i.into_bigint().to_bytes_le()[..8]);
+            i_usize = usize::from_le_bytes(i_bytes);
+        }
+
+        #[cfg(target_pointer_width = "32")]
+        {
+            let mut i_bytes: [u8; 4] = [0; 4];
+            i_bytes.copy_from_slice(&self.i.into_bigint().to_bytes_le()[..4]);
+            i_usize = usize::from_le_bytes(i_bytes);
+        }
+
+        // fold the incoming instance into the running instance of the circuit that generated it
+        let (W_i1, U_i1, cmT, r_bits): (Witness<C1>, CommittedInstance<C1>, C1, Vec<bool>) =
+            NIFS::<C1, CS1, S, H>::prove(
+                &self.cs_pp,
+                &self.r1cs[prev_pc],
+                &mut transcript,
+                self.pp_hash,
+                &self.W_i[prev_pc],
+                &self.U_i[prev_pc],
+                &self.w_i,
+                &self.u_i,
+            )?;
+
+        let mut augmented_F_circuit = AugmentedFCircuit::<C1, C2, FC, S, T> {
+            poseidon_config: self.poseidon_config.clone(),
+            pp_hash: Some(self.pp_hash),
+            i: Some(self.i),
+            i_usize: Some(i_usize),
+            prev_pc: Some(prev_pc),
+            z_0: Some(self.z_0.clone()),
+            z_i: Some(self.z_i.clone()),
+            external_inputs: Some(external_inputs),
+            u_i_cmW: Some(self.u_i.cmW),
+            U_i: Some(self.U_i.clone()),
+            U_i1_cmE: Some(U_i1.cmE),
+            U_i1_cmW: Some(U_i1.cmW),
+            cmT: Some(cmT),
+            F: self.F[pc].clone(),
+            pc,
+            n_circuits: self.F.len(),
+            cf1_u_i_cmW: None,
+            cf2_u_i_cmW: None,
+            cf_U_i: None,
+            cf1_cmT: None,
+            cf2_cmT: None,
+            _s: PhantomData,
+            _t: PhantomData,
+        };
+
+        if self.i != C1::ScalarField::zero() {
+            // CycleFold part:
+            let cfW_circuit = NovaCycleFoldCircuit::<C1> {
+                r_bits: Some(r_bits.clone()),
+                points: Some(vec![self.U_i[prev_pc].cmW, self.u_i.cmW]),
+            };
+            let cfE_circuit = NovaCycleFoldCircuit::<C1> {
+                r_bits: Some(r_bits.clone()),
+                points: Some(vec![self.U_i[prev_pc].cmE, cmT]),
+            };
+
+            // fold self.cf_U_i + cfW_U -> folded running with cfW
+            let (cfW_u_i, cfW_W_i1, cfW_U_i1, cfW_cmT) =
+                fold_cyclefold_circuit::<NovaCycleFoldConfig<C1>, C2, CS2, H>(
+                    &mut transcript,
+                    self.cf_r1cs.clone(),
+                    self.cf_cs_pp.clone(),
+                    self.pp_hash,
+                    self.cf_W_i.clone(),
+                    self.cf_U_i.clone(),
+                    cfW_circuit,
+                    &mut rng,
+                )?;
+            // fold [the output from folding self.cf_U_i + cfW_U] + cfE_U = folded_running_with_cfW + cfE
+            let (cfE_u_i, cf_W_i1, cf_U_i1, cf_cmT) =
+                fold_cyclefold_circuit::<NovaCycleFoldConfig<C1>, C2, CS2, H>(
+                    &mut transcript,
+                    self.cf_r1cs.clone(),
+                    self.cf_cs_pp.clone(),
+                    self.pp_hash,
+                    cfW_W_i1,
+                    cfW_U_i1,
+                    cfE_circuit,
+                    &mut rng,
+                )?;
+
+            augmented_F_circuit.cf1_u_i_cmW = Some(cfW_u_i.cmW);
+            augmented_F_circuit.cf2_u_i_cmW = Some(cfE_u_i.cmW);
+            augmented_F_circuit.cf_U_i = Some(self.cf_U_i.clone());
+            augmented_F_circuit.cf1_cmT = Some(cfW_cmT);
+            augmented_F_circuit.cf2_cmT = Some(cf_cmT);
+
+            self.cf_W_i = cf_W_i1;
+            self.cf_U_i = cf_U_i1;
+        }
+
+        let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
+
+        let z_i1 = augmented_F_circuit
+            .compute_next_state(cs.clone())?
+            .value()?;
+
+        #[cfg(test)]
+        assert!(cs.is_satisfied()?);
+
+        let cs = cs.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
+        let (w_i1, x_i1) = extract_w_x::<C1::ScalarField>(&cs);
+
+        #[cfg(test)]
+        if x_i1.len() != 2 {
+            return Err(Error::NotExpectedLength(x_i1.len(), 2));
+        }
+
+        // set values for next iteration
+        self.i += C1::ScalarField::one();
+        self.z_i = z_i1;
+        self.w_i = Witness::<C1>::new::<H>(w_i1, self.r1cs[pc].n_constraints(), &mut rng);
+        self.u_i = self.w_i.commit::<CS1, H>(&self.cs_pp, x_i1)?;
+        self.W_i[prev_pc] = W_i1;
+        self.U_i[prev_pc] = U_i1;
+        self.prev_pc = pc;
+
+        #[cfg(test)]
+        {
+            self.u_i.check_incoming()?;
+            self.r1cs[pc].check_relation(&self.w_i, &self.u_i)?;
+            self.r1cs[prev_pc].check_relation(&self.W_i[prev_pc], &self.U_i[prev_pc])?;
+        }
+
+        Ok(())
+    }
+
+    /// returns the state at the current step
+    pub fn state(&self) -> Vec<C1::ScalarField> {
+        self.z_i.clone()
+    }
+
+    /// returns the last IVC state proof, which can be verified in the `verify` method
+    pub fn ivc_proof(&self) -> IVCProof<C1, C2> {
+        IVCProof {
+            i: self.i,
+            prev_pc: self.prev_pc,
+            z_0: self.z_0.clone(),
+            z_i: self.z_i.clone(),
+            W_i: self.W_i.clone(),
+            U_i: self.U_i.clone(),
+            w_i: self.w_i.clone(),
+            u_i: self.u_i.clone(),
+            cf_W_i: self.cf_W_i.clone(),
+            cf_U_i: self.cf_U_i.clone(),
+        }
+    }
+
+    /// constructs the SuperNova instance from the given IVCProof, the params of each of the step
+    /// circuits, and the ProverParams & VerifierParams.
+    #[allow(clippy::type_complexity)]
+    pub fn from_ivc_proof(
+        ivc_proof: IVCProof<C1, C2>,
+        fcircuit_params: Vec<FC::Params>,
+        params: (
+            ProverParams<C1, C2, CS1, CS2, H, S::Config>,
+            VerifierParams<C1, C2, CS1, CS2, H, S::Config>,
+        ),
+    ) -> Result<Self, Error> {
+        let IVCProof {
+            i,
+            prev_pc,
+            z_0,
+            z_i,
+            W_i,
+            U_i,
+            w_i,
+            u_i,
+            cf_W_i,
+            cf_U_i,
+        } = ivc_proof;
+        let (pp, vp) = params;
+
+        let F = fcircuit_params
+            .into_iter()
+            .map(FC::new)
+            .collect::<Result<Vec<_>, _>>()?;
+        let (r1cs, cf_r1cs) = get_r1cs::<C1, C2, FC, S, T>(&pp.poseidon_config, &F)?;
+
+        if W_i.len() != F.len() || U_i.len() != F.len() {
+            return Err(Error::NotSameLength(
+                "U_i.len()".to_string(),
+                U_i.len(),
+                "F.len()".to_string(),
+                F.len(),
+            ));
+        }
+
+        Ok(Self {
+            r1cs,
+            cf_r1cs,
+            poseidon_config: pp.poseidon_config,
+            cs_pp: pp.cs_pp,
+            cf_cs_pp: pp.cf_cs_pp,
+            F,
+            pp_hash: vp.pp_hash()?,
+            i,
+            prev_pc,
+            z_0,
+            z_i,
+            w_i,
+            u_i,
+            W_i,
+            U_i,
+            cf_W_i,
+            cf_U_i,
+            _s: PhantomData,
+            _t: PhantomData,
+        })
+    }
+
+    /// Implements IVC.V of SuperNova+CycleFold. Notice that this method does not include the
+    /// commitments verification.
+    pub fn verify(
+        vp: VerifierParams<C1, C2, CS1, CS2, H, S::Config>,
+        ivc_proof: IVCProof<C1, C2>,
+    ) -> Result<(), Error> {
+        let IVCProof {
+            i: num_steps,
+            prev_pc,
+            z_0,
+            z_i,
+            W_i,
+            U_i,
+            w_i,
+            u_i,
+            cf_W_i,
+            cf_U_i,
+        } = ivc_proof;
+
+        let sponge = S::new(&vp.poseidon_config);
+
+        if num_steps == C1::ScalarField::zero() {
+            if z_0 != z_i {
+                return Err(Error::IVCVerificationFail);
+            }
+            return Ok(());
+        }
+
+        if W_i.len() != vp.r1cs.len() || U_i.len() != vp.r1cs.len() || prev_pc >= vp.r1cs.len() {
+            return Err(Error::IVCVerificationFail);
+        }
+
+        if u_i.x.len() != 2 || U_i.iter().any(|U| U.x.len() != 2) {
+            return Err(Error::IVCVerificationFail);
+        }
+
+        let pp_hash = vp.pp_hash()?;
+
+        // check that u_i's output points to the running instances
+        // u_i.X[0] == H(i, prev_pc, z_0, z_i, U_i)
+        let expected_u_i_x =
+            hash_running_instances(&sponge, pp_hash, num_steps, prev_pc, &z_0, &z_i, &U_i);
+        if expected_u_i_x != u_i.x[0] {
+            return Err(Error::IVCVerificationFail);
+        }
+        // u_i.X[1] == H(cf_U_i)
+        let expected_cf_u_i_x = cf_U_i.hash_cyclefold(&sponge, pp_hash);
+        if expected_cf_u_i_x != u_i.x[1] {
+            return Err(Error::IVCVerificationFail);
+        }
+
+        // check R1CS satisfiability of the incoming instance w.r.t. the R1CS of the circuit that
+        // generated it
+        u_i.check_incoming()?;
+        vp.r1cs[prev_pc].check_relation(&w_i, &u_i)?;
+        // check RelaxedR1CS satisfiability of each of the running instances
+        for ((r1cs, W), U) in vp.r1cs.iter().zip(&W_i).zip(&U_i) {
+            r1cs.check_relation(W, U)?;
+        }
+
+        // check CycleFold RelaxedR1CS satisfiability
+        vp.cf_r1cs.check_relation(&cf_W_i, &cf_U_i)?;
+
+        Ok(())
+    }
+}
+
+/// helper method to get the R1CS for each of the AugmentedFCircuits and the CycleFold circuit
+#[allow(clippy::type_complexity)]
+pub fn get_r1cs<C1, C2, FC, S, T>(
+    poseidon_config: &S::Config,
+    F_circuits: &[FC],
+) -> Result<(Vec<R1CS<C1::ScalarField>>, R1CS<C2::ScalarField>), Error>
+where
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    S: Transcript<CF1<C1>>,
+    T: TranscriptVar<CF1<C1>, S>,
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+{
+    let r1cs = F_circuits
+        .iter()
+        .enumerate()
+        .map(|(pc, F_circuit)| {
+            get_r1cs_from_cs::<C1::ScalarField>(AugmentedFCircuit::<C1, C2, FC, S, T>::empty(
+                poseidon_config,
+                F_circuit.clone(),
+                pc,
+                F_circuits.len(),
+            ))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let cf_r1cs = get_r1cs_from_cs::<C2::ScalarField>(NovaCycleFoldCircuit::<C1>::empty())?;
+    Ok((r1cs, cf_r1cs))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use ark_bn254::{Fr, G1Projective as Projective};
+    use ark_grumpkin::Projective as Projective2;
+    use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar};
+    use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+
+    use super::*;
+    use crate::commitment::pedersen::Pedersen;
+    use crate::transcript::poseidon::poseidon_canonical_config;
+
+    /// `OpFCircuit` is a toy instruction set with two opcodes, where the state is `[pc, x]` and the
+    /// next program counter is provided by the prover as external input:
+    /// - opcode 0 computes `x^3 + x + 5`
+    /// - opcode 1 computes `2x`
+    #[derive(Clone, Copy, Debug)]
+    pub struct OpFCircuit {
+        op: usize,
+    }
+    impl FCircuit<Fr> for OpFCircuit {
+        type Params = usize;
+        type ExternalInputs = Fr;
+        type ExternalInputsVar = FpVar<Fr>;
+
+        fn new(op: Self::Params) -> Result<Self, Error> {
+            Ok(Self { op })
+        }
+        fn state_len(&self) -> usize {
+            2
+        }
+        fn generate_step_constraints(
+            &self,
+            cs: ConstraintSystemRef<Fr>,
+            _i: usize,
+            z_i: Vec<FpVar<Fr>>,
+            next_pc: Self::ExternalInputsVar,
+        ) -> Result<Vec<FpVar<Fr>>, SynthesisError> {
+            let x = z_i[1].clone();
+            let x1 = match self.op {
+                0 => &x * &x * &x + &x + FpVar::<Fr>::new_constant(cs.clone(), Fr::from(5u32))?,
+                _ => &x + &x,
+            };
+            Ok(vec![next_pc, x1])
+        }
+    }
+
+    fn op_step_native(op: usize, x: Fr) -> Fr {
+        match op {
+            0 => x * x * x + x + Fr::from(5u32),
+            _ => x + x,
+        }
+    }
+
+    type SN = SuperNova<
+        Projective,
+        Projective2,
+        OpFCircuit,
+        Pedersen<Projective>,
+        Pedersen<Projective2>,
+        false,
+    >;
+
+    #[test]
+    fn test_supernova_ivc() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        let F = vec![OpFCircuit::new(0)?, OpFCircuit::new(1)?];
+        let prep_param = PreprocessorParam::new(poseidon_config.clone(), F.clone());
+        let params = SN::preprocess(&mut rng, &prep_param)?;
+
+        // the program executes the opcodes in the following order
+        let program = [0, 0, 1, 0, 1, 1];
+        let x_0 = Fr::from(3u32);
+        let z_0 = vec![Fr::from(program[0] as u64), x_0];
+        let mut supernova = SN::init(&params, F, z_0)?;
+
+        let mut x = x_0;
+        for (step, op) in program.iter().enumerate() {
+            assert_eq!(supernova.pc()?, *op);
+            let next_pc = program.get(step + 1).copied().unwrap_or(0);
+            supernova.prove_step(&mut rng, Fr::from(next_pc as u64))?;
+            x = op_step_native(*op, x);
+        }
+        assert_eq!(supernova.state(), vec![Fr::zero(), x]);
+        assert_eq!(Fr::from(program.len() as u64), supernova.i);
+
+        // serialize the verifier params and the IVCProof, and deserialize them to verify
+        let mut vp_serialized = vec![];
+        params.1.serialize_compressed(&mut vp_serialized)?;
+        let vp_deserialized = SN::vp_deserialize_with_mode(
+            &mut vp_serialized.as_slice(),
+            ark_serialize::Compress::Yes,
+            ark_serialize::Validate::Yes,
+            poseidon_config,
+            vec![0, 1],
+        )?;
+
+        let ivc_proof = supernova.ivc_proof();
+        let mut ivc_proof_serialized = vec![];
+        ivc_proof.serialize_compressed(&mut ivc_proof_serialized)?;
+        let ivc_proof_deserialized = IVCProof::<Projective, Projective2>::deserialize_compressed(
+            ivc_proof_serialized.as_slice(),
+        )?;
+        assert_eq!(ivc_proof, ivc_proof_deserialized);
+
+        SN::verify(vp_deserialized, ivc_proof_deserialized)?;
+
+        // continue the IVC from the IVCProof
+        let mut supernova = SN::from_ivc_proof(ivc_proof, vec![0, 1], params.clone())?;
+        supernova.prove_step(&mut rng, Fr::from(1u32))?;
+        SN::verify(params.1, supernova.ivc_proof())?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_supernova_wrong_pc() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        let F = vec![OpFCircuit::new(0)?, OpFCircuit::new(1)?];
+        let prep_param = PreprocessorParam::new(poseidon_config, F.clone());
+        let params = SN::preprocess(&mut rng, &prep_param)?;
+
+        // the program counter must point to one of the registered circuits
+        let z_0 = vec![Fr::from(2u32), Fr::from(3u32)];
+        assert!(SN::init(&params, F.clone(), z_0).is_err());
+
+        let z_0 = vec![Fr::zero(), Fr::from(3u32)];
+        let mut supernova = SN::init(&params, F, z_0)?;
+        supernova.prove_step(&mut rng, Fr::one())?;
+        supernova.prove_step(&mut rng, Fr::zero())?;
+
+        // claiming that the incoming instance was generated by a different circuit must fail
+        let mut ivc_proof = supernova.ivc_proof();
+        ivc_proof.prev_pc = 0;
+        assert!(SN::verify(params.1.clone(), ivc_proof).is_err());
+
+        SN::verify(params.1, supernova.ivc_proof())?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_supernova_init_mismatched_circuits() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        let F = vec![OpFCircuit::new(0)?, OpFCircuit::new(1)?];
+        let prep_param = PreprocessorParam::new(poseidon_config, F);
+        let params = SN::preprocess(&mut rng, &prep_param)?;
+        let z_0 = vec![Fr::zero(), Fr::from(3u32)];
+
+        // a different number of step circuits than the ones used at preprocessing
+        assert!(matches!(
+            SN::init(&params, vec![OpFCircuit::new(0)?], z_0.clone()),
+            Err(Error::NotSameLength(..))
+        ));
+
+        // the same step circuits, but registered in a different order
+        let F = vec![OpFCircuit::new(1)?, OpFCircuit::new(0)?];
+        assert!(matches!(
+            SN::init(&params, F, z_0.clone()),
+            Err(Error::Other(..))
+        ));
+
+        SN::init(&params, vec![OpFCircuit::new(0)?, OpFCircuit::new(1)?], z_0)?;
+        Ok(())
+    }
+}
@@ -1,14 +1,10 @@
-use ark_crypto_primitives::sponge::{
-    constraints::{AbsorbGadget, CryptographicSpongeVar},
-    poseidon::constraints::PoseidonSpongeVar,
-    Absorb,
-};
+use ark_crypto_primitives::sponge::{constraints::AbsorbGadget, Absorb, CryptographicSponge};
 use ark_ff::PrimeField;
 use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar};
 use ark_relations::r1cs::SynthesisError;
 
 use crate::{
-    transcript::{AbsorbNonNativeGadget, Transcript},
+    transcript::{AbsorbNonNativeGadget, Transcript, TranscriptVar},
     Curve, Error,
 };
 
@@ -45,6 +41,9 @@ pub trait CommittedInstanceOps<C: Curve>: Inputize<CF1<C>> {
     /// Returns the commitments contained in the committed instance.
     fn get_commitments(&self) -> Vec<C>;
 
+    /// Returns the public inputs contained in the committed instance.
+    fn get_public_inputs(&self) -> &[CF1<C>];
+
     /// Returns `true` if the committed instance is an incoming instance, and
     /// `false` if it is a running instance.
     fn is_incoming(&self) -> bool;
@@ -68,9 +67,9 @@ pub trait CommittedInstanceVarOps<C: Curve> {
     /// instance `self` as a vector of field elements, so they can be reused in
     /// other gadgets avoiding recalculating (reconstraining) them.
     #[allow(clippy::type_complexity)]
-    fn hash(
+    fn hash<S: CryptographicSponge, T: TranscriptVar<CF1<C>, S>>(
         &self,
-        sponge: &PoseidonSpongeVar<CF1<C>>,
+        sponge: &T,
         pp_hash: &FpVar<CF1<C>>,
         i: &FpVar<CF1<C>>,
         z_0: &[FpVar<CF1<C>>],
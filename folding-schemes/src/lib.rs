@@ -29,6 +29,7 @@ pub mod commitment;
 pub mod constants;
 pub mod folding;
 pub mod frontend;
+pub mod snark;
 pub mod transcript;
 pub mod utils;
 
@@ -101,6 +102,10 @@ pub enum Error {
     IncorrectBlinding(bool, String),
     #[error("Commitment verification failed")]
     CommitmentVerificationFail,
+    #[error("Invalid SRS: {0}")]
+    InvalidSRS(String),
+    #[error("Commitment parameters do not match the ones derived from the given label")]
+    CommitmentParamsMismatch,
 
     // Polynomial IOP errors, from https://github.com/EspressoSystems/hyperplonk/blob/main/subroutines/src/poly_iop/errors.rs
     #[error("Invalid Polynomial IOP Prover: {0}")]
@@ -0,0 +1,19 @@
+/// This module contains the SNARKs implemented in this crate, which can be used by the Deciders
+/// in place of the ones from arkworks (eg. Groth16).
+use ark_snark::UniversalSetupIndexError;
+use ark_std::fmt::Debug;
+
+use crate::Error;
+
+pub mod plonk;
+
+/// converts the error returned by `UniversalSetupSNARK::index` into an `Error`
+pub fn index_error<B: Debug, E: ToString>(e: UniversalSetupIndexError<B, E>) -> Error {
+    match e {
+        UniversalSetupIndexError::NeedLargerBound(bound) => Error::SNARKSetupFail(format!(
+            "the universal parameters are too small for the circuit, which needs a bound of {:?}",
+            bound
+        )),
+        UniversalSetupIndexError::Other(e) => Error::SNARKSetupFail(e.to_string()),
+    }
+}
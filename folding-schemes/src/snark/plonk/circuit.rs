@@ -0,0 +1,206 @@
+/// This file implements the translation of an R1CS into PLONK's arithmetization, so that the
+/// circuits built with `ark_relations` can be proven with the PLONK SNARK of the parent module.
+///
+/// Each gate `i` of a PLONK circuit enforces
+/// `q_M[i]⋅a[i]⋅b[i] + q_L[i]⋅a[i] + q_R[i]⋅b[i] + q_O[i]⋅c[i] + q_C[i] = 0`, where the wires `a`,
+/// `b`, `c` point to the variables of the circuit. Since the rows of the R1CS are linear
+/// combinations of any length, each linear combination is first reduced to a single (scaled)
+/// variable plus a constant, by chaining additions into auxiliary variables, and then the R1CS row
+/// `(k_A⋅v_A + d_A)⋅(k_B⋅v_B + d_B) = k_C⋅v_C + d_C` becomes a single gate.
+use ark_ff::PrimeField;
+
+use crate::arith::{r1cs::R1CS, Arith};
+
+/// Index of the selectors in `PlonkCircuit::selectors`
+pub const Q_M: usize = 0;
+pub const Q_L: usize = 1;
+pub const Q_R: usize = 2;
+pub const Q_O: usize = 3;
+pub const Q_C: usize = 4;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PlonkCircuit<F: PrimeField> {
+    /// number of public inputs, which are placed at the first gates of the circuit
+    pub n_public_inputs: usize,
+    /// number of variables of the R1CS, ie. the length of `z = (1, x, w)`
+    pub n_r1cs_vars: usize,
+    /// auxiliary variables, appended after the R1CS variables, where each one is defined as the
+    /// sum of two scaled variables
+    pub aux_vars: Vec<[(F, usize); 2]>,
+    /// selectors `[q_M, q_L, q_R, q_O, q_C]`
+    pub selectors: [Vec<F>; 5],
+    /// wires `[a, b, c]`, where `None` denotes a wire that is not used by its gate
+    pub wires: [Vec<Option<usize>>; 3],
+}
+
+impl<F: PrimeField> PlonkCircuit<F> {
+    pub fn from_r1cs(r1cs: &R1CS<F>) -> Self {
+        let mut circuit = Self {
+            n_public_inputs: r1cs.n_public_inputs(),
+            n_r1cs_vars: r1cs.n_variables(),
+            aux_vars: vec![],
+            selectors: Default::default(),
+            wires: Default::default(),
+        };
+
+        // public input gates, which enforce `a[i] = x_i` together with the public input
+        // polynomial
+        for i in 0..circuit.n_public_inputs {
+            circuit.add_gate(
+                [F::zero(), F::one(), F::zero(), F::zero(), F::zero()],
+                [Some(1 + i), None, None],
+            );
+        }
+
+        for ((a, b), c) in r1cs.A.coeffs.iter().zip(&r1cs.B.coeffs).zip(&r1cs.C.coeffs) {
+            let (k_a, v_a, d_a) = circuit.reduce_lc(a);
+            let (k_b, v_b, d_b) = circuit.reduce_lc(b);
+            let (k_c, v_c, d_c) = circuit.reduce_lc(c);
+            circuit.add_gate(
+                [k_a * k_b, k_a * d_b, d_a * k_b, -k_c, d_a * d_b - d_c],
+                [v_a, v_b, v_c],
+            );
+        }
+        circuit
+    }
+
+    pub fn n_gates(&self) -> usize {
+        self.selectors[Q_M].len()
+    }
+
+    /// number of variables of the circuit, including the auxiliary ones
+    pub fn n_vars(&self) -> usize {
+        self.n_r1cs_vars + self.aux_vars.len()
+    }
+
+    fn add_gate(&mut self, selectors: [F; 5], wires: [Option<usize>; 3]) {
+        for (q, s) in self.selectors.iter_mut().zip(selectors) {
+            q.push(s);
+        }
+        for (w, v) in self.wires.iter_mut().zip(wires) {
+            w.push(v);
+        }
+    }
+
+    /// reduces the given linear combination into `(k, v, d)` such that the linear combination
+    /// equals `k⋅v + d`, adding the gates of the auxiliary variables needed to do so. Notice that
+    /// the variable `0` of the R1CS (the constant `1`) is folded into `d`.
+    fn reduce_lc(&mut self, lc: &[(F, usize)]) -> (F, Option<usize>, F) {
+        let d = lc
+            .iter()
+            .filter(|(_, v)| *v == 0)
+            .map(|(k, _)| *k)
+            .sum::<F>();
+        let mut terms = lc.iter().filter(|(k, v)| *v != 0 && !k.is_zero());
+
+        let Some(&(k, v)) = terms.next() else {
+            return (F::zero(), None, d);
+        };
+        let (mut k_acc, mut v_acc) = (k, v);
+        for &(k, v) in terms {
+            let s = self.n_vars();
+            self.aux_vars.push([(k_acc, v_acc), (k, v)]);
+            self.add_gate(
+                [F::zero(), k_acc, k, -F::one(), F::zero()],
+                [Some(v_acc), Some(v), Some(s)],
+            );
+            (k_acc, v_acc) = (F::one(), s);
+        }
+        (k_acc, Some(v_acc), d)
+    }
+
+    /// returns the values of all the variables of the circuit, given the R1CS assignment
+    /// `z = (1, x, w)`
+    pub fn assignment(&self, z: &[F]) -> Vec<F> {
+        let mut vars = z.to_vec();
+        for [(k_1, v_1), (k_2, v_2)] in &self.aux_vars {
+            let s = *k_1 * vars[*v_1] + *k_2 * vars[*v_2];
+            vars.push(s);
+        }
+        vars
+    }
+
+    /// returns the values of the wires `[a, b, c]` at each gate, given the values of all the
+    /// variables of the circuit
+    pub fn wire_values(&self, vars: &[F]) -> [Vec<F>; 3] {
+        self.wires.clone().map(|wire| {
+            wire.iter()
+                .map(|v| v.map_or(F::zero(), |v| vars[v]))
+                .collect()
+        })
+    }
+
+    /// returns the permutation `σ` of the copy constraints over a domain of size `n`, where the
+    /// position of the wire `j` at the gate `i` is encoded as `j⋅n + i`. The positions of the
+    /// wires that point to the same variable form a cycle, while the rest are fixed points.
+    pub fn permutation(&self, n: usize) -> [Vec<usize>; 3] {
+        let mut sigma: [Vec<usize>; 3] = core::array::from_fn(|j| (j * n..(j + 1) * n).collect());
+
+        let mut positions: Vec<Vec<usize>> = vec![vec![]; self.n_vars()];
+        for (j, wire) in self.wires.iter().enumerate() {
+            for (i, v) in wire.iter().enumerate() {
+                if let Some(v) = v {
+                    positions[*v].push(j * n + i);
+                }
+            }
+        }
+        for cycle in positions {
+            for (k, p) in cycle.iter().enumerate() {
+                sigma[p / n][p % n] = cycle[(k + 1) % cycle.len()];
+            }
+        }
+        sigma
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use ark_pallas::Fr;
+    use ark_std::{One, Zero};
+
+    use super::*;
+    use crate::arith::r1cs::tests::{get_test_r1cs, get_test_z};
+
+    /// checks that all the gates are satisfied by the given wire values, where the public input
+    /// gates are checked against the values `x`
+    fn gates_satisfied(circuit: &PlonkCircuit<Fr>, wires: &[Vec<Fr>; 3], x: &[Fr]) -> bool {
+        let [q_m, q_l, q_r, q_o, q_c] = &circuit.selectors;
+        let [a, b, c] = wires;
+        (0..circuit.n_gates()).all(|i| {
+            let pi = x.get(i).map_or(Fr::zero(), |x_i| -*x_i);
+            (q_m[i] * a[i] * b[i] + q_l[i] * a[i] + q_r[i] * b[i] + q_o[i] * c[i] + q_c[i] + pi)
+                .is_zero()
+        })
+    }
+
+    #[test]
+    fn test_plonk_circuit_from_r1cs() {
+        let r1cs = get_test_r1cs::<Fr>();
+        let circuit = PlonkCircuit::from_r1cs(&r1cs);
+
+        let z = get_test_z(3);
+        let x = &z[1..1 + circuit.n_public_inputs];
+        let vars = circuit.assignment(&z);
+        let wires = circuit.wire_values(&vars);
+        assert!(gates_satisfied(&circuit, &wires, x));
+
+        // the wires that are copies of each other hold the same value
+        let n = circuit.n_gates().next_power_of_two();
+        let sigma = circuit.permutation(n);
+        let value = |p: usize| wires[p / n].get(p % n).copied().unwrap_or_default();
+        for (j, sigma_j) in sigma.iter().enumerate() {
+            for (i, p) in sigma_j.iter().enumerate() {
+                assert_eq!(value(j * n + i), value(*p));
+            }
+        }
+
+        // a wrong witness does not satisfy the gates
+        let mut bad_vars = vars.clone();
+        bad_vars[circuit.n_r1cs_vars - 1] += Fr::one();
+        assert!(!gates_satisfied(
+            &circuit,
+            &circuit.wire_values(&bad_vars),
+            x
+        ));
+    }
+}
@@ -0,0 +1,756 @@
+/// This module implements the PLONK SNARK (https://eprint.iacr.org/2019/953) over the circuits
+/// built with `ark_relations`, which are translated into PLONK's arithmetization as described in
+/// `circuit.rs`.
+///
+/// Unlike Groth16, its setup is universal: a single KZG SRS (eg. the one loaded from a powers of
+/// tau ceremony through `commitment::srs`) can be indexed for any circuit whose number of gates
+/// fits in it, so that the Deciders of different `FCircuit`s do not need a trusted setup each.
+///
+/// The Fiat-Shamir challenges are taken from a `KeccakTranscript`, so that they can be recomputed
+/// by the Solidity verifier. As in the paper, the wire polynomials are blinded with
+/// `(b_1X + b_2)Z_H(X)` and the permutation accumulator with `(b_7X² + b_8X + b_9)Z_H(X)`, so that
+/// the proofs are zero-knowledge.
+use ark_crypto_primitives::sponge::CryptographicSponge;
+use ark_ec::{pairing::Pairing, VariableBaseMSM};
+use ark_ff::{batch_inversion, FftField, Field, PrimeField};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_poly_commit::kzg10::VerifierKey as KZGVerifierKey;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_snark::{UniversalSetupIndexError, UniversalSetupSNARK, SNARK};
+use ark_std::{
+    rand::{CryptoRng, RngCore},
+    One, UniformRand, Zero,
+};
+use core::marker::PhantomData;
+
+use crate::arith::r1cs::{extract_r1cs, extract_w_x, R1CS};
+use crate::commitment::{
+    kzg::{ProverKey as KZGProverKey, KZG},
+    CommitmentScheme,
+};
+use crate::transcript::{keccak::KeccakTranscript, Transcript};
+use crate::{Curve, Error};
+
+pub mod circuit;
+use circuit::{PlonkCircuit, Q_C, Q_L, Q_M, Q_O, Q_R};
+
+#[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct VerifyingKey<E: Pairing> {
+    /// size of the evaluation domain, ie. the number of gates padded to a power of two
+    pub n: usize,
+    pub n_public_inputs: usize,
+    /// commitments to the selectors `[q_M, q_L, q_R, q_O, q_C]`
+    pub selectors: [E::G1; 5],
+    /// commitments to the permutation polynomials `[S_σ1, S_σ2, S_σ3]`
+    pub sigmas: [E::G1; 3],
+    /// elements of the KZG SRS used by the verifier
+    pub g: E::G1Affine,
+    pub h: E::G2Affine,
+    pub beta_h: E::G2Affine,
+}
+
+impl<E: Pairing<G1: Curve>> VerifyingKey<E> {
+    /// digest of the verifying key, which is absorbed at the beginning of the transcript to bind
+    /// the proofs to the circuit
+    pub fn digest(&self) -> E::ScalarField {
+        let mut transcript = KeccakTranscript::<E::ScalarField>::new(&());
+        transcript.absorb(&E::ScalarField::from(self.n as u64));
+        transcript.absorb(&E::ScalarField::from(self.n_public_inputs as u64));
+        for cm in self.selectors.iter().chain(&self.sigmas) {
+            transcript.absorb_nonnative(cm);
+        }
+        transcript.get_challenge()
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ProvingKey<E: Pairing> {
+    pub vk: VerifyingKey<E>,
+    /// powers of the KZG SRS, truncated to the size of the circuit
+    pub powers_of_g: Vec<E::G1Affine>,
+    /// coefficients of the selectors `[q_M, q_L, q_R, q_O, q_C]`
+    pub selectors: [Vec<E::ScalarField>; 5],
+    /// coefficients of the permutation polynomials `[S_σ1, S_σ2, S_σ3]`
+    pub sigmas: [Vec<E::ScalarField>; 3],
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Proof<E: Pairing> {
+    /// commitments to the wire polynomials `[a, b, c]`
+    pub wires: [E::G1; 3],
+    /// commitment to the permutation accumulator `z`
+    pub z: E::G1,
+    /// commitments to the parts `[t_lo, t_mid, t_hi]` of the quotient polynomial, of `n + 2`
+    /// coefficients each
+    pub t: [E::G1; 3],
+    /// KZG opening proofs at `ζ` and `ζω`
+    pub w_zeta: E::G1,
+    pub w_zeta_omega: E::G1,
+    /// evaluations `[a(ζ), b(ζ), c(ζ)]`
+    pub wire_evals: [E::ScalarField; 3],
+    /// evaluations `[S_σ1(ζ), S_σ2(ζ)]`
+    pub sigma_evals: [E::ScalarField; 2],
+    /// evaluation `z(ζω)`
+    pub z_omega_eval: E::ScalarField,
+}
+
+/// PLONK SNARK, whose universal parameters are the ones of the `KZG` commitment scheme.
+#[derive(Clone, Debug)]
+pub struct Plonk<E: Pairing> {
+    _e: PhantomData<E>,
+}
+
+impl<E: Pairing<G1: Curve>> Plonk<E> {
+    /// returns the computation bound needed by `universal_setup` to index the given circuit, ie.
+    /// its number of gates padded to a power of two (of at least 8)
+    pub fn computation_bound<C: ConstraintSynthesizer<E::ScalarField>>(
+        circuit: C,
+    ) -> Result<usize, Error> {
+        let (r1cs, _) = synthesize(circuit)?;
+        Ok(domain_size(PlonkCircuit::from_r1cs(&r1cs).n_gates()))
+    }
+}
+
+impl<E: Pairing<G1: Curve>> SNARK<E::ScalarField> for Plonk<E> {
+    type ProvingKey = ProvingKey<E>;
+    type VerifyingKey = VerifyingKey<E>;
+    type Proof = Proof<E>;
+    type ProcessedVerifyingKey = VerifyingKey<E>;
+    type Error = Error;
+
+    /// samples a KZG SRS that fits the given circuit and indexes the circuit with it. Use
+    /// `universal_setup` and `index` instead to reuse the same SRS among different circuits.
+    fn circuit_specific_setup<C: ConstraintSynthesizer<E::ScalarField>, R: RngCore + CryptoRng>(
+        circuit: C,
+        rng: &mut R,
+    ) -> Result<(Self::ProvingKey, Self::VerifyingKey), Self::Error> {
+        let (r1cs, _) = synthesize(circuit)?;
+        let circuit = PlonkCircuit::from_r1cs(&r1cs);
+        let pp = Self::universal_setup(&domain_size(circuit.n_gates()), rng)?;
+        index_circuit(&pp, &circuit)
+    }
+
+    fn prove<C: ConstraintSynthesizer<E::ScalarField>, R: RngCore + CryptoRng>(
+        pk: &Self::ProvingKey,
+        circuit: C,
+        rng: &mut R,
+    ) -> Result<Self::Proof, Self::Error> {
+        let (r1cs, z) = synthesize(circuit)?;
+        prove_circuit(pk, &PlonkCircuit::from_r1cs(&r1cs), &z, rng)
+    }
+
+    fn process_vk(vk: &Self::VerifyingKey) -> Result<Self::ProcessedVerifyingKey, Self::Error> {
+        Ok(vk.clone())
+    }
+
+    fn verify_with_processed_vk(
+        vk: &Self::ProcessedVerifyingKey,
+        public_input: &[E::ScalarField],
+        proof: &Self::Proof,
+    ) -> Result<bool, Self::Error> {
+        verify_proof(vk, public_input, proof)
+    }
+}
+
+impl<E: Pairing<G1: Curve>> UniversalSetupSNARK<E::ScalarField> for Plonk<E> {
+    /// maximum number of gates of the circuits that can be indexed
+    type ComputationBound = usize;
+    /// KZG SRS
+    type PublicParameters = (KZGProverKey<'static, E::G1>, KZGVerifierKey<E>);
+
+    /// samples a KZG SRS containing the powers needed by the circuits of up to `bound` gates,
+    /// including the extra ones needed by the blinded polynomials
+    fn universal_setup<R: RngCore + CryptoRng>(
+        bound: &Self::ComputationBound,
+        rng: &mut R,
+    ) -> Result<Self::PublicParameters, Self::Error> {
+        KZG::<'static, E>::setup(rng, *bound + N_EXTRA_POWERS)
+    }
+
+    fn index<C: ConstraintSynthesizer<E::ScalarField>, R: RngCore + CryptoRng>(
+        pp: &Self::PublicParameters,
+        circuit: C,
+        _rng: &mut R,
+    ) -> Result<
+        (Self::ProvingKey, Self::VerifyingKey),
+        UniversalSetupIndexError<Self::ComputationBound, Self::Error>,
+    > {
+        let (r1cs, _) = synthesize(circuit).map_err(UniversalSetupIndexError::Other)?;
+        let circuit = PlonkCircuit::from_r1cs(&r1cs);
+        let n = domain_size(circuit.n_gates());
+        if n + N_EXTRA_POWERS > pp.0.powers_of_g.len() {
+            return Err(UniversalSetupIndexError::NeedLargerBound(n));
+        }
+        index_circuit(pp, &circuit).map_err(UniversalSetupIndexError::Other)
+    }
+}
+
+/// synthesizes the given circuit, returning its R1CS and its assignment `z = (1, x, w)`
+fn synthesize<F: PrimeField>(
+    circuit: impl ConstraintSynthesizer<F>,
+) -> Result<(R1CS<F>, Vec<F>), Error> {
+    let cs = ConstraintSystem::<F>::new_ref();
+    circuit.generate_constraints(cs.clone())?;
+    cs.finalize();
+    let cs = cs.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
+
+    let r1cs = extract_r1cs::<F>(&cs)?;
+    let (w, x) = extract_w_x::<F>(&cs);
+    Ok((r1cs, [vec![F::one()], x, w].concat()))
+}
+
+fn index_circuit<E: Pairing<G1: Curve>>(
+    (srs_pk, srs_vk): &(KZGProverKey<'static, E::G1>, KZGVerifierKey<E>),
+    circuit: &PlonkCircuit<E::ScalarField>,
+) -> Result<(ProvingKey<E>, VerifyingKey<E>), Error> {
+    let n = domain_size(circuit.n_gates());
+    let domain = Radix2EvaluationDomain::<E::ScalarField>::new(n).ok_or(Error::NewDomainFail)?;
+    let powers_of_g =
+        srs_pk.powers_of_g[..(n + N_EXTRA_POWERS).min(srs_pk.powers_of_g.len())].to_vec();
+
+    let selectors = circuit.selectors.clone().map(|q| domain.ifft(&q));
+
+    // the position `j⋅n + i` of the permutation is encoded as `k_j⋅ω^i`
+    let omegas: Vec<E::ScalarField> = domain.elements().collect();
+    let shifts = coset_shifts::<E::ScalarField>();
+    let sigmas = circuit.permutation(n).map(|sigma| {
+        let evals: Vec<_> = sigma
+            .iter()
+            .map(|p| shifts[p / n] * omegas[p % n])
+            .collect();
+        domain.ifft(&evals)
+    });
+
+    let vk = VerifyingKey {
+        n,
+        n_public_inputs: circuit.n_public_inputs,
+        selectors: commit_all::<E, 5>(&powers_of_g, &selectors)?,
+        sigmas: commit_all::<E, 3>(&powers_of_g, &sigmas)?,
+        g: srs_vk.g,
+        h: srs_vk.h,
+        beta_h: srs_vk.beta_h,
+    };
+    let pk = ProvingKey {
+        vk: vk.clone(),
+        powers_of_g,
+        selectors,
+        sigmas,
+    };
+    Ok((pk, vk))
+}
+
+fn prove_circuit<E: Pairing<G1: Curve>>(
+    pk: &ProvingKey<E>,
+    circuit: &PlonkCircuit<E::ScalarField>,
+    z: &[E::ScalarField],
+    mut rng: impl RngCore,
+) -> Result<Proof<E>, Error> {
+    let vk = &pk.vk;
+    let n = vk.n;
+    if domain_size(circuit.n_gates()) != n {
+        return Err(Error::NotExpectedLength(domain_size(circuit.n_gates()), n));
+    }
+    if circuit.n_public_inputs != vk.n_public_inputs {
+        return Err(Error::NotExpectedLength(
+            circuit.n_public_inputs,
+            vk.n_public_inputs,
+        ));
+    }
+    let domain = Radix2EvaluationDomain::<E::ScalarField>::new(n).ok_or(Error::NewDomainFail)?;
+    // domain over whose coset the quotient polynomial is computed
+    let domain_4n =
+        Radix2EvaluationDomain::<E::ScalarField>::new(4 * n).ok_or(Error::NewDomainFail)?;
+    let omegas: Vec<E::ScalarField> = domain.elements().collect();
+    let shifts = coset_shifts::<E::ScalarField>();
+    let public_input = &z[1..1 + vk.n_public_inputs];
+    let b: [E::ScalarField; 9] = core::array::from_fn(|_| E::ScalarField::rand(&mut rng));
+
+    // round 1: commit to the wires, blinded with (b_1X + b_2)Z_H, (b_3X + b_4)Z_H, (b_5X + b_6)Z_H
+    let wire_evals = circuit.wire_values(&circuit.assignment(z)).map(|mut w| {
+        w.resize(n, E::ScalarField::zero());
+        w
+    });
+    let wire_polys: [Vec<E::ScalarField>; 3] = core::array::from_fn(|j| {
+        add_blinding(domain.ifft(&wire_evals[j]), &[b[2 * j + 1], b[2 * j]])
+    });
+    let wires = commit_all::<E, 3>(&pk.powers_of_g, &wire_polys)?;
+
+    let mut transcript = new_transcript(vk, public_input);
+    for cm in &wires {
+        transcript.absorb_nonnative(cm);
+    }
+    let beta = transcript.get_challenge();
+    let gamma = transcript.get_challenge();
+
+    // round 2: commit to the permutation accumulator
+    // z(ω^0) = 1, z(ω^{i+1}) = z(ω^i) ∏_j (w_j(ω^i) + β k_j ω^i + γ) / (w_j(ω^i) + β S_σj(ω^i) + γ)
+    let sigma_evals: [Vec<E::ScalarField>; 3] = core::array::from_fn(|j| domain.fft(&pk.sigmas[j]));
+    let (num, mut den): (Vec<E::ScalarField>, Vec<E::ScalarField>) = (0..n)
+        .map(|i| {
+            let (mut num, mut den) = (E::ScalarField::one(), E::ScalarField::one());
+            for ((w, s), k) in wire_evals.iter().zip(&sigma_evals).zip(shifts) {
+                num *= w[i] + beta * k * omegas[i] + gamma;
+                den *= w[i] + beta * s[i] + gamma;
+            }
+            (num, den)
+        })
+        .unzip();
+    batch_inversion(&mut den);
+    let mut z_evals = Vec::with_capacity(n);
+    let mut acc = E::ScalarField::one();
+    for (num_i, den_i) in num.iter().zip(&den) {
+        z_evals.push(acc);
+        acc *= *num_i * den_i;
+    }
+    // blinded with (b_7X² + b_8X + b_9)Z_H
+    let z_poly = add_blinding(domain.ifft(&z_evals), &[b[8], b[7], b[6]]);
+    let z_cm = commit::<E>(&pk.powers_of_g, &z_poly)?;
+
+    transcript.absorb_nonnative(&z_cm);
+    let alpha = transcript.get_challenge();
+
+    // round 3: commit to the quotient polynomial, which is computed over the coset g⋅H' of the
+    // domain H' of size 4n, where Z_H does not vanish
+    let coset = |p: &[E::ScalarField]| coset_fft(&domain_4n, p);
+    let [a, b, c]: [Vec<_>; 3] = core::array::from_fn(|j| coset(&wire_polys[j]));
+    let [q_m, q_l, q_r, q_o, q_c]: [Vec<_>; 5] = core::array::from_fn(|k| coset(&pk.selectors[k]));
+    let [s_1, s_2, s_3]: [Vec<_>; 3] = core::array::from_fn(|j| coset(&pk.sigmas[j]));
+    let z_4n = coset(&z_poly);
+    let l_0 = coset(&domain.ifft(&[E::ScalarField::one()]));
+    let pi = coset(&domain.ifft(&public_input.iter().map(|x| -*x).collect::<Vec<_>>()));
+
+    // Z_H(g⋅ω'^i) = g^n⋅ω'^{n⋅i} - 1 only takes 4 different values
+    let omega_4n = domain_4n.group_gen();
+    let g_n = E::ScalarField::GENERATOR.pow([n as u64]);
+    let mut zh_inv: Vec<E::ScalarField> = (0..4)
+        .map(|i| g_n * omega_4n.pow([(n * i) as u64]) - E::ScalarField::one())
+        .collect();
+    batch_inversion(&mut zh_inv);
+
+    let mut x = E::ScalarField::GENERATOR;
+    let t_evals: Vec<E::ScalarField> = (0..4 * n)
+        .map(|i| {
+            let gate = a[i] * b[i] * q_m[i]
+                + a[i] * q_l[i]
+                + b[i] * q_r[i]
+                + c[i] * q_o[i]
+                + pi[i]
+                + q_c[i];
+            // since ω = ω'^4, z(ω⋅x_i) = z(x_{i+4})
+            let perm = (a[i] + beta * x + gamma)
+                * (b[i] + beta * shifts[1] * x + gamma)
+                * (c[i] + beta * shifts[2] * x + gamma)
+                * z_4n[i]
+                - (a[i] + beta * s_1[i] + gamma)
+                    * (b[i] + beta * s_2[i] + gamma)
+                    * (c[i] + beta * s_3[i] + gamma)
+                    * z_4n[(i + 4) % (4 * n)];
+            let boundary = (z_4n[i] - E::ScalarField::one()) * l_0[i];
+            x *= omega_4n;
+            (gate + alpha * (perm + alpha * boundary)) * zh_inv[i % 4]
+        })
+        .collect();
+    let t = coset_ifft(&domain_4n, &t_evals);
+    // if the circuit is not satisfied, the numerator is not divisible by Z_H and the result of
+    // the interpolation has a higher degree than the 3n + 5 of the blinded quotient
+    if t[3 * (n + 2)..].iter().any(|t_i| !t_i.is_zero()) {
+        return Err(Error::NotSatisfied);
+    }
+    let t_parts: [Vec<E::ScalarField>; 3] =
+        core::array::from_fn(|k| t[k * (n + 2)..(k + 1) * (n + 2)].to_vec());
+    let t_cms = commit_all::<E, 3>(&pk.powers_of_g, &t_parts)?;
+
+    for cm in &t_cms {
+        transcript.absorb_nonnative(cm);
+    }
+    let zeta = transcript.get_challenge();
+
+    // round 4: evaluate the polynomials at ζ and ζω
+    let zeta_omega = zeta * domain.group_gen();
+    let wire_evals_zeta: [E::ScalarField; 3] =
+        core::array::from_fn(|j| evaluate(&wire_polys[j], zeta));
+    let sigma_evals_zeta: [E::ScalarField; 2] =
+        core::array::from_fn(|j| evaluate(&pk.sigmas[j], zeta));
+    let z_omega_eval = evaluate(&z_poly, zeta_omega);
+
+    transcript.absorb(&[&wire_evals_zeta[..], &sigma_evals_zeta, &[z_omega_eval]].concat());
+    let v = transcript.get_challenge();
+
+    // round 5: compute the opening proofs. Since the constant terms do not change the quotient
+    // of the division by (X - ζ), they are omitted from the linearization polynomial r(X) and
+    // from the evaluations subtracted from the opened polynomials.
+    let [a_zeta, b_zeta, c_zeta] = wire_evals_zeta;
+    let zh_zeta = domain.evaluate_vanishing_polynomial(zeta);
+    let zeta_n_2 = (zh_zeta + E::ScalarField::one()) * zeta.square();
+    let l_0_zeta = lagrange_evals(&domain, 1, zeta)[0];
+    let perm_1 = alpha
+        * (a_zeta + beta * zeta + gamma)
+        * (b_zeta + beta * shifts[1] * zeta + gamma)
+        * (c_zeta + beta * shifts[2] * zeta + gamma);
+    let perm_2 = alpha
+        * (a_zeta + beta * sigma_evals_zeta[0] + gamma)
+        * (b_zeta + beta * sigma_evals_zeta[1] + gamma)
+        * z_omega_eval;
+
+    let mut w = vec![E::ScalarField::zero(); n];
+    add_scaled(&mut w, a_zeta * b_zeta, &pk.selectors[Q_M]);
+    add_scaled(&mut w, a_zeta, &pk.selectors[Q_L]);
+    add_scaled(&mut w, b_zeta, &pk.selectors[Q_R]);
+    add_scaled(&mut w, c_zeta, &pk.selectors[Q_O]);
+    add_scaled(&mut w, E::ScalarField::one(), &pk.selectors[Q_C]);
+    add_scaled(&mut w, perm_1 + alpha.square() * l_0_zeta, &z_poly);
+    add_scaled(&mut w, -perm_2 * beta, &pk.sigmas[2]);
+    add_scaled(&mut w, -zh_zeta, &t_parts[0]);
+    add_scaled(&mut w, -zh_zeta * zeta_n_2, &t_parts[1]);
+    add_scaled(&mut w, -zh_zeta * zeta_n_2.square(), &t_parts[2]);
+    let mut v_k = E::ScalarField::one();
+    for p in wire_polys.iter().chain(&pk.sigmas[..2]) {
+        v_k *= v;
+        add_scaled(&mut w, v_k, p);
+    }
+    let w_zeta = commit::<E>(&pk.powers_of_g, &divide_by_linear(&w, zeta))?;
+    let w_zeta_omega = commit::<E>(&pk.powers_of_g, &divide_by_linear(&z_poly, zeta_omega))?;
+
+    Ok(Proof {
+        wires,
+        z: z_cm,
+        t: t_cms,
+        w_zeta,
+        w_zeta_omega,
+        wire_evals: wire_evals_zeta,
+        sigma_evals: sigma_evals_zeta,
+        z_omega_eval,
+    })
+}
+
+fn verify_proof<E: Pairing<G1: Curve>>(
+    vk: &VerifyingKey<E>,
+    public_input: &[E::ScalarField],
+    proof: &Proof<E>,
+) -> Result<bool, Error> {
+    if public_input.len() != vk.n_public_inputs {
+        return Err(Error::NotExpectedLength(
+            public_input.len(),
+            vk.n_public_inputs,
+        ));
+    }
+    let domain = Radix2EvaluationDomain::<E::ScalarField>::new(vk.n).ok_or(Error::NewDomainFail)?;
+    let shifts = coset_shifts::<E::ScalarField>();
+
+    let mut transcript = new_transcript(vk, public_input);
+    for cm in &proof.wires {
+        transcript.absorb_nonnative(cm);
+    }
+    let beta = transcript.get_challenge();
+    let gamma = transcript.get_challenge();
+    transcript.absorb_nonnative(&proof.z);
+    let alpha = transcript.get_challenge();
+    for cm in &proof.t {
+        transcript.absorb_nonnative(cm);
+    }
+    let zeta = transcript.get_challenge();
+    transcript.absorb(
+        &[
+            &proof.wire_evals[..],
+            &proof.sigma_evals,
+            &[proof.z_omega_eval],
+        ]
+        .concat(),
+    );
+    let v = transcript.get_challenge();
+    transcript.absorb_nonnative(&proof.w_zeta);
+    transcript.absorb_nonnative(&proof.w_zeta_omega);
+    let u = transcript.get_challenge();
+
+    let [a, b, c] = proof.wire_evals;
+    let [s_1, s_2] = proof.sigma_evals;
+    let z_omega = proof.z_omega_eval;
+
+    let zh = domain.evaluate_vanishing_polynomial(zeta);
+    let zeta_n_2 = (zh + E::ScalarField::one()) * zeta.square();
+    let lagrange = lagrange_evals(&domain, vk.n_public_inputs.max(1), zeta);
+    let l_0 = lagrange[0];
+    let pi = -public_input
+        .iter()
+        .zip(&lagrange)
+        .map(|(x_i, l_i)| *x_i * l_i)
+        .sum::<E::ScalarField>();
+
+    let alpha_2 = alpha.square();
+    let perm_1 = alpha
+        * (a + beta * zeta + gamma)
+        * (b + beta * shifts[1] * zeta + gamma)
+        * (c + beta * shifts[2] * zeta + gamma);
+    let perm_2 = alpha * (a + beta * s_1 + gamma) * (b + beta * s_2 + gamma) * z_omega;
+    // constant term of the linearization polynomial r(X)
+    let r_0 = pi - alpha_2 * l_0 - perm_2 * (c + gamma);
+
+    // commitment to the non-constant part of r(X)
+    let [q_m, q_l, q_r, q_o, q_c] = vk.selectors;
+    let [s_1_cm, s_2_cm, s_3_cm] = vk.sigmas;
+    let [t_lo, t_mid, t_hi] = proof.t;
+    let d =
+        q_m * (a * b) + q_l * a + q_r * b + q_o * c + q_c + proof.z * (perm_1 + alpha_2 * l_0 + u)
+            - s_3_cm * (perm_2 * beta)
+            - (t_lo + t_mid * zeta_n_2 + t_hi * zeta_n_2.square()) * zh;
+
+    // batch the openings at ζ with the powers of v, and the ones at ζ and ζω with u
+    let (v_2, v_3) = (v.square(), v.square() * v);
+    let (v_4, v_5) = (v_2.square(), v_2.square() * v);
+    let f = d
+        + proof.wires[0] * v
+        + proof.wires[1] * v_2
+        + proof.wires[2] * v_3
+        + s_1_cm * v_4
+        + s_2_cm * v_5;
+    let e = -r_0 + v * a + v_2 * b + v_3 * c + v_4 * s_1 + v_5 * s_2 + u * z_omega;
+
+    // e(W_ζ + u⋅W_ζω, βH) = e(ζ⋅W_ζ + uζω⋅W_ζω + F - E, H)
+    let lhs = proof.w_zeta + proof.w_zeta_omega * u;
+    let rhs =
+        proof.w_zeta * zeta + proof.w_zeta_omega * (u * zeta * domain.group_gen()) + f - vk.g * e;
+    Ok(E::multi_pairing([lhs, -rhs], [vk.beta_h, vk.h]).is_zero())
+}
+
+/// size of the evaluation domain for the given number of gates, which is at least 8 so that the
+/// blinded quotient polynomial, of degree `3n + 5`, can be interpolated over a domain of size `4n`
+fn domain_size(n_gates: usize) -> usize {
+    n_gates.max(8).next_power_of_two()
+}
+
+/// number of powers of the SRS needed on top of the size of the domain, since the blinded
+/// permutation accumulator `z(X)` has `n + 3` coefficients
+const N_EXTRA_POWERS: usize = 3;
+
+/// returns the constants `[1, k_1, k_2]` that define the cosets `H`, `k_1⋅H`, `k_2⋅H` over which
+/// the positions of the wires `a`, `b`, `c` are encoded
+pub fn coset_shifts<F: PrimeField>() -> [F; 3] {
+    [F::one(), F::GENERATOR, F::GENERATOR.square()]
+}
+
+/// initializes the transcript of a proof with the digest of the verifying key and the public
+/// inputs
+fn new_transcript<E: Pairing<G1: Curve>>(
+    vk: &VerifyingKey<E>,
+    public_input: &[E::ScalarField],
+) -> KeccakTranscript<E::ScalarField> {
+    let mut transcript = KeccakTranscript::new(&());
+    transcript.absorb(&vk.digest());
+    transcript.absorb(&public_input.to_vec());
+    transcript
+}
+
+/// evaluates the first `k` Lagrange polynomials of the domain at `zeta` (which is not in the
+/// domain), as `L_i(ζ) = ω^i (ζ^n - 1) / (n (ζ - ω^i))`
+fn lagrange_evals<F: PrimeField>(domain: &Radix2EvaluationDomain<F>, k: usize, zeta: F) -> Vec<F> {
+    let zh = domain.evaluate_vanishing_polynomial(zeta);
+    let mut dens: Vec<F> = domain
+        .elements()
+        .take(k)
+        .map(|omega_i| domain.size_as_field_element() * (zeta - omega_i))
+        .collect();
+    batch_inversion(&mut dens);
+    domain
+        .elements()
+        .zip(dens)
+        .map(|(omega_i, den)| omega_i * zh * den)
+        .collect()
+}
+
+/// commits to the polynomial with the given coefficients
+fn commit<E: Pairing>(powers_of_g: &[E::G1Affine], p: &[E::ScalarField]) -> Result<E::G1, Error> {
+    if p.len() > powers_of_g.len() {
+        return Err(Error::PolyCommitError(
+            ark_poly_commit::Error::TooManyCoefficients {
+                num_coefficients: p.len(),
+                num_powers: powers_of_g.len(),
+            },
+        ));
+    }
+    Ok(<E::G1 as VariableBaseMSM>::msm_unchecked(
+        &powers_of_g[..p.len()],
+        p,
+    ))
+}
+
+fn commit_all<E: Pairing, const N: usize>(
+    powers_of_g: &[E::G1Affine],
+    polys: &[Vec<E::ScalarField>; N],
+) -> Result<[E::G1; N], Error> {
+    let mut cms = [E::G1::zero(); N];
+    for (cm, p) in cms.iter_mut().zip(polys) {
+        *cm = commit::<E>(powers_of_g, p)?;
+    }
+    Ok(cms)
+}
+
+/// evaluates the polynomial with the given coefficients at `x`
+fn evaluate<F: Field>(p: &[F], x: F) -> F {
+    p.iter().rev().fold(F::zero(), |acc, c| acc * x + c)
+}
+
+/// `acc += k⋅p`, where both are given by their coefficients
+fn add_scaled<F: Field>(acc: &mut Vec<F>, k: F, p: &[F]) {
+    if acc.len() < p.len() {
+        acc.resize(p.len(), F::zero());
+    }
+    for (acc_i, p_i) in acc.iter_mut().zip(p) {
+        *acc_i += k * p_i;
+    }
+}
+
+/// returns `p(X) + b(X)⋅Z_H(X)`, where `Z_H(X) = X^n - 1` is the vanishing polynomial of the
+/// domain of size `n = p.len()`, and `b(X)` is given by its coefficients
+fn add_blinding<F: Field>(mut p: Vec<F>, b: &[F]) -> Vec<F> {
+    let n = p.len();
+    p.resize(n + b.len(), F::zero());
+    for (i, b_i) in b.iter().enumerate() {
+        p[i] -= b_i;
+        p[n + i] += b_i;
+    }
+    p
+}
+
+/// returns the quotient of the division of `p(X)` by `(X - z)`, dropping the remainder `p(z)`
+fn divide_by_linear<F: Field>(p: &[F], z: F) -> Vec<F> {
+    let p_high = p.get(1..).unwrap_or(&[]);
+    let mut q = vec![F::zero(); p_high.len()];
+    let mut acc = F::zero();
+    for (q_i, p_i) in q.iter_mut().zip(p_high).rev() {
+        acc = acc * z + p_i;
+        *q_i = acc;
+    }
+    q
+}
+
+/// evaluates the polynomial with the given coefficients over the coset `g⋅D` of the domain `D`,
+/// where `g` is the generator of the field
+fn coset_fft<F: PrimeField>(domain: &Radix2EvaluationDomain<F>, p: &[F]) -> Vec<F> {
+    let mut p = p.to_vec();
+    distribute_powers(&mut p, F::GENERATOR);
+    domain.fft(&p)
+}
+
+/// inverse of `coset_fft`
+fn coset_ifft<F: PrimeField>(domain: &Radix2EvaluationDomain<F>, evals: &[F]) -> Vec<F> {
+    let mut p = domain.ifft(evals);
+    distribute_powers(&mut p, F::GENERATOR.inverse().unwrap_or_default());
+    p
+}
+
+/// `p_i = p_i⋅g^i`, so that `p(g⋅X)` is obtained from `p(X)`
+fn distribute_powers<F: Field>(p: &mut [F], g: F) {
+    let mut g_i = F::one();
+    for p_i in p.iter_mut() {
+        *p_i *= g_i;
+        g_i *= g;
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use ark_bn254::{Bn254, Fr};
+    use ark_r1cs_std::{alloc::AllocVar, eq::EqGadget, fields::fp::FpVar, fields::FieldVar};
+    use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+    use ark_std::UniformRand;
+
+    use super::*;
+    use crate::snark::index_error;
+
+    /// circuit that proves the knowledge of `x` such that `x^(2^k) + x = y`, for a public `y`
+    #[derive(Clone, Copy)]
+    struct TestCircuit<F: PrimeField> {
+        x: F,
+        y: F,
+        k: usize,
+    }
+
+    impl<F: PrimeField> TestCircuit<F> {
+        fn new(x: F, k: usize) -> Self {
+            let y = (0..k).fold(x, |acc, _| acc.square()) + x;
+            Self { x, y, k }
+        }
+    }
+
+    impl<F: PrimeField> ConstraintSynthesizer<F> for TestCircuit<F> {
+        fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+            let y = FpVar::new_input(cs.clone(), || Ok(self.y))?;
+            let x = FpVar::new_witness(cs, || Ok(self.x))?;
+            let mut acc = x.clone();
+            for _ in 0..self.k {
+                acc = acc.square()?;
+            }
+            (acc + x).enforce_equal(&y)
+        }
+    }
+
+    #[test]
+    fn test_plonk() -> Result<(), Error> {
+        let mut rng = rand::rngs::OsRng;
+
+        // the same SRS is indexed for circuits of different sizes
+        let srs = Plonk::<Bn254>::universal_setup(&(1 << 6), &mut rng)?;
+        for k in [1, 10, 40] {
+            let circuit = TestCircuit::new(Fr::rand(&mut rng), k);
+            let (pk, vk) = Plonk::<Bn254>::index(&srs, circuit, &mut rng).map_err(index_error)?;
+
+            let proof = Plonk::<Bn254>::prove(&pk, circuit, &mut rng)?;
+            assert!(Plonk::<Bn254>::verify(&vk, &[circuit.y], &proof)?);
+
+            // wrong public input
+            assert!(!Plonk::<Bn254>::verify(
+                &vk,
+                &[circuit.y + Fr::one()],
+                &proof
+            )?);
+            // wrong evaluation
+            let mut bad_proof = proof.clone();
+            bad_proof.wire_evals[0] += Fr::one();
+            assert!(!Plonk::<Bn254>::verify(&vk, &[circuit.y], &bad_proof)?);
+        }
+
+        // the SRS is too small for a bigger circuit
+        let circuit = TestCircuit::new(Fr::rand(&mut rng), 100);
+        assert!(matches!(
+            Plonk::<Bn254>::index(&srs, circuit, &mut rng),
+            Err(UniversalSetupIndexError::NeedLargerBound(128))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_plonk_blinding() -> Result<(), Error> {
+        let mut rng = rand::rngs::OsRng;
+        let circuit = TestCircuit::new(Fr::rand(&mut rng), 10);
+        let (pk, vk) = Plonk::<Bn254>::circuit_specific_setup(circuit, &mut rng)?;
+
+        // two proofs of the same witness are different, since the polynomials are blinded
+        let proof_1 = Plonk::<Bn254>::prove(&pk, circuit, &mut rng)?;
+        let proof_2 = Plonk::<Bn254>::prove(&pk, circuit, &mut rng)?;
+        assert!(Plonk::<Bn254>::verify(&vk, &[circuit.y], &proof_1)?);
+        assert!(Plonk::<Bn254>::verify(&vk, &[circuit.y], &proof_2)?);
+        assert_ne!(proof_1.wires, proof_2.wires);
+        assert_ne!(proof_1.z, proof_2.z);
+        assert_ne!(proof_1.wire_evals, proof_2.wire_evals);
+        assert_ne!(proof_1.z_omega_eval, proof_2.z_omega_eval);
+        Ok(())
+    }
+
+    #[test]
+    fn test_plonk_not_satisfied() -> Result<(), Error> {
+        let mut rng = rand::rngs::OsRng;
+        let circuit = TestCircuit::new(Fr::rand(&mut rng), 3);
+        let (pk, _) = Plonk::<Bn254>::circuit_specific_setup(circuit, &mut rng)?;
+
+        let bad_circuit = TestCircuit {
+            y: circuit.y + Fr::one(),
+            ..circuit
+        };
+        assert!(matches!(
+            Plonk::<Bn254>::prove(&pk, bad_circuit, &mut rng),
+            Err(Error::NotSatisfied)
+        ));
+        Ok(())
+    }
+}
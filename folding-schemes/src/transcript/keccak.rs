@@ -0,0 +1,200 @@
+/// This file implements a Keccak256 based `Transcript`, whose challenges can be reproduced in
+/// Solidity with the `keccak256` precompile, which makes it cheaper than Poseidon for the parts of
+/// the verification that happen natively on-chain.
+///
+/// The transcript keeps a 32-byte `state` and a buffer of absorbed data. Each absorbed field
+/// element is appended to the buffer as a 32-byte big-endian word (as `abi.encodePacked` does with
+/// an `uint256`), and each squeeze updates the state as
+/// `state = keccak256(abi.encodePacked(state, buffer))`, emptying the buffer.
+///
+/// Note that there is no in-circuit counterpart (`TranscriptVar`) of this transcript, since
+/// Keccak256 is expensive to arithmetize, so it should only be used for challenges that are not
+/// recomputed in-circuit.
+use ark_crypto_primitives::sponge::{Absorb, CryptographicSponge};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, PrimeField};
+use ark_std::marker::PhantomData;
+use sha3::{Digest, Keccak256};
+
+use super::{AbsorbNonNative, Transcript};
+
+/// Keccak256 based transcript. Its challenges match the ones computed by the `KeccakTranscript`
+/// Solidity library rendered by the `solidity-verifiers` crate.
+#[derive(Clone, Debug)]
+pub struct KeccakTranscript<F: PrimeField> {
+    state: [u8; 32],
+    buffer: Vec<u8>,
+    _f: PhantomData<F>,
+}
+
+impl<F: PrimeField> KeccakTranscript<F> {
+    /// updates the state with the absorbed data and returns the new state
+    fn squeeze_block(&mut self) -> [u8; 32] {
+        let mut hasher = Keccak256::new();
+        hasher.update(self.state);
+        hasher.update(&self.buffer);
+        self.state = hasher.finalize().into();
+        self.buffer.clear();
+        self.state
+    }
+
+    fn absorb_field_element(&mut self, v: &F) {
+        self.buffer.extend(v.into_bigint().to_bytes_be());
+    }
+}
+
+impl<F: PrimeField> CryptographicSponge for KeccakTranscript<F> {
+    type Config = ();
+
+    fn new(_config: &Self::Config) -> Self {
+        Self {
+            state: [0u8; 32],
+            buffer: vec![],
+            _f: PhantomData,
+        }
+    }
+
+    fn absorb(&mut self, input: &impl Absorb) {
+        for v in input.to_sponge_field_elements_as_vec::<F>() {
+            self.absorb_field_element(&v);
+        }
+    }
+
+    fn squeeze_bytes(&mut self, num_bytes: usize) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(num_bytes.next_multiple_of(32));
+        while bytes.len() < num_bytes {
+            bytes.extend(self.squeeze_block());
+        }
+        bytes.truncate(num_bytes);
+        bytes
+    }
+
+    /// Returns the bits of the squeezed blocks, where each block is interpreted as an `uint256`
+    /// and its bits are returned from the least to the most significant one, so that the first
+    /// `nbits <= 256` bits match `uint256(state) & ((1 << nbits) - 1)` in Solidity.
+    fn squeeze_bits(&mut self, num_bits: usize) -> Vec<bool> {
+        let mut bits = Vec::with_capacity(num_bits.next_multiple_of(256));
+        while bits.len() < num_bits {
+            let block = self.squeeze_block();
+            bits.extend(
+                block
+                    .iter()
+                    .rev()
+                    .flat_map(|byte| (0..8).map(move |i| (byte >> i) & 1 == 1)),
+            );
+        }
+        bits.truncate(num_bits);
+        bits
+    }
+}
+
+impl<F: PrimeField> Transcript<F> for KeccakTranscript<F> {
+    // Compatible with `absorbPoint` of the Solidity library
+    fn absorb_point<C: CurveGroup<BaseField = F>>(&mut self, p: &C) {
+        let (x, y) = p.into_affine().xy().unwrap_or_default();
+        self.absorb_field_element(&x);
+        self.absorb_field_element(&y);
+    }
+    fn absorb_nonnative<V: AbsorbNonNative>(&mut self, v: &V) {
+        for e in v.to_native_sponge_field_elements_as_vec::<F>() {
+            self.absorb_field_element(&e);
+        }
+    }
+    /// Returns `uint256(state) % F::MODULUS`, where `state` is the updated state.
+    fn get_challenge(&mut self) -> F {
+        F::from_be_bytes_mod_order(&self.squeeze_block())
+    }
+    fn get_challenge_nbits(&mut self, nbits: usize) -> Vec<bool> {
+        self.squeeze_bits(nbits)
+    }
+    fn get_challenges(&mut self, n: usize) -> Vec<F> {
+        (0..n).map(|_| self.get_challenge()).collect()
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use ark_pallas::{Fr, Projective};
+    use ark_std::{test_rng, UniformRand};
+
+    use super::*;
+    use crate::arith::{r1cs::tests::get_test_r1cs, r1cs::tests::get_test_z, Arith, ArithRelation};
+    use crate::commitment::{pedersen::Pedersen, CommitmentScheme};
+    use crate::folding::nova::nifs::{nova::NIFS, NIFSTrait};
+    use crate::Error;
+
+    #[test]
+    fn test_keccak_transcript() -> Result<(), Error> {
+        let mut rng = test_rng();
+        let v = Fr::rand(&mut rng);
+        let p = Projective::rand(&mut rng);
+
+        let mut tr1 = KeccakTranscript::<Fr>::new(&());
+        let mut tr2 = KeccakTranscript::<Fr>::new(&());
+        tr1.absorb(&v);
+        tr2.absorb(&v);
+        tr1.absorb_nonnative(&p);
+        tr2.absorb_nonnative(&p);
+        assert_eq!(tr1.get_challenge(), tr2.get_challenge());
+        assert_eq!(tr1.get_challenges(3), tr2.get_challenges(3));
+
+        // the challenges depend on the absorbed data
+        let mut tr3 = KeccakTranscript::<Fr>::new(&());
+        tr3.absorb(&(v + Fr::from(1)));
+        tr3.absorb_nonnative(&p);
+        let mut tr4 = KeccakTranscript::<Fr>::new(&());
+        tr4.absorb(&v);
+        tr4.absorb_nonnative(&p);
+        assert_ne!(tr3.get_challenge(), tr4.get_challenge());
+
+        // consecutive challenges differ
+        let c = tr1.get_challenges(2);
+        assert_ne!(c[0], c[1]);
+
+        // the nbits challenges are the lowest bits of the next state
+        let mut tr5 = tr1.clone();
+        let bits = tr1.get_challenge_nbits(128);
+        assert_eq!(bits.len(), 128);
+        let state = tr5.squeeze_bytes(32);
+        let c = Fr::from_be_bytes_mod_order(&state[16..]);
+        assert_eq!(
+            Fr::from_bigint(<Fr as PrimeField>::BigInt::from_bits_le(&bits)).unwrap(),
+            c
+        );
+        Ok(())
+    }
+
+    // checks that the transcript can be used by the existing protocols, by folding with Nova's NIFS
+    #[test]
+    fn test_nifs_with_keccak_transcript() -> Result<(), Error> {
+        type N = NIFS<Projective, Pedersen<Projective>, KeccakTranscript<Fr>>;
+        let mut rng = test_rng();
+        let r1cs = get_test_r1cs();
+        let (pedersen_params, _) =
+            Pedersen::<Projective>::setup(&mut rng, r1cs.n_constraints().max(r1cs.n_witnesses()))?;
+        let pp_hash = Fr::rand(&mut rng);
+
+        let (w, x) = r1cs.split_z(&get_test_z(3));
+        let W_i = N::new_witness(w, r1cs.n_constraints(), &mut rng);
+        let U_i = N::new_instance(&mut rng, &pedersen_params, &W_i, x, vec![])?;
+        let (w, x) = r1cs.split_z(&get_test_z(4));
+        let w_i = N::new_witness(w, r1cs.n_constraints(), &mut rng);
+        let u_i = N::new_instance(&mut rng, &pedersen_params, &w_i, x, vec![])?;
+
+        let mut transcript_p = KeccakTranscript::<Fr>::new(&());
+        let mut transcript_v = KeccakTranscript::<Fr>::new(&());
+        let (W_i1, _, proof, _) = N::prove(
+            &pedersen_params,
+            &r1cs,
+            &mut transcript_p,
+            pp_hash,
+            &W_i,
+            &U_i,
+            &w_i,
+            &u_i,
+        )?;
+        let (U_i1, _) = N::verify(&mut transcript_v, pp_hash, &U_i, &u_i, &proof)?;
+        r1cs.check_relation(&W_i1, &U_i1)?;
+        Ok(())
+    }
+}
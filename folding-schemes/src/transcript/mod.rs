@@ -3,8 +3,13 @@ use ark_ec::CurveGroup;
 use ark_ff::PrimeField;
 use ark_r1cs_std::{boolean::Boolean, fields::fp::FpVar, groups::CurveVar};
 use ark_relations::r1cs::SynthesisError;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::fmt::Debug;
 
+pub mod keccak;
 pub mod poseidon;
+pub mod poseidon2;
+pub mod recorder;
 
 /// An interface for objects that can be absorbed by a `Transcript`.
 ///
@@ -56,7 +61,23 @@ impl<F: PrimeField, T: AbsorbNonNativeGadget<F>> AbsorbNonNativeGadget<F> for [T
     }
 }
 
-pub trait Transcript<F: PrimeField>: CryptographicSponge {
+/// A sponge configuration that has a canonical instance, which is the one used to rebuild the
+/// configuration when deserializing the public parameters of the schemes (where it is not stored).
+pub trait CanonicalConfig {
+    fn canonical_config() -> Self;
+}
+
+impl CanonicalConfig for () {
+    fn canonical_config() -> Self {}
+}
+
+/// A sponge used as the Fiat-Shamir transcript of the folding schemes. Its configuration is part
+/// of the public parameters of the schemes, so it is required to be serializable.
+pub trait Transcript<F: PrimeField>:
+    CryptographicSponge<
+    Config: Clone + Debug + CanonicalSerialize + CanonicalDeserialize + CanonicalConfig,
+>
+{
     /// `absorb_point` is for absorbing points whose `BaseField` is the field of
     /// the sponge, i.e., the type `C` of these points should satisfy
     /// `C::BaseField = F`.
@@ -87,8 +108,10 @@ pub trait Transcript<F: PrimeField>: CryptographicSponge {
     fn get_challenges(&mut self, n: usize) -> Vec<F>;
 }
 
+/// In-circuit counterpart of [`Transcript`], which is initialized with the same configuration as
+/// the native sponge `S`.
 pub trait TranscriptVar<F: PrimeField, S: CryptographicSponge>:
-    CryptographicSpongeVar<F, S>
+    CryptographicSpongeVar<F, S, Parameters = S::Config>
 {
     /// `absorb_point` is for absorbing points whose `BaseField` is the field of
     /// the sponge, i.e., the type `C` of these points should satisfy
@@ -8,7 +8,7 @@ use ark_ff::{BigInteger, PrimeField};
 use ark_r1cs_std::{boolean::Boolean, fields::fp::FpVar, groups::CurveVar};
 use ark_relations::r1cs::SynthesisError;
 
-use super::{AbsorbNonNative, AbsorbNonNativeGadget, Transcript, TranscriptVar};
+use super::{AbsorbNonNative, AbsorbNonNativeGadget, CanonicalConfig, Transcript, TranscriptVar};
 
 impl<F: PrimeField + Absorb> Transcript<F> for PoseidonSponge<F> {
     // Compatible with the in-circuit `TranscriptVar::absorb_point`
@@ -79,6 +79,12 @@ impl<F: PrimeField> TranscriptVar<F, PoseidonSponge<F>> for PoseidonSpongeVar<F>
     }
 }
 
+impl<F: PrimeField> CanonicalConfig for PoseidonConfig<F> {
+    fn canonical_config() -> Self {
+        poseidon_canonical_config()
+    }
+}
+
 /// This Poseidon configuration generator agrees with Circom's Poseidon(4) in the case of BN254's scalar field
 pub fn poseidon_canonical_config<F: PrimeField>() -> PoseidonConfig<F> {
     // 120 bit security target as in
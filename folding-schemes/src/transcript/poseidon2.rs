@@ -0,0 +1,787 @@
+/// This file implements the Poseidon2 permutation (https://eprint.iacr.org/2023/323.pdf), its
+/// duplex sponge and the in-circuit counterpart of the sponge, together with their `Transcript` and
+/// `TranscriptVar` implementations.
+///
+/// Poseidon2 replaces the MDS matrix of Poseidon by cheaper linear layers. In R1CS the linear
+/// layers are free (they are linear combinations), so the cost of a permutation is the one of its
+/// S-boxes, `3 * (t * full_rounds + partial_rounds)` constraints for `alpha = 5`.
+use ark_crypto_primitives::sponge::{
+    constraints::{AbsorbGadget, CryptographicSpongeVar},
+    poseidon::find_poseidon_ark_and_mds,
+    Absorb, CryptographicSponge, DuplexSpongeMode, FieldElementSize,
+};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::{
+    boolean::Boolean,
+    convert::{ToBitsGadget, ToBytesGadget},
+    fields::{fp::FpVar, FieldVar},
+    groups::CurveVar,
+    uint8::UInt8,
+};
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::ops::{Add, Mul};
+
+use super::{AbsorbNonNative, AbsorbNonNativeGadget, CanonicalConfig, Transcript, TranscriptVar};
+
+/// Parameters of the Poseidon2 permutation and its sponge.
+#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Poseidon2Config<F: PrimeField> {
+    /// Number of full rounds, half of them are applied before the partial rounds and the other
+    /// half after them.
+    pub full_rounds: usize,
+    /// Number of partial rounds.
+    pub partial_rounds: usize,
+    /// Exponent of the S-box.
+    pub alpha: u64,
+    /// Round constants of the full rounds, `full_rounds` vectors of `rate + capacity` elements.
+    pub ark_external: Vec<Vec<F>>,
+    /// Round constants of the partial rounds, one per round.
+    pub ark_internal: Vec<F>,
+    /// Diagonal `d` of the internal matrix `M_I = J + diag(d)`, where `J` is the all-ones matrix.
+    pub internal_diag: Vec<F>,
+    /// The rate (in terms of number of field elements).
+    pub rate: usize,
+    /// The capacity (in terms of number of field elements).
+    pub capacity: usize,
+}
+
+impl<F: PrimeField> Poseidon2Config<F> {
+    /// Returns the width `t` of the permutation, which must be 2, 3 or a multiple of 4.
+    pub fn width(&self) -> usize {
+        self.rate + self.capacity
+    }
+}
+
+impl<F: PrimeField> CanonicalConfig for Poseidon2Config<F> {
+    fn canonical_config() -> Self {
+        poseidon2_canonical_config()
+    }
+}
+
+/// Poseidon2 configuration with width `t = 3`, whose round numbers and linear layers match the
+/// ones of the `t = 3` instance of the reference implementation for BN254's scalar field
+/// (https://github.com/HorizenLabs/poseidon2): 8 full rounds, 56 partial rounds, `alpha = 5` and
+/// `M_I = [[2, 1, 1], [1, 2, 1], [1, 1, 3]]`.
+///
+/// The round constants are NOT the ones of the reference implementation, but are obtained with
+/// the same Grain LFSR as `poseidon_canonical_config`, so the outputs of the permutation are not
+/// compatible with the reference (nor with other Poseidon2 implementations using its constants).
+/// Using the reference constants requires loading them into a `Poseidon2Config`.
+pub fn poseidon2_canonical_config<F: PrimeField>() -> Poseidon2Config<F> {
+    let full_rounds = 8;
+    let partial_rounds = 56;
+    let alpha = 5;
+    let rate = 2;
+
+    let (ark, _) = find_poseidon_ark_and_mds::<F>(
+        F::MODULUS_BIT_SIZE as u64,
+        rate,
+        full_rounds,
+        partial_rounds,
+        0,
+    );
+    let half_full_rounds = full_rounds as usize / 2;
+    let ark_internal = ark[half_full_rounds..half_full_rounds + partial_rounds as usize]
+        .iter()
+        .map(|rc| rc[0])
+        .collect();
+    let ark_external = [
+        &ark[..half_full_rounds],
+        &ark[half_full_rounds + partial_rounds as usize..],
+    ]
+    .concat();
+
+    Poseidon2Config {
+        full_rounds: full_rounds as usize,
+        partial_rounds: partial_rounds as usize,
+        alpha,
+        ark_external,
+        ark_internal,
+        internal_diag: vec![F::one(), F::one(), F::from(2_u64)],
+        rate,
+        capacity: 1,
+    }
+}
+
+/// 4x4 matrix used by the external linear layer for widths that are a multiple of 4.
+const M4: [[u64; 4]; 4] = [[5, 7, 1, 3], [4, 6, 1, 1], [1, 3, 5, 7], [1, 1, 4, 6]];
+
+fn sum<T: Clone + for<'a> Add<&'a T, Output = T>>(state: &[T]) -> T {
+    state[1..].iter().fold(state[0].clone(), |acc, x| acc + x)
+}
+
+/// Multiplies the state by the external matrix `M_E`, which is `circ(2, 1)` and `circ(2, 1, 1)`
+/// for widths 2 and 3, `M4` for width 4, and `circ(2 M4, M4, ..., M4)` for the other widths that
+/// are a multiple of 4.
+fn external_linear_layer<F: PrimeField, T>(state: &mut [T])
+where
+    T: Clone + for<'a> Add<&'a T, Output = T> + Mul<F, Output = T>,
+{
+    if state.len() < 4 {
+        let s = sum(state);
+        for x in state.iter_mut() {
+            *x = x.clone() + &s;
+        }
+        return;
+    }
+    for chunk in state.chunks_exact_mut(4) {
+        let x = chunk.to_vec();
+        for (y, row) in chunk.iter_mut().zip(M4) {
+            *y = (1..4).fold(x[0].clone() * F::from(row[0]), |acc, j| {
+                acc + &(x[j].clone() * F::from(row[j]))
+            });
+        }
+    }
+    if state.len() == 4 {
+        return;
+    }
+    let sums = (0..4)
+        .map(|i| sum(&state.iter().skip(i).step_by(4).cloned().collect::<Vec<_>>()))
+        .collect::<Vec<_>>();
+    for (i, x) in state.iter_mut().enumerate() {
+        *x = x.clone() + &sums[i % 4];
+    }
+}
+
+/// Multiplies the state by the internal matrix `M_I = J + diag(d)`.
+fn internal_linear_layer<F: PrimeField, T>(state: &mut [T], diag: &[F])
+where
+    T: Clone + for<'a> Add<&'a T, Output = T> + Mul<F, Output = T>,
+{
+    let s = sum(state);
+    for (x, d) in state.iter_mut().zip(diag) {
+        *x = x.clone() * *d + &s;
+    }
+}
+
+/// Poseidon2 permutation, generic over the representation of the state so that it is shared by
+/// the native sponge and its gadget, which provide the S-box.
+fn permute<F: PrimeField, T, E>(
+    config: &Poseidon2Config<F>,
+    state: &mut [T],
+    sbox: impl Fn(&T) -> Result<T, E>,
+) -> Result<(), E>
+where
+    T: Clone + Add<F, Output = T> + for<'a> Add<&'a T, Output = T> + Mul<F, Output = T>,
+{
+    let half_full_rounds = config.full_rounds / 2;
+    let full_round = |state: &mut [T], rc: &[F]| -> Result<(), E> {
+        for (x, c) in state.iter_mut().zip(rc) {
+            *x = sbox(&(x.clone() + *c))?;
+        }
+        external_linear_layer::<F, T>(state);
+        Ok(())
+    };
+
+    external_linear_layer::<F, T>(state);
+    for rc in &config.ark_external[..half_full_rounds] {
+        full_round(state, rc.as_slice())?;
+    }
+    for c in &config.ark_internal {
+        state[0] = sbox(&(state[0].clone() + *c))?;
+        internal_linear_layer(state, &config.internal_diag);
+    }
+    for rc in &config.ark_external[half_full_rounds..] {
+        full_round(state, rc.as_slice())?;
+    }
+    Ok(())
+}
+
+/// Duplex sponge based on the Poseidon2 permutation.
+#[derive(Clone, Debug)]
+pub struct Poseidon2Sponge<F: PrimeField> {
+    /// Sponge configuration.
+    pub config: Poseidon2Config<F>,
+    /// Current sponge state.
+    pub state: Vec<F>,
+    /// Mode the sponge is currently in.
+    pub mode: DuplexSpongeMode,
+}
+
+impl<F: PrimeField> Poseidon2Sponge<F> {
+    fn permute(&mut self) {
+        let alpha = [self.config.alpha];
+        // the native S-box never fails
+        let _ = permute::<F, F, ()>(&self.config, &mut self.state, |x| Ok(x.pow(alpha)));
+    }
+
+    // Absorbs everything in elements, this does not end in an absorption.
+    fn absorb_internal(&mut self, mut rate_start_index: usize, elements: &[F]) {
+        let mut remaining_elements = elements;
+        loop {
+            // if we can finish in this call
+            if rate_start_index + remaining_elements.len() <= self.config.rate {
+                for (i, element) in remaining_elements.iter().enumerate() {
+                    self.state[self.config.capacity + i + rate_start_index] += element;
+                }
+                self.mode = DuplexSpongeMode::Absorbing {
+                    next_absorb_index: rate_start_index + remaining_elements.len(),
+                };
+                return;
+            }
+            // otherwise absorb (rate - rate_start_index) elements
+            let num_elements_absorbed = self.config.rate - rate_start_index;
+            for (i, element) in remaining_elements
+                .iter()
+                .enumerate()
+                .take(num_elements_absorbed)
+            {
+                self.state[self.config.capacity + i + rate_start_index] += element;
+            }
+            self.permute();
+            remaining_elements = &remaining_elements[num_elements_absorbed..];
+            rate_start_index = 0;
+        }
+    }
+
+    // Squeezes elements into output, this does not end in a squeeze.
+    fn squeeze_internal(&mut self, mut rate_start_index: usize, output: &mut [F]) {
+        let mut output_remaining = output;
+        loop {
+            // if we can finish in this call
+            if rate_start_index + output_remaining.len() <= self.config.rate {
+                let start = self.config.capacity + rate_start_index;
+                output_remaining
+                    .clone_from_slice(&self.state[start..start + output_remaining.len()]);
+                self.mode = DuplexSpongeMode::Squeezing {
+                    next_squeeze_index: rate_start_index + output_remaining.len(),
+                };
+                return;
+            }
+            // otherwise squeeze (rate - rate_start_index) elements
+            let num_elements_squeezed = self.config.rate - rate_start_index;
+            let start = self.config.capacity + rate_start_index;
+            output_remaining[..num_elements_squeezed]
+                .clone_from_slice(&self.state[start..start + num_elements_squeezed]);
+            // repeat with updated output slices
+            output_remaining = &mut output_remaining[num_elements_squeezed..];
+            if !output_remaining.is_empty() {
+                self.permute();
+            }
+            rate_start_index = 0;
+        }
+    }
+
+    /// Squeezes `num_elements` elements of the sponge's field.
+    pub fn squeeze_native_field_elements(&mut self, num_elements: usize) -> Vec<F> {
+        let mut squeezed_elems = vec![F::zero(); num_elements];
+        match self.mode {
+            DuplexSpongeMode::Absorbing { .. } => {
+                self.permute();
+                self.squeeze_internal(0, &mut squeezed_elems);
+            }
+            DuplexSpongeMode::Squeezing { next_squeeze_index } => {
+                let mut squeeze_index = next_squeeze_index;
+                if squeeze_index == self.config.rate {
+                    self.permute();
+                    squeeze_index = 0;
+                }
+                self.squeeze_internal(squeeze_index, &mut squeezed_elems);
+            }
+        };
+        squeezed_elems
+    }
+}
+
+impl<F: PrimeField> CryptographicSponge for Poseidon2Sponge<F> {
+    type Config = Poseidon2Config<F>;
+
+    fn new(config: &Self::Config) -> Self {
+        Self {
+            config: config.clone(),
+            state: vec![F::zero(); config.width()],
+            mode: DuplexSpongeMode::Absorbing {
+                next_absorb_index: 0,
+            },
+        }
+    }
+
+    fn absorb(&mut self, input: &impl Absorb) {
+        let elems = input.to_sponge_field_elements_as_vec::<F>();
+        if elems.is_empty() {
+            return;
+        }
+        match self.mode {
+            DuplexSpongeMode::Absorbing { next_absorb_index } => {
+                let mut absorb_index = next_absorb_index;
+                if absorb_index == self.config.rate {
+                    self.permute();
+                    absorb_index = 0;
+                }
+                self.absorb_internal(absorb_index, &elems);
+            }
+            DuplexSpongeMode::Squeezing { .. } => {
+                self.permute();
+                self.absorb_internal(0, &elems);
+            }
+        };
+    }
+
+    fn squeeze_bytes(&mut self, num_bytes: usize) -> Vec<u8> {
+        let usable_bytes = ((F::MODULUS_BIT_SIZE - 1) / 8) as usize;
+        let num_elements = num_bytes.div_ceil(usable_bytes);
+        let mut bytes = self
+            .squeeze_native_field_elements(num_elements)
+            .iter()
+            .flat_map(|e| e.into_bigint().to_bytes_le()[..usable_bytes].to_vec())
+            .collect::<Vec<_>>();
+        bytes.truncate(num_bytes);
+        bytes
+    }
+
+    fn squeeze_bits(&mut self, num_bits: usize) -> Vec<bool> {
+        let usable_bits = (F::MODULUS_BIT_SIZE - 1) as usize;
+        let num_elements = num_bits.div_ceil(usable_bits);
+        let mut bits = self
+            .squeeze_native_field_elements(num_elements)
+            .iter()
+            .flat_map(|e| e.into_bigint().to_bits_le()[..usable_bits].to_vec())
+            .collect::<Vec<_>>();
+        bits.truncate(num_bits);
+        bits
+    }
+
+    fn squeeze_field_elements<F2: PrimeField>(&mut self, num_elements: usize) -> Vec<F2> {
+        if F::characteristic() != F2::characteristic() {
+            return self.squeeze_field_elements_with_sizes::<F2>(&vec![
+                FieldElementSize::Full;
+                num_elements
+            ]);
+        }
+        // native case, the elements are returned as they are
+        self.squeeze_native_field_elements(num_elements)
+            .iter()
+            .map(|e| F2::from_le_bytes_mod_order(&e.into_bigint().to_bytes_le()))
+            .collect()
+    }
+}
+
+impl<F: PrimeField + Absorb> Transcript<F> for Poseidon2Sponge<F> {
+    // Compatible with the in-circuit `TranscriptVar::absorb_point`
+    fn absorb_point<C: CurveGroup<BaseField = F>>(&mut self, p: &C) {
+        let (x, y) = p.into_affine().xy().unwrap_or_default();
+        self.absorb(&x);
+        self.absorb(&y);
+    }
+    fn absorb_nonnative<V: AbsorbNonNative>(&mut self, v: &V) {
+        self.absorb(&v.to_native_sponge_field_elements_as_vec::<F>());
+    }
+    fn get_challenge(&mut self) -> F {
+        let c = self.squeeze_native_field_elements(1);
+        self.absorb(&c[0]);
+        c[0]
+    }
+    fn get_challenge_nbits(&mut self, nbits: usize) -> Vec<bool> {
+        let bits = self.squeeze_bits(nbits);
+        self.absorb(&F::from(F::BigInt::from_bits_le(&bits)));
+        bits
+    }
+    fn get_challenges(&mut self, n: usize) -> Vec<F> {
+        let c = self.squeeze_native_field_elements(n);
+        self.absorb(&c);
+        c
+    }
+}
+
+/// In-circuit counterpart of [`Poseidon2Sponge`].
+#[derive(Clone)]
+pub struct Poseidon2SpongeVar<F: PrimeField> {
+    /// Constraint system.
+    pub cs: ConstraintSystemRef<F>,
+    /// Sponge configuration.
+    pub config: Poseidon2Config<F>,
+    /// Current sponge state.
+    pub state: Vec<FpVar<F>>,
+    /// Mode the sponge is currently in.
+    pub mode: DuplexSpongeMode,
+}
+
+impl<F: PrimeField> Poseidon2SpongeVar<F> {
+    fn permute(&mut self) -> Result<(), SynthesisError> {
+        let alpha = [self.config.alpha];
+        permute(&self.config, &mut self.state, |x: &FpVar<F>| {
+            x.pow_by_constant(alpha)
+        })
+    }
+
+    fn absorb_internal(
+        &mut self,
+        mut rate_start_index: usize,
+        elements: &[FpVar<F>],
+    ) -> Result<(), SynthesisError> {
+        let mut remaining_elements = elements;
+        loop {
+            // if we can finish in this call
+            if rate_start_index + remaining_elements.len() <= self.config.rate {
+                for (i, element) in remaining_elements.iter().enumerate() {
+                    self.state[self.config.capacity + i + rate_start_index] += element;
+                }
+                self.mode = DuplexSpongeMode::Absorbing {
+                    next_absorb_index: rate_start_index + remaining_elements.len(),
+                };
+                return Ok(());
+            }
+            // otherwise absorb (rate - rate_start_index) elements
+            let num_elements_absorbed = self.config.rate - rate_start_index;
+            for (i, element) in remaining_elements
+                .iter()
+                .enumerate()
+                .take(num_elements_absorbed)
+            {
+                self.state[self.config.capacity + i + rate_start_index] += element;
+            }
+            self.permute()?;
+            remaining_elements = &remaining_elements[num_elements_absorbed..];
+            rate_start_index = 0;
+        }
+    }
+
+    fn squeeze_internal(
+        &mut self,
+        mut rate_start_index: usize,
+        output: &mut [FpVar<F>],
+    ) -> Result<(), SynthesisError> {
+        let mut output_remaining = output;
+        loop {
+            // if we can finish in this call
+            if rate_start_index + output_remaining.len() <= self.config.rate {
+                let start = self.config.capacity + rate_start_index;
+                output_remaining
+                    .clone_from_slice(&self.state[start..start + output_remaining.len()]);
+                self.mode = DuplexSpongeMode::Squeezing {
+                    next_squeeze_index: rate_start_index + output_remaining.len(),
+                };
+                return Ok(());
+            }
+            // otherwise squeeze (rate - rate_start_index) elements
+            let num_elements_squeezed = self.config.rate - rate_start_index;
+            let start = self.config.capacity + rate_start_index;
+            output_remaining[..num_elements_squeezed]
+                .clone_from_slice(&self.state[start..start + num_elements_squeezed]);
+            // repeat with updated output slices
+            output_remaining = &mut output_remaining[num_elements_squeezed..];
+            if !output_remaining.is_empty() {
+                self.permute()?;
+            }
+            rate_start_index = 0;
+        }
+    }
+}
+
+impl<F: PrimeField> CryptographicSpongeVar<F, Poseidon2Sponge<F>> for Poseidon2SpongeVar<F> {
+    type Parameters = Poseidon2Config<F>;
+
+    fn new(cs: ConstraintSystemRef<F>, config: &Poseidon2Config<F>) -> Self {
+        Self {
+            cs,
+            config: config.clone(),
+            state: vec![FpVar::zero(); config.width()],
+            mode: DuplexSpongeMode::Absorbing {
+                next_absorb_index: 0,
+            },
+        }
+    }
+
+    fn cs(&self) -> ConstraintSystemRef<F> {
+        self.cs.clone()
+    }
+
+    fn absorb(&mut self, input: &impl AbsorbGadget<F>) -> Result<(), SynthesisError> {
+        let input = input.to_sponge_field_elements()?;
+        if input.is_empty() {
+            return Ok(());
+        }
+        match self.mode {
+            DuplexSpongeMode::Absorbing { next_absorb_index } => {
+                let mut absorb_index = next_absorb_index;
+                if absorb_index == self.config.rate {
+                    self.permute()?;
+                    absorb_index = 0;
+                }
+                self.absorb_internal(absorb_index, &input)
+            }
+            DuplexSpongeMode::Squeezing { .. } => {
+                self.permute()?;
+                self.absorb_internal(0, &input)
+            }
+        }
+    }
+
+    fn squeeze_bytes(&mut self, num_bytes: usize) -> Result<Vec<UInt8<F>>, SynthesisError> {
+        let usable_bytes = ((F::MODULUS_BIT_SIZE - 1) / 8) as usize;
+        let num_elements = num_bytes.div_ceil(usable_bytes);
+        let mut bytes = vec![];
+        for e in self.squeeze_field_elements(num_elements)? {
+            bytes.extend_from_slice(&e.to_bytes_le()?[..usable_bytes]);
+        }
+        bytes.truncate(num_bytes);
+        Ok(bytes)
+    }
+
+    fn squeeze_bits(&mut self, num_bits: usize) -> Result<Vec<Boolean<F>>, SynthesisError> {
+        let usable_bits = (F::MODULUS_BIT_SIZE - 1) as usize;
+        let num_elements = num_bits.div_ceil(usable_bits);
+        let mut bits = vec![];
+        for e in self.squeeze_field_elements(num_elements)? {
+            bits.extend_from_slice(&e.to_bits_le()?[..usable_bits]);
+        }
+        bits.truncate(num_bits);
+        Ok(bits)
+    }
+
+    fn squeeze_field_elements(
+        &mut self,
+        num_elements: usize,
+    ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        let mut squeezed_elems = vec![FpVar::zero(); num_elements];
+        match self.mode {
+            DuplexSpongeMode::Absorbing { .. } => {
+                self.permute()?;
+                self.squeeze_internal(0, &mut squeezed_elems)?;
+            }
+            DuplexSpongeMode::Squeezing { next_squeeze_index } => {
+                let mut squeeze_index = next_squeeze_index;
+                if squeeze_index == self.config.rate {
+                    self.permute()?;
+                    squeeze_index = 0;
+                }
+                self.squeeze_internal(squeeze_index, &mut squeezed_elems)?;
+            }
+        };
+        Ok(squeezed_elems)
+    }
+}
+
+impl<F: PrimeField> TranscriptVar<F, Poseidon2Sponge<F>> for Poseidon2SpongeVar<F> {
+    fn absorb_point<C: CurveGroup<BaseField = F>, GC: CurveVar<C, F>>(
+        &mut self,
+        v: &GC,
+    ) -> Result<(), SynthesisError> {
+        let mut vec = v.to_constraint_field()?;
+        // as in `PoseidonSpongeVar`, the last element (the infinity flag) is not absorbed, since
+        // the infinity point is represented as (0, 0), and no other point has y = 0.
+        vec.pop();
+        self.absorb(&vec)
+    }
+    fn absorb_nonnative<V: AbsorbNonNativeGadget<F>>(
+        &mut self,
+        v: &V,
+    ) -> Result<(), SynthesisError> {
+        self.absorb(&v.to_native_sponge_field_elements()?)
+    }
+    fn get_challenge(&mut self) -> Result<FpVar<F>, SynthesisError> {
+        let c = self.squeeze_field_elements(1)?;
+        self.absorb(&c[0])?;
+        Ok(c[0].clone())
+    }
+    fn get_challenge_nbits(&mut self, nbits: usize) -> Result<Vec<Boolean<F>>, SynthesisError> {
+        let bits = self.squeeze_bits(nbits)?;
+        self.absorb(&Boolean::le_bits_to_fp(&bits)?)?;
+        Ok(bits)
+    }
+    fn get_challenges(&mut self, n: usize) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        let c = self.squeeze_field_elements(n)?;
+        self.absorb(&c)?;
+        Ok(c)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use ark_bn254::{constraints::GVar, Fq, Fr, G1Projective as G1};
+    use ark_ec::PrimeGroup;
+    use ark_ff::UniformRand;
+    use ark_r1cs_std::{alloc::AllocVar, R1CSVar};
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::test_rng;
+
+    use super::*;
+    use crate::folding::circuits::nonnative::affine::NonNativeAffineVar;
+    use crate::folding::nova::{nifs::nova_circuits::CommittedInstanceVar, CommittedInstance};
+    use crate::folding::traits::{CommittedInstanceOps, CommittedInstanceVarOps};
+    use crate::Error;
+
+    #[test]
+    fn test_permutation_native_and_gadget() -> Result<(), Error> {
+        let mut rng = test_rng();
+        let config = poseidon2_canonical_config::<Fr>();
+        let input: Vec<Fr> = (0..config.width()).map(|_| Fr::rand(&mut rng)).collect();
+
+        let mut sponge = Poseidon2Sponge::<Fr>::new(&config);
+        sponge.state = input.clone();
+        sponge.permute();
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let mut sponge_var = Poseidon2SpongeVar::<Fr>::new(cs.clone(), &config);
+        sponge_var.state = Vec::new_witness(cs.clone(), || Ok(input.clone()))?;
+        sponge_var.permute()?;
+
+        assert_ne!(sponge.state, input);
+        assert_eq!(sponge.state, sponge_var.state.value()?);
+        assert!(cs.is_satisfied()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_wide_permutation_native_and_gadget() -> Result<(), Error> {
+        // width 8, which uses the `circ(2 M4, M4)` external matrix
+        let mut rng = test_rng();
+        let (ark, _) = find_poseidon_ark_and_mds::<Fr>(254, 7, 8, 56, 0);
+        let config = Poseidon2Config {
+            full_rounds: 8,
+            partial_rounds: 56,
+            alpha: 5,
+            ark_external: [&ark[..4], &ark[60..]].concat(),
+            ark_internal: ark[4..60].iter().map(|rc| rc[0]).collect(),
+            internal_diag: (0..8).map(|_| Fr::rand(&mut rng)).collect(),
+            rate: 7,
+            capacity: 1,
+        };
+        let input: Vec<Fr> = (0..config.width()).map(|_| Fr::rand(&mut rng)).collect();
+
+        let mut sponge = Poseidon2Sponge::<Fr>::new(&config);
+        sponge.state = input.clone();
+        sponge.permute();
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let mut sponge_var = Poseidon2SpongeVar::<Fr>::new(cs.clone(), &config);
+        sponge_var.state = Vec::new_witness(cs.clone(), || Ok(input))?;
+        sponge_var.permute()?;
+
+        assert_eq!(sponge.state, sponge_var.state.value()?);
+        assert!(cs.is_satisfied()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_transcript_and_transcriptvar_absorb_native_point() -> Result<(), Error> {
+        // use 'native' transcript
+        let config = poseidon2_canonical_config::<Fq>();
+        let mut tr = Poseidon2Sponge::<Fq>::new(&config);
+        let rng = &mut test_rng();
+
+        let p = G1::rand(rng);
+        tr.absorb_point(&p);
+        let c = tr.get_challenge();
+
+        // use 'gadget' transcript
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        let mut tr_var = Poseidon2SpongeVar::<Fq>::new(cs.clone(), &config);
+        let p_var = GVar::new_witness(cs.clone(), || Ok(p))?;
+        tr_var.absorb_point(&p_var)?;
+        let c_var = tr_var.get_challenge()?;
+
+        // assert that native & gadget transcripts return the same challenge
+        assert_eq!(c, c_var.value()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_transcript_and_transcriptvar_absorb_nonnative_point() -> Result<(), Error> {
+        // use 'native' transcript
+        let config = poseidon2_canonical_config::<Fr>();
+        let mut tr = Poseidon2Sponge::<Fr>::new(&config);
+        let rng = &mut test_rng();
+
+        let p = G1::rand(rng);
+        tr.absorb_nonnative(&p);
+        let c = tr.get_challenges(3);
+
+        // use 'gadget' transcript
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let mut tr_var = Poseidon2SpongeVar::<Fr>::new(cs.clone(), &config);
+        let p_var = NonNativeAffineVar::<G1>::new_witness(cs.clone(), || Ok(p))?;
+        tr_var.absorb_nonnative(&p_var)?;
+        let c_var = tr_var.get_challenges(3)?;
+
+        // assert that native & gadget transcripts return the same challenges
+        assert_eq!(c, c_var.value()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_transcript_and_transcriptvar_nbits() -> Result<(), Error> {
+        let nbits = crate::constants::NOVA_N_BITS_RO;
+
+        // use 'native' transcript
+        let config = poseidon2_canonical_config::<Fq>();
+        let mut tr = Poseidon2Sponge::<Fq>::new(&config);
+        tr.absorb(&Fq::from(42_u32));
+        let c_bits = tr.get_challenge_nbits(nbits);
+        let c = tr.get_challenge();
+
+        // use 'gadget' transcript
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        let mut tr_var = Poseidon2SpongeVar::<Fq>::new(cs.clone(), &config);
+        let v = FpVar::<Fq>::new_witness(cs.clone(), || Ok(Fq::from(42_u32)))?;
+        tr_var.absorb(&v)?;
+        let c_bits_var = tr_var.get_challenge_nbits(nbits)?;
+        let c_var = tr_var.get_challenge()?;
+
+        // the bits are the same, and so is the state of the transcripts after absorbing them
+        assert_eq!(c_bits, c_bits_var.value()?);
+        assert_eq!(c, c_var.value()?);
+
+        // c*P is the same natively and in-circuit
+        let P = G1::generator();
+        let PVar = GVar::new_witness(cs.clone(), || Ok(P))?;
+        let cP_native = P.mul_bits_be(c_bits.into_iter().rev());
+        let cPVar = PVar.scalar_mul_le(c_bits_var.iter())?;
+        assert_eq!(cP_native.into_affine(), cPVar.value()?.into_affine());
+        assert!(cs.is_satisfied()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_permutation_constraints() -> Result<(), Error> {
+        let config = poseidon2_canonical_config::<Fr>();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let mut sponge_var = Poseidon2SpongeVar::<Fr>::new(cs.clone(), &config);
+        sponge_var.state = Vec::new_witness(cs.clone(), || Ok(vec![Fr::from(42_u32); 3]))?;
+        sponge_var.permute()?;
+
+        // only the S-boxes add constraints, 3 per S-box since alpha = 5
+        assert_eq!(
+            cs.num_constraints(),
+            3 * (config.width() * config.full_rounds + config.partial_rounds)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_committed_instance_hash() -> Result<(), Error> {
+        let mut rng = test_rng();
+        let config = poseidon2_canonical_config::<Fr>();
+        let pp_hash = Fr::from(42u32); // only for test
+        let i = Fr::from(3_u32);
+        let z_0 = vec![Fr::from(3_u32)];
+        let z_i = vec![Fr::from(4_u32)];
+        let ci = CommittedInstance::<G1> {
+            cmE: G1::rand(&mut rng),
+            u: Fr::rand(&mut rng),
+            cmW: G1::rand(&mut rng),
+            x: vec![Fr::rand(&mut rng); 2],
+        };
+
+        // compute the CommittedInstance hash natively
+        let sponge = Poseidon2Sponge::<Fr>::new(&config);
+        let h = ci.hash(&sponge, pp_hash, i, &z_0, &z_i);
+
+        // compute the CommittedInstance hash in-circuit
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let pp_hashVar = FpVar::<Fr>::new_witness(cs.clone(), || Ok(pp_hash))?;
+        let iVar = FpVar::<Fr>::new_witness(cs.clone(), || Ok(i))?;
+        let z_0Var = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(z_0.clone()))?;
+        let z_iVar = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(z_i.clone()))?;
+        let ciVar = CommittedInstanceVar::<G1>::new_witness(cs.clone(), || Ok(ci.clone()))?;
+        let sponge = Poseidon2SpongeVar::<Fr>::new(cs.clone(), &config);
+        let (hVar, _) = ciVar.hash(&sponge, &pp_hashVar, &iVar, &z_0Var, &z_iVar)?;
+        assert!(cs.is_satisfied()?);
+
+        // check that the natively computed and in-circuit computed hashes match
+        assert_eq!(hVar.value()?, h);
+        Ok(())
+    }
+}
@@ -0,0 +1,451 @@
+/// This file implements wrappers of `Transcript` and `TranscriptVar` that record every absorb and
+/// squeeze operation, together with a utility to find the first operation where a native run and
+/// an in-circuit run diverge. They are meant to be used for debugging, since when the native and
+/// in-circuit transcripts of a scheme diverge, the only symptom is a failing verification.
+///
+/// Usage: replace the transcript of the native and in-circuit runs by `RecordingTranscript` and
+/// `RecordingTranscriptVar` respectively (optionally calling `set_label` to group the operations),
+/// and compare their `records` with `find_first_divergence`.
+///
+/// Note that the records of a transcript are not shared with its clones, so operations performed
+/// over a cloned transcript (e.g. in `CommittedInstanceOps::hash`) are not recorded in the original
+/// one.
+use ark_crypto_primitives::sponge::{
+    constraints::{AbsorbGadget, CryptographicSpongeVar},
+    Absorb, CryptographicSponge, FieldElementSize,
+};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::{boolean::Boolean, fields::fp::FpVar, groups::CurveVar, uint8::UInt8, R1CSVar};
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use ark_std::{fmt, marker::PhantomData};
+
+use super::{AbsorbNonNative, AbsorbNonNativeGadget, Transcript, TranscriptVar};
+
+/// Kind of operation performed over a transcript.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TranscriptOp {
+    Absorb,
+    Squeeze,
+}
+
+/// Record of an operation performed over a transcript.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TranscriptRecord<F: PrimeField> {
+    /// Label that was set when the operation was performed.
+    pub label: String,
+    /// Method of the transcript that performed the operation.
+    pub method: &'static str,
+    pub op: TranscriptOp,
+    /// Absorbed or squeezed values, where bits and bytes are represented as field elements. It is
+    /// `None` in-circuit when the values are not assigned (e.g. in setup mode).
+    pub values: Option<Vec<F>>,
+}
+
+impl<F: PrimeField> fmt::Display for TranscriptRecord<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {} ({:?}): ", self.label, self.method, self.op)?;
+        match &self.values {
+            Some(values) => write!(
+                f,
+                "{:?}",
+                values.iter().map(|v| v.to_string()).collect::<Vec<_>>()
+            ),
+            None => write!(f, "unassigned"),
+        }
+    }
+}
+
+/// First operation where two recorded runs diverge.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TranscriptDivergence<F: PrimeField> {
+    /// Position of the operation in the records.
+    pub index: usize,
+    /// Operation of the native run, `None` if the native run has fewer operations.
+    pub native: Option<TranscriptRecord<F>>,
+    /// Operation of the in-circuit run, `None` if the in-circuit run has fewer operations.
+    pub circuit: Option<TranscriptRecord<F>>,
+}
+
+impl<F: PrimeField> fmt::Display for TranscriptDivergence<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let display = |r: &Option<TranscriptRecord<F>>| match r {
+            Some(r) => r.to_string(),
+            None => "no operation".to_string(),
+        };
+        writeln!(f, "transcripts diverge at operation {}:", self.index)?;
+        writeln!(f, "  native:  {}", display(&self.native))?;
+        write!(f, "  circuit: {}", display(&self.circuit))
+    }
+}
+
+/// Returns the first operation where the records of a native run and of an in-circuit run differ,
+/// either in the kind of operation or in its values, or `None` if both runs match. The values of
+/// unassigned in-circuit operations are not compared.
+pub fn find_first_divergence<F: PrimeField>(
+    native: &[TranscriptRecord<F>],
+    circuit: &[TranscriptRecord<F>],
+) -> Option<TranscriptDivergence<F>> {
+    (0..native.len().max(circuit.len())).find_map(|index| {
+        let (n, c) = (native.get(index), circuit.get(index));
+        let matches = match (n, c) {
+            (Some(n), Some(c)) => {
+                n.op == c.op
+                    && match (&n.values, &c.values) {
+                        (Some(n_values), Some(c_values)) => n_values == c_values,
+                        _ => true,
+                    }
+            }
+            _ => false,
+        };
+        (!matches).then(|| TranscriptDivergence {
+            index,
+            native: n.cloned(),
+            circuit: c.cloned(),
+        })
+    })
+}
+
+fn bits_to_field_elements<F: PrimeField>(bits: &[bool]) -> Vec<F> {
+    bits.iter().map(|&b| F::from(b)).collect()
+}
+
+fn cast_field_elements<F: PrimeField, F2: PrimeField>(v: &[F2]) -> Vec<F> {
+    v.iter()
+        .map(|e| F::from_le_bytes_mod_order(&e.into_bigint().to_bytes_le()))
+        .collect()
+}
+
+/// Wrapper of a native `Transcript` that records the operations performed over it.
+#[derive(Clone, Debug)]
+pub struct RecordingTranscript<F: PrimeField, T: Transcript<F>> {
+    inner: T,
+    label: String,
+    /// Operations performed over the transcript.
+    pub records: Vec<TranscriptRecord<F>>,
+}
+
+impl<F: PrimeField, T: Transcript<F>> RecordingTranscript<F, T> {
+    /// Wraps the given transcript, which can already contain absorbed data.
+    pub fn wrap(inner: T) -> Self {
+        Self {
+            inner,
+            label: String::new(),
+            records: vec![],
+        }
+    }
+
+    /// Sets the label of the next operations.
+    pub fn set_label(&mut self, label: &str) {
+        self.label = label.to_string();
+    }
+
+    /// Returns the wrapped transcript.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    fn record(&mut self, method: &'static str, op: TranscriptOp, values: Vec<F>) {
+        self.records.push(TranscriptRecord {
+            label: self.label.clone(),
+            method,
+            op,
+            values: Some(values),
+        });
+    }
+}
+
+impl<F: PrimeField, T: Transcript<F>> CryptographicSponge for RecordingTranscript<F, T> {
+    type Config = T::Config;
+
+    fn new(config: &Self::Config) -> Self {
+        Self::wrap(T::new(config))
+    }
+
+    fn absorb(&mut self, input: &impl Absorb) {
+        self.record(
+            "absorb",
+            TranscriptOp::Absorb,
+            input.to_sponge_field_elements_as_vec(),
+        );
+        self.inner.absorb(input);
+    }
+
+    fn squeeze_bytes(&mut self, num_bytes: usize) -> Vec<u8> {
+        let bytes = self.inner.squeeze_bytes(num_bytes);
+        let values = bytes.iter().map(|&b| F::from(b)).collect();
+        self.record("squeeze_bytes", TranscriptOp::Squeeze, values);
+        bytes
+    }
+
+    fn squeeze_bits(&mut self, num_bits: usize) -> Vec<bool> {
+        let bits = self.inner.squeeze_bits(num_bits);
+        let values = bits_to_field_elements(&bits);
+        self.record("squeeze_bits", TranscriptOp::Squeeze, values);
+        bits
+    }
+
+    fn squeeze_field_elements_with_sizes<F2: PrimeField>(
+        &mut self,
+        sizes: &[FieldElementSize],
+    ) -> Vec<F2> {
+        let elements = self.inner.squeeze_field_elements_with_sizes(sizes);
+        let values = cast_field_elements(&elements);
+        self.record(
+            "squeeze_field_elements_with_sizes",
+            TranscriptOp::Squeeze,
+            values,
+        );
+        elements
+    }
+
+    fn squeeze_field_elements<F2: PrimeField>(&mut self, num_elements: usize) -> Vec<F2> {
+        let elements = self.inner.squeeze_field_elements(num_elements);
+        let values = cast_field_elements(&elements);
+        self.record("squeeze_field_elements", TranscriptOp::Squeeze, values);
+        elements
+    }
+}
+
+impl<F: PrimeField, T: Transcript<F>> Transcript<F> for RecordingTranscript<F, T> {
+    fn absorb_point<C: CurveGroup<BaseField = F>>(&mut self, v: &C) {
+        let (x, y) = v.into_affine().xy().unwrap_or_default();
+        self.record("absorb_point", TranscriptOp::Absorb, vec![x, y]);
+        self.inner.absorb_point(v);
+    }
+    fn absorb_nonnative<V: AbsorbNonNative>(&mut self, v: &V) {
+        self.record(
+            "absorb_nonnative",
+            TranscriptOp::Absorb,
+            v.to_native_sponge_field_elements_as_vec(),
+        );
+        self.inner.absorb_nonnative(v);
+    }
+    fn get_challenge(&mut self) -> F {
+        let c = self.inner.get_challenge();
+        self.record("get_challenge", TranscriptOp::Squeeze, vec![c]);
+        c
+    }
+    fn get_challenge_nbits(&mut self, nbits: usize) -> Vec<bool> {
+        let bits = self.inner.get_challenge_nbits(nbits);
+        let values = bits_to_field_elements(&bits);
+        self.record("get_challenge_nbits", TranscriptOp::Squeeze, values);
+        bits
+    }
+    fn get_challenges(&mut self, n: usize) -> Vec<F> {
+        let c = self.inner.get_challenges(n);
+        self.record("get_challenges", TranscriptOp::Squeeze, c.clone());
+        c
+    }
+}
+
+/// Wrapper of a `TranscriptVar` that records the operations performed over it.
+///
+/// Obtaining the values to record requires computing the sponge representation of the absorbed
+/// objects once more, which may add constraints to the circuit, so this wrapper is only meant to
+/// be used for debugging.
+#[derive(Clone)]
+pub struct RecordingTranscriptVar<F: PrimeField, S: CryptographicSponge, T: TranscriptVar<F, S>> {
+    inner: T,
+    label: String,
+    /// Operations performed over the transcript.
+    pub records: Vec<TranscriptRecord<F>>,
+    _s: PhantomData<S>,
+}
+
+impl<F: PrimeField, S: CryptographicSponge, T: TranscriptVar<F, S>>
+    RecordingTranscriptVar<F, S, T>
+{
+    /// Wraps the given transcript, which can already contain absorbed data.
+    pub fn wrap(inner: T) -> Self {
+        Self {
+            inner,
+            label: String::new(),
+            records: vec![],
+            _s: PhantomData,
+        }
+    }
+
+    /// Sets the label of the next operations.
+    pub fn set_label(&mut self, label: &str) {
+        self.label = label.to_string();
+    }
+
+    /// Returns the wrapped transcript.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    fn record(&mut self, method: &'static str, op: TranscriptOp, values: Option<Vec<F>>) {
+        self.records.push(TranscriptRecord {
+            label: self.label.clone(),
+            method,
+            op,
+            values,
+        });
+    }
+}
+
+impl<F: PrimeField, S: CryptographicSponge, T: TranscriptVar<F, S>> CryptographicSpongeVar<F, S>
+    for RecordingTranscriptVar<F, S, T>
+{
+    type Parameters = T::Parameters;
+
+    fn new(cs: ConstraintSystemRef<F>, params: &Self::Parameters) -> Self {
+        Self::wrap(T::new(cs, params))
+    }
+
+    fn cs(&self) -> ConstraintSystemRef<F> {
+        self.inner.cs()
+    }
+
+    fn absorb(&mut self, input: &impl AbsorbGadget<F>) -> Result<(), SynthesisError> {
+        let values = input.to_sponge_field_elements()?.value().ok();
+        self.record("absorb", TranscriptOp::Absorb, values);
+        self.inner.absorb(input)
+    }
+
+    fn squeeze_bytes(&mut self, num_bytes: usize) -> Result<Vec<UInt8<F>>, SynthesisError> {
+        let bytes = self.inner.squeeze_bytes(num_bytes)?;
+        let values = bytes
+            .value()
+            .ok()
+            .map(|bytes| bytes.into_iter().map(F::from).collect());
+        self.record("squeeze_bytes", TranscriptOp::Squeeze, values);
+        Ok(bytes)
+    }
+
+    fn squeeze_bits(&mut self, num_bits: usize) -> Result<Vec<Boolean<F>>, SynthesisError> {
+        let bits = self.inner.squeeze_bits(num_bits)?;
+        let values = bits.value().ok().map(|b| bits_to_field_elements(&b));
+        self.record("squeeze_bits", TranscriptOp::Squeeze, values);
+        Ok(bits)
+    }
+
+    fn squeeze_field_elements(
+        &mut self,
+        num_elements: usize,
+    ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        let elements = self.inner.squeeze_field_elements(num_elements)?;
+        let values = elements.value().ok();
+        self.record("squeeze_field_elements", TranscriptOp::Squeeze, values);
+        Ok(elements)
+    }
+}
+
+impl<F: PrimeField, S: CryptographicSponge, T: TranscriptVar<F, S>> TranscriptVar<F, S>
+    for RecordingTranscriptVar<F, S, T>
+{
+    fn absorb_point<C: CurveGroup<BaseField = F>, GC: CurveVar<C, F>>(
+        &mut self,
+        v: &GC,
+    ) -> Result<(), SynthesisError> {
+        let values = v.value().ok().map(|p| {
+            let (x, y) = p.into_affine().xy().unwrap_or_default();
+            vec![x, y]
+        });
+        self.record("absorb_point", TranscriptOp::Absorb, values);
+        self.inner.absorb_point(v)
+    }
+    fn absorb_nonnative<V: AbsorbNonNativeGadget<F>>(
+        &mut self,
+        v: &V,
+    ) -> Result<(), SynthesisError> {
+        let values = v.to_native_sponge_field_elements()?.value().ok();
+        self.record("absorb_nonnative", TranscriptOp::Absorb, values);
+        self.inner.absorb_nonnative(v)
+    }
+    fn get_challenge(&mut self) -> Result<FpVar<F>, SynthesisError> {
+        let c = self.inner.get_challenge()?;
+        let values = c.value().ok().map(|c| vec![c]);
+        self.record("get_challenge", TranscriptOp::Squeeze, values);
+        Ok(c)
+    }
+    fn get_challenge_nbits(&mut self, nbits: usize) -> Result<Vec<Boolean<F>>, SynthesisError> {
+        let bits = self.inner.get_challenge_nbits(nbits)?;
+        let values = bits.value().ok().map(|b| bits_to_field_elements(&b));
+        self.record("get_challenge_nbits", TranscriptOp::Squeeze, values);
+        Ok(bits)
+    }
+    fn get_challenges(&mut self, n: usize) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        let c = self.inner.get_challenges(n)?;
+        let values = c.value().ok();
+        self.record("get_challenges", TranscriptOp::Squeeze, values);
+        Ok(c)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use ark_crypto_primitives::sponge::poseidon::{constraints::PoseidonSpongeVar, PoseidonSponge};
+    use ark_pallas::{Fr, Projective};
+    use ark_r1cs_std::alloc::AllocVar;
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::{test_rng, UniformRand};
+
+    use super::*;
+    use crate::folding::circuits::nonnative::affine::NonNativeAffineVar;
+    use crate::transcript::poseidon::poseidon_canonical_config;
+    use crate::Error;
+
+    type TR = RecordingTranscript<Fr, PoseidonSponge<Fr>>;
+    type TRVar = RecordingTranscriptVar<Fr, PoseidonSponge<Fr>, PoseidonSpongeVar<Fr>>;
+
+    #[test]
+    fn test_recording_transcripts() -> Result<(), Error> {
+        let mut rng = test_rng();
+        let config = poseidon_canonical_config::<Fr>();
+        let v = Fr::rand(&mut rng);
+        let p = Projective::rand(&mut rng);
+
+        // native run
+        let mut tr = TR::new(&config);
+        tr.set_label("inputs");
+        tr.absorb(&v);
+        tr.absorb_nonnative(&p);
+        tr.set_label("challenges");
+        let c = tr.get_challenge();
+        let c_bits = tr.get_challenge_nbits(10);
+
+        // in-circuit run, which absorbs a different value after the first challenge
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let mut tr_var = TRVar::new(cs.clone(), &config);
+        tr_var.set_label("inputs");
+        tr_var.absorb(&FpVar::new_witness(cs.clone(), || Ok(v))?)?;
+        tr_var.absorb_nonnative(&NonNativeAffineVar::<Projective>::new_witness(
+            cs.clone(),
+            || Ok(p),
+        )?)?;
+        tr_var.set_label("challenges");
+        let c_var = tr_var.get_challenge()?;
+        let c_bits_var = tr_var.get_challenge_nbits(10)?;
+        assert_eq!(c, c_var.value()?);
+        assert_eq!(c_bits, c_bits_var.value()?);
+
+        // both runs match, and the records contain the expected operations
+        assert_eq!(find_first_divergence(&tr.records, &tr_var.records), None);
+        assert_eq!(tr.records.len(), 4);
+        assert_eq!(tr.records[0].values, Some(vec![v]));
+        assert_eq!(tr.records[2].label, "challenges");
+        assert_eq!(tr.records[2].op, TranscriptOp::Squeeze);
+
+        // diverge from the 5th operation on
+        tr.absorb(&c);
+        tr_var.absorb(&FpVar::new_witness(cs.clone(), || Ok(c + Fr::from(1)))?)?;
+        tr.get_challenge();
+        let _ = tr_var.get_challenge()?;
+        let divergence = find_first_divergence(&tr.records, &tr_var.records).unwrap();
+        assert_eq!(divergence.index, 4);
+        assert_eq!(divergence.native.unwrap().values, Some(vec![c]));
+        assert_eq!(
+            divergence.circuit.unwrap().values,
+            Some(vec![c + Fr::from(1)])
+        );
+
+        // a missing operation is also reported
+        let divergence = find_first_divergence(&tr.records[..4], &tr_var.records[..3]).unwrap();
+        assert_eq!(divergence.index, 3);
+        assert_eq!(divergence.circuit, None);
+        Ok(())
+    }
+}
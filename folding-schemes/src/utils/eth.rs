@@ -8,6 +8,8 @@ use ark_ec::{
 use ark_ff::{BigInteger, Fp, Fp2, Fp2Config, FpConfig, PrimeField};
 use ark_groth16::Proof;
 
+use crate::snark::plonk::Proof as PlonkProof;
+
 pub trait ToEth {
     fn to_eth(&self) -> Vec<u8>;
 }
@@ -56,3 +58,19 @@ impl<E: Pairing<G1Affine: ToEth, G2Affine: ToEth>> ToEth for Proof<E> {
         [self.a.to_eth(), self.b.to_eth(), self.c.to_eth()].concat()
     }
 }
+
+impl<E: Pairing<G1: ToEth, ScalarField: ToEth>> ToEth for PlonkProof<E> {
+    fn to_eth(&self) -> Vec<u8> {
+        [
+            self.wires.to_eth(),
+            self.z.to_eth(),
+            self.t.to_eth(),
+            self.w_zeta.to_eth(),
+            self.w_zeta_omega.to_eth(),
+            self.wire_evals.to_eth(),
+            self.sigma_evals.to_eth(),
+            self.z_omega_eval.to_eth(),
+        ]
+        .concat()
+    }
+}
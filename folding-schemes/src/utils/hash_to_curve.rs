@@ -0,0 +1,77 @@
+/// Transparent derivation of curve points from a domain separation tag, used to obtain commitment
+/// generators for which nobody knows a discrete-log relation.
+///
+/// The points are obtained with a try-and-increment map: the coordinate `x` is derived from
+/// `SHA3-512(len(dst) || dst || msg || counter)`, increasing the counter until `x` is the
+/// coordinate of a point of the curve (`AffineRepr::from_random_bytes`, which also takes the sign
+/// of `y` from the hash), and the result is multiplied by the cofactor. This works for every
+/// short Weierstrass curve, including the ones with `a = 0` (BN254, Grumpkin, Pallas, Vesta) for
+/// which the SWU map is not directly applicable. Note that the map is not constant time, which is
+/// not an issue since it is only applied to public data.
+use ark_ec::AffineRepr;
+use sha3::{Digest, Sha3_512};
+
+use crate::Curve;
+
+/// Returns the point of `C` obtained by hashing `msg` with the domain separation tag `dst`.
+pub fn hash_to_curve<C: Curve>(dst: &[u8], msg: &[u8]) -> C::Affine {
+    let mut counter: u64 = 0;
+    loop {
+        let mut hasher = Sha3_512::new();
+        hasher.update((dst.len() as u64).to_le_bytes());
+        hasher.update(dst);
+        hasher.update(msg);
+        hasher.update(counter.to_le_bytes());
+        let bytes = hasher.finalize();
+
+        if let Some(p) = C::Affine::from_random_bytes(&bytes) {
+            let p = p.clear_cofactor();
+            if !p.is_zero() {
+                return p;
+            }
+        }
+        counter += 1;
+    }
+}
+
+/// Returns the `n` points `hash_to_curve(dst, "g" || i)` for `i` in `0..n`, so that the first
+/// points do not depend on `n`.
+pub fn hash_to_curve_generators<C: Curve>(dst: &[u8], n: usize) -> Vec<C::Affine> {
+    (0..n)
+        .map(|i| hash_to_curve::<C>(dst, &[b"g".as_slice(), &(i as u64).to_le_bytes()].concat()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ec::short_weierstrass::{Affine, SWCurveConfig};
+
+    use super::*;
+
+    fn test_hash_to_curve_opt<P: SWCurveConfig, C: Curve<Affine = Affine<P>>>() {
+        let dst = b"sonobe-test";
+        let generators = hash_to_curve_generators::<C>(dst, 8);
+        for (i, g) in generators.iter().enumerate() {
+            assert!(g.is_on_curve() && g.is_in_correct_subgroup_assuming_on_curve());
+            assert!(!g.is_zero());
+            // all the generators are different
+            assert!(!generators[..i].contains(g));
+        }
+        // deterministic, and the first generators do not depend on the number of generators
+        assert_eq!(hash_to_curve_generators::<C>(dst, 4), generators[..4]);
+        // depends on the domain separation tag and on the message
+        assert_ne!(
+            hash_to_curve::<C>(b"sonobe-test-2", b"g"),
+            hash_to_curve::<C>(dst, b"g")
+        );
+        assert_ne!(hash_to_curve::<C>(dst, b"h"), hash_to_curve::<C>(dst, b"g"));
+    }
+
+    #[test]
+    fn test_hash_to_curve() {
+        test_hash_to_curve_opt::<ark_bn254::g1::Config, ark_bn254::G1Projective>();
+        test_hash_to_curve_opt::<ark_grumpkin::GrumpkinConfig, ark_grumpkin::Projective>();
+        test_hash_to_curve_opt::<ark_pallas::PallasConfig, ark_pallas::Projective>();
+        test_hash_to_curve_opt::<ark_vesta::VestaConfig, ark_vesta::Projective>();
+    }
+}
@@ -1,7 +1,6 @@
 use std::path::Path;
 use std::path::PathBuf;
 
-use ark_crypto_primitives::sponge::poseidon::PoseidonConfig;
 use ark_ec::AffineRepr;
 use ark_ff::PrimeField;
 use ark_serialize::CanonicalSerialize;
@@ -13,6 +12,7 @@ use crate::{Curve, Error};
 
 pub mod eth;
 pub mod gadgets;
+pub mod hash_to_curve;
 pub mod hypercube;
 pub mod lagrange_poly;
 pub mod mle;
@@ -47,7 +47,7 @@ pub fn pp_hash<C1, C2, CS1, CS2, const H: bool>(
     cf_arith: &impl ArithSerializer,
     cs_vp: &CS1::VerifierParams,
     cf_cs_vp: &CS2::VerifierParams,
-    poseidon_config: &PoseidonConfig<C1::ScalarField>,
+    poseidon_config: &impl CanonicalSerialize,
 ) -> Result<C1::ScalarField, Error>
 where
     C1: Curve,
@@ -71,29 +71,10 @@ where
     let mut cf_cs_vp_bytes = Vec::new();
     cf_cs_vp.serialize_uncompressed(&mut cf_cs_vp_bytes)?;
     hasher.update(cf_cs_vp_bytes);
-    // poseidon params
+    // sponge params (for Poseidon, this is the same as serializing each of the fields of its
+    // config)
     let mut poseidon_config_bytes = Vec::new();
-    poseidon_config
-        .full_rounds
-        .serialize_uncompressed(&mut poseidon_config_bytes)?;
-    poseidon_config
-        .partial_rounds
-        .serialize_uncompressed(&mut poseidon_config_bytes)?;
-    poseidon_config
-        .alpha
-        .serialize_uncompressed(&mut poseidon_config_bytes)?;
-    poseidon_config
-        .ark
-        .serialize_uncompressed(&mut poseidon_config_bytes)?;
-    poseidon_config
-        .mds
-        .serialize_uncompressed(&mut poseidon_config_bytes)?;
-    poseidon_config
-        .rate
-        .serialize_uncompressed(&mut poseidon_config_bytes)?;
-    poseidon_config
-        .capacity
-        .serialize_uncompressed(&mut poseidon_config_bytes)?;
+    poseidon_config.serialize_uncompressed(&mut poseidon_config_bytes)?;
     hasher.update(poseidon_config_bytes);
 
     let public_params_hash = hasher.finalize();
@@ -4,6 +4,11 @@ pub mod verifiers;
 
 pub use verifiers::*;
 pub use verifiers::{
-    get_decider_template_for_cyclefold_decider, Groth16VerifierKey, KZG10VerifierKey,
-    NovaCycleFoldVerifierKey, ProtocolVerifierKey,
+    get_decider_template_for_cyclefold_decider, get_decider_template_for_cyclefold_plonk_decider,
+    get_decider_template_for_hypernova_cyclefold_decider,
+    get_decider_template_for_protogalaxy_cyclefold_decider,
+    get_stateful_decider_template_for_cyclefold_decider, prepare_update_state_calldata,
+    Groth16VerifierKey, HyperNovaCycleFoldVerifierKey, KZG10VerifierKey,
+    NovaCycleFoldPlonkVerifierKey, NovaCycleFoldVerifierKey, PlonkVerifierKey,
+    ProtoGalaxyCycleFoldVerifierKey, ProtocolVerifierKey,
 };
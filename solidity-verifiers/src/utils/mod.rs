@@ -22,7 +22,59 @@ pub fn get_function_selector_for_nova_cyclefold_verifier(
     first_param_array_length: usize,
 ) -> [u8; 4] {
     let mut hasher = Sha3::keccak256();
-    let fn_sig = format!("verifyNovaProof(uint256[{}],uint256[4],uint256[2],uint256[3],uint256[2],uint256[2][2],uint256[2],uint256[4],uint256[2][2])", first_param_array_length);
+    let fn_sig = format!("verifyNovaProof(uint256[{}],uint256[4],uint256[2],uint256[3],uint256[2],uint256[2][2],uint256[2],uint256[6],uint256[2][4])", first_param_array_length);
+    hasher.input_str(&fn_sig);
+    let hash = &mut [0u8; 32];
+    hasher.result(hash);
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// Computes the function selector for the nova cyclefold verifier whose decider SNARK is PLONK
+/// It is computed on the fly since it depends on the length of the first parameter array
+pub fn get_function_selector_for_nova_cyclefold_plonk_verifier(
+    first_param_array_length: usize,
+) -> [u8; 4] {
+    let mut hasher = Sha3::keccak256();
+    let fn_sig = format!("verifyNovaProof(uint256[{}],uint256[4],uint256[2],uint256[3],uint256[24],uint256[6],uint256[2][4])", first_param_array_length);
+    hasher.input_str(&fn_sig);
+    let hash = &mut [0u8; 32];
+    hasher.result(hash);
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// Computes the function selector for the `updateState` method of the nova cyclefold stateful
+/// verifier. It is computed on the fly since it depends on the length of the first parameter array
+pub fn get_function_selector_for_nova_cyclefold_stateful_verifier(
+    first_param_array_length: usize,
+) -> [u8; 4] {
+    let mut hasher = Sha3::keccak256();
+    let fn_sig = format!("updateState(uint256[{}],uint256[4],uint256[2],uint256[3],uint256[2],uint256[2][2],uint256[2],uint256[6],uint256[2][4])", first_param_array_length);
+    hasher.input_str(&fn_sig);
+    let hash = &mut [0u8; 32];
+    hasher.result(hash);
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// Computes the function selector for the hypernova cyclefold verifier
+/// It is computed on the fly since it depends on the length of the first parameter array
+pub fn get_function_selector_for_hypernova_cyclefold_verifier(
+    first_param_array_length: usize,
+) -> [u8; 4] {
+    let mut hasher = Sha3::keccak256();
+    let fn_sig = format!("verifyHyperNovaProof(uint256[{}],uint256[4],uint256,uint256[2],uint256[2][2],uint256[2],uint256[2],uint256[2])", first_param_array_length);
+    hasher.input_str(&fn_sig);
+    let hash = &mut [0u8; 32];
+    hasher.result(hash);
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// Computes the function selector for the protogalaxy cyclefold verifier
+/// It is computed on the fly since it depends on the length of the first parameter array
+pub fn get_function_selector_for_protogalaxy_cyclefold_verifier(
+    first_param_array_length: usize,
+) -> [u8; 4] {
+    let mut hasher = Sha3::keccak256();
+    let fn_sig = format!("verifyProtoGalaxyProof(uint256[{}],uint256[4],uint256[2],uint256[2],uint256[2][2],uint256[2],uint256[2],uint256[2])", first_param_array_length);
     hasher.input_str(&fn_sig);
     let hash = &mut [0u8; 32];
     hasher.result(hash);
@@ -0,0 +1,381 @@
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+#![allow(clippy::upper_case_acronyms)]
+
+use ark_bn254::{Bn254, Fq, Fr, G1Affine, G1Projective};
+use ark_groth16::VerifyingKey as ArkG16VerifierKey;
+use ark_poly_commit::kzg10::VerifierKey as ArkKZG10VerifierKey;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use askama::Template;
+
+use folding_schemes::folding::circuits::nonnative::uint::NonNativeUintVar;
+// HyperNova's onchain Decider shares its VerifierParam with Nova's onchain Decider
+use folding_schemes::folding::nova::decider_eth::VerifierParam as DeciderVerifierParam;
+
+use super::g16::Groth16Verifier;
+use super::kzg::KZG10Verifier;
+use crate::utils::HeaderInclusion;
+use crate::{Groth16VerifierKey, KZG10VerifierKey, ProtocolVerifierKey, PRAGMA_GROTH16_VERIFIER};
+
+pub fn get_decider_template_for_hypernova_cyclefold_decider(
+    hypernova_cyclefold_vk: HyperNovaCycleFoldVerifierKey,
+) -> String {
+    HeaderInclusion::<HyperNovaCycleFoldDecider>::builder()
+        .template(hypernova_cyclefold_vk)
+        .build()
+        .render()
+        .unwrap()
+}
+
+#[derive(Template, Default)]
+#[template(path = "hypernova_cyclefold_decider.askama.sol", ext = "sol")]
+pub struct HyperNovaCycleFoldDecider {
+    pp_hash: Fr, // public params hash
+    groth16_verifier: Groth16Verifier,
+    kzg10_verifier: KZG10Verifier,
+    // z_len denotes the FCircuit state (z_i) length
+    z_len: usize,
+    public_inputs_len: usize,
+    num_limbs: usize,
+    bits_per_limb: usize,
+}
+
+impl From<HyperNovaCycleFoldVerifierKey> for HyperNovaCycleFoldDecider {
+    fn from(value: HyperNovaCycleFoldVerifierKey) -> Self {
+        let groth16_verifier = Groth16Verifier::from(value.g16_vk);
+        let public_inputs_len = groth16_verifier.gamma_abc_len;
+        let bits_per_limb = NonNativeUintVar::<Fq>::bits_per_limb();
+        Self {
+            pp_hash: value.pp_hash,
+            groth16_verifier,
+            kzg10_verifier: KZG10Verifier::from(value.kzg_vk),
+            z_len: value.z_len,
+            public_inputs_len,
+            num_limbs: (250_f32 / (bits_per_limb as f32)).ceil() as usize,
+            bits_per_limb,
+        }
+    }
+}
+
+#[derive(CanonicalDeserialize, CanonicalSerialize, PartialEq, Debug, Clone)]
+pub struct HyperNovaCycleFoldVerifierKey {
+    pp_hash: Fr,
+    g16_vk: Groth16VerifierKey,
+    kzg_vk: KZG10VerifierKey,
+    z_len: usize,
+}
+
+impl ProtocolVerifierKey for HyperNovaCycleFoldVerifierKey {
+    const PROTOCOL_NAME: &'static str = "HyperNovaCycleFold";
+
+    fn render_as_template(self, pragma: Option<String>) -> Vec<u8> {
+        HeaderInclusion::<HyperNovaCycleFoldDecider>::builder()
+            .pragma_version(pragma.unwrap_or(PRAGMA_GROTH16_VERIFIER.to_string()))
+            .template(self)
+            .build()
+            .render()
+            .unwrap()
+            .into_bytes()
+    }
+}
+
+impl From<(Fr, Groth16VerifierKey, KZG10VerifierKey, usize)> for HyperNovaCycleFoldVerifierKey {
+    fn from(value: (Fr, Groth16VerifierKey, KZG10VerifierKey, usize)) -> Self {
+        Self {
+            pp_hash: value.0,
+            g16_vk: value.1,
+            kzg_vk: value.2,
+            z_len: value.3,
+        }
+    }
+}
+
+// implements From assuming that the 'batchCheck' method from the KZG10 template will not be used
+// in the HyperNovaCycleFoldDecider verifier contract
+impl
+    From<(
+        DeciderVerifierParam<G1Projective, ArkKZG10VerifierKey<Bn254>, ArkG16VerifierKey<Bn254>>,
+        usize,
+    )> for HyperNovaCycleFoldVerifierKey
+{
+    fn from(
+        value: (
+            DeciderVerifierParam<
+                G1Projective,
+                ArkKZG10VerifierKey<Bn254>,
+                ArkG16VerifierKey<Bn254>,
+            >,
+            usize,
+        ),
+    ) -> Self {
+        let decider_vp = value.0;
+        let g16_vk = Groth16VerifierKey::from(decider_vp.snark_vp);
+        // pass `Vec::new()` since batchCheck will not be used
+        let kzg_vk = KZG10VerifierKey::from((decider_vp.cs_vp, Vec::new()));
+        Self {
+            pp_hash: decider_vp.pp_hash,
+            g16_vk,
+            kzg_vk,
+            z_len: value.1,
+        }
+    }
+}
+
+impl HyperNovaCycleFoldVerifierKey {
+    pub fn new(
+        pp_hash: Fr,
+        vkey_g16: ArkG16VerifierKey<Bn254>,
+        vkey_kzg: ArkKZG10VerifierKey<Bn254>,
+        crs_points: Vec<G1Affine>,
+        z_len: usize,
+    ) -> Self {
+        Self {
+            pp_hash,
+            g16_vk: Groth16VerifierKey::from(vkey_g16),
+            kzg_vk: KZG10VerifierKey::from((vkey_kzg, crs_points)),
+            z_len,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::{Bn254, Fr, G1Projective as G1};
+    use ark_groth16::Groth16;
+    use ark_grumpkin::Projective as G2;
+    use askama::Template;
+    use std::time::Instant;
+
+    use folding_schemes::{
+        commitment::{kzg::KZG, pedersen::Pedersen},
+        folding::{
+            hypernova::{
+                decider_eth::{prepare_calldata, Decider as DeciderEth},
+                HyperNova,
+            },
+            nova::PreprocessorParam,
+            traits::CommittedInstanceOps,
+        },
+        frontend::FCircuit,
+        transcript::poseidon::poseidon_canonical_config,
+        Decider, FoldingScheme,
+    };
+
+    use super::{DeciderVerifierParam, HyperNovaCycleFoldDecider};
+    use crate::verifiers::nova_cyclefold::tests::{CubicFCircuit, MultiInputsFCircuit};
+    use crate::verifiers::tests::{setup, DEFAULT_SETUP_LEN};
+    use crate::{
+        evm::{compile_solidity, save_solidity, Evm},
+        utils::{get_function_selector_for_hypernova_cyclefold_verifier, HeaderInclusion},
+        verifiers::hypernova_cyclefold::get_decider_template_for_hypernova_cyclefold_decider,
+        HyperNovaCycleFoldVerifierKey, ProtocolVerifierKey,
+    };
+
+    const MU: usize = 1;
+    const NU: usize = 1;
+
+    type HYPERNOVA<FC> = HyperNova<G1, G2, FC, KZG<'static, Bn254>, Pedersen<G2>, MU, NU, false>;
+    type DECIDER<FC> = DeciderEth<
+        G1,
+        G2,
+        FC,
+        KZG<'static, Bn254>,
+        Pedersen<G2>,
+        Groth16<Bn254>,
+        HYPERNOVA<FC>,
+        MU,
+        NU,
+    >;
+
+    type FS_PP<FC> = <HYPERNOVA<FC> as FoldingScheme<G1, G2, FC>>::ProverParam;
+    type FS_VP<FC> = <HYPERNOVA<FC> as FoldingScheme<G1, G2, FC>>::VerifierParam;
+    type DECIDER_PP<FC> = <DECIDER<FC> as Decider<G1, G2, FC, HYPERNOVA<FC>>>::ProverParam;
+    type DECIDER_VP<FC> = <DECIDER<FC> as Decider<G1, G2, FC, HYPERNOVA<FC>>>::VerifierParam;
+
+    #[test]
+    fn hypernova_cyclefold_vk_serde_roundtrip() {
+        let (pp_hash, _, kzg_vk, _, g16_vk, _) = setup(DEFAULT_SETUP_LEN);
+
+        let decider_vp = DeciderVerifierParam {
+            pp_hash,
+            snark_vp: g16_vk,
+            cs_vp: kzg_vk,
+        };
+        let hypernova_cyclefold_vk = HyperNovaCycleFoldVerifierKey::from((decider_vp, 1));
+
+        let mut bytes = vec![];
+        hypernova_cyclefold_vk
+            .serialize_protocol_verifier_key(&mut bytes)
+            .unwrap();
+        let obtained_hypernova_cyclefold_vk =
+            HyperNovaCycleFoldVerifierKey::deserialize_protocol_verifier_key(bytes.as_slice())
+                .unwrap();
+
+        assert_eq!(hypernova_cyclefold_vk, obtained_hypernova_cyclefold_vk)
+    }
+
+    #[test]
+    fn hypernova_cyclefold_decider_template_renders() {
+        let (pp_hash, _, kzg_vk, _, g16_vk, _) = setup(DEFAULT_SETUP_LEN);
+        let decider_vp = DeciderVerifierParam {
+            pp_hash,
+            snark_vp: g16_vk,
+            cs_vp: kzg_vk,
+        };
+        let hypernova_cyclefold_vk = HyperNovaCycleFoldVerifierKey::from((decider_vp, 1));
+
+        let decider_solidity_code = HeaderInclusion::<HyperNovaCycleFoldDecider>::builder()
+            .template(hypernova_cyclefold_vk)
+            .build();
+
+        save_solidity(
+            "HyperNovaDecider.sol",
+            &decider_solidity_code.render().unwrap(),
+        );
+    }
+
+    /// Initializes HyperNova parameters and DeciderEth parameters. Only for test purposes.
+    #[allow(clippy::type_complexity)]
+    fn init_params<FC: FCircuit<Fr, Params = ()>>(
+    ) -> ((FS_PP<FC>, FS_VP<FC>), (DECIDER_PP<FC>, DECIDER_VP<FC>)) {
+        let mut rng = ark_std::rand::rngs::OsRng;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        let f_circuit = FC::new(()).unwrap();
+        let prep_param =
+            PreprocessorParam::<G1, G2, FC, KZG<'static, Bn254>, Pedersen<G2>, false>::new(
+                poseidon_config,
+                f_circuit.clone(),
+            );
+        let hypernova_params = HYPERNOVA::preprocess(&mut rng, &prep_param).unwrap();
+        let decider_params =
+            DECIDER::<FC>::preprocess(&mut rng, (hypernova_params.clone(), f_circuit.state_len()))
+                .unwrap();
+
+        (hypernova_params, decider_params)
+    }
+
+    /// This function allows to define which FCircuit to use for the test, and how many prove_step
+    /// rounds to perform.
+    /// Actions performed by this test:
+    /// - runs the HyperNovaCycleFold folding scheme for the given FCircuit and n_steps times
+    /// - generates a DeciderEth proof, and executes it through the EVM
+    /// - modifies the calldata and checks that it does not pass the EVM check
+    /// - modifies the z_0 and checks that it does not pass the EVM check
+    #[allow(clippy::type_complexity)]
+    fn hypernova_cyclefold_solidity_verifier_opt<FC: FCircuit<Fr, Params = ()>>(
+        fs_params: (FS_PP<FC>, FS_VP<FC>),
+        decider_params: (DECIDER_PP<FC>, DECIDER_VP<FC>),
+        z_0: Vec<Fr>,
+        n_steps: usize,
+    ) {
+        let (decider_pp, decider_vp) = decider_params;
+
+        let f_circuit = FC::new(()).unwrap();
+
+        let hypernova_cyclefold_vk =
+            HyperNovaCycleFoldVerifierKey::from((decider_vp.clone(), f_circuit.state_len()));
+
+        let mut rng = ark_std::rand::rngs::OsRng;
+
+        let mut hypernova = HYPERNOVA::<FC>::init(&fs_params, f_circuit, z_0).unwrap();
+        for _ in 0..n_steps {
+            hypernova
+                .prove_step(&mut rng, FC::ExternalInputs::default(), None)
+                .unwrap();
+        }
+
+        let start = Instant::now();
+        let proof = DECIDER::<FC>::prove(rng, decider_pp, hypernova.clone()).unwrap();
+        println!("generated Decider proof: {:?}", start.elapsed());
+
+        let verified = DECIDER::<FC>::verify(
+            decider_vp,
+            hypernova.i,
+            hypernova.z_0.clone(),
+            hypernova.z_i.clone(),
+            &hypernova.U_i.get_commitments(),
+            &hypernova.u_i.get_commitments(),
+            &proof,
+        )
+        .unwrap();
+        assert!(verified);
+
+        let function_selector =
+            get_function_selector_for_hypernova_cyclefold_verifier(hypernova.z_0.len() * 2 + 1);
+
+        let calldata: Vec<u8> = prepare_calldata(
+            function_selector,
+            hypernova.i,
+            hypernova.z_0,
+            hypernova.z_i,
+            &hypernova.U_i,
+            &hypernova.u_i,
+            proof,
+        )
+        .unwrap();
+
+        let decider_solidity_code =
+            get_decider_template_for_hypernova_cyclefold_decider(hypernova_cyclefold_vk);
+
+        let hypernova_cyclefold_verifier_bytecode =
+            compile_solidity(decider_solidity_code, "HyperNovaDecider");
+
+        let mut evm = Evm::default();
+        let verifier_address = evm.create(hypernova_cyclefold_verifier_bytecode);
+
+        let (_, output) = evm.call(verifier_address, calldata.clone());
+        assert_eq!(*output.last().unwrap(), 1);
+
+        // change i to make calldata invalid, placed between bytes 4 - 35
+        let mut invalid_calldata = calldata.clone();
+        invalid_calldata[35] += 1;
+        let (_, output) = evm.call(verifier_address, invalid_calldata.clone());
+        assert_eq!(*output.last().unwrap(), 0);
+
+        // change z_0 to make the EVM check fail, placed between bytes 35 - 67
+        let mut invalid_calldata = calldata.clone();
+        invalid_calldata[67] += 1;
+        let (_, output) = evm.call(verifier_address, invalid_calldata.clone());
+        assert_eq!(*output.last().unwrap(), 0);
+
+        // change z_i to make the EVM check fail, placed between bytes 68 - 100
+        let mut invalid_calldata = calldata.clone();
+        invalid_calldata[99] += 1;
+        let (_, output) = evm.call(verifier_address, invalid_calldata.clone());
+        assert_eq!(*output.last().unwrap(), 0);
+    }
+
+    #[test]
+    fn hypernova_cyclefold_solidity_verifier() {
+        let (hypernova_params, decider_params) = init_params::<CubicFCircuit<Fr>>();
+        let z_0 = vec![Fr::from(3_u32)];
+        hypernova_cyclefold_solidity_verifier_opt::<CubicFCircuit<Fr>>(
+            hypernova_params.clone(),
+            decider_params.clone(),
+            z_0.clone(),
+            2,
+        );
+        hypernova_cyclefold_solidity_verifier_opt::<CubicFCircuit<Fr>>(
+            hypernova_params,
+            decider_params,
+            z_0,
+            3,
+        );
+
+        let (hypernova_params, decider_params) = init_params::<MultiInputsFCircuit<Fr>>();
+        let z_0 = vec![
+            Fr::from(1_u32),
+            Fr::from(1_u32),
+            Fr::from(1_u32),
+            Fr::from(1_u32),
+            Fr::from(1_u32),
+        ];
+        hypernova_cyclefold_solidity_verifier_opt::<MultiInputsFCircuit<Fr>>(
+            hypernova_params,
+            decider_params,
+            z_0,
+            2,
+        );
+    }
+}
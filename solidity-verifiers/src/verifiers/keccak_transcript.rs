@@ -0,0 +1,134 @@
+use ark_ff::PrimeField;
+use askama::Template;
+use num_bigint::BigUint;
+
+/// Solidity library that computes the same challenges as
+/// `folding_schemes::transcript::keccak::KeccakTranscript<F>`, to be included in the contracts that
+/// verify natively the parts of a proof whose challenges come from such transcript.
+#[derive(Template)]
+#[template(path = "keccak_transcript.askama.sol", ext = "sol")]
+pub struct KeccakTranscriptLibrary {
+    /// The modulus of the field of the transcript's challenges.
+    pub(crate) modulus: BigUint,
+    /// Number of bits of each of the limbs in which `absorbNonNativePoint` decomposes the
+    /// coordinates of a point.
+    pub(crate) nonnative_bits_per_limb: usize,
+    /// Number of limbs in which `absorbNonNativePoint` decomposes the coordinates of a point.
+    pub(crate) nonnative_num_limbs: usize,
+}
+
+impl KeccakTranscriptLibrary {
+    /// Returns the library for a transcript over `F`, whose `absorbNonNativePoint` method absorbs
+    /// points whose base field is `B`.
+    pub fn new<F: PrimeField, B: PrimeField>() -> Self {
+        // matches the limbs of `AbsorbNonNative` for the elements of `B`
+        let nonnative_bits_per_limb = F::MODULUS_BIT_SIZE as usize - 1;
+        Self {
+            modulus: F::MODULUS.into(),
+            nonnative_bits_per_limb,
+            nonnative_num_limbs: (B::MODULUS_BIT_SIZE as usize).div_ceil(nonnative_bits_per_limb),
+        }
+    }
+}
+
+impl Default for KeccakTranscriptLibrary {
+    fn default() -> Self {
+        Self::new::<ark_bn254::Fr, ark_bn254::Fq>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::{Fr, G1Projective};
+    use ark_crypto_primitives::sponge::CryptographicSponge;
+    use ark_ff::{BigInteger, PrimeField};
+    use ark_grumpkin::Projective as GrumpkinProjective;
+    use ark_std::{test_rng, UniformRand};
+    use askama::Template;
+    use crypto::{digest::Digest, sha3::Sha3};
+
+    use folding_schemes::transcript::{keccak::KeccakTranscript, Transcript};
+    use folding_schemes::utils::eth::ToEth;
+
+    use super::KeccakTranscriptLibrary;
+    use crate::evm::{compile_solidity, Evm};
+    use crate::utils::HeaderInclusion;
+
+    /// Contract that runs the KeccakTranscript library over the given inputs, mirroring
+    /// `native_challenges`.
+    const TEST_CONTRACT: &str = r#"
+contract KeccakTranscriptTest {
+    using KeccakTranscript for KeccakTranscript.State;
+
+    function run(uint256[3] calldata v, uint256[2] calldata p, uint256[2] calldata q) public pure returns (uint256[4] memory c) {
+        KeccakTranscript.State memory t;
+        t.absorb(v[0]);
+        t.absorb(v[1]);
+        t.absorb(v[2]);
+        t.absorbPoint(p);
+        t.absorbNonNativePoint(q);
+        c[0] = t.getChallenge();
+        c[1] = t.getChallenge();
+        c[2] = t.getChallengeNBits(128);
+        t.absorb(c[2]);
+        c[3] = t.getChallenge();
+    }
+}
+"#;
+
+    fn get_function_selector_for_run() -> [u8; 4] {
+        let mut hasher = Sha3::keccak256();
+        hasher.input_str("run(uint256[3],uint256[2],uint256[2])");
+        let hash = &mut [0u8; 32];
+        hasher.result(hash);
+        [hash[0], hash[1], hash[2], hash[3]]
+    }
+
+    fn native_challenges(v: &[Fr], p: &GrumpkinProjective, q: &G1Projective) -> Vec<Fr> {
+        let mut transcript = KeccakTranscript::<Fr>::new(&());
+        transcript.absorb(&v.to_vec());
+        transcript.absorb_point(p);
+        transcript.absorb_nonnative(q);
+        let mut c = transcript.get_challenges(2);
+        let bits = transcript.get_challenge_nbits(128);
+        c.push(Fr::from(<Fr as PrimeField>::BigInt::from_bits_le(&bits)));
+        transcript.absorb(&c[2]);
+        c.push(transcript.get_challenge());
+        c
+    }
+
+    #[test]
+    fn keccak_transcript_matches_solidity() {
+        let mut rng = test_rng();
+
+        let library = HeaderInclusion::<KeccakTranscriptLibrary>::builder()
+            .template(KeccakTranscriptLibrary::default())
+            .build()
+            .render()
+            .unwrap();
+        let bytecode = compile_solidity(
+            format!("{}{}", library, TEST_CONTRACT),
+            "KeccakTranscriptTest",
+        );
+        let mut evm = Evm::default();
+        let address = evm.create(bytecode);
+
+        for _ in 0..3 {
+            let v: Vec<Fr> = (0..3).map(|_| Fr::rand(&mut rng)).collect();
+            let p = GrumpkinProjective::rand(&mut rng);
+            let q = G1Projective::rand(&mut rng);
+
+            let (_, output) = evm.call(
+                address,
+                [
+                    get_function_selector_for_run().to_vec(),
+                    v.to_eth(),
+                    p.to_eth(),
+                    q.to_eth(),
+                ]
+                .concat(),
+            );
+            assert_eq!(output, native_challenges(&v, &p, &q).to_eth());
+        }
+    }
+}
@@ -14,6 +14,8 @@ use super::PRAGMA_KZG10_VERIFIER;
 pub struct KZG10Verifier {
     /// The generator of `G1`.
     pub(crate) g1: G1Repr,
+    /// The generator `γG` of `G1` used to blind the commitments in hiding mode.
+    pub(crate) gamma_g1: G1Repr,
     /// The generator of `G2`.
     pub(crate) g2: G2Repr,
     /// The verification key
@@ -28,6 +30,7 @@ impl From<KZG10VerifierKey> for KZG10Verifier {
     fn from(data: KZG10VerifierKey) -> Self {
         Self {
             g1: g1_to_fq_repr(data.vk.g),
+            gamma_g1: g1_to_fq_repr(data.vk.gamma_g),
             g2: g2_to_fq_repr(data.vk.h),
             vk: g2_to_fq_repr(data.vk.beta_h),
             g1_crs_len: data.g1_crs_batch_points.len(),
@@ -78,7 +81,7 @@ mod tests {
         utils::HeaderInclusion,
         ProtocolVerifierKey,
     };
-    use ark_bn254::{Bn254, Fr};
+    use ark_bn254::{Bn254, Fr, G1Projective};
     use ark_crypto_primitives::sponge::{poseidon::PoseidonSponge, CryptographicSponge};
     use ark_ec::{AffineRepr, CurveGroup};
     use ark_ff::{BigInteger, PrimeField};
@@ -88,8 +91,12 @@ mod tests {
     use askama::Template;
 
     use folding_schemes::{
-        commitment::{kzg::KZG, CommitmentScheme},
+        commitment::{
+            kzg::{Proof, KZG},
+            CommitmentScheme,
+        },
         transcript::{poseidon::poseidon_canonical_config, Transcript},
+        utils::eth::ToEth,
     };
 
     use super::KZG10Verifier;
@@ -97,6 +104,36 @@ mod tests {
 
     const FUNCTION_SELECTOR_KZG10_CHECK: [u8; 4] = [0x9e, 0x78, 0xcc, 0xf7];
 
+    fn get_function_selector_for_check_multiple() -> [u8; 4] {
+        use crypto::{digest::Digest, sha3::Sha3};
+        let mut hasher = Sha3::keccak256();
+        hasher.input_str("checkMultiple(uint256[2][],uint256[2][],uint256[2][],uint256[],uint256[],uint256[],uint256)");
+        let hash = &mut [0u8; 32];
+        hasher.result(hash);
+        [hash[0], hash[1], hash[2], hash[3]]
+    }
+
+    /// ABI-encodes the call to `checkMultiple`, whose arguments are the arrays `c`, `pi`, `masks`,
+    /// `x_vals`, `y_vals` and `r_vals` (each one given as the list of its encoded elements) and
+    /// `rho`.
+    fn encode_check_multiple(arrays: [Vec<Vec<u8>>; 6], rho: Fr) -> Vec<u8> {
+        // the head contains the offsets of the arrays, followed by rho
+        let mut head = vec![];
+        let mut tail = vec![];
+        for array in arrays {
+            head.extend(Fr::from((32 * 7 + tail.len()) as u64).to_eth());
+            tail.extend(Fr::from(array.len() as u64).to_eth());
+            tail.extend(array.concat());
+        }
+        head.extend(rho.to_eth());
+        [
+            &get_function_selector_for_check_multiple()[..],
+            &head,
+            &tail,
+        ]
+        .concat()
+    }
+
     #[test]
     fn kzg_vk_serde_roundtrip() {
         let (_, pk, vk, _, _, _) = setup(DEFAULT_SETUP_LEN);
@@ -180,4 +217,71 @@ mod tests {
         let (_, output) = evm.call(verifier_address, calldata);
         assert_eq!(*output.last().unwrap(), 0);
     }
+
+    #[test]
+    fn kzg_verifier_accepts_and_rejects_hiding_batched_proofs() {
+        let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+        let (kzg_pk, kzg_vk) = KZG::<Bn254, true>::setup(&mut rng, DEFAULT_SETUP_LEN).unwrap();
+
+        let (mut cms, mut proofs, mut challenges) = (vec![], vec![], vec![]);
+        for _ in 0..2 {
+            let v: Vec<Fr> = std::iter::repeat_with(|| Fr::rand(&mut rng))
+                .take(DEFAULT_SETUP_LEN)
+                .collect();
+            let blind = Fr::rand(&mut rng);
+            let challenge = Fr::rand(&mut rng);
+            let cm = KZG::<Bn254, true>::commit(&kzg_pk, &v, &blind).unwrap();
+            let proof = KZG::<Bn254, true>::prove_with_challenge(
+                &kzg_pk,
+                challenge,
+                &v,
+                &blind,
+                Some(&mut rng),
+            )
+            .unwrap();
+            assert_ne!(proof.random_v, blind);
+            cms.push(cm);
+            proofs.push(proof);
+            challenges.push(challenge);
+        }
+
+        let kzg_vk = KZG10VerifierKey::from((kzg_vk, kzg_pk.powers_of_g[0..3].to_vec()));
+        let template = HeaderInclusion::<KZG10Verifier>::builder()
+            .template(kzg_vk)
+            .build()
+            .render()
+            .unwrap();
+        let kzg_verifier_bytecode = compile_solidity(template, "KZG10");
+        let mut evm = Evm::default();
+        let verifier_address = evm.create(kzg_verifier_bytecode);
+
+        let calldata = |proofs: &[Proof<G1Projective>]| {
+            encode_check_multiple(
+                [
+                    cms.iter().map(|cm| cm.to_eth()).collect(),
+                    proofs.iter().map(|p| p.proof.to_eth()).collect(),
+                    proofs.iter().map(|p| p.mask.to_eth()).collect(),
+                    challenges.iter().map(|c| c.to_eth()).collect(),
+                    proofs.iter().map(|p| p.eval.to_eth()).collect(),
+                    proofs.iter().map(|p| p.random_v.to_eth()).collect(),
+                ],
+                Fr::rand(&mut test_rng()),
+            )
+        };
+
+        let (_, output) = evm.call(verifier_address, calldata(&proofs));
+        assert_eq!(*output.last().unwrap(), 1);
+
+        // the check fails without the blinding evaluations
+        let mut invalid_proofs = proofs.clone();
+        invalid_proofs[1].random_v = Fr::zero();
+        let (_, output) = evm.call(verifier_address, calldata(&invalid_proofs));
+        assert_eq!(*output.last().unwrap(), 0);
+
+        // and without the masks
+        let mut invalid_proofs = proofs.clone();
+        invalid_proofs[0].mask = Default::default();
+        let (_, output) = evm.call(verifier_address, calldata(&invalid_proofs));
+        assert_eq!(*output.last().unwrap(), 0);
+    }
 }
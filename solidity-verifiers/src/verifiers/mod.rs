@@ -4,6 +4,7 @@
 // Pragma statements for verifiers
 pub const PRAGMA_GROTH16_VERIFIER: &str = "pragma solidity >=0.7.0 <0.9.0;"; // from snarkjs, avoid changing
 pub const PRAGMA_KZG10_VERIFIER: &str = "pragma solidity >=0.8.1 <=0.8.4;";
+pub const PRAGMA_PLONK_VERIFIER: &str = "pragma solidity >=0.7.0 <0.9.0;";
 
 /// Default SDPX License identifier
 pub const GPL3_SDPX_IDENTIFIER: &str = "// SPDX-License-Identifier: GPL-3.0";
@@ -11,12 +12,32 @@ pub const MIT_SDPX_IDENTIFIER: &str = "// SPDX-License-Identifier: MIT";
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
 
 pub mod g16;
+pub mod hypernova_cyclefold;
+pub mod keccak_transcript;
 pub mod kzg;
 pub mod nova_cyclefold;
+pub mod nova_cyclefold_plonk;
+pub mod plonk;
+pub mod protogalaxy_cyclefold;
 
 pub use g16::Groth16VerifierKey;
+pub use hypernova_cyclefold::{
+    get_decider_template_for_hypernova_cyclefold_decider, HyperNovaCycleFoldVerifierKey,
+};
+pub use keccak_transcript::KeccakTranscriptLibrary;
 pub use kzg::KZG10VerifierKey;
-pub use nova_cyclefold::{get_decider_template_for_cyclefold_decider, NovaCycleFoldVerifierKey};
+pub use nova_cyclefold::{
+    get_decider_template_for_cyclefold_decider,
+    get_stateful_decider_template_for_cyclefold_decider, prepare_update_state_calldata,
+    NovaCycleFoldVerifierKey,
+};
+pub use nova_cyclefold_plonk::{
+    get_decider_template_for_cyclefold_plonk_decider, NovaCycleFoldPlonkVerifierKey,
+};
+pub use plonk::PlonkVerifierKey;
+pub use protogalaxy_cyclefold::{
+    get_decider_template_for_protogalaxy_cyclefold_decider, ProtoGalaxyCycleFoldVerifierKey,
+};
 
 pub trait ProtocolVerifierKey: CanonicalDeserialize + CanonicalSerialize {
     const PROTOCOL_NAME: &'static str;
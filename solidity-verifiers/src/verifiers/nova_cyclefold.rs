@@ -3,17 +3,23 @@
 #![allow(clippy::upper_case_acronyms)]
 
 use ark_bn254::{Bn254, Fq, Fr, G1Affine, G1Projective};
-use ark_groth16::VerifyingKey as ArkG16VerifierKey;
+use ark_groth16::{Groth16, VerifyingKey as ArkG16VerifierKey};
 use ark_poly_commit::kzg10::VerifierKey as ArkKZG10VerifierKey;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use askama::Template;
 
+use folding_schemes::commitment::kzg::KZG;
 use folding_schemes::folding::circuits::nonnative::uint::NonNativeUintVar;
-use folding_schemes::folding::nova::decider_eth::VerifierParam as DeciderVerifierParam;
+use folding_schemes::folding::nova::{
+    decider_eth::{prepare_calldata, Proof as DeciderProof, VerifierParam as DeciderVerifierParam},
+    CommittedInstance,
+};
+use folding_schemes::Error;
 
 use super::g16::Groth16Verifier;
+use super::keccak_transcript::KeccakTranscriptLibrary;
 use super::kzg::KZG10Verifier;
-use crate::utils::HeaderInclusion;
+use crate::utils::{get_function_selector_for_nova_cyclefold_stateful_verifier, HeaderInclusion};
 use crate::{Groth16VerifierKey, KZG10VerifierKey, ProtocolVerifierKey, PRAGMA_GROTH16_VERIFIER};
 
 pub fn get_decider_template_for_cyclefold_decider(
@@ -26,17 +32,58 @@ pub fn get_decider_template_for_cyclefold_decider(
         .unwrap()
 }
 
+/// Renders the NovaDecider contract together with the NovaStatefulDecider contract, which on top
+/// of verifying the proofs keeps track of the latest verified IVC state.
+pub fn get_stateful_decider_template_for_cyclefold_decider(
+    nova_cyclefold_vk: NovaCycleFoldVerifierKey,
+) -> String {
+    let mut decider = NovaCycleFoldDecider::from(nova_cyclefold_vk);
+    decider.stateful = true;
+    HeaderInclusion::<NovaCycleFoldDecider>::builder()
+        .template(decider)
+        .build()
+        .render()
+        .unwrap()
+}
+
+/// Prepares the calldata for calling the `updateState` method of the NovaStatefulDecider contract,
+/// which takes the same inputs as the `verifyNovaProof` method.
+pub fn prepare_update_state_calldata(
+    i: Fr,
+    z_0: Vec<Fr>,
+    z_i: Vec<Fr>,
+    running_instance: &CommittedInstance<G1Projective>,
+    incoming_instance: &CommittedInstance<G1Projective>,
+    proof: DeciderProof<G1Projective, KZG<'static, Bn254>, Groth16<Bn254>>,
+) -> Result<Vec<u8>, Error> {
+    let function_selector =
+        get_function_selector_for_nova_cyclefold_stateful_verifier(z_0.len() * 2 + 1);
+    prepare_calldata(
+        function_selector,
+        i,
+        z_0,
+        z_i,
+        running_instance,
+        incoming_instance,
+        proof,
+    )
+}
+
 #[derive(Template, Default)]
 #[template(path = "nova_cyclefold_decider.askama.sol", ext = "sol")]
 pub struct NovaCycleFoldDecider {
     pp_hash: Fr, // public params hash
     groth16_verifier: Groth16Verifier,
     kzg10_verifier: KZG10Verifier,
+    // used to derive the coefficient of the batched KZG check
+    keccak_transcript: KeccakTranscriptLibrary,
     // z_len denotes the FCircuit state (z_i) length
     z_len: usize,
     public_inputs_len: usize,
     num_limbs: usize,
     bits_per_limb: usize,
+    // whether to also render the NovaStatefulDecider contract
+    stateful: bool,
 }
 
 impl From<NovaCycleFoldVerifierKey> for NovaCycleFoldDecider {
@@ -48,10 +95,12 @@ impl From<NovaCycleFoldVerifierKey> for NovaCycleFoldDecider {
             pp_hash: value.pp_hash,
             groth16_verifier,
             kzg10_verifier: KZG10Verifier::from(value.kzg_vk),
+            keccak_transcript: KeccakTranscriptLibrary::default(),
             z_len: value.z_len,
             public_inputs_len,
             num_limbs: (250_f32 / (bits_per_limb as f32)).ceil() as usize,
             bits_per_limb,
+            stateful: false,
         }
     }
 }
@@ -138,7 +187,7 @@ impl NovaCycleFoldVerifierKey {
 }
 
 #[cfg(test)]
-mod tests {
+pub mod tests {
     use ark_bn254::{Bn254, Fr, G1Projective as G1};
     use ark_ff::PrimeField;
     use ark_groth16::Groth16;
@@ -161,10 +210,14 @@ mod tests {
         },
         frontend::FCircuit,
         transcript::poseidon::poseidon_canonical_config,
+        utils::eth::ToEth,
         Decider, Error, FoldingScheme,
     };
 
-    use super::{DeciderVerifierParam, NovaCycleFoldDecider};
+    use super::{
+        get_stateful_decider_template_for_cyclefold_decider, prepare_update_state_calldata,
+        DeciderVerifierParam, NovaCycleFoldDecider,
+    };
     use crate::verifiers::tests::{setup, DEFAULT_SETUP_LEN};
     use crate::{
         evm::{compile_solidity, save_solidity, Evm},
@@ -438,4 +491,100 @@ mod tests {
             3,
         );
     }
+
+    /// Checks that the NovaStatefulDecider contract only accepts proofs for the stored `z_0` that
+    /// advance the number of folded steps, and that it keeps track of the last verified state.
+    #[test]
+    fn nova_cyclefold_stateful_solidity_verifier() {
+        let (fs_params, (decider_pp, decider_vp)) = init_params::<CubicFCircuit<Fr>>();
+        let f_circuit = CubicFCircuit::<Fr>::new(()).unwrap();
+        let z_0 = vec![Fr::from(3_u32)];
+
+        let nova_cyclefold_vk =
+            NovaCycleFoldVerifierKey::from((decider_vp.clone(), f_circuit.state_len()));
+        let decider_solidity_code =
+            get_stateful_decider_template_for_cyclefold_decider(nova_cyclefold_vk);
+        let bytecode = compile_solidity(decider_solidity_code, "NovaStatefulDecider");
+
+        let mut evm = Evm::default();
+        // the constructor receives the initial state z_0
+        let verifier_address = evm.create([bytecode.clone(), z_0.to_eth()].concat());
+
+        let mut rng = ark_std::rand::rngs::OsRng;
+        let mut nova = NOVA::<CubicFCircuit<Fr>>::init(&fs_params, f_circuit, z_0.clone()).unwrap();
+
+        let mut last_calldata = vec![];
+        for n_steps in [2_u32, 3] {
+            while nova.i < Fr::from(n_steps) {
+                nova.prove_step(&mut rng, (), None).unwrap();
+            }
+            let proof =
+                DECIDER::<CubicFCircuit<Fr>>::prove(rng, decider_pp.clone(), nova.clone()).unwrap();
+            let calldata = prepare_update_state_calldata(
+                nova.i,
+                nova.z_0.clone(),
+                nova.z_i.clone(),
+                &nova.U_i,
+                &nova.u_i,
+                proof,
+            )
+            .unwrap();
+
+            let (_, output) = evm.call(verifier_address, calldata.clone());
+            assert_eq!(*output.last().unwrap(), 1);
+
+            // the same state can not be submitted twice
+            let (_, output) = evm.call(verifier_address, calldata.clone());
+            assert_eq!(*output.last().unwrap(), 0);
+
+            // the stored (i, z_i) match the last verified ones, `i()` and `z_i(0)` are the
+            // getters of the public state variables
+            let (_, output) = evm.call(verifier_address, get_function_selector("i()").to_vec());
+            assert_eq!(output, nova.i.to_eth());
+            let (_, output) = evm.call(
+                verifier_address,
+                [
+                    get_function_selector("z_i(uint256)").to_vec(),
+                    Fr::from(0).to_eth(),
+                ]
+                .concat(),
+            );
+            assert_eq!(output, nova.z_i[0].to_eth());
+
+            last_calldata = calldata;
+        }
+
+        // an older state (i=2 < 3) is rejected even if its proof is valid
+        let mut nova = NOVA::<CubicFCircuit<Fr>>::init(&fs_params, f_circuit, z_0.clone()).unwrap();
+        nova.prove_step(&mut rng, (), None).unwrap();
+        nova.prove_step(&mut rng, (), None).unwrap();
+        let proof =
+            DECIDER::<CubicFCircuit<Fr>>::prove(rng, decider_pp.clone(), nova.clone()).unwrap();
+        let old_calldata = prepare_update_state_calldata(
+            nova.i,
+            nova.z_0.clone(),
+            nova.z_i.clone(),
+            &nova.U_i,
+            &nova.u_i,
+            proof,
+        )
+        .unwrap();
+        let (_, output) = evm.call(verifier_address, old_calldata);
+        assert_eq!(*output.last().unwrap(), 0);
+
+        // a contract deployed for a different z_0 rejects the proofs
+        let other_verifier_address = evm.create([bytecode, [Fr::from(4_u32)].to_eth()].concat());
+        let (_, output) = evm.call(other_verifier_address, last_calldata);
+        assert_eq!(*output.last().unwrap(), 0);
+    }
+
+    /// Computes the selector of a method without dynamic-length arguments.
+    fn get_function_selector(fn_sig: &str) -> [u8; 4] {
+        use crypto::{digest::Digest, sha3::Sha3};
+        let mut hasher = Sha3::keccak256();
+        hasher.input_str(fn_sig);
+        let hash = &mut [0u8; 32];
+        hasher.result(hash);
+        [hash[0], hash[1], hash[2], hash[3]]
+    }
 }
@@ -0,0 +1,244 @@
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+#![allow(clippy::upper_case_acronyms)]
+
+use ark_bn254::{Bn254, Fq, Fr, G1Projective};
+use ark_poly_commit::kzg10::VerifierKey as ArkKZG10VerifierKey;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use askama::Template;
+
+use folding_schemes::folding::circuits::nonnative::uint::NonNativeUintVar;
+use folding_schemes::folding::nova::decider_eth::VerifierParam as DeciderVerifierParam;
+use folding_schemes::snark::plonk::VerifyingKey as PlonkVerifyingKey;
+
+use super::kzg::KZG10Verifier;
+use super::plonk::{PlonkVerifier, PlonkVerifierKey};
+use crate::utils::HeaderInclusion;
+use crate::{KZG10VerifierKey, ProtocolVerifierKey, PRAGMA_PLONK_VERIFIER};
+
+/// Renders the NovaPlonkDecider contract, which verifies the proofs of the Nova decider whose
+/// SNARK is `folding_schemes::snark::plonk::Plonk`.
+pub fn get_decider_template_for_cyclefold_plonk_decider(
+    nova_cyclefold_vk: NovaCycleFoldPlonkVerifierKey,
+) -> String {
+    HeaderInclusion::<NovaCycleFoldPlonkDecider>::builder()
+        .template(nova_cyclefold_vk)
+        .build()
+        .render()
+        .unwrap()
+}
+
+#[derive(Template, Default)]
+#[template(path = "nova_cyclefold_plonk_decider.askama.sol", ext = "sol")]
+pub struct NovaCycleFoldPlonkDecider {
+    pp_hash: Fr, // public params hash
+    plonk_verifier: PlonkVerifier,
+    kzg10_verifier: KZG10Verifier,
+    // z_len denotes the FCircuit state (z_i) length
+    z_len: usize,
+    public_inputs_len: usize,
+    num_limbs: usize,
+    bits_per_limb: usize,
+}
+
+impl From<NovaCycleFoldPlonkVerifierKey> for NovaCycleFoldPlonkDecider {
+    fn from(value: NovaCycleFoldPlonkVerifierKey) -> Self {
+        let plonk_verifier = PlonkVerifier::from(value.plonk_vk);
+        let public_inputs_len = plonk_verifier.n_public_inputs;
+        let bits_per_limb = NonNativeUintVar::<Fq>::bits_per_limb();
+        Self {
+            pp_hash: value.pp_hash,
+            plonk_verifier,
+            kzg10_verifier: KZG10Verifier::from(value.kzg_vk),
+            z_len: value.z_len,
+            public_inputs_len,
+            num_limbs: (250_f32 / (bits_per_limb as f32)).ceil() as usize,
+            bits_per_limb,
+        }
+    }
+}
+
+#[derive(CanonicalDeserialize, CanonicalSerialize, PartialEq, Debug, Clone)]
+pub struct NovaCycleFoldPlonkVerifierKey {
+    pp_hash: Fr,
+    plonk_vk: PlonkVerifierKey,
+    kzg_vk: KZG10VerifierKey,
+    z_len: usize,
+}
+
+impl ProtocolVerifierKey for NovaCycleFoldPlonkVerifierKey {
+    const PROTOCOL_NAME: &'static str = "NovaCycleFoldPlonk";
+
+    fn render_as_template(self, pragma: Option<String>) -> Vec<u8> {
+        HeaderInclusion::<NovaCycleFoldPlonkDecider>::builder()
+            .pragma_version(pragma.unwrap_or(PRAGMA_PLONK_VERIFIER.to_string()))
+            .template(self)
+            .build()
+            .render()
+            .unwrap()
+            .into_bytes()
+    }
+}
+
+// implements From assuming that the 'batchCheck' method from the KZG10 template will not be used
+// in the NovaCycleFoldPlonkDecider verifier contract
+impl
+    From<(
+        DeciderVerifierParam<G1Projective, ArkKZG10VerifierKey<Bn254>, PlonkVerifyingKey<Bn254>>,
+        usize,
+    )> for NovaCycleFoldPlonkVerifierKey
+{
+    fn from(
+        value: (
+            DeciderVerifierParam<
+                G1Projective,
+                ArkKZG10VerifierKey<Bn254>,
+                PlonkVerifyingKey<Bn254>,
+            >,
+            usize,
+        ),
+    ) -> Self {
+        let decider_vp = value.0;
+        let plonk_vk = PlonkVerifierKey::from(decider_vp.snark_vp);
+        // pass `Vec::new()` since batchCheck will not be used
+        let kzg_vk = KZG10VerifierKey::from((decider_vp.cs_vp, Vec::new()));
+        Self {
+            pp_hash: decider_vp.pp_hash,
+            plonk_vk,
+            kzg_vk,
+            z_len: value.1,
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use ark_bn254::{Bn254, Fr, G1Projective as G1};
+    use ark_grumpkin::Projective as G2;
+    use ark_snark::UniversalSetupSNARK;
+    use std::time::Instant;
+
+    use folding_schemes::{
+        commitment::{kzg::KZG, pedersen::Pedersen},
+        folding::{
+            nova::{
+                decider_eth::{prepare_calldata, Decider as DeciderEth, DeciderEthCircuit},
+                Nova, PreprocessorParam,
+            },
+            traits::CommittedInstanceOps,
+        },
+        frontend::FCircuit,
+        snark::plonk::Plonk,
+        transcript::poseidon::poseidon_canonical_config,
+        Decider, FoldingScheme,
+    };
+
+    use super::{get_decider_template_for_cyclefold_plonk_decider, NovaCycleFoldPlonkVerifierKey};
+    use crate::{
+        evm::{compile_solidity, Evm},
+        utils::get_function_selector_for_nova_cyclefold_plonk_verifier,
+        verifiers::nova_cyclefold::tests::CubicFCircuit,
+        ProtocolVerifierKey,
+    };
+
+    type FC = CubicFCircuit<Fr>;
+    type NOVA = Nova<G1, G2, FC, KZG<'static, Bn254>, Pedersen<G2>, false>;
+    type DECIDER = DeciderEth<G1, G2, FC, KZG<'static, Bn254>, Pedersen<G2>, Plonk<Bn254>, NOVA>;
+
+    /// Runs Nova with the decider that uses PLONK, indexing its circuit with a universal setup, and
+    /// checks that the EVM accepts the decider proof and rejects it when the calldata is modified.
+    #[test]
+    fn nova_cyclefold_plonk_solidity_verifier() {
+        let mut rng = ark_std::rand::rngs::OsRng;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        let f_circuit = FC::new(()).unwrap();
+        let z_0 = vec![Fr::from(3_u32)];
+        let prep_param =
+            PreprocessorParam::<G1, G2, FC, KZG<'static, Bn254>, Pedersen<G2>, false>::new(
+                poseidon_config,
+                f_circuit,
+            );
+        let nova_params = NOVA::preprocess(&mut rng, &prep_param).unwrap();
+
+        let mut nova = NOVA::init(&nova_params, f_circuit, z_0).unwrap();
+        for _ in 0..2 {
+            nova.prove_step(&mut rng, (), None).unwrap();
+        }
+
+        // the universal SRS is sized to the decider circuit here, while it could be any SRS that
+        // is large enough, eg. one loaded from a powers of tau ceremony
+        let bound = Plonk::<Bn254>::computation_bound(
+            DeciderEthCircuit::<G1, G2>::try_from(nova.clone()).unwrap(),
+        )
+        .unwrap();
+        let srs = Plonk::<Bn254>::universal_setup(&bound, &mut rng).unwrap();
+        let (decider_pp, decider_vp) =
+            DECIDER::preprocess_universal(&mut rng, (nova_params, f_circuit.state_len()), &srs)
+                .unwrap();
+
+        let nova_cyclefold_vk =
+            NovaCycleFoldPlonkVerifierKey::from((decider_vp.clone(), f_circuit.state_len()));
+
+        // the verifier key roundtrips through its serialization
+        let mut bytes = vec![];
+        nova_cyclefold_vk
+            .serialize_protocol_verifier_key(&mut bytes)
+            .unwrap();
+        let obtained_nova_cyclefold_vk =
+            NovaCycleFoldPlonkVerifierKey::deserialize_protocol_verifier_key(bytes.as_slice())
+                .unwrap();
+        assert_eq!(nova_cyclefold_vk, obtained_nova_cyclefold_vk);
+
+        let start = Instant::now();
+        let proof = DECIDER::prove(rng, decider_pp, nova.clone()).unwrap();
+        println!("generated Decider proof: {:?}", start.elapsed());
+
+        let verified = DECIDER::verify(
+            decider_vp,
+            nova.i,
+            nova.z_0.clone(),
+            nova.z_i.clone(),
+            &nova.U_i.get_commitments(),
+            &nova.u_i.get_commitments(),
+            &proof,
+        )
+        .unwrap();
+        assert!(verified);
+
+        let function_selector =
+            get_function_selector_for_nova_cyclefold_plonk_verifier(nova.z_0.len() * 2 + 1);
+        let calldata: Vec<u8> = prepare_calldata(
+            function_selector,
+            nova.i,
+            nova.z_0,
+            nova.z_i,
+            &nova.U_i,
+            &nova.u_i,
+            proof,
+        )
+        .unwrap();
+
+        let decider_solidity_code =
+            get_decider_template_for_cyclefold_plonk_decider(nova_cyclefold_vk);
+        let bytecode = compile_solidity(decider_solidity_code, "NovaPlonkDecider");
+
+        let mut evm = Evm::default();
+        let verifier_address = evm.create(bytecode);
+
+        let (_, output) = evm.call(verifier_address, calldata.clone());
+        assert_eq!(*output.last().unwrap(), 1);
+
+        // change i to make calldata invalid, placed between bytes 4 - 35
+        let mut invalid_calldata = calldata.clone();
+        invalid_calldata[35] += 1;
+        let (_, output) = evm.call(verifier_address, invalid_calldata);
+        assert_eq!(*output.last().unwrap(), 0);
+
+        // change z_i to make the EVM check fail, placed between bytes 68 - 100
+        let mut invalid_calldata = calldata;
+        invalid_calldata[99] += 1;
+        let (_, output) = evm.call(verifier_address, invalid_calldata);
+        assert_eq!(*output.last().unwrap(), 0);
+    }
+}
@@ -0,0 +1,180 @@
+use ark_bn254::{Bn254, Fr};
+use ark_ff::FftField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use askama::Template;
+
+use folding_schemes::snark::plonk::{coset_shifts, VerifyingKey as PlonkVerifyingKey};
+
+use super::keccak_transcript::KeccakTranscriptLibrary;
+use crate::utils::encoding::{g1_to_fq_repr, g2_to_fq_repr};
+use crate::utils::encoding::{G1Repr, G2Repr};
+use crate::utils::HeaderInclusion;
+use crate::{ProtocolVerifierKey, MIT_SDPX_IDENTIFIER, PRAGMA_PLONK_VERIFIER};
+
+/// Solidity verifier of the proofs of `folding_schemes::snark::plonk::Plonk`, which hardcodes the
+/// verifying key of a circuit indexed from a universal KZG setup. Notice that the template expects
+/// the circuit to have at least one public input.
+#[derive(Template, Default)]
+#[template(path = "plonk_verifier.askama.sol", ext = "sol")]
+pub struct PlonkVerifier {
+    /// Size of the evaluation domain.
+    pub(crate) n: usize,
+    /// Logarithm of the size of the evaluation domain.
+    pub(crate) log_n: u32,
+    /// Number of public inputs of the circuit.
+    pub(crate) n_public_inputs: usize,
+    /// Generator of the evaluation domain.
+    pub(crate) omega: Fr,
+    /// Shifts of the cosets of the wires `b` and `c`.
+    pub(crate) k1: Fr,
+    pub(crate) k2: Fr,
+    /// Digest of the verifying key, which initializes the transcript.
+    pub(crate) digest: Fr,
+    /// Commitments to the selectors and to the permutation polynomials.
+    pub(crate) cms: Vec<G1Repr>,
+    /// The generator of `G1`.
+    pub(crate) g1: G1Repr,
+    /// The generator of `G2`.
+    pub(crate) h: G2Repr,
+    /// The `beta * H` of the KZG setup.
+    pub(crate) beta_h: G2Repr,
+    /// Used to derive the challenges of the proof.
+    pub(crate) keccak_transcript: KeccakTranscriptLibrary,
+}
+
+impl From<PlonkVerifierKey> for PlonkVerifier {
+    fn from(value: PlonkVerifierKey) -> Self {
+        let vk = value.0;
+        let [_, k1, k2] = coset_shifts::<Fr>();
+        Self {
+            n: vk.n,
+            log_n: vk.n.ilog2(),
+            n_public_inputs: vk.n_public_inputs,
+            // same generator as the one of `Radix2EvaluationDomain::new(n)`
+            omega: Fr::get_root_of_unity(vk.n as u64).unwrap(),
+            k1,
+            k2,
+            digest: vk.digest(),
+            cms: vk
+                .selectors
+                .iter()
+                .chain(&vk.sigmas)
+                .map(|cm| g1_to_fq_repr((*cm).into()))
+                .collect(),
+            g1: g1_to_fq_repr(vk.g),
+            h: g2_to_fq_repr(vk.h),
+            beta_h: g2_to_fq_repr(vk.beta_h),
+            keccak_transcript: KeccakTranscriptLibrary::default(),
+        }
+    }
+}
+
+#[derive(CanonicalDeserialize, CanonicalSerialize, Clone, PartialEq, Debug)]
+pub struct PlonkVerifierKey(pub(crate) PlonkVerifyingKey<Bn254>);
+
+impl From<PlonkVerifyingKey<Bn254>> for PlonkVerifierKey {
+    fn from(value: PlonkVerifyingKey<Bn254>) -> Self {
+        Self(value)
+    }
+}
+
+impl ProtocolVerifierKey for PlonkVerifierKey {
+    const PROTOCOL_NAME: &'static str = "Plonk";
+
+    fn render_as_template(self, pragma: Option<String>) -> Vec<u8> {
+        HeaderInclusion::<PlonkVerifier>::builder()
+            .sdpx(MIT_SDPX_IDENTIFIER.to_string())
+            .pragma_version(pragma.unwrap_or(PRAGMA_PLONK_VERIFIER.to_string()))
+            .template(self)
+            .build()
+            .render()
+            .unwrap()
+            .into_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::{Bn254, Fr};
+    use ark_snark::{UniversalSetupSNARK, SNARK};
+    use ark_std::rand::{RngCore, SeedableRng};
+    use ark_std::test_rng;
+    use askama::Template;
+    use crypto::{digest::Digest, sha3::Sha3};
+
+    use folding_schemes::snark::{index_error, plonk::Plonk};
+    use folding_schemes::utils::eth::ToEth;
+
+    use super::{PlonkVerifier, PlonkVerifierKey};
+    use crate::{
+        evm::{compile_solidity, Evm},
+        utils::HeaderInclusion,
+        verifiers::tests::{setup, DEFAULT_SETUP_LEN},
+        ProtocolVerifierKey,
+    };
+
+    /// Indexes the test circuit from a universal setup, and returns its verifying key together
+    /// with the calldata of a valid proof.
+    fn plonk_setup() -> (PlonkVerifierKey, Vec<u8>) {
+        let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+        let (_, _, _, _, _, circuit) = setup(DEFAULT_SETUP_LEN);
+
+        let bound = Plonk::<Bn254>::computation_bound(circuit).unwrap();
+        let srs = Plonk::<Bn254>::universal_setup(&bound, &mut rng).unwrap();
+        let (pk, vk) = Plonk::<Bn254>::index(&srs, circuit, &mut rng)
+            .map_err(index_error)
+            .unwrap();
+        let proof = Plonk::<Bn254>::prove(&pk, circuit, &mut rng).unwrap();
+        assert!(Plonk::<Bn254>::verify(&vk, &[Fr::from(42)], &proof).unwrap());
+
+        let mut hasher = Sha3::keccak256();
+        hasher.input_str("verifyPlonkProof(uint256[24],uint256[1])");
+        let hash = &mut [0u8; 32];
+        hasher.result(hash);
+        let calldata = [&hash[..4], &proof.to_eth(), &[Fr::from(42)].to_eth()].concat();
+        (PlonkVerifierKey::from(vk), calldata)
+    }
+
+    #[test]
+    fn plonk_vk_serde_roundtrip() {
+        let (plonk_vk, _) = plonk_setup();
+
+        let mut bytes = vec![];
+        plonk_vk
+            .serialize_protocol_verifier_key(&mut bytes)
+            .unwrap();
+        let obtained_plonk_vk =
+            PlonkVerifierKey::deserialize_protocol_verifier_key(bytes.as_slice()).unwrap();
+
+        assert_eq!(plonk_vk, obtained_plonk_vk)
+    }
+
+    #[test]
+    fn plonk_verifier_accepts_and_rejects_proofs() {
+        let (plonk_vk, calldata) = plonk_setup();
+
+        let template = HeaderInclusion::<PlonkVerifier>::builder()
+            .template(plonk_vk)
+            .build()
+            .render()
+            .unwrap();
+        let plonk_verifier_bytecode = compile_solidity(template, "PlonkVerifier");
+        let mut evm = Evm::default();
+        let verifier_address = evm.create(plonk_verifier_bytecode);
+
+        let (_, output) = evm.call(verifier_address, calldata.clone());
+        assert_eq!(*output.last().unwrap(), 1);
+
+        // change the public input to make the proof invalid
+        let mut invalid_calldata = calldata.clone();
+        *invalid_calldata.last_mut().unwrap() += 1;
+        let (_, output) = evm.call(verifier_address, invalid_calldata);
+        assert_eq!(*output.last().unwrap(), 0);
+
+        // flip a bit of the evaluation of a (the 19th word of the proof) to make it invalid
+        let mut invalid_calldata = calldata;
+        invalid_calldata[4 + 32 * 19 - 1] ^= 1;
+        let (_, output) = evm.call(verifier_address, invalid_calldata);
+        assert_eq!(*output.last().unwrap(), 0);
+    }
+}
@@ -0,0 +1,62 @@
+/* =============================== */
+/* Keccak256 transcript */
+/**
+ * @author  PSE & 0xPARC
+ * @title   KeccakTranscript library, to compute the same challenges as sonobe's `KeccakTranscript`.
+ * @dev     Field elements are absorbed as 32-byte big-endian words (absorbed values should be
+ *          reduced modulo MODULUS), and each squeeze sets `state = keccak256(abi.encodePacked(state, buffer))`,
+ *          emptying the buffer.
+ *          Compatible with sonobe::folding-schemes::transcript::keccak::KeccakTranscript.
+ */
+library KeccakTranscript {
+    uint256 constant MODULUS = {{ modulus }};
+
+    struct State {
+        bytes32 state;
+        bytes buffer;
+    }
+
+    function absorb(State memory t, uint256 x) internal pure {
+        t.buffer = abi.encodePacked(t.buffer, x);
+    }
+
+    /**
+     * @notice  Absorbs the affine coordinates of a point whose base field is the one of the transcript.
+     * @dev     The point at infinity is represented as (0, 0).
+     */
+    function absorbPoint(State memory t, uint256[2] memory p) internal pure {
+        t.buffer = abi.encodePacked(t.buffer, p[0], p[1]);
+    }
+
+    /**
+     * @notice  Absorbs the affine coordinates of a point whose base field is not the one of the transcript,
+     *          decomposing each coordinate into {{ nonnative_num_limbs }} limbs of {{ nonnative_bits_per_limb }} bits.
+     * @dev     The point at infinity is represented as (0, 0).
+     *          Compatible with `absorb_nonnative` of sonobe's `KeccakTranscript` when absorbing a point.
+     */
+    function absorbNonNativePoint(State memory t, uint256[2] memory p) internal pure {
+        for (uint256 k = 0; k < 2; k++) {
+            for (uint256 j = 0; j < {{ nonnative_num_limbs }}; j++) {
+                absorb(t, (p[k] >> ({{ nonnative_bits_per_limb }} * j)) & ((1 << {{ nonnative_bits_per_limb }}) - 1));
+            }
+        }
+    }
+
+    function squeeze(State memory t) internal pure returns (uint256) {
+        t.state = keccak256(abi.encodePacked(t.state, t.buffer));
+        t.buffer = "";
+        return uint256(t.state);
+    }
+
+    function getChallenge(State memory t) internal pure returns (uint256) {
+        return squeeze(t) % MODULUS;
+    }
+
+    /**
+     * @notice  Returns a challenge of nbits bits, which are the lowest bits of the updated state.
+     */
+    function getChallengeNBits(State memory t, uint256 nbits) internal pure returns (uint256) {
+        require(nbits < 256, "KeccakTranscript: nbits should be smaller than 256");
+        return squeeze(t) & ((1 << nbits) - 1);
+    }
+}
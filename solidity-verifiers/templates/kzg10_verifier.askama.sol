@@ -115,6 +115,10 @@ contract KZG10Verifier {
             {{ g1.0[0] }},
             {{ g1.0[1] }}
     ];
+    uint256[2] GAMMA_G_1 = [
+            {{ gamma_g1.0[0] }},
+            {{ gamma_g1.0[1] }}
+    ];
     uint256[2][2] G_2 = [
         [
             {{ g2.0[0][0] }},
@@ -185,6 +189,73 @@ contract KZG10Verifier {
         return pairing(pi, VK, rhs_pairing, G_2);
     }
 
+    /**
+     * @notice  Computes the challenge with which the mask of a hiding proof is added to the opened commitment.
+     * @dev     Compatible with sonobe's `KZG` in hiding mode, where it is computed as keccak256(x, y, mask) reduced modulo
+     *          the scalar field.
+     * @param   x  Value at which the polynomial is evaluated.
+     * @param   y  Evaluation poly(x).
+     * @param   mask  G_1 point commitment to the blinding polynomial of the proof.
+     * @return  uint256  Challenge of the mask.
+     */
+    function maskChallenge(uint256 x, uint256 y, uint256[2] memory mask) public pure returns (uint256) {
+        return uint256(keccak256(abi.encodePacked(x, y, mask[0], mask[1]))) % BN254_SCALAR_FIELD;
+    }
+
+    /**
+     * @notice  Verifies the point evaluation proofs of several commitments, at possibly different values, with a single pairing check.
+     * @dev     Compatible with sonobe's `KZG::batch_verify_with_challenges`, both for hiding and non-hiding proofs (whose
+     *          masks and blinding evaluations are zero). `rho` is the coefficient of the random linear combination of the
+     *          proofs, so it must be derived after fixing all the other inputs.
+     * @param   c  G_1 point commitments to the polynomials.
+     * @param   pi  G_1 point proofs.
+     * @param   masks  G_1 point commitments to the blinding polynomials of the proofs.
+     * @param   x_vals  Values to prove evaluation of each polynomial at.
+     * @param   y_vals  Evaluations poly_i(x_i).
+     * @param   r_vals  Evaluations of the blinding polynomials at x_i.
+     * @param   rho  Coefficient of the random linear combination.
+     * @return  result  Indicates if all the KZG proofs are correct.
+     */
+    function checkMultiple(
+        uint256[2][] memory c,
+        uint256[2][] memory pi,
+        uint256[2][] memory masks,
+        uint256[] memory x_vals,
+        uint256[] memory y_vals,
+        uint256[] memory r_vals,
+        uint256 rho
+    ) public view returns (bool result) {
+        require(
+            c.length == pi.length && c.length == masks.length && c.length == x_vals.length
+                && c.length == y_vals.length && c.length == r_vals.length,
+            "KZG: batch lengths mismatch"
+        );
+        //
+        // each proof opens the masked commitment c'_i = c_i + maskChallenge(x_i, y_i, mask_i) * mask_i, with the
+        // single check e(pi_i, vk) * e(x_i * -pi_i - c'_i + y_i * g1 + r_i * gamma_g1, g2) = 1 (see `check`), and all
+        // of them are implied, except with negligible probability, by their random linear combination:
+        //
+        //   e(sum_i rho^i * pi_i, vk) * e(sum_i rho^i * (x_i * -pi_i - c'_i) + (sum_i rho^i * y_i) * g1 + (sum_i rho^i * r_i) * gamma_g1, g2) = 1
+        //         |_ lhs_pairing _|       |_                                    rhs_pairing                                            _|
+        //
+        uint256[2] memory lhs_pairing;
+        uint256[2] memory rhs_pairing;
+        uint256 y = 0;
+        uint256 r = 0;
+        uint256 rho_i = 1;
+        for (uint256 i = 0; i < c.length; i++) {
+            uint256[2] memory c_i = add(c[i], mulScalar(masks[i], maskChallenge(x_vals[i], y_vals[i], masks[i])));
+            uint256[2] memory pi_i = mulScalar(pi[i], rho_i);
+            lhs_pairing = add(lhs_pairing, pi_i);
+            rhs_pairing = add(rhs_pairing, add(mulScalar(negate(pi_i), x_vals[i]), negate(mulScalar(c_i, rho_i))));
+            y = addmod(y, mulmod(rho_i, y_vals[i], BN254_SCALAR_FIELD), BN254_SCALAR_FIELD);
+            r = addmod(r, mulmod(rho_i, r_vals[i], BN254_SCALAR_FIELD), BN254_SCALAR_FIELD);
+            rho_i = mulmod(rho_i, rho, BN254_SCALAR_FIELD);
+        }
+        rhs_pairing = add(rhs_pairing, add(mulScalar(G_1, y), mulScalar(GAMMA_G_1, r)));
+        return pairing(lhs_pairing, VK, rhs_pairing, G_2);
+    }
+
     function evalPolyAt(uint256[] memory _coefficients, uint256 _index) public pure returns (uint256) {
         uint256 m = BN254_SCALAR_FIELD;
         uint256 result = 0;
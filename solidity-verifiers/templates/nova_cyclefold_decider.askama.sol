@@ -10,7 +10,9 @@
     https://github.com/weijiekoh/libkzg.
     Additionally we implement the NovaDecider contract, which combines the
     Groth16 and KZG10 verifiers to verify the zkSNARK proofs coming from
-    Nova+CycleFold folding.
+    Nova+CycleFold folding, where both KZG proofs are verified with a single
+    pairing check, taking the coefficient of their random linear combination
+    from a Keccak256 transcript.
 */
 
 
@@ -22,6 +24,8 @@
 /* Groth16 verifier methods */
 {{ groth16_verifier }}
 
+{{ keccak_transcript }}
+
 
 /* =============================== */
 /* Nova+CycleFold Decider verifier */
@@ -45,6 +49,8 @@ library LimbsDecomposition {
  * @dev     This is an askama template which, when templated, features a Groth16 and KZG10 verifiers from which this contract inherits.
  */
 contract NovaDecider is Groth16Verifier, KZG10Verifier {
+    using KeccakTranscript for KeccakTranscript.State;
+
     /**
      * @notice  Computes the linear combination of a and b with r as the coefficient.
      * @dev     All ops are done mod the BN254 scalar field prime
@@ -56,7 +62,44 @@ contract NovaDecider is Groth16Verifier, KZG10Verifier {
     }
 
     /**
-     * @notice  Verifies a nova cyclefold proof consisting of two KZG proofs and of a groth16 proof.
+     * @notice  Verifies the KZG proofs of the folded cmW and cmE with a single pairing check, where the coefficient of
+     *          their random linear combination is derived by absorbing the commitments, the proofs, the masks, the
+     *          challenges, the evaluations and the evaluations of the blinding polynomials into a Keccak256 transcript.
+     * @dev     Compatible with sonobe's `KZG::batch_verify_with_challenges` using a `KeccakTranscript`, both for hiding
+     *          and non-hiding proofs (whose masks and blinding evaluations are zero).
+     */
+    function verifyKZGProofs(
+        uint256[2][] memory cms,
+        uint256[2][4] calldata kzg_proof,
+        uint256[6] calldata challenge_W_challenge_E_kzg_evals
+    ) internal view returns (bool) {
+        uint256[2][] memory proofs = new uint256[2][](2);
+        uint256[2][] memory masks = new uint256[2][](2);
+        uint256[] memory challenges = new uint256[](2);
+        uint256[] memory evals = new uint256[](2);
+        uint256[] memory random_vs = new uint256[](2);
+
+        KeccakTranscript.State memory t;
+        for (uint8 k = 0; k < 2; k++) {
+            proofs[k] = kzg_proof[k];
+            masks[k] = kzg_proof[2 + k];
+            challenges[k] = challenge_W_challenge_E_kzg_evals[k];
+            evals[k] = challenge_W_challenge_E_kzg_evals[2 + k];
+            random_vs[k] = challenge_W_challenge_E_kzg_evals[4 + k];
+            t.absorbNonNativePoint(cms[k]);
+            t.absorbNonNativePoint(proofs[k]);
+            t.absorbNonNativePoint(masks[k]);
+        }
+        for (uint8 k = 0; k < 6; k++) {
+            t.absorb(challenge_W_challenge_E_kzg_evals[k]);
+        }
+        uint256 rho = t.getChallenge();
+
+        return super.checkMultiple(cms, proofs, masks, challenges, evals, random_vs, rho);
+    }
+
+    /**
+     * @notice  Verifies a nova cyclefold proof consisting of two KZG proofs, checked in a batch, and of a groth16 proof.
      * @dev     The selector of this function is "dynamic", since it depends on `z_len`.
      */
     function verifyNovaProof(
@@ -68,8 +111,8 @@ contract NovaDecider is Groth16Verifier, KZG10Verifier {
         uint256[2] calldata pA, // groth16 
         uint256[2][2] calldata pB, // groth16
         uint256[2] calldata pC, // groth16
-        uint256[4] calldata challenge_W_challenge_E_kzg_evals, // [challenge_W, challenge_E, eval_W, eval_E]
-        uint256[2][2] calldata kzg_proof // [proof_W, proof_E]
+        uint256[6] calldata challenge_W_challenge_E_kzg_evals, // [challenge_W, challenge_E, eval_W, eval_E, random_v_W, random_v_E]
+        uint256[2][4] calldata kzg_proof // [proof_W, proof_E, mask_W, mask_E]
     ) public view returns (bool) {
 
         require(i_z0_zi[0] >= 2, "Folding: the number of folded steps should be at least 2");
@@ -84,6 +127,9 @@ contract NovaDecider is Groth16Verifier, KZG10Verifier {
             public_inputs[2 + i] = i_z0_zi[1 + i];
         }
 
+        // [cmW, cmE] of the folded instance, whose KZG proofs are checked together below
+        uint256[2][] memory cms = new uint256[2][](2);
+
         {
             // U_i.cmW + r * u_i.cmW
             uint256[2] memory mulScalarPoint = super.mulScalar([u_i_cmW[0], u_i_cmW[1]], cmT_r[2]);
@@ -98,8 +144,8 @@ contract NovaDecider is Groth16Verifier, KZG10Verifier {
                     public_inputs[{{ z_len * 2 + 2 + num_limbs }} + k] = cmW_y_limbs[k];
                 }
             }
-        
-            require(this.check(cmW, kzg_proof[0], challenge_W_challenge_E_kzg_evals[0], challenge_W_challenge_E_kzg_evals[2]), "KZG: verifying proof for challenge W failed");
+
+            cms[0] = cmW;
         }
 
         {
@@ -117,9 +163,11 @@ contract NovaDecider is Groth16Verifier, KZG10Verifier {
                 }
             }
 
-            require(this.check(cmE, kzg_proof[1], challenge_W_challenge_E_kzg_evals[1], challenge_W_challenge_E_kzg_evals[3]), "KZG: verifying proof for challenge E failed");
+            cms[1] = cmE;
         }
 
+        require(verifyKZGProofs(cms, kzg_proof, challenge_W_challenge_E_kzg_evals), "KZG: verifying proofs for challenges W and E failed");
+
         {
             // add challenges
             public_inputs[{{ z_len * 2 + 2 + num_limbs * 4 }}] = challenge_W_challenge_E_kzg_evals[0];
@@ -145,3 +193,57 @@ contract NovaDecider is Groth16Verifier, KZG10Verifier {
         return(true);
     }
 }
+{% if stateful %}
+
+/**
+ * @author  PSE & 0xPARC
+ * @title   NovaStatefulDecider contract, for keeping track of the latest verified Nova IVC state.
+ * @dev     Stores the initial state `z_0` and the last verified `(i, z_i)`, and only accepts
+ *          proofs for the same `z_0` that advance the number of folded steps `i`.
+ */
+contract NovaStatefulDecider is NovaDecider {
+    uint256[{{ z_len }}] public z_0;
+    uint256 public i;
+    uint256[{{ z_len }}] public z_i;
+
+    event StateUpdated(uint256 i, uint256[{{ z_len }}] z_i);
+
+    constructor(uint256[{{ z_len }}] memory initial_state) {
+        z_0 = initial_state;
+        z_i = initial_state;
+    }
+
+    /**
+     * @notice  Verifies a nova cyclefold proof for the stored `z_0` and, if valid, updates the stored `(i, z_i)`.
+     * @dev     Takes the same inputs as `verifyNovaProof`, its selector is "dynamic" too, since it depends on `z_len`.
+     */
+    function updateState(
+        // inputs are grouped to prevent errors due stack too deep
+        uint256[{{ 1 + z_len * 2 }}] calldata i_z0_zi, // [i, z0, zi] where |z0| == |zi|
+        uint256[4] calldata U_i_cmW_U_i_cmE, // [U_i_cmW[2], U_i_cmE[2]]
+        uint256[2] calldata u_i_cmW, // [u_i_cmW[2]]
+        uint256[3] calldata cmT_r, // [cmT[2], r]
+        uint256[2] calldata pA, // groth16
+        uint256[2][2] calldata pB, // groth16
+        uint256[2] calldata pC, // groth16
+        uint256[6] calldata challenge_W_challenge_E_kzg_evals, // [challenge_W, challenge_E, eval_W, eval_E, random_v_W, random_v_E]
+        uint256[2][4] calldata kzg_proof // [proof_W, proof_E, mask_W, mask_E]
+    ) public returns (bool) {
+        require(i_z0_zi[0] > i, "State: the number of folded steps should be greater than the current one");
+        for (uint k = 0; k < {{ z_len }}; k++) {
+            require(i_z0_zi[1 + k] == z_0[k], "State: z_0 does not match the stored one");
+        }
+
+        bool success = this.verifyNovaProof(i_z0_zi, U_i_cmW_U_i_cmE, u_i_cmW, cmT_r, pA, pB, pC, challenge_W_challenge_E_kzg_evals, kzg_proof);
+        require(success == true, "Nova: verifying proof failed");
+
+        i = i_z0_zi[0];
+        for (uint k = 0; k < {{ z_len }}; k++) {
+            z_i[k] = i_z0_zi[1 + {{ z_len }} + k];
+        }
+
+        emit StateUpdated(i, z_i);
+        return(true);
+    }
+}
+{% endif %}
@@ -0,0 +1,185 @@
+/*
+    Sonobe's Nova + CycleFold decider verifier, using PLONK as the decider's SNARK.
+    Joint effort by 0xPARC & PSE.
+
+    More details at https://github.com/privacy-scaling-explorations/sonobe
+    Usage and design documentation at https://privacy-scaling-explorations.github.io/sonobe-docs/
+
+    Same as the Groth16-based NovaDecider, but the proof of the decider circuit is
+    a PLONK proof whose verifying key comes from indexing the circuit with a
+    universal KZG setup, so that the same SRS can be reused by the deciders of
+    different circuits instead of running a circuit-specific setup for each one.
+    The KZG10 Solidity template is adapted from https://github.com/weijiekoh/libkzg.
+*/
+
+
+/* =============================== */
+/* KZG10 verifier methods */
+{{ kzg10_verifier }}
+
+{{ plonk_verifier }}
+
+
+/* =============================== */
+/* Nova+CycleFold Decider verifier */
+/**
+ * @notice  Computes the decomposition of a `uint256` into num_limbs limbs of bits_per_limb bits each.
+ * @dev     Compatible with sonobe::folding-schemes::folding::circuits::nonnative::nonnative_field_to_field_elements.
+ */
+library LimbsDecomposition {
+    function decompose(uint256 x) internal pure returns (uint256[{{num_limbs}}] memory) {
+        uint256[{{num_limbs}}] memory limbs;
+        for (uint8 i = 0; i < {{num_limbs}}; i++) {
+            limbs[i] = (x >> ({{bits_per_limb}} * i)) & ((1 << {{bits_per_limb}}) - 1);
+        }
+        return limbs;
+    }
+}
+
+/**
+ * @author  PSE & 0xPARC
+ * @title   NovaPlonkDecider contract, for verifying Nova IVC SNARK proofs whose decider SNARK is PLONK.
+ * @dev     This is an askama template which, when templated, features a PLONK and KZG10 verifiers from which this contract inherits.
+ */
+contract NovaPlonkDecider is PlonkVerifier, KZG10Verifier {
+    using KeccakTranscript for KeccakTranscript.State;
+
+    /**
+     * @notice  Computes the linear combination of a and b with r as the coefficient.
+     * @dev     All ops are done mod the BN254 scalar field prime
+     */
+    function rlc(uint256 a, uint256 r, uint256 b) internal pure returns (uint256 result) {
+        assembly {
+            result := addmod(a, mulmod(r, b, BN254_SCALAR_FIELD), BN254_SCALAR_FIELD)
+        }
+    }
+
+    /**
+     * @notice  Verifies the KZG proofs of the folded cmW and cmE with a single pairing check, where the coefficient of
+     *          their random linear combination is derived by absorbing the commitments, the proofs, the masks, the
+     *          challenges, the evaluations and the evaluations of the blinding polynomials into a Keccak256 transcript.
+     * @dev     Compatible with sonobe's `KZG::batch_verify_with_challenges` using a `KeccakTranscript`, both for hiding
+     *          and non-hiding proofs (whose masks and blinding evaluations are zero).
+     */
+    function verifyKZGProofs(
+        uint256[2][] memory cms,
+        uint256[2][4] calldata kzg_proof,
+        uint256[6] calldata challenge_W_challenge_E_kzg_evals
+    ) internal view returns (bool) {
+        uint256[2][] memory proofs = new uint256[2][](2);
+        uint256[2][] memory masks = new uint256[2][](2);
+        uint256[] memory challenges = new uint256[](2);
+        uint256[] memory evals = new uint256[](2);
+        uint256[] memory random_vs = new uint256[](2);
+
+        KeccakTranscript.State memory t;
+        for (uint8 k = 0; k < 2; k++) {
+            proofs[k] = kzg_proof[k];
+            masks[k] = kzg_proof[2 + k];
+            challenges[k] = challenge_W_challenge_E_kzg_evals[k];
+            evals[k] = challenge_W_challenge_E_kzg_evals[2 + k];
+            random_vs[k] = challenge_W_challenge_E_kzg_evals[4 + k];
+            t.absorbNonNativePoint(cms[k]);
+            t.absorbNonNativePoint(proofs[k]);
+            t.absorbNonNativePoint(masks[k]);
+        }
+        for (uint8 k = 0; k < 6; k++) {
+            t.absorb(challenge_W_challenge_E_kzg_evals[k]);
+        }
+        uint256 rho = t.getChallenge();
+
+        return super.checkMultiple(cms, proofs, masks, challenges, evals, random_vs, rho);
+    }
+
+    /**
+     * @notice  Verifies a nova cyclefold proof consisting of two KZG proofs, checked in a batch, and of a PLONK proof.
+     * @dev     The selector of this function is "dynamic", since it depends on `z_len`.
+     */
+    function verifyNovaProof(
+        // inputs are grouped to prevent errors due stack too deep
+        uint256[{{ 1 + z_len * 2 }}] calldata i_z0_zi, // [i, z0, zi] where |z0| == |zi|
+        uint256[4] calldata U_i_cmW_U_i_cmE, // [U_i_cmW[2], U_i_cmE[2]]
+        uint256[2] calldata u_i_cmW, // [u_i_cmW[2]]
+        uint256[3] calldata cmT_r, // [cmT[2], r]
+        uint256[24] calldata plonk_proof, // see `verifyPlonkProof` for its layout
+        uint256[6] calldata challenge_W_challenge_E_kzg_evals, // [challenge_W, challenge_E, eval_W, eval_E, random_v_W, random_v_E]
+        uint256[2][4] calldata kzg_proof // [proof_W, proof_E, mask_W, mask_E]
+    ) public view returns (bool) {
+
+        require(i_z0_zi[0] >= 2, "Folding: the number of folded steps should be at least 2");
+
+        uint256[{{ public_inputs_len }}] memory public_inputs;
+
+        public_inputs[0] = {{pp_hash}};
+        public_inputs[1] = i_z0_zi[0];
+
+        for (uint i = 0; i < {{ z_len * 2 }}; i++) {
+            public_inputs[2 + i] = i_z0_zi[1 + i];
+        }
+
+        // [cmW, cmE] of the folded instance, whose KZG proofs are checked together below
+        uint256[2][] memory cms = new uint256[2][](2);
+
+        {
+            // U_i.cmW + r * u_i.cmW
+            uint256[2] memory mulScalarPoint = super.mulScalar([u_i_cmW[0], u_i_cmW[1]], cmT_r[2]);
+            uint256[2] memory cmW = super.add([U_i_cmW_U_i_cmE[0], U_i_cmW_U_i_cmE[1]], mulScalarPoint);
+
+            {
+                uint256[{{num_limbs}}] memory cmW_x_limbs = LimbsDecomposition.decompose(cmW[0]);
+                uint256[{{num_limbs}}] memory cmW_y_limbs = LimbsDecomposition.decompose(cmW[1]);
+        
+                for (uint8 k = 0; k < {{num_limbs}}; k++) {
+                    public_inputs[{{ z_len * 2 + 2 }} + k] = cmW_x_limbs[k];
+                    public_inputs[{{ z_len * 2 + 2 + num_limbs }} + k] = cmW_y_limbs[k];
+                }
+            }
+
+            cms[0] = cmW;
+        }
+
+        {
+            // U_i.cmE + r * cmT
+            uint256[2] memory mulScalarPoint = super.mulScalar([cmT_r[0], cmT_r[1]], cmT_r[2]);
+            uint256[2] memory cmE = super.add([U_i_cmW_U_i_cmE[2], U_i_cmW_U_i_cmE[3]], mulScalarPoint);
+
+            {
+                uint256[{{num_limbs}}] memory cmE_x_limbs = LimbsDecomposition.decompose(cmE[0]);
+                uint256[{{num_limbs}}] memory cmE_y_limbs = LimbsDecomposition.decompose(cmE[1]);
+            
+                for (uint8 k = 0; k < {{num_limbs}}; k++) {
+                    public_inputs[{{ z_len * 2 + 2 + num_limbs * 2 }} + k] = cmE_x_limbs[k];
+                    public_inputs[{{ z_len * 2 + 2 + num_limbs * 3 }} + k] = cmE_y_limbs[k];
+                }
+            }
+
+            cms[1] = cmE;
+        }
+
+        require(verifyKZGProofs(cms, kzg_proof, challenge_W_challenge_E_kzg_evals), "KZG: verifying proofs for challenges W and E failed");
+
+        {
+            // add challenges
+            public_inputs[{{ z_len * 2 + 2 + num_limbs * 4 }}] = challenge_W_challenge_E_kzg_evals[0];
+            public_inputs[{{ z_len * 2 + 2 + num_limbs * 4 + 1 }}] = challenge_W_challenge_E_kzg_evals[1];
+            public_inputs[{{ z_len * 2 + 2 + num_limbs * 4 + 2 }}] = challenge_W_challenge_E_kzg_evals[2];
+            public_inputs[{{ z_len * 2 + 2 + num_limbs * 4 + 3 }}] = challenge_W_challenge_E_kzg_evals[3];
+
+            uint256[{{num_limbs}}] memory cmT_x_limbs;
+            uint256[{{num_limbs}}] memory cmT_y_limbs;
+        
+            cmT_x_limbs = LimbsDecomposition.decompose(cmT_r[0]);
+            cmT_y_limbs = LimbsDecomposition.decompose(cmT_r[1]);
+        
+            for (uint8 k = 0; k < {{num_limbs}}; k++) {
+                public_inputs[{{ z_len * 2 + 2 + num_limbs * 4 }} + 4 + k] = cmT_x_limbs[k]; 
+                public_inputs[{{ z_len * 2 + 2 + num_limbs * 5 }} + 4 + k] = cmT_y_limbs[k];
+            }
+
+            bool success_plonk = verifyPlonkProof(plonk_proof, public_inputs);
+            require(success_plonk == true, "PLONK: verifying proof failed");
+        }
+
+        return(true);
+    }
+}
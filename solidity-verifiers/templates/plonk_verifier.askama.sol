@@ -0,0 +1,356 @@
+/* =============================== */
+/* PLONK verifier methods */
+{{ keccak_transcript }}
+
+/**
+ * @author  PSE & 0xPARC
+ * @title   PlonkVerifier contract, for verifying the proofs of sonobe's `snark::plonk::Plonk` over BN254.
+ * @dev     This is an askama template, where the verifying key of the circuit is hardcoded. The challenges are
+ *          derived from a Keccak256 transcript that absorbs the same values as sonobe's prover, and the openings of
+ *          the proof at ζ and ζω are checked with a single pairing against the universal KZG setup.
+ */
+contract PlonkVerifier {
+    using KeccakTranscript for KeccakTranscript.State;
+
+    uint256 constant PLONK_R = 21888242871839275222246405745257275088548364400416034343698204186575808495617;
+    uint256 constant PLONK_Q = 21888242871839275222246405745257275088696311157297823662689037894645226208583;
+
+    // size of the evaluation domain, its logarithm and generator
+    uint256 constant PLONK_N = {{ n }};
+    uint256 constant PLONK_LOG_N = {{ log_n }};
+    uint256 constant PLONK_OMEGA = {{ omega }};
+    // shifts of the cosets of the wires b and c
+    uint256 constant PLONK_K1 = {{ k1 }};
+    uint256 constant PLONK_K2 = {{ k2 }};
+    // digest of the verifying key, absorbed first into the transcript
+    uint256 constant PLONK_VK_DIGEST = {{ digest }};
+
+    // commitments to the selectors [q_M, q_L, q_R, q_O, q_C] and to the permutation polynomials [S_σ1, S_σ2, S_σ3]
+    uint256[2][8] PLONK_CMS = [
+    {%- for cm in cms %}
+        [
+            {{ cm.0[0] }},
+            {{ cm.0[1] }}
+        {% if loop.last -%}
+        ]
+        {%- else -%}
+        ],
+        {%- endif -%}
+    {% endfor %}
+    ];
+    uint256[2] PLONK_G_1 = [
+            {{ g1.0[0] }},
+            {{ g1.0[1] }}
+    ];
+    uint256[2][2] PLONK_H = [
+        [
+            {{ h.0[0][0] }},
+            {{ h.0[0][1] }}
+        ],
+        [
+            {{ h.0[1][0] }},
+            {{ h.0[1][1] }}
+        ]
+    ];
+    uint256[2][2] PLONK_BETA_H = [
+        [
+            {{ beta_h.0[0][0] }},
+            {{ beta_h.0[0][1] }}
+        ],
+        [
+            {{ beta_h.0[1][0] }},
+            {{ beta_h.0[1][1] }}
+        ]
+    ];
+
+    struct PlonkChallenges {
+        uint256 beta;
+        uint256 gamma;
+        uint256 alpha;
+        uint256 zeta;
+        uint256 v;
+        uint256 u;
+    }
+
+    /**
+     * @notice  Verifies a PLONK proof for the given public inputs.
+     * @dev     The proof is laid out as sonobe's `ToEth` encoding of `snark::plonk::Proof`:
+     *          [a, b, c, z, t_lo, t_mid, t_hi, W_ζ, W_ζω] as G_1 points (18 words), followed by
+     *          [ā, b̄, c̄, s̄_σ1, s̄_σ2, z̄_ω] (6 words).
+     * @param   proof  The PLONK proof.
+     * @param   public_inputs  The public inputs of the circuit.
+     * @return  bool  Whether the proof is valid.
+     */
+    function verifyPlonkProof(uint256[24] calldata proof, uint256[{{ n_public_inputs }}] memory public_inputs)
+        public
+        view
+        returns (bool)
+    {
+        for (uint256 i = 18; i < 24; i++) {
+            require(proof[i] < PLONK_R, "PLONK: evaluation not in the scalar field");
+        }
+        for (uint256 i = 0; i < {{ n_public_inputs }}; i++) {
+            require(public_inputs[i] < PLONK_R, "PLONK: public input not in the scalar field");
+        }
+
+        PlonkChallenges memory ch = plonkChallenges(proof, public_inputs);
+        (uint256[2] memory f, uint256 e) = plonkBatchedOpening(proof, ch, public_inputs);
+
+        // e(W_ζ + u⋅W_ζω, βH) = e(ζ⋅W_ζ + uζω⋅W_ζω + F - e⋅G, H)
+        uint256[2] memory w_zeta = [proof[14], proof[15]];
+        uint256[2] memory w_zeta_omega = [proof[16], proof[17]];
+        uint256[2] memory lhs = ecAdd(w_zeta, ecMul(w_zeta_omega, ch.u));
+        uint256[2] memory rhs = ecAdd(
+            ecMul(w_zeta, ch.zeta),
+            ecMul(w_zeta_omega, mulmod(mulmod(ch.u, ch.zeta, PLONK_R), PLONK_OMEGA, PLONK_R))
+        );
+        rhs = ecAdd(rhs, f);
+        rhs = ecAdd(rhs, ecMul(PLONK_G_1, PLONK_R - e));
+        return ecPairing(lhs, PLONK_BETA_H, ecNeg(rhs), PLONK_H);
+    }
+
+    /**
+     * @notice  Derives the challenges of the proof, absorbing the same values as sonobe's prover.
+     */
+    function plonkChallenges(uint256[24] calldata proof, uint256[{{ n_public_inputs }}] memory public_inputs)
+        internal
+        pure
+        returns (PlonkChallenges memory ch)
+    {
+        KeccakTranscript.State memory t;
+        t.absorb(PLONK_VK_DIGEST);
+        for (uint256 i = 0; i < {{ n_public_inputs }}; i++) {
+            t.absorb(public_inputs[i]);
+        }
+        // [a, b, c]
+        for (uint256 i = 0; i < 6; i += 2) {
+            t.absorbNonNativePoint([proof[i], proof[i + 1]]);
+        }
+        ch.beta = t.getChallenge();
+        ch.gamma = t.getChallenge();
+        // z
+        t.absorbNonNativePoint([proof[6], proof[7]]);
+        ch.alpha = t.getChallenge();
+        // [t_lo, t_mid, t_hi]
+        for (uint256 i = 8; i < 14; i += 2) {
+            t.absorbNonNativePoint([proof[i], proof[i + 1]]);
+        }
+        ch.zeta = t.getChallenge();
+        // evaluations
+        for (uint256 i = 18; i < 24; i++) {
+            t.absorb(proof[i]);
+        }
+        ch.v = t.getChallenge();
+        // [W_ζ, W_ζω]
+        t.absorbNonNativePoint([proof[14], proof[15]]);
+        t.absorbNonNativePoint([proof[16], proof[17]]);
+        ch.u = t.getChallenge();
+    }
+
+    /**
+     * @notice  Computes the commitment F and the evaluation e whose opening at ζ is checked by the pairing, where
+     *          F = [D] + v[a] + v²[b] + v³[c] + v⁴[S_σ1] + v⁵[S_σ2] and [D] is the commitment to the non-constant part
+     *          of the linearization polynomial r(X).
+     */
+    function plonkBatchedOpening(
+        uint256[24] calldata proof,
+        PlonkChallenges memory ch,
+        uint256[{{ n_public_inputs }}] memory public_inputs
+    ) internal view returns (uint256[2] memory f, uint256 e) {
+        uint256 zh;
+        uint256 alpha_2_l_0;
+        {
+            uint256 l_0;
+            uint256 pi;
+            (zh, l_0, pi) = plonkEvals(ch.zeta, public_inputs);
+            alpha_2_l_0 = mulmod(mulmod(ch.alpha, ch.alpha, PLONK_R), l_0, PLONK_R);
+            // e starts as -PI(ζ), see below
+            e = PLONK_R - pi;
+        }
+
+        // α⋅(ā + βζ + γ)(b̄ + βk_1ζ + γ)(c̄ + βk_2ζ + γ)
+        uint256 perm_1;
+        {
+            uint256 beta_zeta = mulmod(ch.beta, ch.zeta, PLONK_R);
+            perm_1 = mulmod(ch.alpha, addmod(addmod(proof[18], beta_zeta, PLONK_R), ch.gamma, PLONK_R), PLONK_R);
+            perm_1 = mulmod(
+                perm_1,
+                addmod(addmod(proof[19], mulmod(beta_zeta, PLONK_K1, PLONK_R), PLONK_R), ch.gamma, PLONK_R),
+                PLONK_R
+            );
+            perm_1 = mulmod(
+                perm_1,
+                addmod(addmod(proof[20], mulmod(beta_zeta, PLONK_K2, PLONK_R), PLONK_R), ch.gamma, PLONK_R),
+                PLONK_R
+            );
+        }
+        // α⋅(ā + βs̄_σ1 + γ)(b̄ + βs̄_σ2 + γ)⋅z̄_ω
+        uint256 perm_2 = mulmod(
+            ch.alpha,
+            addmod(addmod(proof[18], mulmod(ch.beta, proof[21], PLONK_R), PLONK_R), ch.gamma, PLONK_R),
+            PLONK_R
+        );
+        perm_2 = mulmod(
+            perm_2,
+            addmod(addmod(proof[19], mulmod(ch.beta, proof[22], PLONK_R), PLONK_R), ch.gamma, PLONK_R),
+            PLONK_R
+        );
+        perm_2 = mulmod(perm_2, proof[23], PLONK_R);
+
+        // [D] = āb̄[q_M] + ā[q_L] + b̄[q_R] + c̄[q_O] + [q_C] + (perm_1 + α²L_0(ζ) + u)[z] - perm_2⋅β[S_σ3]
+        //       - Z_H(ζ)([t_lo] + ζⁿ⁺²[t_mid] + ζ²ⁿ⁺⁴[t_hi])
+        f = ecMul(PLONK_CMS[0], mulmod(proof[18], proof[19], PLONK_R));
+        f = ecAdd(f, ecMul(PLONK_CMS[1], proof[18]));
+        f = ecAdd(f, ecMul(PLONK_CMS[2], proof[19]));
+        f = ecAdd(f, ecMul(PLONK_CMS[3], proof[20]));
+        f = ecAdd(f, PLONK_CMS[4]);
+        f = ecAdd(f, ecMul([proof[6], proof[7]], addmod(addmod(perm_1, alpha_2_l_0, PLONK_R), ch.u, PLONK_R)));
+        f = ecAdd(f, ecMul(PLONK_CMS[7], PLONK_R - mulmod(perm_2, ch.beta, PLONK_R)));
+        {
+            // the parts of the quotient have n + 2 coefficients, due to the blinding of the wires and of z
+            uint256 zeta_n_2 = mulmod(addmod(zh, 1, PLONK_R), mulmod(ch.zeta, ch.zeta, PLONK_R), PLONK_R);
+            uint256[2] memory t = ecAdd([proof[8], proof[9]], ecMul([proof[10], proof[11]], zeta_n_2));
+            t = ecAdd(t, ecMul([proof[12], proof[13]], mulmod(zeta_n_2, zeta_n_2, PLONK_R)));
+            f = ecAdd(f, ecMul(t, PLONK_R - zh));
+        }
+
+        // e = -r_0 + vā + v²b̄ + v³c̄ + v⁴s̄_σ1 + v⁵s̄_σ2 + uz̄_ω, where r_0 = PI(ζ) - α²L_0(ζ) - perm_2⋅(c̄ + γ) is the
+        // constant term of r(X)
+        e = addmod(e, alpha_2_l_0, PLONK_R);
+        e = addmod(e, mulmod(perm_2, addmod(proof[20], ch.gamma, PLONK_R), PLONK_R), PLONK_R);
+        e = addmod(e, mulmod(ch.u, proof[23], PLONK_R), PLONK_R);
+        uint256[2][5] memory cms;
+        cms[0] = [proof[0], proof[1]];
+        cms[1] = [proof[2], proof[3]];
+        cms[2] = [proof[4], proof[5]];
+        cms[3] = PLONK_CMS[5];
+        cms[4] = PLONK_CMS[6];
+        uint256 v_k = 1;
+        for (uint256 k = 0; k < 5; k++) {
+            v_k = mulmod(v_k, ch.v, PLONK_R);
+            f = ecAdd(f, ecMul(cms[k], v_k));
+            e = addmod(e, mulmod(v_k, proof[18 + k], PLONK_R), PLONK_R);
+        }
+    }
+
+    /**
+     * @notice  Evaluates at ζ the vanishing polynomial Z_H, the first Lagrange polynomial L_0 and the public input
+     *          polynomial PI(X) = -Σ x_i⋅L_i(X).
+     * @dev     Uses L_i(ζ) = ωⁱ⋅Z_H(ζ) / (n⋅(ζ - ωⁱ)), inverting all the denominators at once.
+     */
+    function plonkEvals(uint256 zeta, uint256[{{ n_public_inputs }}] memory public_inputs)
+        internal
+        view
+        returns (uint256 zh, uint256 l_0, uint256 pi)
+    {
+        uint256 zeta_n = zeta;
+        for (uint256 i = 0; i < PLONK_LOG_N; i++) {
+            zeta_n = mulmod(zeta_n, zeta_n, PLONK_R);
+        }
+        zh = addmod(zeta_n, PLONK_R - 1, PLONK_R);
+
+        uint256[{{ n_public_inputs }}] memory omegas;
+        uint256[{{ n_public_inputs }}] memory dens;
+        // prods[i] = dens[0]⋅...⋅dens[i - 1]
+        uint256[{{ n_public_inputs }}] memory prods;
+        uint256 acc = 1;
+        uint256 omega_i = 1;
+        for (uint256 i = 0; i < {{ n_public_inputs }}; i++) {
+            omegas[i] = omega_i;
+            dens[i] = mulmod(PLONK_N, addmod(zeta, PLONK_R - omega_i, PLONK_R), PLONK_R);
+            prods[i] = acc;
+            acc = mulmod(acc, dens[i], PLONK_R);
+            omega_i = mulmod(omega_i, PLONK_OMEGA, PLONK_R);
+        }
+        // inverse of dens[0]⋅...⋅dens[i - 1], for i from the last one down to 1
+        uint256 acc_inv = plonkInverse(acc);
+        for (uint256 i = {{ n_public_inputs }}; i > 0; i--) {
+            uint256 l_i = mulmod(mulmod(acc_inv, prods[i - 1], PLONK_R), mulmod(omegas[i - 1], zh, PLONK_R), PLONK_R);
+            acc_inv = mulmod(acc_inv, dens[i - 1], PLONK_R);
+            pi = addmod(pi, PLONK_R - mulmod(public_inputs[i - 1], l_i, PLONK_R), PLONK_R);
+            l_0 = l_i;
+        }
+    }
+
+    /**
+     * @notice  Computes the inverse of x in the BN254 scalar field, through the modexp precompile.
+     */
+    function plonkInverse(uint256 x) internal view returns (uint256 result) {
+        require(x != 0, "PLONK: inverse of zero");
+        uint256[6] memory input = [uint256(32), 32, 32, x, PLONK_R - 2, PLONK_R];
+        uint256[1] memory out;
+        bool success;
+        assembly {
+            success := staticcall(sub(gas(), 2000), 5, input, 0xc0, out, 0x20)
+        }
+        require(success, "bn254: modexp failed");
+        return out[0];
+    }
+
+    /**
+     * @notice  Performs scalar multiplication in G_1.
+     */
+    function ecMul(uint256[2] memory p, uint256 s) internal view returns (uint256[2] memory r) {
+        uint256[3] memory input = [p[0], p[1], s];
+        bool success;
+        assembly {
+            success := staticcall(sub(gas(), 2000), 7, input, 0x60, r, 0x40)
+        }
+        require(success, "bn254: scalar mul failed");
+    }
+
+    /**
+     * @notice  Adds two points in G_1.
+     */
+    function ecAdd(uint256[2] memory p1, uint256[2] memory p2) internal view returns (uint256[2] memory r) {
+        uint256[4] memory input = [p1[0], p1[1], p2[0], p2[1]];
+        bool success;
+        assembly {
+            success := staticcall(sub(gas(), 2000), 6, input, 0x80, r, 0x40)
+        }
+        require(success, "bn254: point add failed");
+    }
+
+    /**
+     * @notice  Negates a point in G_1.
+     */
+    function ecNeg(uint256[2] memory p) internal pure returns (uint256[2] memory) {
+        if (p[0] == 0 && p[1] == 0) {
+            return p;
+        }
+        return [p[0], PLONK_Q - (p[1] % PLONK_Q)];
+    }
+
+    /**
+     * @notice  Computes the pairing check e(a_1, a_2) * e(b_1, b_2) == 1.
+     * @dev     G_2 points are encoded as [[x.c0, x.c1], [y.c0, y.c1]], while the precompile takes the imaginary part
+     *          first.
+     */
+    function ecPairing(uint256[2] memory a_1, uint256[2][2] memory a_2, uint256[2] memory b_1, uint256[2][2] memory b_2)
+        internal
+        view
+        returns (bool)
+    {
+        uint256[12] memory input = [
+            a_1[0],
+            a_1[1],
+            a_2[0][1],
+            a_2[0][0],
+            a_2[1][1],
+            a_2[1][0],
+            b_1[0],
+            b_1[1],
+            b_2[0][1],
+            b_2[0][0],
+            b_2[1][1],
+            b_2[1][0]
+        ];
+        uint256[1] memory out;
+        bool success;
+        assembly {
+            success := staticcall(sub(gas(), 2000), 8, input, 0x180, out, 0x20)
+        }
+        require(success, "bn254: pairing failed");
+        return out[0] == 1;
+    }
+}
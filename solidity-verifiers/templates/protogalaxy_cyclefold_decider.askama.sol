@@ -0,0 +1,108 @@
+/*
+    Sonobe's ProtoGalaxy + CycleFold decider verifier.
+    Joint effort by 0xPARC & PSE.
+
+    More details at https://github.com/privacy-scaling-explorations/sonobe
+    Usage and design documentation at https://privacy-scaling-explorations.github.io/sonobe-docs/
+
+    Uses the https://github.com/iden3/snarkjs/blob/master/templates/verifier_groth16.sol.ejs
+    Groth16 verifier implementation and a KZG10 Solidity template adapted from
+    https://github.com/weijiekoh/libkzg.
+    Additionally we implement the ProtoGalaxyDecider contract, which combines the
+    Groth16 and KZG10 verifiers to verify the zkSNARK proofs coming from
+    ProtoGalaxy+CycleFold folding.
+*/
+
+
+/* =============================== */
+/* KZG10 verifier methods */
+{{ kzg10_verifier }}
+
+/* =============================== */
+/* Groth16 verifier methods */
+{{ groth16_verifier }}
+
+
+/* =============================== */
+/* ProtoGalaxy+CycleFold Decider verifier */
+/**
+ * @notice  Computes the decomposition of a `uint256` into num_limbs limbs of bits_per_limb bits each.
+ * @dev     Compatible with sonobe::folding-schemes::folding::circuits::nonnative::nonnative_field_to_field_elements.
+ */
+library LimbsDecomposition {
+    function decompose(uint256 x) internal pure returns (uint256[{{num_limbs}}] memory) {
+        uint256[{{num_limbs}}] memory limbs;
+        for (uint8 i = 0; i < {{num_limbs}}; i++) {
+            limbs[i] = (x >> ({{bits_per_limb}} * i)) & ((1 << {{bits_per_limb}}) - 1);
+        }
+        return limbs;
+    }
+}
+
+/**
+ * @author  PSE & 0xPARC
+ * @title   ProtoGalaxyDecider contract, for verifying ProtoGalaxy IVC SNARK proofs.
+ * @dev     This is an askama template which, when templated, features a Groth16 and KZG10 verifiers from which this contract inherits.
+ */
+contract ProtoGalaxyDecider is Groth16Verifier, KZG10Verifier {
+    /**
+     * @notice  Verifies a protogalaxy cyclefold proof consisting of a KZG proof and of a groth16 proof.
+     * @dev     The selector of this function is "dynamic", since it depends on `z_len`.
+     */
+    function verifyProtoGalaxyProof(
+        // inputs are grouped to prevent errors due stack too deep
+        uint256[{{ 1 + z_len * 2 }}] calldata i_z0_zi, // [i, z0, zi] where |z0| == |zi|
+        uint256[4] calldata U_i_phi_u_i_phi, // [U_i_phi[2], u_i_phi[2]]
+        uint256[2] calldata L_X_evals, // L_X_evals used at the last fold
+        uint256[2] calldata pA, // groth16
+        uint256[2][2] calldata pB, // groth16
+        uint256[2] calldata pC, // groth16
+        uint256[2] calldata challenge_kzg_eval, // [challenge, eval]
+        uint256[2] calldata kzg_proof // [proof]
+    ) public view returns (bool) {
+
+        require(i_z0_zi[0] >= 2, "Folding: the number of folded steps should be at least 2");
+
+        // from gamma_abc_len, we subtract 1.
+        uint256[{{ public_inputs_len - 1 }}] memory public_inputs;
+
+        public_inputs[0] = {{pp_hash}};
+        public_inputs[1] = i_z0_zi[0];
+
+        for (uint i = 0; i < {{ z_len * 2 }}; i++) {
+            public_inputs[2 + i] = i_z0_zi[1 + i];
+        }
+
+        {
+            // L_0(gamma) * U_i.phi + L_1(gamma) * u_i.phi
+            uint256[2] memory U_i_phi = super.mulScalar([U_i_phi_u_i_phi[0], U_i_phi_u_i_phi[1]], L_X_evals[0]);
+            uint256[2] memory u_i_phi = super.mulScalar([U_i_phi_u_i_phi[2], U_i_phi_u_i_phi[3]], L_X_evals[1]);
+            uint256[2] memory phi = super.add(U_i_phi, u_i_phi);
+
+            {
+                uint256[{{num_limbs}}] memory phi_x_limbs = LimbsDecomposition.decompose(phi[0]);
+                uint256[{{num_limbs}}] memory phi_y_limbs = LimbsDecomposition.decompose(phi[1]);
+
+                for (uint8 k = 0; k < {{num_limbs}}; k++) {
+                    public_inputs[{{ z_len * 2 + 2 }} + k] = phi_x_limbs[k];
+                    public_inputs[{{ z_len * 2 + 2 + num_limbs }} + k] = phi_y_limbs[k];
+                }
+            }
+
+            require(this.check(phi, kzg_proof, challenge_kzg_eval[0], challenge_kzg_eval[1]), "KZG: verifying proof for challenge phi failed");
+        }
+
+        {
+            // add challenge, eval and L_X_evals
+            public_inputs[{{ z_len * 2 + 2 + num_limbs * 2 }}] = challenge_kzg_eval[0];
+            public_inputs[{{ z_len * 2 + 2 + num_limbs * 2 + 1 }}] = challenge_kzg_eval[1];
+            public_inputs[{{ z_len * 2 + 2 + num_limbs * 2 + 2 }}] = L_X_evals[0];
+            public_inputs[{{ z_len * 2 + 2 + num_limbs * 2 + 3 }}] = L_X_evals[1];
+
+            bool success_g16 = this.verifyProof(pA, pB, pC, public_inputs);
+            require(success_g16 == true, "Groth16: verifying proof failed");
+        }
+
+        return(true);
+    }
+}